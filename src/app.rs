@@ -6,24 +6,43 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::layout::Rect;
 use ratatui::widgets::TableState;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::time::Instant;
+use zeroize::Zeroize;
 
 use crate::constants;
+use crate::core::archive;
+use crate::core::bundle;
+use crate::core::credentials::{self, CredentialNeeds, OpenVpnAuth, Stored};
+use crate::core::duplicates::{self, Duplicate};
+use crate::core::grouping::{self, SidebarRow};
+use crate::core::importer::{self, Conflict, Outcome, Summary};
 use crate::core::scanner;
+use crate::core::search::{self, ProfileMatch};
 use crate::core::telemetry::{self, TelemetryUpdate};
+use crate::core::vault;
 use crate::logger::{self, LogLevel};
 use crate::message::{self, Message, ScrollMove, SelectionMove};
 use crate::utils;
 
 // Re-export state types for convenient access
 pub use crate::state::{
-    ConnectionState, DetailedConnectionInfo, FocusedPanel, InputMode, Protocol, Toast, ToastType,
-    VpnProfile, DISMISS_DURATION,
+    ConnectionState, CredentialField, CredentialPrompt, CredentialStore, DetailedConnectionInfo,
+    FocusedPanel, GroupBy, ImportConfirm, InputMode, NewProfileWizard, PreviewItem, PromptField,
+    Protocol, SortBy, Toast, ToastType, VpnProfile, WizardField, DISMISS_DURATION,
 };
 
+/// A batch import waiting for the user to decide about a duplicate.
+struct PendingImport {
+    /// Files still to import; the duplicate is the first one.
+    queue: VecDeque<PathBuf>,
+    index: duplicates::Index,
+    duplicate: Duplicate,
+    summary: Summary,
+}
+
 /// Main application state container.
 ///
 /// The `App` struct holds all state for the Vortix TUI application including
@@ -47,6 +66,11 @@ pub struct App {
     pub profiles: Vec<VpnProfile>,
     /// When the current session started.
     pub session_start: Option<Instant>,
+    /// Since when a measured latency is only held in memory.
+    latency_unsaved: Option<Instant>,
+    /// Split tunnel rules applied to the current (or just dropped)
+    /// connection, with hostnames resolved.
+    pub split_policy: Option<crate::core::split_tunnel::Policy>,
 
     // === Network Telemetry ===
     /// Historical download throughput data points for charting.
@@ -63,6 +87,11 @@ pub struct App {
     pub location: String,
     pub isp: String,
     pub dns_server: String,
+    /// Route-based DNS leak check for the active connection.
+    pub dns_report: Option<crate::core::leaks::DnsReport>,
+    /// Local IPv6 leak check for the active connection.
+    pub ipv6_report: Option<crate::core::leaks::Ipv6Report>,
+    /// IPv6-only sites answered (remote confirmation, opt-in).
     pub ipv6_leak: bool,
 
     // === System Info ===
@@ -72,6 +101,20 @@ pub struct App {
     /// Scroll position for logs panel (logs stored in logger module)
     pub logs_scroll: u16,
     pub logs_auto_scroll: bool,
+    /// Routing table shown in the Routes panel, refreshed every tick.
+    pub route_table: crate::core::routes::RouteTable,
+    /// Scroll position for the Routes panel.
+    pub routes_scroll: u16,
+    /// A routing table read is running in the background.
+    routes_busy: bool,
+    /// Leak-test checks of the active connection (as `vortix audit`),
+    /// re-evaluated every tick.
+    pub audit: Vec<crate::core::audit::Check>,
+    /// `AllowedIPs` of the connected profile, read once per profile.
+    audit_allowed_ips: Option<(String, Option<Vec<crate::vpn::wireguard::Cidr>>)>,
+    /// Whether the connected profile sets `PersistentKeepalive`, read with
+    /// its `AllowedIPs`.
+    audit_keepalive: bool,
 
     // === UI State (Panel-based) ===
     pub focused_panel: FocusedPanel,
@@ -83,6 +126,19 @@ pub struct App {
     pub action_menu_state: ratatui::widgets::ListState,
     pub config_scroll: u16,
     pub profile_list_state: TableState,
+    /// Sidebar filter query entered via `/` (empty shows every profile).
+    pub profile_filter: String,
+    /// Profiles shown in the sidebar, in display order (used for quick slots).
+    pub visible_profiles: Vec<ProfileMatch>,
+    /// Sidebar rows (group headers and profiles). Rows in
+    /// `profile_list_state` index into this list, not into `profiles`.
+    pub sidebar_rows: Vec<SidebarRow>,
+    /// Sidebar grouping (persisted in `sidebar.json`).
+    pub group_by: GroupBy,
+    /// Sidebar sort order (persisted in `sidebar.json`).
+    pub sort_by: SortBy,
+    /// Keys of collapsed sidebar groups (persisted in `sidebar.json`).
+    pub collapsed_groups: HashSet<String>,
     pub panel_areas: HashMap<FocusedPanel, Rect>,
     pub toast: Option<Toast>,
     pub terminal_size: (u16, u16),
@@ -95,16 +151,45 @@ pub struct App {
     pub killswitch_mode: crate::state::KillSwitchMode,
     /// Current kill switch state (Disabled, Armed, Blocking).
     pub killswitch_state: crate::state::KillSwitchState,
+    /// What the kill switch allows besides the tunnel (`killswitch.json`).
+    pub killswitch_policy: crate::core::killswitch::KillSwitchPolicy,
+    /// Resolved addresses of the servers the kill switch lets through.
+    pub killswitch_endpoints: Vec<crate::core::killswitch::ResolvedEndpoint>,
+    /// The kill switch mode changed; its rules load once every profile's
+    /// servers are resolved, while DNS still works.
+    killswitch_pending: bool,
+    /// Last time the server hostnames were resolved again.
+    endpoints_checked: Option<Instant>,
+    /// Addresses of the portal probe URL, resolved before DNS is blocked.
+    portal_probe: Vec<crate::core::killswitch::ResolvedEndpoint>,
+    /// Open captive portal exception and when it ends.
+    pub portal_window: Option<(crate::core::killswitch::PortalWindow, Instant)>,
+    /// Last captive portal probe.
+    portal_probed: Option<Instant>,
+    /// Login page of the captive portal last detected.
+    portal_location: Option<String>,
 
     // === Async Communication ===
     telemetry_rx: Option<mpsc::Receiver<TelemetryUpdate>>,
     cmd_tx: mpsc::Sender<Message>,
     cmd_rx: mpsc::Receiver<Message>,
     network_stats: telemetry::NetworkStats,
+
+    /// Import paused on a duplicate (see `InputMode::Duplicate`).
+    pending_import: Option<PendingImport>,
+    /// Unpacked archive the running import reads from.
+    import_scratch: Option<vault::ScratchDir>,
+
+    // === Subscriptions ===
+    /// A background subscription refresh is running.
+    subscriptions_busy: bool,
+    /// Last time scheduled subscriptions were looked at.
+    subscriptions_checked: Option<Instant>,
 }
 
 impl App {
     /// Create a new App instance with default state
+    #[allow(clippy::too_many_lines)]
     pub fn new() -> Self {
         let (cmd_tx, cmd_rx) = mpsc::channel::<Message>();
         let down_history = (0..60).map(|i| (f64::from(i), 0.0)).collect();
@@ -115,6 +200,8 @@ impl App {
             connection_state: ConnectionState::Disconnected,
             profiles: Vec::new(),
             session_start: None,
+            latency_unsaved: None,
+            split_policy: None,
 
             down_history,
             up_history,
@@ -126,11 +213,19 @@ impl App {
             location: "Detecting...".to_string(),
             isp: "Detecting...".to_string(),
             dns_server: "Detecting...".to_string(),
+            dns_report: None,
+            ipv6_report: None,
             ipv6_leak: false,
 
             public_ip: "Detecting...".to_string(),
-            real_ip: None,
+            real_ip: crate::core::audit::load_real_ip(),
             logs_scroll: 0,
+            route_table: crate::core::routes::RouteTable::default(),
+            routes_scroll: 0,
+            routes_busy: false,
+            audit: Vec::new(),
+            audit_allowed_ips: None,
+            audit_keepalive: false,
             logs_auto_scroll: true,
 
             // Panel-based UI state
@@ -143,6 +238,12 @@ impl App {
             action_menu_state: ratatui::widgets::ListState::default(),
             config_scroll: 0,
             profile_list_state: TableState::default(),
+            profile_filter: String::new(),
+            visible_profiles: Vec::new(),
+            sidebar_rows: Vec::new(),
+            group_by: GroupBy::default(),
+            sort_by: SortBy::default(),
+            collapsed_groups: HashSet::new(),
             panel_areas: HashMap::new(),
             toast: None,
             terminal_size: (0, 0),
@@ -152,37 +253,59 @@ impl App {
             // Kill switch - load from persisted state for crash recovery
             killswitch_mode: crate::state::KillSwitchMode::default(),
             killswitch_state: crate::state::KillSwitchState::default(),
+            killswitch_policy: crate::core::killswitch::load_policy(),
+            killswitch_endpoints: Vec::new(),
+            killswitch_pending: false,
+            endpoints_checked: None,
+            portal_probe: Vec::new(),
+            portal_window: None,
+            portal_probed: None,
+            portal_location: None,
 
             telemetry_rx: None,
             cmd_tx,
             cmd_rx,
             network_stats: telemetry::NetworkStats::default(),
+
+            pending_import: None,
+            import_scratch: None,
+
+            subscriptions_busy: false,
+            subscriptions_checked: None,
         };
 
         // Recover kill switch state from crash if persisted
-        if let Some(persisted) = crate::core::killswitch::load_state() {
+        let persisted = crate::core::killswitch::load_state();
+        if let Some(persisted) = &persisted {
             app.killswitch_mode = persisted.mode;
-            // If we were blocking when crashed, release it now
-            if persisted.state == crate::state::KillSwitchState::Blocking {
-                app.log("WARN: Kill switch was blocking when app crashed. Releasing...");
-                let _ = crate::core::killswitch::disable_blocking();
-                app.killswitch_state = crate::state::KillSwitchState::Disabled;
-                crate::core::killswitch::clear_state();
-            } else {
-                app.killswitch_state = persisted.state;
+            app.killswitch_state = persisted.state;
+            if persisted.is_stale() {
+                // Rules left by a dead session: ask before touching them
+                app.log(&format!(
+                    "WARN: Kill switch was left blocking by a vortix session that is no longer running{}",
+                    persisted.pid.map(|pid| format!(" (PID {pid})")).unwrap_or_default()
+                ));
             }
+            app.killswitch_endpoints.clone_from(&persisted.endpoints);
         }
 
         // Load profiles from ~/.config/vortix/profiles/
         app.profiles = crate::vpn::load_profiles();
+        app.input_mode = InputMode::at_startup(
+            persisted.as_ref(),
+            vault::is_enabled() && !vault::is_unlocked(),
+        );
+
+        let prefs = utils::load_sidebar_prefs();
+        app.group_by = prefs.group_by;
+        app.sort_by = prefs.sort_by;
+        app.collapsed_groups = prefs.collapsed_groups.into_iter().collect();
 
         app.load_metadata();
         app.sort_profiles();
 
         // Select first profile if available
-        if !app.profiles.is_empty() {
-            app.profile_list_state.select(Some(0));
-        }
+        app.select_first_profile_row();
 
         // Initialize logs with boot sequence
         app.log(&format!(
@@ -277,6 +400,7 @@ impl App {
                 KeyCode::End | KeyCode::Char('G') => {
                     self.handle_message(Message::Scroll(ScrollMove::Bottom));
                 }
+                KeyCode::Char('Q') => self.handle_message(Message::OpenQr),
                 _ => {} // Ignore other keys
             }
             return;
@@ -305,7 +429,56 @@ impl App {
                     self.handle_message(Message::CloseOverlay);
                 }
             }
+            InputMode::Search { mut cursor } => {
+                self.handle_input_search(key, &mut cursor);
+                if let InputMode::Search { .. } = self.input_mode {
+                    self.input_mode = InputMode::Search { cursor };
+                }
+            }
             InputMode::ConfirmDelete { .. } => self.handle_confirm_delete_keys(key),
+            InputMode::Credentials { .. } => self.handle_credential_keys(key),
+            InputMode::Unlock { .. } => self.handle_unlock_keys(key),
+            InputMode::Export { .. } => self.handle_export_keys(key),
+            InputMode::NewProfile { .. } => self.handle_new_profile_keys(key),
+            InputMode::ConfirmImport { .. } => self.handle_confirm_import_keys(key),
+            InputMode::ImportPreview { .. } => self.handle_import_preview_keys(key),
+            InputMode::Duplicate { .. } => self.handle_duplicate_keys(key),
+            InputMode::StaleKillSwitch { .. } => match key.code {
+                KeyCode::Char('r') | KeyCode::Enter => {
+                    self.handle_message(Message::RestoreStaleKillSwitch);
+                }
+                KeyCode::Char('x') => self.handle_message(Message::ReleaseStaleKillSwitch),
+                _ => {}
+            },
+            InputMode::PortalWindow { .. } => match key.code {
+                KeyCode::Enter | KeyCode::Char('y') => {
+                    self.handle_message(Message::OpenPortalWindow);
+                }
+                KeyCode::Esc | KeyCode::Char('n') => self.handle_message(Message::CloseOverlay),
+                _ => {}
+            },
+            InputMode::ProfileCreated { .. } => {
+                if matches!(key.code, KeyCode::Esc | KeyCode::Enter) {
+                    self.handle_message(Message::CloseOverlay);
+                }
+            }
+            InputMode::QrWarning { .. } => match key.code {
+                KeyCode::Esc => self.handle_message(Message::CloseOverlay),
+                KeyCode::Enter => self.handle_message(Message::ShowQr),
+                KeyCode::Char('h' | ' ') => {
+                    if let InputMode::QrWarning { exclude_hooks, .. } = &mut self.input_mode {
+                        *exclude_hooks = !*exclude_hooks;
+                    }
+                }
+                _ => {}
+            },
+            InputMode::QrCode { .. } => {
+                if matches!(key.code, KeyCode::Esc | KeyCode::Enter) {
+                    self.handle_message(Message::CloseOverlay);
+                }
+            }
+            InputMode::BundlePassphrase { .. } => self.handle_bundle_passphrase_keys(key),
+            InputMode::KillSwitchPreview { .. } => self.handle_killswitch_preview_keys(key),
             InputMode::Normal => self.handle_normal_keys(key),
         }
     }
@@ -347,7 +520,7 @@ impl App {
             FocusedPanel::Sidebar => {
                 // Scroll Profiles
                 let current = self.profile_list_state.selected().unwrap_or(0);
-                let last = self.profiles.len().saturating_sub(1);
+                let last = self.sidebar_rows.len().saturating_sub(1);
                 if current < last {
                     self.profile_list_state.select(Some(current + 1));
                 }
@@ -364,6 +537,13 @@ impl App {
                     self.logs_auto_scroll = true;
                 }
             }
+            FocusedPanel::Routes => {
+                let max_scroll = u16::try_from(self.route_table.routes.len().saturating_sub(1))
+                    .unwrap_or(u16::MAX);
+                if self.routes_scroll < max_scroll {
+                    self.routes_scroll += 1;
+                }
+            }
             _ => {}
         }
     }
@@ -389,6 +569,7 @@ impl App {
                 self.logs_auto_scroll = false;
                 self.logs_scroll = self.logs_scroll.saturating_sub(1);
             }
+            FocusedPanel::Routes => self.routes_scroll = self.routes_scroll.saturating_sub(1),
             _ => {}
         }
     }
@@ -428,21 +609,246 @@ impl App {
         }
     }
 
+    /// Keys in the vault unlock prompt. Esc skips unlocking; encrypted
+    /// profiles then ask again on connect.
+    fn handle_unlock_keys(&mut self, key: KeyEvent) {
+        let InputMode::Unlock { passphrase, .. } = &mut self.input_mode else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                passphrase.zeroize();
+                self.handle_message(Message::CloseOverlay);
+            }
+            KeyCode::Enter => {
+                let passphrase = std::mem::take(passphrase);
+                self.handle_message(Message::UnlockVault(passphrase));
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                passphrase.zeroize();
+            }
+            KeyCode::Backspace => {
+                passphrase.pop();
+            }
+            KeyCode::Char(c) => passphrase.push(c),
+            _ => {}
+        }
+    }
+
+    /// Keys in the export dialog. Tab switches between path and passphrase.
+    fn handle_export_keys(&mut self, key: KeyEvent) {
+        let InputMode::Export {
+            path,
+            passphrase,
+            on_passphrase,
+        } = &mut self.input_mode
+        else {
+            return;
+        };
+        let field = if *on_passphrase { passphrase } else { path };
+        match key.code {
+            KeyCode::Esc => self.handle_message(Message::CloseOverlay),
+            KeyCode::Enter => {
+                let InputMode::Export {
+                    path, passphrase, ..
+                } = std::mem::take(&mut self.input_mode)
+                else {
+                    return;
+                };
+                let passphrase = (!passphrase.is_empty()).then_some(passphrase);
+                self.handle_message(Message::ExportProfiles { path, passphrase });
+            }
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                *on_passphrase = !*on_passphrase;
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                field.zeroize();
+            }
+            KeyCode::Backspace => {
+                field.pop();
+            }
+            KeyCode::Char(c) => field.push(c),
+            _ => {}
+        }
+    }
+
+    /// Keys in the duplicate prompt: s/o/k decide, a toggles "apply to all".
+    fn handle_duplicate_keys(&mut self, key: KeyEvent) {
+        let choice = match key.code {
+            KeyCode::Char('s') => Conflict::Skip,
+            KeyCode::Char('o') => Conflict::Overwrite,
+            KeyCode::Char('k') => Conflict::KeepBoth,
+            KeyCode::Char('a') => {
+                if let InputMode::Duplicate { apply_to_all, .. } = &mut self.input_mode {
+                    *apply_to_all = !*apply_to_all;
+                }
+                return;
+            }
+            KeyCode::Esc => {
+                self.handle_message(Message::ResolveDuplicate(None));
+                return;
+            }
+            _ => return,
+        };
+        self.handle_message(Message::ResolveDuplicate(Some(choice)));
+    }
+
+    /// Keys in the import confirmation: edit name and location, Enter saves.
+    fn handle_confirm_import_keys(&mut self, key: KeyEvent) {
+        let InputMode::ConfirmImport { confirm } = &mut self.input_mode else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.handle_message(Message::CloseOverlay),
+            KeyCode::Enter => self.handle_message(Message::SaveImport),
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                confirm.toggle_focus();
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                confirm.focused_value().clear();
+            }
+            KeyCode::Backspace => {
+                confirm.focused_value().pop();
+            }
+            KeyCode::Char(c) => confirm.focused_value().push(c),
+            _ => {}
+        }
+    }
+
+    /// Keys in the import preview: move, check files, then import them.
+    fn handle_import_preview_keys(&mut self, key: KeyEvent) {
+        let InputMode::ImportPreview { items, cursor } = &mut self.input_mode else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.handle_message(Message::CloseOverlay),
+            KeyCode::Enter => self.handle_message(Message::ConfirmImportPreview),
+            KeyCode::Down | KeyCode::Char('j') => {
+                *cursor = (*cursor + 1).min(items.len().saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => *cursor = cursor.saturating_sub(1),
+            KeyCode::Char(' ') => {
+                if let Some(item) = items.get_mut(*cursor).filter(|i| i.preview.is_ok()) {
+                    item.checked = !item.checked;
+                }
+            }
+            KeyCode::Char('a') => {
+                let check = !items.iter().any(|i| i.checked);
+                for item in items.iter_mut().filter(|i| i.preview.is_ok()) {
+                    item.checked = check;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Keys in the new profile wizard. Enter creates the profile.
+    fn handle_new_profile_keys(&mut self, key: KeyEvent) {
+        let InputMode::NewProfile { wizard } = &mut self.input_mode else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.handle_message(Message::CloseOverlay),
+            KeyCode::Enter => self.handle_message(Message::CreateProfile),
+            KeyCode::Tab | KeyCode::Down => wizard.focus_next(),
+            KeyCode::BackTab | KeyCode::Up => wizard.focus_prev(),
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                wizard.focused_value().clear();
+            }
+            KeyCode::Backspace => {
+                wizard.focused_value().pop();
+            }
+            KeyCode::Char(c) => wizard.focused_value().push(c),
+            _ => {}
+        }
+    }
+
+    /// Keys in the encrypted bundle passphrase prompt.
+    fn handle_bundle_passphrase_keys(&mut self, key: KeyEvent) {
+        let InputMode::BundlePassphrase { passphrase, .. } = &mut self.input_mode else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                passphrase.zeroize();
+                self.handle_message(Message::CloseOverlay);
+            }
+            KeyCode::Enter => {
+                let InputMode::BundlePassphrase {
+                    path, passphrase, ..
+                } = std::mem::take(&mut self.input_mode)
+                else {
+                    return;
+                };
+                self.handle_message(Message::ImportBundle {
+                    path,
+                    passphrase: Some(passphrase),
+                });
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                passphrase.zeroize();
+            }
+            KeyCode::Backspace => {
+                passphrase.pop();
+            }
+            KeyCode::Char(c) => passphrase.push(c),
+            _ => {}
+        }
+    }
+
+    /// Keys in the credential prompt. Input is append-only since the values
+    /// are masked; Ctrl+U clears the focused field.
+    fn handle_credential_keys(&mut self, key: KeyEvent) {
+        let InputMode::Credentials { prompt } = &mut self.input_mode else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.handle_message(Message::CloseOverlay);
+                self.show_toast(
+                    constants::MSG_CREDENTIALS_CANCELLED.to_string(),
+                    ToastType::Info,
+                );
+            }
+            KeyCode::Enter => self.handle_message(Message::SubmitCredentials),
+            KeyCode::Tab | KeyCode::Down => prompt.focus_next(),
+            KeyCode::BackTab | KeyCode::Up => prompt.focus_prev(),
+            KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right if prompt.on_store_row() => {
+                prompt.cycle_store();
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(field) = prompt.focused_field() {
+                    field.value.clear();
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(field) = prompt.focused_field() {
+                    field.value.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(field) = prompt.focused_field() {
+                    field.value.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn handle_input_import(&mut self, key: KeyEvent, path: &mut String, cursor: &mut usize) {
         match key.code {
             KeyCode::Esc => self.handle_message(Message::CloseOverlay),
             KeyCode::Enter => {
                 let path_clone = path.clone();
-                self.handle_message(Message::Import(path_clone));
+                // Close first: importing an encrypted bundle opens a prompt
                 self.handle_message(Message::CloseOverlay);
+                self.handle_message(Message::Import(path_clone));
             }
             KeyCode::Left => {
                 *cursor = cursor.saturating_sub(1);
             }
-            KeyCode::Right => {
-                if *cursor < path.len() {
-                    *cursor += 1;
-                }
+            KeyCode::Right if *cursor < path.len() => {
+                *cursor += 1;
             }
             KeyCode::Home => {
                 *cursor = 0;
@@ -450,16 +856,12 @@ impl App {
             KeyCode::End => {
                 *cursor = path.len();
             }
-            KeyCode::Backspace => {
-                if *cursor > 0 {
-                    path.remove(*cursor - 1);
-                    *cursor -= 1;
-                }
+            KeyCode::Backspace if *cursor > 0 => {
+                path.remove(*cursor - 1);
+                *cursor -= 1;
             }
-            KeyCode::Delete => {
-                if *cursor < path.len() {
-                    path.remove(*cursor);
-                }
+            KeyCode::Delete if *cursor < path.len() => {
+                path.remove(*cursor);
             }
             KeyCode::Char(c) => {
                 path.insert(*cursor, c);
@@ -469,6 +871,53 @@ impl App {
         }
     }
 
+    /// Keys while the sidebar search bar is open. The query itself lives in
+    /// `profile_filter` so the list is filtered live as the user types.
+    fn handle_input_search(&mut self, key: KeyEvent, cursor: &mut usize) {
+        let mut query: Vec<char> = self.profile_filter.chars().collect();
+        match key.code {
+            KeyCode::Esc => {
+                self.handle_message(Message::FilterProfiles(String::new()));
+                self.handle_message(Message::CloseOverlay);
+                return;
+            }
+            KeyCode::Enter => {
+                // Keep the filter applied and return to list navigation
+                self.handle_message(Message::CloseOverlay);
+                return;
+            }
+            KeyCode::Down => {
+                self.handle_message(Message::ProfileMove(SelectionMove::Next));
+                return;
+            }
+            KeyCode::Up => {
+                self.handle_message(Message::ProfileMove(SelectionMove::Prev));
+                return;
+            }
+            KeyCode::Left => *cursor = cursor.saturating_sub(1),
+            KeyCode::Right if *cursor < query.len() => *cursor += 1,
+            KeyCode::Home => *cursor = 0,
+            KeyCode::End => *cursor = query.len(),
+            KeyCode::Backspace if *cursor > 0 => {
+                query.remove(*cursor - 1);
+                *cursor -= 1;
+            }
+            KeyCode::Delete if *cursor < query.len() => {
+                query.remove(*cursor);
+            }
+            KeyCode::Char(c) => {
+                query.insert(*cursor, c);
+                *cursor += 1;
+            }
+            _ => return,
+        }
+
+        let query: String = query.into_iter().collect();
+        if query != self.profile_filter {
+            self.handle_message(Message::FilterProfiles(query));
+        }
+    }
+
     fn handle_normal_keys(&mut self, key: KeyEvent) {
         match key.code {
             // Global Toggles
@@ -490,8 +939,11 @@ impl App {
             KeyCode::Esc => {
                 if self.zoomed_panel.is_some() {
                     self.zoomed_panel = None;
+                } else if !self.profile_filter.is_empty() {
+                    self.handle_message(Message::FilterProfiles(String::new()));
                 }
             }
+            KeyCode::Char('/') => self.handle_message(Message::OpenSearch),
 
             // Profile List Navigation (always available in Normal mode)
             KeyCode::Home | KeyCode::Char('g') => {
@@ -507,7 +959,7 @@ impl App {
             }
             KeyCode::PageDown => {
                 let current = self.profile_list_state.selected().unwrap_or(0);
-                let last = self.profiles.len().saturating_sub(1);
+                let last = self.sidebar_rows.len().saturating_sub(1);
                 let next = (current + 10).min(last);
                 self.profile_list_state.select(Some(next));
             }
@@ -549,11 +1001,19 @@ impl App {
                 KeyCode::Delete | KeyCode::Backspace => {
                     self.handle_message(Message::OpenDelete(None));
                 }
-                KeyCode::Char('c') | KeyCode::Enter => {
-                    self.handle_message(Message::ToggleConnect(None));
+                KeyCode::Char('c' | ' ') | KeyCode::Enter => {
+                    // On a group header these keys collapse/expand the group
+                    if let Some(group) = self.selected_group() {
+                        let group = group.to_string();
+                        self.handle_message(Message::ToggleGroup(group));
+                    } else if key.code != KeyCode::Char(' ') {
+                        self.handle_message(Message::ToggleConnect(None));
+                    }
                 }
+                KeyCode::Char('t') => self.handle_message(Message::CycleGrouping),
+                KeyCode::Char('s') => self.handle_message(Message::CycleSort),
                 KeyCode::Char('v') => {
-                    if self.profile_list_state.selected().is_some() {
+                    if self.selected_profile_index().is_some() {
                         self.handle_message(Message::OpenConfig);
                     } else {
                         self.show_toast(
@@ -595,8 +1055,19 @@ impl App {
                     _ => {}
                 }
             }
+            FocusedPanel::Routes => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.scroll_up(),
+                KeyCode::Down | KeyCode::Char('j') => self.scroll_down(),
+                KeyCode::Home | KeyCode::Char('g') => self.routes_scroll = 0,
+                _ => {}
+            },
+            FocusedPanel::Security => match key.code {
+                KeyCode::Char('p') => self.handle_message(Message::OpenKillSwitchPreview),
+                KeyCode::Char('c') => self.handle_message(Message::TogglePortalWindow),
+                _ => {}
+            },
             // Read-only panels
-            FocusedPanel::ConnectionDetails | FocusedPanel::Chart | FocusedPanel::Security => {}
+            FocusedPanel::ConnectionDetails | FocusedPanel::Chart => {}
         }
     }
 
@@ -604,7 +1075,8 @@ impl App {
     fn next_panel(&mut self) {
         self.focused_panel = match self.focused_panel {
             FocusedPanel::Sidebar => FocusedPanel::Chart,
-            FocusedPanel::Chart => FocusedPanel::ConnectionDetails,
+            FocusedPanel::Chart => FocusedPanel::Routes,
+            FocusedPanel::Routes => FocusedPanel::ConnectionDetails,
             FocusedPanel::ConnectionDetails => FocusedPanel::Security,
             FocusedPanel::Security => FocusedPanel::Logs,
             FocusedPanel::Logs => FocusedPanel::Sidebar,
@@ -617,7 +1089,8 @@ impl App {
             FocusedPanel::Sidebar => FocusedPanel::Logs,
             FocusedPanel::Logs => FocusedPanel::Security,
             FocusedPanel::Security => FocusedPanel::ConnectionDetails,
-            FocusedPanel::ConnectionDetails => FocusedPanel::Chart,
+            FocusedPanel::ConnectionDetails => FocusedPanel::Routes,
+            FocusedPanel::Routes => FocusedPanel::Chart,
             FocusedPanel::Chart => FocusedPanel::Sidebar,
         };
     }
@@ -704,18 +1177,18 @@ impl App {
 
             // Profile actions
             Message::ToggleConnect(idx) => {
-                let index = idx.or_else(|| self.profile_list_state.selected());
+                let index = idx.or_else(|| self.selected_profile_index());
                 if let Some(i) = index {
                     self.toggle_connection(i);
                 }
             }
             Message::OpenConfig => {
-                if self.profile_list_state.selected().is_some() {
+                if self.selected_profile_index().is_some() {
                     self.show_config = true;
                 }
             }
             Message::OpenDelete(idx) => {
-                let index = idx.or_else(|| self.profile_list_state.selected());
+                let index = idx.or_else(|| self.selected_profile_index());
                 if let Some(i) = index {
                     self.request_delete(i);
                 }
@@ -725,12 +1198,63 @@ impl App {
                     self.confirm_delete(index);
                 }
             }
+            Message::SubmitCredentials => self.submit_credentials(),
+            Message::SplitTunnelApplied(policy) => {
+                self.log(&format!(
+                    "SPLIT: {} included, {} excluded network(s)",
+                    policy.include.len(),
+                    policy.exclude.len()
+                ));
+                self.split_policy = Some(policy);
+            }
+            Message::EndpointsResolved(endpoints, ack) => {
+                self.update_endpoints(endpoints);
+                if let Some(ack) = ack {
+                    let _ = ack.send(());
+                }
+            }
+            Message::UnlockVault(mut passphrase) => {
+                let result = vault::unlock(&passphrase);
+                passphrase.zeroize();
+                match result {
+                    Ok(()) => {
+                        self.input_mode = InputMode::Normal;
+                        self.log("VAULT: Profile vault unlocked");
+                        self.show_toast(
+                            constants::MSG_VAULT_UNLOCKED.to_string(),
+                            ToastType::Success,
+                        );
+                    }
+                    Err(e) => {
+                        self.input_mode = InputMode::Unlock {
+                            passphrase: String::new(),
+                            error: Some(e),
+                        };
+                    }
+                }
+            }
+            Message::ForgetCredentials => {
+                if let Some(name) = self.selected_profile().map(|p| p.name.clone()) {
+                    if credentials::forget(&name) {
+                        self.log(&format!("AUTH: Removed saved credentials for '{name}'"));
+                        self.show_toast(
+                            format!("{}{name}", constants::MSG_CREDENTIALS_FORGOTTEN),
+                            ToastType::Success,
+                        );
+                    } else {
+                        self.show_toast(
+                            format!("{}{name}", constants::MSG_NO_SAVED_CREDENTIALS),
+                            ToastType::Info,
+                        );
+                    }
+                }
+            }
             Message::ProfileMove(mv) => match mv {
                 SelectionMove::Next => self.profile_next(),
                 SelectionMove::Prev => self.profile_previous(),
                 SelectionMove::First => self.profile_list_state.select(Some(0)),
                 SelectionMove::Last => {
-                    let last = self.profiles.len().saturating_sub(1);
+                    let last = self.sidebar_rows.len().saturating_sub(1);
                     self.profile_list_state.select(Some(last));
                 }
             },
@@ -738,8 +1262,9 @@ impl App {
             // Connection
             Message::Disconnect => self.disconnect(),
             Message::Reconnect => self.reconnect(),
-            Message::QuickConnect(idx) => {
-                if idx < self.profiles.len() {
+            Message::QuickConnect(slot) => {
+                // Slots follow the filtered view so hidden profiles are never targeted
+                if let Some(idx) = self.visible_profiles.get(slot).map(|m| m.index) {
                     self.toggle_connection(idx);
                 }
             }
@@ -753,6 +1278,9 @@ impl App {
                 }
             }
             Message::CloseOverlay => {
+                if matches!(self.input_mode, InputMode::ImportPreview { .. }) {
+                    self.import_scratch = None;
+                }
                 self.show_config = false;
                 self.show_action_menu = false;
                 self.show_bulk_menu = false;
@@ -777,6 +1305,122 @@ impl App {
                     cursor: 0,
                 };
             }
+            Message::OpenNewProfile => {
+                self.input_mode = InputMode::NewProfile {
+                    wizard: NewProfileWizard::default(),
+                };
+            }
+            Message::CreateProfile => self.create_profile(),
+            Message::OpenQr => {
+                if let Some(idx) = self.selected_profile_index() {
+                    if self.profiles[idx].protocol == Protocol::WireGuard {
+                        self.show_config = false;
+                        self.input_mode = InputMode::QrWarning {
+                            profile_index: idx,
+                            exclude_hooks: false,
+                        };
+                    } else {
+                        self.show_toast(
+                            constants::MSG_QR_WIREGUARD_ONLY.to_string(),
+                            ToastType::Warning,
+                        );
+                    }
+                }
+            }
+            Message::ShowQr => self.show_qr(),
+            Message::RefreshSubscriptions => self.refresh_subscriptions(true),
+            Message::SubscriptionsRefreshed {
+                manual,
+                changed,
+                failed,
+            } => {
+                self.subscriptions_busy = false;
+                if changed {
+                    self.profiles = crate::vpn::load_profiles();
+                    self.load_metadata();
+                    self.sort_profiles();
+                }
+                if failed > 0 {
+                    self.show_toast(constants::MSG_SUBS_FAILED.to_string(), ToastType::Error);
+                } else if changed {
+                    self.show_toast(constants::MSG_SUBS_UPDATED.to_string(), ToastType::Success);
+                } else if manual {
+                    self.show_toast(constants::MSG_SUBS_UP_TO_DATE.to_string(), ToastType::Info);
+                }
+            }
+            Message::OpenExport => {
+                if search::filter_profiles(&self.profiles, &self.profile_filter).is_empty() {
+                    self.show_toast(
+                        constants::MSG_NOTHING_TO_EXPORT.to_string(),
+                        ToastType::Warning,
+                    );
+                } else {
+                    self.input_mode = InputMode::Export {
+                        path: format!(
+                            "{}.{}",
+                            constants::DEFAULT_EXPORT_FILE,
+                            bundle::BUNDLE_EXTENSION
+                        ),
+                        passphrase: String::new(),
+                        on_passphrase: false,
+                    };
+                }
+            }
+            Message::ExportProfiles {
+                path,
+                mut passphrase,
+            } => {
+                self.export_profiles(&path, passphrase.as_deref());
+                passphrase.zeroize();
+            }
+            Message::SaveImport => self.save_import(),
+            Message::ConfirmImportPreview => self.confirm_import_preview(),
+            Message::ResolveDuplicate(choice) => self.resolve_duplicate(choice),
+            Message::ImportBundle {
+                path,
+                mut passphrase,
+            } => {
+                self.import_bundle(&path, passphrase.as_deref());
+                passphrase.zeroize();
+                self.sort_profiles();
+            }
+            Message::OpenSearch => {
+                self.focused_panel = FocusedPanel::Sidebar;
+                self.input_mode = InputMode::Search {
+                    cursor: self.profile_filter.chars().count(),
+                };
+            }
+            Message::FilterProfiles(query) => {
+                self.profile_filter = query;
+                self.apply_profile_filter();
+                // Jump to the best match while the query is being edited
+                self.select_first_profile_row();
+            }
+            Message::CycleGrouping => {
+                self.group_by = self.group_by.next();
+                self.apply_profile_filter();
+                self.save_sidebar_prefs();
+                self.show_toast(
+                    format!("{}{}", constants::MSG_GROUP_BY, self.group_by.label()),
+                    ToastType::Info,
+                );
+            }
+            Message::CycleSort => {
+                self.sort_by = self.sort_by.next();
+                self.sort_profiles();
+                self.save_sidebar_prefs();
+                self.show_toast(
+                    format!("{}{}", constants::MSG_SORT_BY, self.sort_by.label()),
+                    ToastType::Info,
+                );
+            }
+            Message::ToggleGroup(key) => {
+                if !self.collapsed_groups.remove(&key) {
+                    self.collapsed_groups.insert(key);
+                }
+                self.apply_profile_filter();
+                self.save_sidebar_prefs();
+            }
 
             // Scrolling
             Message::Scroll(mv) => match mv {
@@ -795,14 +1439,53 @@ impl App {
             },
 
             // Kill Switch
+            Message::OpenKillSwitchPreview => self.open_killswitch_preview(),
+            Message::TogglePortalWindow => {
+                if self.portal_window.is_some() {
+                    self.close_portal_window("closed by user");
+                } else {
+                    self.offer_portal_window(constants::MSG_PORTAL_MANUAL);
+                }
+            }
+            Message::OpenPortalWindow => self.open_portal_window(),
+            Message::PortalProbeResolved(probe) => self.portal_probe = probe,
+            Message::PortalWindowReady(window) => self.portal_window_ready(window),
+            Message::PortalProbed(probe) => self.portal_probed(probe),
+            Message::RestoreStaleKillSwitch => {
+                self.log("SEC: Restoring the kill switch left by the previous session");
+                self.killswitch_state = crate::state::KillSwitchState::Blocking;
+                self.killswitch_mode =
+                    crate::core::killswitch::takeover_mode(self.killswitch_mode, true);
+                // Reloads the rules for this session and takes over the state
+                self.sync_killswitch();
+                self.finish_stale_killswitch();
+            }
+            Message::ReleaseStaleKillSwitch => {
+                self.log("SEC: Releasing the kill switch left by the previous session");
+                self.killswitch_state = crate::state::KillSwitchState::Blocking;
+                self.killswitch_mode =
+                    crate::core::killswitch::takeover_mode(self.killswitch_mode, false);
+                self.sync_killswitch();
+                self.show_toast("Kill Switch OFF".to_string(), ToastType::Info);
+                self.finish_stale_killswitch();
+            }
             Message::ToggleKillSwitch => {
                 use crate::state::KillSwitchMode;
 
                 // Cycle to next mode
                 self.killswitch_mode = self.killswitch_mode.next();
-
-                // Sync state and firewall
-                self.sync_killswitch();
+                if self.killswitch_mode == KillSwitchMode::Off {
+                    self.killswitch_pending = false;
+                    self.sync_killswitch();
+                } else {
+                    // Blocking waits until every profile's servers are
+                    // resolved, so any of them can connect without DNS
+                    self.killswitch_pending = true;
+                    self.resolve_endpoints(self.all_server_hosts());
+                    if self.portal_probe.is_empty() {
+                        self.resolve_portal_probe();
+                    }
+                }
 
                 // Log and toast based on new mode
                 match self.killswitch_mode {
@@ -831,12 +1514,16 @@ impl App {
                     self.killswitch_mode,
                     self.killswitch_state,
                     None,
-                    None,
+                    &self.killswitch_endpoints,
+                    self.split_policy.as_ref(),
                 );
             }
 
             // System
-            Message::Quit => self.should_quit = true,
+            Message::Quit => {
+                self.save_latency();
+                self.should_quit = true;
+            }
             Message::Log(msg) => self.log(&msg),
             Message::Toast(msg, t_type) => self.show_toast(msg, t_type),
             Message::CopyIp => self.copy_ip_to_clipboard(),
@@ -854,10 +1541,13 @@ impl App {
 
                         // Store as real_ip when disconnected (for security comparison)
                         if matches!(self.connection_state, ConnectionState::Disconnected) {
-                            if self.real_ip.is_none() {
+                            if ip.parse::<std::net::IpAddr>().is_ok()
+                                && self.real_ip.as_deref() != Some(ip.as_str())
+                            {
                                 self.log(&format!("NET: Real IP detected: {ip}"));
+                                crate::core::audit::save_real_ip(&ip);
+                                self.real_ip = Some(ip.clone());
                             }
-                            self.real_ip = Some(ip.clone());
                         } else if self.public_ip != ip && self.public_ip != constants::MSG_FETCHING
                         {
                             self.log(&format!("NET: ✓ Public IP changed from {old_ip} to {ip}"));
@@ -880,7 +1570,10 @@ impl App {
                         }
                         self.public_ip = ip;
                     }
-                    TelemetryUpdate::Latency(ms) => self.latency_ms = ms,
+                    TelemetryUpdate::Latency(ms) => {
+                        self.latency_ms = ms;
+                        self.record_profile_latency(ms);
+                    }
                     TelemetryUpdate::PacketLoss(loss) => {
                         self.packet_loss = loss;
                         self.log(&format!("NET: Packet loss: {loss:.1}%"));
@@ -903,25 +1596,64 @@ impl App {
                     }
                     TelemetryUpdate::Dns(dns) => {
                         if self.dns_server != dns && self.dns_server != constants::MSG_NO_DATA {
-                            let leak_warn = if utils::is_private_ip(&dns) {
-                                " ⚠ POSSIBLE LEAK"
-                            } else {
-                                ""
-                            };
-                            self.log(&format!("SEC: DNS server: {dns}{leak_warn}"));
+                            self.log(&format!("SEC: DNS server: {dns}"));
                         }
                         self.dns_server = dns;
+                        self.check_leaks();
+                    }
+                    TelemetryUpdate::DnsCheck(report) => {
+                        if !matches!(self.connection_state, ConnectionState::Connected { .. }) {
+                            return;
+                        }
+                        let was_leaking = self
+                            .dns_report
+                            .as_ref()
+                            .is_some_and(crate::core::leaks::DnsReport::is_leaking);
+                        if report.is_leaking() && !was_leaking {
+                            for resolver in report
+                                .resolvers
+                                .iter()
+                                .filter(|r| r.verdict == crate::core::leaks::Verdict::Leak)
+                            {
+                                self.log(&format!(
+                                    "SEC: ⚠ DNS leak: {} ({})",
+                                    resolver.server, resolver.reason
+                                ));
+                            }
+                        } else if was_leaking && !report.is_leaking() {
+                            self.log("SEC: DNS secure (resolvers routed through the tunnel)");
+                        }
+                        self.dns_report = Some(report);
                     }
                     TelemetryUpdate::Ipv6Leak(leak) => {
                         if self.ipv6_leak != leak {
                             if leak {
-                                self.log("SEC: ⚠ IPv6 leak detected!");
+                                self.log("SEC: ⚠ IPv6 leak confirmed by remote check!");
                             } else {
-                                self.log("SEC: IPv6 secure (blocked)");
+                                self.log("SEC: IPv6 remote check passed");
                             }
                         }
                         self.ipv6_leak = leak;
                     }
+                    TelemetryUpdate::Routes(table) => {
+                        self.routes_busy = false;
+                        self.route_table = table;
+                    }
+                    TelemetryUpdate::Ipv6Check(report) => {
+                        if !matches!(self.connection_state, ConnectionState::Connected { .. }) {
+                            return;
+                        }
+                        let was_leaking = self
+                            .ipv6_report
+                            .as_ref()
+                            .is_some_and(crate::core::leaks::Ipv6Report::is_leaking);
+                        if report.is_leaking() && !was_leaking {
+                            self.log(&format!("SEC: ⚠ IPv6 leak: {}", report.reason));
+                        } else if was_leaking && !report.is_leaking() {
+                            self.log(&format!("SEC: IPv6 secure ({})", report.reason));
+                        }
+                        self.ipv6_report = Some(report);
+                    }
                     TelemetryUpdate::Log(level, msg) => {
                         // Log through central logging system
                         logger::log(level, "TELEMETRY", msg);
@@ -938,6 +1670,9 @@ impl App {
                             self.log(&format!("STATUS: Disconnected from '{profile_name}'"));
                             self.connection_state = ConnectionState::Disconnected;
                             self.session_start = None;
+                            self.save_latency();
+                            vault::release_all();
+                            credentials::release_all();
                         }
                         return;
                     }
@@ -997,6 +1732,7 @@ impl App {
                                 self.profiles.iter_mut().find(|p| p.name == profile_name)
                             {
                                 profile.last_used = Some(std::time::SystemTime::now());
+                                profile.connect_count = profile.connect_count.saturating_add(1);
                             }
                             self.save_metadata();
                             self.session_start = Some(start_time);
@@ -1091,6 +1827,7 @@ impl App {
                             self.log("INFO: Synced uptime with system process.");
                         }
                         self.log("INFO: Waiting for telemetry...");
+                        self.check_leaks();
                     }
                     self.session_start = Some(start_time);
                 } else if !matches!(self.connection_state, ConnectionState::Disconnected) {
@@ -1158,11 +1895,19 @@ impl App {
                     }
                     self.connection_state = ConnectionState::Disconnected;
                     self.session_start = None;
+                    self.save_latency();
+                    // The tunnel is gone, so no daemon needs its decrypted
+                    // copy or credentials
+                    vault::release_all();
+                    credentials::release_all();
+                    self.dns_report = None;
+                    self.ipv6_report = None;
                 }
             }
             Message::ConnectionTimeout(profile_name) => {
                 self.connection_state = ConnectionState::Disconnected;
                 self.log(&format!("ERR: Connection timed out for '{profile_name}'"));
+                self.check_captive_portal();
             }
             Message::Tick => {
                 // 1. Connection Timeout Safeguard
@@ -1181,6 +1926,8 @@ impl App {
                 // 3. Trigger external syncs
                 let active = scanner::get_active_profiles(&self.profiles);
                 self.handle_message(Message::SyncSystemState(active));
+                self.refresh_routes();
+                self.refresh_audit();
 
                 // 4. Process telemetry via dispatch
                 self.process_telemetry();
@@ -1188,7 +1935,47 @@ impl App {
                 // 5. Update network stats
                 self.update_network_stats();
 
-                // 6. Update network stats history
+                // 6. Scheduled subscription refresh
+                let check_due = self.subscriptions_checked.map_or(true, |checked| {
+                    checked.elapsed().as_secs() >= constants::SUBSCRIPTION_CHECK_INTERVAL_SECS
+                });
+                if check_due {
+                    self.subscriptions_checked = Some(Instant::now());
+                    self.refresh_subscriptions(false);
+                }
+
+                // 7. Follow DNS changes of the kill switch's server addresses
+                // and the portal probe URL
+                let refresh_due = self.killswitch_mode != crate::state::KillSwitchMode::Off
+                    && self.endpoints_checked.map_or(true, |checked| {
+                        checked.elapsed().as_secs() >= constants::ENDPOINT_REFRESH_INTERVAL_SECS
+                    });
+                if refresh_due {
+                    self.endpoints_checked = Some(Instant::now());
+                    self.resolve_endpoints(self.all_server_hosts());
+                    self.resolve_portal_probe();
+                }
+
+                // 8. Captive portal window: close it when time is up, and
+                // probe meanwhile to notice the login
+                if let Some((_, until)) = &self.portal_window {
+                    if Instant::now() >= *until {
+                        self.close_portal_window("time limit reached");
+                    } else if self.portal_probed.map_or(true, |probed| {
+                        probed.elapsed().as_secs() >= constants::PORTAL_RECHECK_SECS
+                    }) {
+                        self.probe_portal();
+                    }
+                }
+
+                // 9. Persist the measured latency now and then
+                if self.latency_unsaved.is_some_and(|since| {
+                    since.elapsed().as_secs() >= constants::LATENCY_SAVE_INTERVAL_SECS
+                }) {
+                    self.save_latency();
+                }
+
+                // 10. Update network stats history
                 for i in 0..59 {
                     self.down_history[i].1 = self.down_history[i + 1].1;
                     self.up_history[i].1 = self.up_history[i + 1].1;
@@ -1207,6 +1994,10 @@ impl App {
 
     /// Check if a specific panel should be drawn as focused (visually)
     pub fn should_draw_focus(&self, panel: &FocusedPanel) -> bool {
+        // Typing in the sidebar search bar keeps the sidebar highlighted
+        if let InputMode::Search { .. } = self.input_mode {
+            return *panel == FocusedPanel::Sidebar;
+        }
         // If an overlay is active, no background panel has focus
         if self.show_config
             || self.show_action_menu
@@ -1226,24 +2017,142 @@ impl App {
     /// Get the maximum scroll position for the config viewer
     /// This accounts for viewport height so scrolling stops when last line is visible
     fn get_config_max_scroll(&self) -> u16 {
-        if let Some(idx) = self.profile_list_state.selected() {
-            if let Some(profile) = self.profiles.get(idx) {
-                if let Ok(content) = std::fs::read_to_string(&profile.config_path) {
-                    #[allow(clippy::cast_possible_truncation)]
-                    let total_lines = content.lines().count() as u16;
-                    // Viewport height: 85% of terminal height - 4 (borders + path line + title bottom)
-                    let viewport_height = (self.terminal_size.1 * 85 / 100).saturating_sub(4);
-                    return total_lines.saturating_sub(viewport_height);
-                }
+        if let Some(profile) = self.selected_profile() {
+            if let Ok(content) = vault::read_config(&profile.config_path) {
+                #[allow(clippy::cast_possible_truncation)]
+                let total_lines = content.lines().count() as u16;
+                // Viewport height: 85% of terminal height - 4 (borders + path line + title bottom)
+                let viewport_height = (self.terminal_size.1 * 85 / 100).saturating_sub(4);
+                return total_lines.saturating_sub(viewport_height);
             }
         }
         0
     }
 
+    /// Index into `profiles` of the profile under the sidebar cursor.
+    ///
+    /// Returns `None` when the cursor is on a group header.
+    pub fn selected_profile_index(&self) -> Option<usize> {
+        match self.sidebar_rows.get(self.profile_list_state.selected()?)? {
+            SidebarRow::Profile(m) => Some(m.index),
+            SidebarRow::Group { .. } => None,
+        }
+    }
+
+    /// Key of the group the given sidebar row belongs to (or heads).
+    fn row_group(&self, row: usize) -> Option<&str> {
+        self.sidebar_rows
+            .get(..=row)?
+            .iter()
+            .rev()
+            .find_map(|r| match r {
+                SidebarRow::Group { key, .. } => Some(key.as_str()),
+                SidebarRow::Profile(_) => None,
+            })
+    }
+
+    /// Key of the group header under the sidebar cursor, if any.
+    pub fn selected_group(&self) -> Option<&str> {
+        match self.sidebar_rows.get(self.profile_list_state.selected()?)? {
+            SidebarRow::Group { key, .. } => Some(key),
+            SidebarRow::Profile(_) => None,
+        }
+    }
+
+    /// Move the cursor to the first profile row, skipping group headers.
+    fn select_first_profile_row(&mut self) {
+        let row = self
+            .sidebar_rows
+            .iter()
+            .position(|r| matches!(r, SidebarRow::Profile(_)))
+            .or(if self.sidebar_rows.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+        self.profile_list_state.select(row);
+    }
+
+    /// The profile under the sidebar cursor, if any.
+    pub fn selected_profile(&self) -> Option<&VpnProfile> {
+        self.selected_profile_index()
+            .and_then(|idx| self.profiles.get(idx))
+    }
+
+    /// Recompute the sidebar rows from the filter query, grouping and
+    /// collapsed groups.
+    ///
+    /// Must be called whenever `profiles`, `profile_filter`, `group_by` or
+    /// `collapsed_groups` change. Keeps the cursor on the same profile if it is
+    /// still visible, falls back to its group header if the group was
+    /// collapsed, otherwise moves it to the first row.
+    fn apply_profile_filter(&mut self) {
+        let previous_path = self.selected_profile().map(|p| p.config_path.clone());
+        let previous_group = self
+            .profile_list_state
+            .selected()
+            .and_then(|row| self.row_group(row))
+            .map(str::to_string);
+
+        let matches = search::filter_profiles(&self.profiles, &self.profile_filter);
+        self.sidebar_rows = grouping::build_rows(
+            &self.profiles,
+            &matches,
+            self.group_by,
+            self.sort_by,
+            &self.collapsed_groups,
+            !self.profile_filter.is_empty(),
+        );
+
+        // Quick slots: visible profiles in display order, each listed once
+        let mut seen = HashSet::new();
+        self.visible_profiles = self
+            .sidebar_rows
+            .iter()
+            .filter_map(|r| match r {
+                SidebarRow::Profile(m) if seen.insert(m.index) => Some(m.clone()),
+                _ => None,
+            })
+            .collect();
+
+        let row = previous_path
+            .and_then(|path| {
+                self.sidebar_rows.iter().position(|r| {
+                    matches!(r, SidebarRow::Profile(m) if self.profiles[m.index].config_path == path)
+                })
+            })
+            .or_else(|| {
+                let group = previous_group?;
+                self.sidebar_rows
+                    .iter()
+                    .position(|r| matches!(r, SidebarRow::Group { key, .. } if *key == group))
+            })
+            .or(if self.sidebar_rows.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+        self.profile_list_state.select(row);
+    }
+
+    fn save_sidebar_prefs(&mut self) {
+        let mut collapsed_groups: Vec<String> = self.collapsed_groups.iter().cloned().collect();
+        collapsed_groups.sort();
+
+        let prefs = utils::SidebarPrefs {
+            group_by: self.group_by,
+            sort_by: self.sort_by,
+            collapsed_groups,
+        };
+        if let Err(e) = utils::save_sidebar_prefs(&prefs) {
+            self.log(&format!("CONFIG: {e}"));
+        }
+    }
+
     fn profile_next(&mut self) {
         let i = match self.profile_list_state.selected() {
             Some(i) => {
-                if i >= self.profiles.len().saturating_sub(1) {
+                if i >= self.sidebar_rows.len().saturating_sub(1) {
                     0
                 } else {
                     i + 1
@@ -1258,7 +2167,7 @@ impl App {
         let i = match self.profile_list_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.profiles.len().saturating_sub(1)
+                    self.sidebar_rows.len().saturating_sub(1)
                 } else {
                     i - 1
                 }
@@ -1303,23 +2212,22 @@ impl App {
 
         // Get config path before removing
         let config_path = self.profiles[idx].config_path.clone();
+        credentials::forget(&self.profiles[idx].name);
 
         // Remove from profiles
         self.profiles.remove(idx);
 
         // Try to delete from disk
-        if config_path.exists() {
-            let _ = std::fs::remove_file(&config_path);
-        }
+        crate::vpn::remove_profile(&config_path);
 
-        // Adjust selection
-        if self.profiles.is_empty() {
+        // Adjust selection (row stays put so the next profile slides under the cursor)
+        let selected = self.profile_list_state.selected();
+        self.apply_profile_filter();
+        if self.sidebar_rows.is_empty() {
             self.profile_list_state.select(None);
-        } else if let Some(selected) = self.profile_list_state.selected() {
-            if selected >= self.profiles.len() {
-                self.profile_list_state
-                    .select(Some(self.profiles.len() - 1));
-            }
+        } else if let Some(row) = selected {
+            self.profile_list_state
+                .select(Some(row.min(self.sidebar_rows.len() - 1)));
         }
 
         self.show_toast("Profile deleted".to_string(), ToastType::Success);
@@ -1332,6 +2240,14 @@ impl App {
                 let key = profile.config_path.to_string_lossy().to_string();
                 if let Some(meta) = metadata.get(&key) {
                     profile.last_used = meta.last_used;
+                    profile.tags.clone_from(&meta.tags);
+                    profile.last_latency_ms = meta.latency_ms;
+                    profile.connect_count = meta.connect_count;
+                    if let Some(location) = &meta.location {
+                        profile.location.clone_from(location);
+                    }
+                    profile.custom_location.clone_from(&meta.location);
+                    profile.split_tunnel.clone_from(&meta.split_tunnel);
                 }
             }
         }
@@ -1347,6 +2263,11 @@ impl App {
                 key,
                 utils::ProfileMetadata {
                     last_used: profile.last_used,
+                    tags: profile.tags.clone(),
+                    latency_ms: profile.last_latency_ms,
+                    connect_count: profile.connect_count,
+                    location: profile.custom_location.clone(),
+                    split_tunnel: profile.split_tunnel.clone(),
                 },
             );
         }
@@ -1354,11 +2275,43 @@ impl App {
         let _ = utils::save_profile_metadata(&metadata);
     }
 
-    /// Sort profiles alphabetically by name, updating quick slots
+    /// Remember the latency measured while connected, for latency sorting.
+    ///
+    /// The value is kept in memory and written by [`Self::save_latency`], so
+    /// a profile sorts by the last latency seen during its latest session.
+    fn record_profile_latency(&mut self, ms: u64) {
+        let ConnectionState::Connected { profile: name, .. } = &self.connection_state else {
+            return;
+        };
+        if ms == 0 {
+            return;
+        }
+        let name = name.clone();
+        let changed = self
+            .profiles
+            .iter_mut()
+            .find(|p| p.name == name)
+            .filter(|p| p.last_latency_ms != Some(ms))
+            .map(|p| p.last_latency_ms = Some(ms))
+            .is_some();
+        if changed && self.latency_unsaved.is_none() {
+            self.latency_unsaved = Some(Instant::now());
+        }
+    }
+
+    /// Write latencies recorded since the last save to `metadata.json`.
+    fn save_latency(&mut self) {
+        if self.latency_unsaved.take().is_some() {
+            self.save_metadata();
+        }
+    }
+
+    /// Sort profiles by the chosen sidebar order, updating quick slots
     fn sort_profiles(&mut self) {
-        self.profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        grouping::sort_profiles(&mut self.profiles, self.sort_by);
 
-        // Update quick slots logic removed - key `1` now maps to index `0` dynamically
+        // Quick slots follow the visible (filtered) order, so refresh it
+        self.apply_profile_filter();
     }
 
     /// Smart connection toggle: Connect, Disconnect, or Switch
@@ -1433,12 +2386,11 @@ impl App {
     /// Connect to a profile
     fn connect_profile(&mut self, idx: usize) {
         // Clone needed data to release borrow on self
-        let (name, protocol, config_path, cmd_tx) = if let Some(profile) = self.profiles.get(idx) {
+        let (name, protocol, config_path) = if let Some(profile) = self.profiles.get(idx) {
             (
                 profile.name.clone(),
                 profile.protocol,
                 profile.config_path.clone(),
-                self.cmd_tx.clone(),
             )
         } else {
             return;
@@ -1459,6 +2411,197 @@ impl App {
             return;
         }
 
+        if !vault::is_unlocked() && vault::is_sealed_file(&config_path) {
+            self.input_mode = InputMode::Unlock {
+                passphrase: String::new(),
+                error: None,
+            };
+            return;
+        }
+
+        if protocol == Protocol::OpenVPN {
+            let needs = vault::read_config(&config_path)
+                .map(|content| CredentialNeeds::for_config(&content, &config_path))
+                .unwrap_or_default();
+            if needs.any() {
+                self.request_credentials(idx, &name, needs);
+                return;
+            }
+        }
+
+        self.start_connection(idx, None);
+    }
+
+    /// Connect with saved credentials when they cover everything the profile
+    /// needs, otherwise open the credential prompt.
+    fn request_credentials(&mut self, idx: usize, name: &str, needs: CredentialNeeds) {
+        let stored = credentials::load(name);
+        if let Some(Stored::Plain(saved)) = &stored {
+            if needs.challenge.is_none() && needs.satisfied_by(saved) {
+                self.log(&format!("AUTH: Using saved credentials for '{name}'"));
+                let auth = OpenVpnAuth {
+                    needs,
+                    credentials: saved.clone(),
+                    challenge_response: None,
+                };
+                self.start_connection(idx, Some(auth));
+                return;
+            }
+        }
+
+        let mut fields = Vec::new();
+        match stored {
+            Some(Stored::Locked(_)) => fields.push(PromptField::new(CredentialField::Unlock)),
+            Some(Stored::Plain(saved)) if needs.satisfied_by(&saved) => {}
+            _ => {
+                if needs.user_pass {
+                    fields.push(PromptField::new(CredentialField::Username));
+                    fields.push(PromptField::new(CredentialField::Password));
+                }
+                if needs.key_passphrase {
+                    fields.push(PromptField::new(CredentialField::KeyPassphrase));
+                }
+            }
+        }
+        // Saving is offered only when new credentials are typed in
+        let offer_store = fields.iter().any(|f| f.kind != CredentialField::Unlock);
+        if let Some(challenge) = &needs.challenge {
+            let mut field = PromptField::new(CredentialField::Challenge);
+            field.masked = !challenge.echo;
+            fields.push(field);
+        }
+
+        self.input_mode = InputMode::Credentials {
+            prompt: CredentialPrompt {
+                profile_index: idx,
+                profile: name.to_string(),
+                challenge: needs.challenge.map(|c| c.text),
+                fields,
+                focused: 0,
+                store: offer_store.then_some(CredentialStore::None),
+                error: None,
+            },
+        };
+    }
+
+    /// Resolve the prompt into credentials, save them if asked, and connect.
+    /// Problems are shown inside the prompt so the user can correct them.
+    fn submit_credentials(&mut self) {
+        let InputMode::Credentials { prompt } = &self.input_mode else {
+            return;
+        };
+        let prompt = prompt.clone();
+        let Some(profile) = self.profiles.get(prompt.profile_index) else {
+            self.input_mode = InputMode::Normal;
+            return;
+        };
+        let config_path = profile.config_path.clone();
+        let needs = vault::read_config(&config_path)
+            .map(|content| CredentialNeeds::for_config(&content, &config_path))
+            .unwrap_or_default();
+
+        match Self::resolve_credentials(&prompt, needs) {
+            Ok(auth) => {
+                let store = prompt.store.unwrap_or_default();
+                let passphrase = prompt.value(CredentialField::StorePassphrase);
+                if let Err(e) =
+                    credentials::save(&prompt.profile, &auth.credentials, store, passphrase)
+                {
+                    self.set_prompt_error(e);
+                    return;
+                }
+                if store != CredentialStore::None {
+                    self.log(&format!(
+                        "AUTH: Saved credentials for '{}' ({})",
+                        prompt.profile,
+                        store.label()
+                    ));
+                }
+                self.input_mode = InputMode::Normal;
+                self.start_connection(prompt.profile_index, Some(auth));
+            }
+            Err(e) => self.set_prompt_error(e),
+        }
+    }
+
+    fn set_prompt_error(&mut self, error: String) {
+        if let InputMode::Credentials { prompt } = &mut self.input_mode {
+            prompt.error = Some(error);
+        }
+    }
+
+    /// Build the credentials for a connect from the prompt and any saved ones.
+    fn resolve_credentials(
+        prompt: &CredentialPrompt,
+        needs: CredentialNeeds,
+    ) -> Result<OpenVpnAuth, String> {
+        let credentials = if let Some(passphrase) = prompt.value(CredentialField::Unlock) {
+            match credentials::load(&prompt.profile) {
+                Some(Stored::Locked(sealed)) => credentials::unlock(&sealed, passphrase)?,
+                _ => return Err("Saved credentials are no longer available".to_string()),
+            }
+        } else if prompt.value(CredentialField::Username).is_some()
+            || prompt.value(CredentialField::KeyPassphrase).is_some()
+        {
+            credentials::Credentials {
+                username: prompt
+                    .value(CredentialField::Username)
+                    .unwrap_or_default()
+                    .to_string(),
+                password: prompt
+                    .value(CredentialField::Password)
+                    .unwrap_or_default()
+                    .to_string(),
+                key_passphrase: prompt
+                    .value(CredentialField::KeyPassphrase)
+                    .map(str::to_string),
+            }
+        } else {
+            match credentials::load(&prompt.profile) {
+                Some(Stored::Plain(saved)) => saved,
+                _ => return Err("Saved credentials are no longer available".to_string()),
+            }
+        };
+
+        if needs.user_pass && credentials.username.is_empty() {
+            return Err("Username is required".to_string());
+        }
+        let challenge_response = prompt.value(CredentialField::Challenge).map(str::to_string);
+        if challenge_response.as_deref() == Some("") {
+            return Err("Challenge response is required".to_string());
+        }
+        if prompt.store == Some(CredentialStore::Encrypted)
+            && prompt
+                .value(CredentialField::StorePassphrase)
+                .map_or(true, str::is_empty)
+        {
+            return Err("Enter a passphrase to encrypt the saved credentials".to_string());
+        }
+
+        Ok(OpenVpnAuth {
+            needs,
+            credentials,
+            challenge_response,
+        })
+    }
+
+    /// Launch the VPN for a profile in the background. `auth` carries
+    /// credentials for `OpenVPN` profiles that need them.
+    fn start_connection(&mut self, idx: usize, auth: Option<OpenVpnAuth>) {
+        let Some(profile) = self.profiles.get(idx) else {
+            return;
+        };
+        let (name, protocol, config_path, split_tunnel, cmd_tx) = (
+            profile.name.clone(),
+            profile.protocol,
+            profile.config_path.clone(),
+            profile.split_tunnel.clone(),
+            self.cmd_tx.clone(),
+        );
+        let hosts = self.all_server_hosts();
+        let cached = self.killswitch_endpoints.clone();
+        self.split_policy = None;
+
         // Start connecting
         self.connection_state = ConnectionState::Connecting {
             started: Instant::now(),
@@ -1468,17 +2611,49 @@ impl App {
 
         // Execute command in background to prevent TUI freeze
         std::thread::spawn(move || {
+            // Hostnames are resolved before the tunnel takes over DNS. All
+            // servers of every profile, so the kill switch lets reconnection
+            // and failover through. The tunnel starts once the rules allow
+            // them, with the addresses written into its config.
+            let endpoints = crate::core::killswitch::resolve_endpoints(&hosts, &cached);
+            let (ack_tx, ack_rx) = mpsc::channel();
+            let sent = cmd_tx.send(Message::EndpointsResolved(endpoints.clone(), Some(ack_tx)));
+            if sent.is_err() || ack_rx.recv().is_err() {
+                return;
+            }
+
+            let split = if split_tunnel.is_empty() {
+                None
+            } else {
+                match split_tunnel.resolve() {
+                    Ok(policy) => Some(policy),
+                    Err(e) => {
+                        let msg = format!("Failed to start {protocol} for '{name}': {e}");
+                        let _ =
+                            cmd_tx.send(Message::Toast(format!("Error: {msg}"), ToastType::Error));
+                        let _ = cmd_tx.send(Message::Log(format!("CMD_ERR: {msg}")));
+                        return;
+                    }
+                }
+            };
+            if let Some(policy) = &split {
+                if protocol == Protocol::OpenVPN {
+                    for net in policy.openvpn_unsupported() {
+                        let _ = cmd_tx.send(Message::Log(format!(
+                            "WARN: OpenVPN cannot route {net} outside the tunnel; exclusion skipped"
+                        )));
+                    }
+                }
+                let _ = cmd_tx.send(Message::SplitTunnelApplied(policy.clone()));
+            }
+
             let output = match protocol {
-                Protocol::WireGuard => std::process::Command::new("wg-quick")
-                    .args(["up", config_path.to_str().unwrap_or("")])
-                    .stdout(std::process::Stdio::piped())
-                    .stderr(std::process::Stdio::piped())
-                    .output(),
-                Protocol::OpenVPN => std::process::Command::new("openvpn")
-                    .args(["--config", config_path.to_str().unwrap_or(""), "--daemon"])
-                    .stdout(std::process::Stdio::piped())
-                    .stderr(std::process::Stdio::piped())
-                    .output(),
+                Protocol::WireGuard => {
+                    Self::run_wg_quick("up", &config_path, split.as_ref(), &endpoints)
+                }
+                Protocol::OpenVPN => {
+                    Self::run_openvpn(&config_path, auth.as_ref(), split.as_ref(), &endpoints)
+                }
             };
 
             match output {
@@ -1502,6 +2677,146 @@ impl App {
         });
     }
 
+    /// The profile copy a daemon is started with: decrypted if the profile
+    /// is sealed, and with server hostnames replaced by their `endpoints`
+    /// addresses. `None` when the stored config can be used as it is.
+    fn profile_copy(
+        config_path: &Path,
+        protocol: Protocol,
+        endpoints: &[crate::core::killswitch::ResolvedEndpoint],
+    ) -> std::io::Result<Option<vault::Decrypted>> {
+        let copy = vault::materialize(config_path).map_err(std::io::Error::other)?;
+        if endpoints.is_empty() {
+            return Ok(copy);
+        }
+        let path = copy.as_ref().map_or(config_path, |c| c.config.as_path());
+        let mut content = vault::read_config(path).map_err(std::io::Error::other)?;
+        let pinned = crate::vpn::pin_servers(protocol, &content, endpoints);
+        content.zeroize();
+        let Some(mut pinned) = pinned else {
+            return Ok(copy);
+        };
+        let written = match copy {
+            Some(copy) => vault::write_private(&copy.config, pinned.as_bytes()).map(|()| copy),
+            None => vault::copy_with(config_path, &pinned).map_err(std::io::Error::other),
+        };
+        pinned.zeroize();
+        written.map(Some)
+    }
+
+    /// Run `wg-quick up|down`. Vault-encrypted profiles are decrypted to a
+    /// private tmpfs file for the duration of the call.
+    ///
+    /// `up` is given a copy with the peers' `Endpoint` hosts replaced by
+    /// their resolved `endpoints`, and `AllowedIPs` following the split
+    /// tunnel rules. It keeps the file name, since `wg-quick` names the
+    /// interface after it.
+    fn run_wg_quick(
+        action: &str,
+        config_path: &Path,
+        split: Option<&crate::core::split_tunnel::Policy>,
+        endpoints: &[crate::core::killswitch::ResolvedEndpoint],
+    ) -> std::io::Result<std::process::Output> {
+        let decrypted = Self::profile_copy(config_path, Protocol::WireGuard, endpoints)?;
+        let mut path = decrypted
+            .as_ref()
+            .map_or(config_path, |d| d.config.as_path());
+
+        let rewritten = match split {
+            Some(policy) => {
+                let content = vault::read_config(path).map_err(std::io::Error::other)?;
+                // Holds the private key: only in memory if the profile is sealed
+                let scratch = if vault::is_sealed_file(config_path) {
+                    vault::ScratchDir::in_memory("split")
+                } else {
+                    vault::ScratchDir::new("split")
+                }
+                .map_err(std::io::Error::other)?;
+                let copy = scratch.path.join(path.file_name().unwrap_or_default());
+                vault::write_private(
+                    &copy,
+                    crate::core::split_tunnel::apply_wireguard(&content, policy).as_bytes(),
+                )?;
+                Some((scratch, copy))
+            }
+            None => None,
+        };
+        if let Some((_, copy)) = &rewritten {
+            path = copy.as_path();
+        }
+        std::process::Command::new("wg-quick")
+            .args([action, path.to_str().unwrap_or("")])
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .output()
+    }
+
+    /// Run `openvpn --daemon`, handing over credentials if needed. The
+    /// credential files or socket and the profile copy (decrypted, or with
+    /// `remote` hosts replaced by their resolved `endpoints`) stay until the
+    /// connection is down, since `OpenVPN` may read them again to reconnect.
+    ///
+    /// The log goes to a file per profile so the DNS leak check can read the
+    /// options the server pushed.
+    fn run_openvpn(
+        config_path: &Path,
+        auth: Option<&OpenVpnAuth>,
+        split: Option<&crate::core::split_tunnel::Policy>,
+        endpoints: &[crate::core::killswitch::ResolvedEndpoint],
+    ) -> std::io::Result<std::process::Output> {
+        let handoff = auth
+            .map(credentials::Handoff::prepare)
+            .transpose()
+            .map_err(std::io::Error::other)?;
+        let decrypted = Self::profile_copy(config_path, Protocol::OpenVPN, endpoints)?;
+
+        let mut command = std::process::Command::new("openvpn");
+        if let Some(decrypted) = &decrypted {
+            // OpenVPN re-reads the config and key files on SIGHUP/SIGUSR1
+            // restarts, so a started daemon's copy is kept until disconnect.
+            // The stored path is kept on the command line so the scanner
+            // still matches the process to the profile.
+            command.args([
+                "--config",
+                decrypted.config.to_str().unwrap_or(""),
+                "--daemon",
+            ]);
+            command.args([
+                "--setenv",
+                "VORTIX_PROFILE",
+                config_path.to_str().unwrap_or(""),
+            ]);
+        } else {
+            command.args(["--config", config_path.to_str().unwrap_or(""), "--daemon"]);
+        }
+        if let Some(log) = crate::vpn::openvpn_log_path(config_path) {
+            command.args(["--log", log.to_str().unwrap_or("")]);
+        }
+        if let Some(handoff) = &handoff {
+            command.args(handoff.args());
+        }
+        if let Some(policy) = split {
+            command.args(policy.openvpn_args());
+        }
+        let output = command
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .output()?;
+
+        if let (Some(handoff), Some(auth)) = (&handoff, auth) {
+            if output.status.success() {
+                handoff.finish(auth).map_err(std::io::Error::other)?;
+            }
+        }
+        if let Some(decrypted) = decrypted.filter(|_| output.status.success()) {
+            vault::hold(config_path, decrypted);
+        }
+        if let Some(handoff) = handoff.filter(|_| output.status.success()) {
+            credentials::hold(config_path, handoff);
+        }
+        Ok(output)
+    }
+
     /// DISCONNECT from VPN
     /// Synchronizes the kill switch state with the current mode and connection status.
     /// This is the single source of truth for kill switch state transitions and firewall control.
@@ -1509,6 +2824,8 @@ impl App {
         use crate::state::{KillSwitchMode, KillSwitchState};
 
         let old_state = self.killswitch_state;
+        // Pick up changes made with `vortix killswitch policy`
+        self.killswitch_policy = crate::core::killswitch::load_policy();
 
         // 1. Determine the target state
         self.killswitch_state = match self.killswitch_mode {
@@ -1532,21 +2849,34 @@ impl App {
             }
         };
 
+        // A portal window only makes sense while blocking
+        if !self.killswitch_state.is_blocking() && self.portal_window.take().is_some() {
+            self.log("SEC: Captive portal window closed (kill switch no longer blocking)");
+        }
+
         // 2. Sync physical firewall state if target state changed or if forcing sync
         if self.killswitch_state != old_state || self.killswitch_state == KillSwitchState::Blocking
         {
             if self.killswitch_state.is_blocking() {
-                // Determine VPN interface and server IP if connected
-                let (interface, server_ip) = match &self.connection_state {
-                    ConnectionState::Connected { details, .. } => (
-                        details.interface.as_str(),
-                        Some(details.endpoint.split(':').next().unwrap_or("")),
-                    ),
-                    _ => (crate::core::killswitch::DEFAULT_VPN_INTERFACE, None),
-                };
+                let (interface, servers) = self.killswitch_target();
 
                 if self.is_root {
-                    if let Err(e) = crate::core::killswitch::enable_blocking(interface, server_ip) {
+                    let result = match &self.portal_window {
+                        Some((window, _)) => crate::core::killswitch::enable_portal_window(
+                            interface,
+                            &servers,
+                            self.split_policy.as_ref(),
+                            &self.killswitch_policy,
+                            window,
+                        ),
+                        None => crate::core::killswitch::enable_blocking(
+                            interface,
+                            &servers,
+                            self.split_policy.as_ref(),
+                            &self.killswitch_policy,
+                        ),
+                    };
+                    if let Err(e) = result {
                         self.log(&format!("WARN: Failed to enable kill switch: {e}"));
                     }
                 }
@@ -1558,13 +2888,296 @@ impl App {
             }
         }
 
-        // 3. Persist state
+        // 3. Persist state (the interface is what the guard blocks on if
+        // vortix is killed)
         let _ = crate::core::killswitch::save_state(
             self.killswitch_mode,
             self.killswitch_state,
-            None,
-            None,
+            Some(self.killswitch_target().0),
+            &self.killswitch_endpoints,
+            self.split_policy.as_ref(),
+        );
+    }
+
+    /// VPN interface and server addresses the kill switch rules are written
+    /// for: the connected tunnel, or the default interface, plus every
+    /// resolved address of the profile's servers.
+    fn killswitch_target(&self) -> (&str, Vec<String>) {
+        let mut servers = crate::core::killswitch::server_addresses(&self.killswitch_endpoints);
+        match &self.connection_state {
+            ConnectionState::Connected { details, .. } => {
+                let current = details.endpoint.split(':').next().unwrap_or("");
+                if !current.is_empty() && !servers.iter().any(|s| s == current) {
+                    servers.push(current.to_string());
+                }
+                (details.interface.as_str(), servers)
+            }
+            _ => (crate::core::killswitch::DEFAULT_VPN_INTERFACE, servers),
+        }
+    }
+
+    /// Server hosts of every profile, without duplicates.
+    fn all_server_hosts(&self) -> Vec<String> {
+        let mut hosts = Vec::new();
+        for host in self.profiles.iter().flat_map(crate::vpn::server_hosts) {
+            if !hosts.contains(&host) {
+                hosts.push(host);
+            }
+        }
+        hosts
+    }
+
+    /// Resolve server `hosts` in the background for the kill switch rules.
+    fn resolve_endpoints(&self, hosts: Vec<String>) {
+        let cached = self.killswitch_endpoints.clone();
+        let cmd_tx = self.cmd_tx.clone();
+        std::thread::spawn(move || {
+            let endpoints = crate::core::killswitch::resolve_endpoints(&hosts, &cached);
+            let _ = cmd_tx.send(Message::EndpointsResolved(endpoints, None));
+        });
+    }
+
+    /// Resolve the portal probe URL in the background, so a portal window
+    /// can let the probe through once DNS is blocked.
+    fn resolve_portal_probe(&self) {
+        let url = self.killswitch_policy.portal_url.clone();
+        let cached = self.portal_probe.clone();
+        let cmd_tx = self.cmd_tx.clone();
+        std::thread::spawn(move || {
+            let probe = crate::core::portal::resolve_probe(&url, &cached);
+            let _ = cmd_tx.send(Message::PortalProbeResolved(probe));
+        });
+    }
+
+    /// Take new server addresses, reloading the rules if they changed while
+    /// the kill switch is blocking, or loading them for a new mode.
+    fn update_endpoints(&mut self, endpoints: Vec<crate::core::killswitch::ResolvedEndpoint>) {
+        let pending = std::mem::take(&mut self.killswitch_pending);
+        if endpoints == self.killswitch_endpoints {
+            if pending {
+                self.sync_killswitch();
+            }
+            return;
+        }
+        let hosts = |list: &[crate::core::killswitch::ResolvedEndpoint]| {
+            list.iter().map(|e| e.host.clone()).collect::<Vec<_>>()
+        };
+        let messages: Vec<String> = if hosts(&endpoints) == hosts(&self.killswitch_endpoints) {
+            endpoints
+                .iter()
+                .zip(&self.killswitch_endpoints)
+                .filter(|(new, old)| new.addresses != old.addresses)
+                .map(|(new, _)| {
+                    format!(
+                        "SEC: {} now resolves to {}; updating kill switch rules",
+                        new.host,
+                        new.addresses.join(", ")
+                    )
+                })
+                .collect()
+        } else {
+            vec![format!(
+                "SEC: Kill switch allows {} address(es) of {}",
+                crate::core::killswitch::server_addresses(&endpoints).len(),
+                hosts(&endpoints).join(", ")
+            )]
+        };
+        for message in &messages {
+            self.log(message);
+        }
+        self.killswitch_endpoints = endpoints;
+        // Reloads the rules when blocking, and persists the new addresses
+        self.sync_killswitch();
+    }
+
+    /// Leave the stale kill switch prompt for the vault prompt if profiles
+    /// are locked, as at a normal start.
+    fn finish_stale_killswitch(&mut self) {
+        self.input_mode = InputMode::at_startup(None, vault::is_enabled() && !vault::is_unlocked());
+    }
+
+    /// Look for a captive portal after a connection did not come up. The
+    /// probe cannot get out while the kill switch blocks, so a portal
+    /// window is offered instead.
+    fn check_captive_portal(&mut self) {
+        if self.killswitch_mode == crate::state::KillSwitchMode::Off
+            || self.killswitch_policy.portal_url.is_empty()
+            || self.portal_window.is_some()
+        {
+            return;
+        }
+        if self.killswitch_state.is_blocking() {
+            self.offer_portal_window(constants::MSG_PORTAL_BLOCKED);
+        } else {
+            self.probe_portal();
+        }
+    }
+
+    /// Ask before opening a portal window for the current gateway.
+    fn offer_portal_window(&mut self, reason: &'static str) {
+        if self.killswitch_policy.portal_url.is_empty() {
+            self.show_toast(constants::MSG_PORTAL_PROBE_OFF.to_string(), ToastType::Info);
+        } else if !self.killswitch_state.is_blocking() {
+            self.show_toast(
+                constants::MSG_PORTAL_NOT_BLOCKING.to_string(),
+                ToastType::Info,
+            );
+            self.probe_portal();
+        } else if !self.is_root {
+            self.show_toast(constants::MSG_PORTAL_ROOT.to_string(), ToastType::Error);
+        } else if let Some(gateway) = crate::core::portal::gateway() {
+            if self.input_mode == InputMode::Normal {
+                self.input_mode = InputMode::PortalWindow {
+                    gateway,
+                    secs: self.killswitch_policy.portal_window_secs,
+                    reason,
+                };
+            }
+        } else {
+            self.show_toast(
+                constants::MSG_PORTAL_NO_GATEWAY.to_string(),
+                ToastType::Error,
+            );
+        }
+    }
+
+    /// Confirmed: resolve the probe URL in the background (keeping the
+    /// addresses found before DNS was blocked), then load the exception.
+    fn open_portal_window(&mut self) {
+        let InputMode::PortalWindow { gateway, .. } = &self.input_mode else {
+            return;
+        };
+        let gateway = gateway.clone();
+        self.input_mode = InputMode::Normal;
+        let url = self.killswitch_policy.portal_url.clone();
+        let cached = self.portal_probe.clone();
+        let cmd_tx = self.cmd_tx.clone();
+        std::thread::spawn(move || {
+            let probe = crate::core::portal::resolve_probe(&url, &cached);
+            let window = crate::core::killswitch::PortalWindow {
+                network: gateway.network,
+                gateway: gateway.address,
+                probe_addresses: crate::core::killswitch::server_addresses(&probe),
+            };
+            let _ = cmd_tx.send(Message::PortalWindowReady(window));
+        });
+    }
+
+    fn portal_window_ready(&mut self, window: crate::core::killswitch::PortalWindow) {
+        if !self.killswitch_state.is_blocking() {
+            return;
+        }
+        let secs = self.killswitch_policy.portal_window_secs;
+        self.log(&format!(
+            "SEC: Captive portal window opened for {secs}s: HTTP/HTTPS to {}, DNS to {}",
+            window.network, window.gateway
+        ));
+        self.portal_window = Some((
+            window,
+            Instant::now() + std::time::Duration::from_secs(secs),
+        ));
+        self.sync_killswitch();
+        self.show_toast(
+            format!("Portal window open for {secs}s: log in now"),
+            ToastType::Warning,
+        );
+        self.probe_portal();
+    }
+
+    /// Reload the full blocking rules without the portal exception.
+    fn close_portal_window(&mut self, reason: &str) {
+        if self.portal_window.take().is_none() {
+            return;
+        }
+        self.log(&format!(
+            "SEC: Captive portal window closed ({reason}); kill switch fully blocking again"
+        ));
+        self.sync_killswitch();
+        self.show_toast(
+            "Portal window closed, kill switch re-armed".to_string(),
+            ToastType::Info,
+        );
+    }
+
+    fn probe_portal(&mut self) {
+        self.portal_probed = Some(Instant::now());
+        let url = self.killswitch_policy.portal_url.clone();
+        let cmd_tx = self.cmd_tx.clone();
+        std::thread::spawn(move || {
+            let _ = cmd_tx.send(Message::PortalProbed(crate::core::portal::probe(&url)));
+        });
+    }
+
+    fn portal_probed(&mut self, probe: crate::core::portal::Probe) {
+        use crate::core::portal::Probe;
+
+        match probe {
+            Probe::Open => {
+                self.portal_location = None;
+                self.close_portal_window("portal login detected");
+            }
+            Probe::Portal { location } => {
+                let location = location.unwrap_or_else(|| "unknown".to_string());
+                if self.portal_location.as_ref() != Some(&location) {
+                    self.log(&format!(
+                        "SEC: Captive portal detected, login page: {location}"
+                    ));
+                    let next = if self.portal_window.is_some() {
+                        "log in, vortix re-arms after"
+                    } else {
+                        "log in, then connect"
+                    };
+                    self.show_toast(format!("Captive portal: {next}"), ToastType::Warning);
+                    self.portal_location = Some(location);
+                }
+            }
+            Probe::Unreachable(reason) => {
+                if self.portal_window.is_none() {
+                    self.log(&format!(
+                        "NET: Captive portal probe got no answer: {reason}"
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Show the rules the kill switch would install right now.
+    fn open_killswitch_preview(&mut self) {
+        self.killswitch_policy = crate::core::killswitch::load_policy();
+        let (interface, servers) = self.killswitch_target();
+        let preview = crate::core::killswitch::RulePreview::new(
+            interface,
+            &servers,
+            self.split_policy.as_ref(),
+            &self.killswitch_policy,
         );
+        self.input_mode = InputMode::KillSwitchPreview {
+            interface: interface.to_string(),
+            preview,
+            scroll: 0,
+        };
+    }
+
+    fn handle_killswitch_preview_keys(&mut self, key: KeyEvent) {
+        let InputMode::KillSwitchPreview {
+            preview, scroll, ..
+        } = &mut self.input_mode
+        else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.handle_message(Message::CloseOverlay),
+            KeyCode::Down | KeyCode::Char('j') => {
+                let max = u16::try_from(preview.rules.lines().count()).unwrap_or(u16::MAX);
+                *scroll = (*scroll + 1).min(max.saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+            KeyCode::Home | KeyCode::Char('g') => *scroll = 0,
+            KeyCode::Char('v') => {
+                preview.dry_run();
+            }
+            _ => {}
+        }
     }
 
     fn disconnect(&mut self) {
@@ -1599,6 +3212,8 @@ impl App {
                 started: Instant::now(),
                 profile: profile_name.clone(),
             };
+            // Split rules only carry over to an unexpected drop
+            self.split_policy = None;
 
             // KILL SWITCH: Sync state after changing connection state
             self.sync_killswitch();
@@ -1612,14 +3227,10 @@ impl App {
 
             std::thread::spawn(move || {
                 let output = match protocol {
-                    Protocol::WireGuard => std::process::Command::new("wg-quick")
-                        .args(["down", config_path.to_str().unwrap_or("")])
-                        .stdout(std::process::Stdio::piped())
-                        .stderr(std::process::Stdio::piped())
-                        .output(),
+                    Protocol::WireGuard => Self::run_wg_quick("down", &config_path, None, &[]),
                     Protocol::OpenVPN => {
                         // Targeted kill if PID is known, else fallback to pkill
-                        if let Some(p) = pid {
+                        let killed = if let Some(p) = pid {
                             std::process::Command::new("kill")
                                 .arg(p.to_string())
                                 .stdout(std::process::Stdio::piped())
@@ -1631,7 +3242,12 @@ impl App {
                                 .stdout(std::process::Stdio::piped())
                                 .stderr(std::process::Stdio::piped())
                                 .output()
+                        };
+                        if killed.as_ref().is_ok_and(|out| out.status.success()) {
+                            vault::release(&config_path);
+                            credentials::release(&config_path);
                         }
+                        killed
                     }
                 };
 
@@ -1815,6 +3431,105 @@ impl App {
         }
     }
 
+    /// Re-read the routing table for the Routes panel, relative to the
+    /// active tunnel, on a background thread. Skipped while the panel is
+    /// hidden behind another zoomed panel and nothing is connected.
+    fn refresh_routes(&mut self) {
+        let (tunnel, endpoint) = match &self.connection_state {
+            ConnectionState::Connected { details, .. } => {
+                (details.interface.clone(), details.endpoint.clone())
+            }
+            _ => (String::new(), String::new()),
+        };
+        let visible = self
+            .zoomed_panel
+            .as_ref()
+            .map_or(true, |panel| matches!(panel, FocusedPanel::Routes));
+        if self.routes_busy || (tunnel.is_empty() && !visible) {
+            return;
+        }
+        self.routes_busy = true;
+        let tx = self.cmd_tx.clone();
+        std::thread::spawn(move || {
+            let table = crate::core::routes::inspect(&tunnel, &endpoint);
+            let _ = tx.send(Message::Telemetry(TelemetryUpdate::Routes(table)));
+        });
+    }
+
+    /// Re-evaluate the audit checks from the current telemetry, routes and
+    /// kill switch state.
+    fn refresh_audit(&mut self) {
+        let (profile, details) = match &self.connection_state {
+            ConnectionState::Connected {
+                profile, details, ..
+            } => (
+                self.profiles.iter().find(|p| &p.name == profile),
+                Some(details),
+            ),
+            _ => (None, None),
+        };
+        if let Some(profile) = profile {
+            if self
+                .audit_allowed_ips
+                .as_ref()
+                .map_or(true, |(name, _)| name != &profile.name)
+            {
+                self.audit_allowed_ips =
+                    Some((profile.name.clone(), crate::vpn::allowed_ips(profile)));
+                self.audit_keepalive = crate::vpn::has_keepalive(profile);
+            }
+        }
+        let wireguard = profile.is_some_and(|p| p.protocol == Protocol::WireGuard);
+        let inputs = crate::core::audit::Inputs {
+            interface: details.map_or("", |d| d.interface.as_str()),
+            exit_ip: Some(self.public_ip.as_str())
+                .filter(|ip| ip.parse::<std::net::IpAddr>().is_ok()),
+            real_ip: self.real_ip.as_deref(),
+            dns: self.dns_report.as_ref(),
+            ipv6: self.ipv6_report.as_ref(),
+            routes: &self.route_table,
+            split_tunnel: profile.is_some_and(|p| !p.split_tunnel.is_empty()),
+            killswitch_mode: self.killswitch_mode,
+            killswitch_state: self.killswitch_state,
+            killswitch_stale: false,
+            allowed_ips: self
+                .audit_allowed_ips
+                .as_ref()
+                .and_then(|(_, allowed)| allowed.as_deref()),
+            handshake: details
+                .filter(|_| wireguard)
+                .map(|d| d.latest_handshake.as_str()),
+            keepalive: self.audit_keepalive,
+        };
+        self.audit = crate::core::audit::evaluate(&inputs);
+    }
+
+    /// Run the local leak checks on a background thread: which interface
+    /// each resolver is reached through (compared with the connected
+    /// profile's DNS servers), and whether IPv6 can bypass the tunnel.
+    fn check_leaks(&mut self) {
+        let ConnectionState::Connected {
+            profile, details, ..
+        } = &self.connection_state
+        else {
+            self.dns_report = None;
+            self.ipv6_report = None;
+            return;
+        };
+        let Some(profile) = self.profiles.iter().find(|p| &p.name == profile).cloned() else {
+            return;
+        };
+        let tunnel = details.interface.clone();
+        let tx = self.cmd_tx.clone();
+        std::thread::spawn(move || {
+            let expected = crate::vpn::expected_dns(&profile);
+            let report = crate::core::leaks::check_dns(&tunnel, &expected);
+            let _ = tx.send(Message::Telemetry(TelemetryUpdate::DnsCheck(report)));
+            let report = crate::core::leaks::check_ipv6(&tunnel);
+            let _ = tx.send(Message::Telemetry(TelemetryUpdate::Ipv6Check(report)));
+        });
+    }
+
     /// Periodic tick from the event loop.
     pub fn on_tick(&mut self) {
         self.handle_message(Message::Tick);
@@ -1863,6 +3578,90 @@ impl App {
         self.handle_message(Message::Resize(width, height));
     }
 
+    /// Refresh subscriptions on a background thread: all of them when
+    /// `manual`, otherwise only those whose schedule is due.
+    fn refresh_subscriptions(&mut self, manual: bool) {
+        use crate::core::subscription;
+
+        if self.subscriptions_busy {
+            if manual {
+                self.show_toast(constants::MSG_SUBS_BUSY.to_string(), ToastType::Info);
+            }
+            return;
+        }
+        if vault::is_enabled() && !vault::is_unlocked() {
+            if manual {
+                self.show_toast(constants::MSG_VAULT_LOCKED.to_string(), ToastType::Warning);
+            }
+            return;
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let mut subscriptions = subscription::load();
+        if !subscriptions.iter().any(|s| manual || s.is_due(now)) {
+            if manual {
+                self.show_toast(constants::MSG_SUBS_NONE.to_string(), ToastType::Info);
+            }
+            return;
+        }
+
+        // Never delete the profile we are connected through
+        let active = match &self.connection_state {
+            ConnectionState::Connected { profile, .. }
+            | ConnectionState::Connecting { profile, .. }
+            | ConnectionState::Disconnecting { profile, .. } => Some(profile.as_str()),
+            ConnectionState::Disconnected => None,
+        };
+        let in_use: Vec<_> = self
+            .profiles
+            .iter()
+            .filter(|p| Some(p.name.as_str()) == active)
+            .map(|p| p.config_path.clone())
+            .collect();
+
+        if manual {
+            self.show_toast(constants::MSG_SUBS_REFRESHING.to_string(), ToastType::Info);
+        }
+        self.subscriptions_busy = true;
+        let tx = self.cmd_tx.clone();
+        std::thread::spawn(move || {
+            let (mut changed, mut failed) = (false, 0);
+            for sub in subscriptions.iter_mut().filter(|s| manual || s.is_due(now)) {
+                match subscription::refresh(sub, false, &in_use) {
+                    Ok(plan) => {
+                        let _ = tx.send(Message::Log(format!(
+                            "SUB: {}: {}",
+                            sub.name,
+                            plan.summary()
+                        )));
+                        for line in plan.lines() {
+                            let _ = tx.send(Message::Log(format!("SUB:   {line}")));
+                        }
+                        changed |= plan.has_changes();
+                        failed += plan.failed();
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Message::Log(format!(
+                            "SUB: Failed to refresh {}: {e}",
+                            sub.name
+                        )));
+                        failed += 1;
+                    }
+                }
+            }
+            if let Err(e) = subscription::save(&subscriptions) {
+                let _ = tx.send(Message::Log(format!("SUB: {e}")));
+            }
+            let _ = tx.send(Message::SubscriptionsRefreshed {
+                manual,
+                changed,
+                failed,
+            });
+        });
+    }
+
     /// Import a profile from a file path or bulk import from directory
     fn import_profile_from_path(&mut self, path_str: &str) {
         use crate::core::importer::{resolve_target, ImportTarget};
@@ -1872,8 +3671,9 @@ impl App {
                 let tx = self.cmd_tx.clone();
                 self.show_toast(constants::MSG_DOWNLOADING.to_string(), ToastType::Info);
 
-                std::thread::spawn(
-                    move || match crate::core::downloader::download_profile(&url) {
+                let verification = crate::core::downloader::Verification::from_env();
+                std::thread::spawn(move || {
+                    match crate::core::downloader::download_profile(&url, &verification) {
                         Ok(path) => {
                             let path_string = path.to_string_lossy().to_string();
                             let _ = tx.send(Message::Import(path_string));
@@ -1884,8 +3684,8 @@ impl App {
                                 ToastType::Error,
                             ));
                         }
-                    },
-                );
+                    }
+                });
             }
             Ok(ImportTarget::File(path)) => {
                 self.import_single_file(&path);
@@ -1893,6 +3693,9 @@ impl App {
             Ok(ImportTarget::Directory(path)) => {
                 self.import_from_directory(&path);
             }
+            Ok(ImportTarget::Archive(path)) => {
+                self.import_archive(&path);
+            }
             Err(e) => {
                 self.show_toast(e, ToastType::Error);
             }
@@ -1903,103 +3706,453 @@ impl App {
 
     /// Import a single VPN profile file
     fn import_single_file(&mut self, path: &Path) {
-        match crate::vpn::import_profile(path) {
-            Ok(profile) => {
+        if bundle::is_bundle(path) {
+            if bundle::is_encrypted(path) {
+                self.input_mode = InputMode::BundlePassphrase {
+                    path: path.to_path_buf(),
+                    passphrase: String::new(),
+                    error: None,
+                };
+            } else {
+                self.import_bundle(path, None);
+            }
+            return;
+        }
+
+        match crate::vpn::preview_profile(path) {
+            Ok(preview) => {
+                let duplicate_of = duplicates::Index::of(&self.profiles)
+                    .find_duplicate(path)
+                    .map(|d| format!("{} ({})", d.name, d.kind.describe()));
+                self.input_mode = InputMode::ConfirmImport {
+                    confirm: ImportConfirm::new(path, preview, duplicate_of),
+                };
+            }
+            Err(e) => {
+                self.show_toast(
+                    format!("{}{}", constants::MSG_IMPORT_ERROR, e),
+                    ToastType::Error,
+                );
+            }
+        }
+    }
+
+    /// Save the reviewed profile under the chosen name and location. On
+    /// failure the dialog stays open with the error.
+    fn save_import(&mut self) {
+        let InputMode::ConfirmImport { confirm } = &mut self.input_mode else {
+            return;
+        };
+        let saved = crate::vpn::get_profiles_dir().and_then(|dir| confirm.save(&dir));
+        let (profile, warnings) = match saved {
+            Ok(saved) => saved,
+            Err(e) => {
+                confirm.error = Some(e);
+                return;
+            }
+        };
+        self.input_mode = InputMode::Normal;
+
+        let name = profile.name.clone();
+        let custom_location = profile.custom_location.is_some();
+        self.profiles.push(profile);
+        if custom_location {
+            self.save_metadata();
+        }
+        self.sort_profiles();
+
+        for warning in &warnings {
+            self.log(&format!("IMPORT: ⚠ {name}: {warning}"));
+        }
+        if warnings.is_empty() {
+            self.show_toast(
+                format!("{}{}", constants::MSG_IMPORT_SUCCESS, name),
+                ToastType::Success,
+            );
+        } else {
+            self.show_toast(
+                format!(
+                    "{}{} ({} warning(s), see logs)",
+                    constants::MSG_IMPORT_SUCCESS,
+                    name,
+                    warnings.len()
+                ),
+                ToastType::Warning,
+            );
+        }
+    }
+
+    /// Create a `WireGuard` profile from the wizard and show its public key
+    fn create_profile(&mut self) {
+        let InputMode::NewProfile { wizard } = &mut self.input_mode else {
+            return;
+        };
+        let spec = crate::vpn::wireguard::NewProfile {
+            address: wizard.value(WizardField::Address).to_string(),
+            dns: wizard.value(WizardField::Dns).to_string(),
+            peer_public_key: wizard.value(WizardField::PeerPublicKey).to_string(),
+            endpoint: wizard.value(WizardField::Endpoint).to_string(),
+            allowed_ips: wizard.value(WizardField::AllowedIps).to_string(),
+            keepalive: wizard.value(WizardField::Keepalive).to_string(),
+        };
+
+        match crate::vpn::create_wireguard_profile(wizard.value(WizardField::Name), &spec) {
+            Ok((profile, public_key, warnings)) => {
                 let name = profile.name.clone();
+                for warning in &warnings {
+                    self.log(&format!("IMPORT: ⚠ {name}: {warning}"));
+                }
+                self.log(&format!("WIZARD: Created {name}, public key {public_key}"));
                 self.profiles.push(profile);
+                self.sort_profiles();
+                self.input_mode = InputMode::ProfileCreated { name, public_key };
+            }
+            Err(e) => wizard.error = Some(e),
+        }
+    }
+
+    /// Replace the QR warning with the rendered code
+    fn show_qr(&mut self) {
+        let InputMode::QrWarning {
+            profile_index,
+            exclude_hooks,
+        } = self.input_mode
+        else {
+            return;
+        };
+        let Some(profile) = self.profiles.get(profile_index) else {
+            self.input_mode = InputMode::Normal;
+            return;
+        };
+        let name = profile.name.clone();
 
+        let rendered =
+            crate::core::qr::profile_payload(profile, exclude_hooks).and_then(|mut payload| {
+                let rows = crate::core::qr::render(&payload);
+                payload.zeroize();
+                rows
+            });
+        match rendered {
+            Ok(rows) => {
+                self.log(&format!(
+                    "QR: Showing QR code for {name} (private key on screen)"
+                ));
+                self.input_mode = InputMode::QrCode {
+                    profile: name,
+                    rows,
+                };
+            }
+            Err(e) => {
+                self.input_mode = InputMode::Normal;
+                self.show_toast(e, ToastType::Error);
+            }
+        }
+    }
+
+    /// Export the profiles matching the sidebar filter into a bundle
+    fn export_profiles(&mut self, path: &str, passphrase: Option<&str>) {
+        let profiles: Vec<VpnProfile> =
+            search::filter_profiles(&self.profiles, &self.profile_filter)
+                .iter()
+                .map(|m| self.profiles[m.index].clone())
+                .collect();
+        let dest = crate::core::importer::expand_home(path);
+
+        match bundle::export(&profiles, &dest, passphrase) {
+            Ok(count) => {
+                self.log(&format!(
+                    "EXPORT: {count} profile(s) → {}{}",
+                    dest.display(),
+                    if passphrase.is_some() {
+                        " (encrypted)"
+                    } else {
+                        ""
+                    }
+                ));
                 self.show_toast(
-                    format!("{}{}", constants::MSG_IMPORT_SUCCESS, name),
+                    format!("{}{count}", constants::MSG_EXPORT_SUCCESS),
                     ToastType::Success,
                 );
             }
+            Err(e) => {
+                self.log(&format!("EXPORT: Failed: {e}"));
+                self.show_toast(
+                    format!("{}{e}", constants::MSG_EXPORT_ERROR),
+                    ToastType::Error,
+                );
+            }
+        }
+    }
+
+    /// Import every profile from an exported bundle. Profiles that are
+    /// already stored (by content) are kept; see `vortix import --on-conflict`
+    /// to change that.
+    fn import_bundle(&mut self, path: &Path, passphrase: Option<&str>) {
+        let entries = match bundle::read(path, passphrase) {
+            Ok(entries) => entries,
+            Err(e) if passphrase.is_some() => {
+                self.input_mode = InputMode::BundlePassphrase {
+                    path: path.to_path_buf(),
+                    passphrase: String::new(),
+                    error: Some(e),
+                };
+                return;
+            }
             Err(e) => {
                 self.show_toast(
                     format!("{}{}", constants::MSG_IMPORT_ERROR, e),
                     ToastType::Error,
                 );
+                return;
+            }
+        };
+
+        let outcomes = bundle::import(&entries, Conflict::Skip);
+        for outcome in &outcomes {
+            match outcome {
+                Outcome::Imported(profile, warnings) | Outcome::Replaced(profile, warnings) => {
+                    for warning in warnings {
+                        self.log(&format!("IMPORT: ⚠ {}: {warning}", profile.name));
+                    }
+                    self.profiles
+                        .retain(|p| p.config_path != profile.config_path);
+                    self.profiles.push(profile.clone());
+                }
+                Outcome::Skipped(name) => {
+                    self.log(&format!("IMPORT: Skipped {name} (already exists)"));
+                }
+                Outcome::Failed(name, e) => {
+                    self.log(&format!("IMPORT: Failed to import {name}: {e}"));
+                }
             }
         }
+        self.load_metadata();
+
+        let summary = Summary::of(&outcomes);
+        let toast_type = if summary.failed > 0 {
+            ToastType::Warning
+        } else {
+            ToastType::Success
+        };
+        self.show_toast(
+            format!(
+                "Bundle: {} imported, {} skipped, {} failed",
+                summary.imported + summary.replaced,
+                summary.skipped,
+                summary.failed
+            ),
+            toast_type,
+        );
     }
 
-    /// Bulk import all .conf and .ovpn files from a directory
+    /// List the .conf and .ovpn files below a directory for preview
     fn import_from_directory(&mut self, dir_path: &Path) {
-        let mut imported = 0;
-        let mut failed = 0;
-
-        match std::fs::read_dir(dir_path) {
-            Ok(entries) => {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-
-                    // Only process .conf and .ovpn files
-                    if path.is_file()
-                        && path
-                            .extension()
-                            .is_some_and(|ext| ext == "conf" || ext == "ovpn")
-                    {
-                        match crate::vpn::import_profile(&path) {
-                            Ok(profile) => {
-                                self.profiles.push(profile);
+        match importer::find_configs(dir_path, &importer::Filter::default()) {
+            Ok(paths) => self.open_import_preview(&paths, dir_path),
+            Err(e) => {
+                self.log(&format!("IMPORT: Error reading directory: {e}"));
+                self.show_toast(format!("Error reading directory: {e}"), ToastType::Error);
+            }
+        }
+    }
 
-                                imported += 1;
-                            }
-                            Err(e) => {
-                                self.log(&format!(
-                                    "IMPORT: Failed to import {}: {}",
-                                    path.display(),
-                                    e
-                                ));
-                                failed += 1;
-                            }
-                        }
-                    }
+    /// Unpack a ZIP archive and list its configs for preview
+    fn import_archive(&mut self, path: &Path) {
+        let extracted = match archive::extract(path, &importer::Filter::default()) {
+            Ok(extracted) => extracted,
+            Err(e) => {
+                self.log(&format!("IMPORT: {e}"));
+                self.show_toast(format!("Error: {e}"), ToastType::Error);
+                return;
+            }
+        };
+        self.open_import_preview(&extracted.configs, &extracted.dir.path);
+        // Keep the unpacked files until the import is finished or cancelled
+        self.import_scratch = Some(extracted.dir);
+    }
+
+    /// Show what importing `paths` (found below `base`) would do, with
+    /// every importable file checked
+    fn open_import_preview(&mut self, paths: &[PathBuf], base: &Path) {
+        if paths.is_empty() {
+            self.show_toast(
+                constants::MSG_NO_FILES_FOUND.to_string(),
+                ToastType::Warning,
+            );
+            return;
+        }
+
+        let index = duplicates::Index::of(&self.profiles);
+        let items = paths
+            .iter()
+            .map(|path| {
+                let preview = crate::vpn::preview_profile(path);
+                PreviewItem {
+                    path: path.clone(),
+                    label: path
+                        .strip_prefix(base)
+                        .unwrap_or(path)
+                        .display()
+                        .to_string(),
+                    checked: preview.is_ok(),
+                    duplicate_of: index.find_duplicate(path).map(|d| d.name),
+                    preview,
                 }
+            })
+            .collect();
+        self.input_mode = InputMode::ImportPreview { items, cursor: 0 };
+    }
 
-                // Show summary feedback
-                if imported > 0 {
-                    let msg = if failed > 0 {
-                        format!("Imported {imported} profile(s), {failed} failed")
-                    } else {
-                        format!(
-                            "{}{}{}",
-                            constants::MSG_BATCH_IMPORTED,
-                            imported,
-                            constants::MSG_BATCH_IMPORTED_SUFFIX
-                        )
-                    };
-                    // Use Warning if significant failures, but Success is generally appropriate to confirm the action
-                    let t_type = if failed > imported {
-                        ToastType::Warning
-                    } else {
-                        ToastType::Success
+    /// Import the files checked in the preview
+    fn confirm_import_preview(&mut self) {
+        let InputMode::ImportPreview { items, .. } = std::mem::take(&mut self.input_mode) else {
+            return;
+        };
+        let queue: VecDeque<_> = items
+            .into_iter()
+            .filter(|item| item.checked)
+            .map(|item| item.path)
+            .collect();
+
+        self.log(&format!("IMPORT: Importing {} file(s)", queue.len()));
+        let index = duplicates::Index::of(&self.profiles);
+        self.import_files(queue, index, None, Summary::default());
+    }
+
+    /// Import queued files in order. A duplicate pauses the batch with
+    /// `InputMode::Duplicate` unless `on_duplicate` already decides it.
+    fn import_files(
+        &mut self,
+        mut queue: VecDeque<PathBuf>,
+        mut index: duplicates::Index,
+        on_duplicate: Option<Conflict>,
+        mut summary: Summary,
+    ) {
+        while let Some(path) = queue.front().cloned() {
+            let duplicate = index.find_duplicate(&path);
+            let outcome = match (duplicate, on_duplicate) {
+                (Some(duplicate), None) => {
+                    self.input_mode = InputMode::Duplicate {
+                        source: path
+                            .file_name()
+                            .map_or_else(String::new, |n| n.to_string_lossy().into_owned()),
+                        existing: duplicate.name.clone(),
+                        reason: duplicate.kind.describe().to_string(),
+                        apply_to_all: false,
+                        remaining: queue.len() - 1,
                     };
-                    self.show_toast(msg.clone(), t_type);
+                    self.pending_import = Some(PendingImport {
+                        queue,
+                        index,
+                        duplicate,
+                        summary,
+                    });
+                    return;
+                }
+                (Some(duplicate), Some(choice)) => index.import(&path, Some((&duplicate, choice))),
+                (None, _) => index.import(&path, None),
+            };
+            queue.pop_front();
+            self.apply_import_outcome(outcome, &mut summary);
+        }
+        self.finish_import(summary);
+    }
 
-                    self.log(&format!(
-                        "IMPORT: Batch imported {imported} profiles from {}",
-                        dir_path.display()
-                    ));
-                } else if failed > 0 {
-                    // Files found but all failed
-                    self.show_toast(
-                        format!("Failed to import {failed} profiles"),
-                        ToastType::Error,
-                    );
-                } else {
-                    // Truly no files found
-                    self.show_toast(
-                        constants::MSG_NO_FILES_FOUND.to_string(),
-                        ToastType::Warning,
-                    );
+    /// Continue a paused import with the user's choice, or stop it
+    fn resolve_duplicate(&mut self, choice: Option<Conflict>) {
+        let apply_to_all = matches!(
+            self.input_mode,
+            InputMode::Duplicate {
+                apply_to_all: true,
+                ..
+            }
+        );
+        self.input_mode = InputMode::Normal;
+        let Some(PendingImport {
+            mut queue,
+            mut index,
+            duplicate,
+            mut summary,
+        }) = self.pending_import.take()
+        else {
+            return;
+        };
+
+        let Some(choice) = choice else {
+            self.log(&format!(
+                "IMPORT: Stopped, {} file(s) not imported",
+                queue.len()
+            ));
+            self.finish_import(summary);
+            return;
+        };
+        if let Some(path) = queue.pop_front() {
+            let outcome = index.import(&path, Some((&duplicate, choice)));
+            self.apply_import_outcome(outcome, &mut summary);
+        }
+        self.import_files(queue, index, apply_to_all.then_some(choice), summary);
+    }
+
+    /// Reflect one import result in the profile list and the logs
+    fn apply_import_outcome(&mut self, outcome: Outcome, summary: &mut Summary) {
+        summary.add(&outcome);
+        match outcome {
+            Outcome::Imported(profile, warnings) | Outcome::Replaced(profile, warnings) => {
+                for warning in &warnings {
+                    self.log(&format!("IMPORT: ⚠ {}: {warning}", profile.name));
                 }
+                self.profiles
+                    .retain(|p| p.config_path != profile.config_path);
+                self.profiles.push(profile);
             }
-            Err(e) => {
-                self.log(&format!("IMPORT: Error reading directory: {e}"));
-                self.show_toast(format!("Error reading directory: {e}"), ToastType::Error);
+            Outcome::Skipped(name) => {
+                self.log(&format!("IMPORT: Skipped {name} (duplicate)"));
+            }
+            Outcome::Failed(name, e) => {
+                self.log(&format!("IMPORT: Failed to import {name}: {e}"));
             }
         }
     }
+
+    /// Summarise a finished import
+    fn finish_import(&mut self, summary: Summary) {
+        self.import_scratch = None;
+        self.load_metadata();
+        self.sort_profiles();
+
+        if summary.total() == 0 {
+            self.show_toast(
+                constants::MSG_NO_FILES_FOUND.to_string(),
+                ToastType::Warning,
+            );
+            return;
+        }
+
+        let done = summary.imported + summary.replaced;
+        let mut parts = vec![format!(
+            "{}{}{}",
+            constants::MSG_BATCH_IMPORTED,
+            done,
+            constants::MSG_BATCH_IMPORTED_SUFFIX
+        )];
+        if summary.replaced > 0 {
+            parts.push(format!("{} replaced", summary.replaced));
+        }
+        if summary.skipped > 0 {
+            parts.push(format!("{} duplicate(s) skipped", summary.skipped));
+        }
+        if summary.failed > 0 {
+            parts.push(format!("{} failed", summary.failed));
+        }
+        let msg = parts.join(", ");
+        let toast_type = match (done, summary.failed) {
+            (0, failed) if failed > 0 => ToastType::Error,
+            (_, 0) => ToastType::Success,
+            _ => ToastType::Warning,
+        };
+        self.log(&format!("IMPORT: {msg}"));
+        self.show_toast(msg, toast_type);
+    }
 }
 
 impl Default for App {
@@ -1,6 +1,7 @@
 //! Command-line argument definitions.
 
-use clap::{Parser, Subcommand};
+use clap::builder::BoolishValueParser;
+use clap::{Args as ClapArgs, Parser, Subcommand, ValueEnum};
 
 /// Vortix - Professional TUI VPN Manager
 #[derive(Parser, Debug)]
@@ -14,13 +15,328 @@ pub struct Args {
 /// Available CLI commands
 #[derive(Subcommand, Debug)]
 pub enum Commands {
-    /// Import VPN profile(s) from a file, directory, or URL
+    /// Import VPN profile(s) from a file, directory, URL, or exported bundle
     Import {
-        /// Path to a .conf/.ovpn file, directory, or a URL (http/https)
+        /// Path to a .conf/.ovpn file, .vortix bundle, directory, or a URL (http/https)
         file: String,
+        /// What to do with profiles that already exist (asked for each
+        /// duplicate when omitted)
+        #[arg(long, value_enum)]
+        on_conflict: Option<OnConflict>,
+        /// Only import files in a directory or archive whose name or path
+        /// matches this glob, e.g. 'de-*' (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        include: Vec<String>,
+        /// Skip files whose name or path matches this glob (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        exclude: Vec<String>,
+        /// Show each file's protocol, name, location and errors without
+        /// importing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Expected SHA-256 of the downloaded file (URL imports only)
+        #[arg(long, value_name = "HEX")]
+        sha256: Option<String>,
+        /// Minisign public key the download must be signed with
+        #[arg(long, value_name = "KEY", env = "VORTIX_SIGNING_KEY")]
+        pubkey: Option<String>,
+        /// Signature file or URL (default: <url>.sig)
+        #[arg(long, value_name = "FILE|URL", requires = "pubkey")]
+        signature: Option<String>,
+        /// Allow downloading over plain HTTP
+        #[arg(long)]
+        insecure: bool,
+    },
+    /// Export profiles, their metadata and key files into a single bundle
+    Export {
+        /// Bundle file to write (e.g. profiles.vortix)
+        file: String,
+        /// Profile to include (repeatable; default: all profiles)
+        #[arg(short, long = "profile", value_name = "NAME")]
+        profiles: Vec<String>,
+        /// Encrypt the bundle with a passphrase
+        #[arg(long)]
+        encrypt: bool,
     },
     /// Update vortix to the latest version from crates.io
     Update,
     /// Emergency release of kill switch (use if locked out)
     ReleaseKillSwitch,
+    /// Create a new profile with a freshly generated key pair
+    ///
+    /// Required settings that are not given as options are asked for.
+    New {
+        /// Profile name
+        name: String,
+        /// Interface address(es), e.g. 10.0.0.2/32
+        #[arg(long)]
+        address: Option<String>,
+        /// DNS server(s)
+        #[arg(long)]
+        dns: Option<String>,
+        /// Server (peer) public key
+        #[arg(long)]
+        peer_key: Option<String>,
+        /// Server endpoint, host:port
+        #[arg(long)]
+        endpoint: Option<String>,
+        /// Networks routed through the tunnel
+        #[arg(long, default_value = "0.0.0.0/0, ::/0")]
+        allowed_ips: String,
+        /// Persistent keepalive in seconds (0 = off)
+        #[arg(long, default_value = "25")]
+        keepalive: String,
+    },
+    /// Show a profile as a QR code for the mobile app (exposes the private key)
+    Qr {
+        /// Profile name
+        profile: String,
+        /// Leave out PostUp/PostDown lines
+        #[arg(long)]
+        exclude_hooks: bool,
+        /// Skip the private key warning prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Manage the encrypted profile vault
+    Vault {
+        #[command(subcommand)]
+        action: VaultAction,
+    },
+    /// Keep profiles in sync with a provider's subscription URL
+    #[command(alias = "sub")]
+    Subscription {
+        #[command(subcommand)]
+        action: SubscriptionAction,
+    },
+    /// Add or remove a profile's tags (shown when no tags are given)
+    Tag {
+        /// Profile name
+        profile: String,
+        /// Tags to add, or to remove with --remove
+        tags: Vec<String>,
+        /// Remove the given tags instead of adding them
+        #[arg(short, long)]
+        remove: bool,
+    },
+    /// Choose which networks use a profile's tunnel (split tunnelling)
+    Split {
+        #[command(subcommand)]
+        action: SplitAction,
+    },
+    /// Configure the kill switch
+    #[command(name = "killswitch", alias = "ks")]
+    KillSwitch {
+        #[command(subcommand)]
+        action: KillSwitchAction,
+    },
+    /// Leak-test the active connection and print a pass/fail report
+    ///
+    /// Checks the exit IP against the real IP, IPv6 and DNS leaks, the
+    /// default route, the kill switch, allowed IPs coverage and handshake
+    /// freshness. Exits with status 1 when a check fails.
+    Audit {
+        /// Report format
+        #[arg(long, value_enum, default_value_t = AuditFormat::Text)]
+        format: AuditFormat,
+        /// Write the report to this file instead of the terminal
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<std::path::PathBuf>,
+    },
+}
+
+/// Output format of `vortix audit`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditFormat {
+    Text,
+    Json,
+    Markdown,
+}
+
+/// Kill switch configuration.
+#[derive(Subcommand, Debug, Clone)]
+pub enum KillSwitchAction {
+    /// Print the firewall rules the kill switch would install now
+    Show {
+        /// Check the rules with `pfctl -n` without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Show or change what the kill switch lets through besides the tunnel
+    ///
+    /// Prints the resulting firewall rules, which are installed the next
+    /// time the kill switch blocks.
+    Policy(PolicyArgs),
+    /// Report the saved kill switch state and whether its session is alive
+    Status,
+    /// Probe for a captive portal and show the gateway network a portal
+    /// window would open (the window itself is opened in the TUI)
+    Portal,
+}
+
+/// Changes to the kill switch policy; without any, it is only shown.
+#[derive(ClapArgs, Debug, Clone)]
+pub struct PolicyArgs {
+    /// Allow local network (RFC1918) traffic
+    #[arg(
+        long,
+        value_name = "on|off",
+        value_parser = BoolishValueParser::new(),
+        hide_possible_values = true
+    )]
+    pub lan: Option<bool>,
+    /// Allow DHCP
+    #[arg(
+        long,
+        value_name = "on|off",
+        value_parser = BoolishValueParser::new(),
+        hide_possible_values = true
+    )]
+    pub dhcp: Option<bool>,
+    /// Send DNS only through the tunnel, even to resolvers on the LAN
+    #[arg(
+        long,
+        value_name = "on|off",
+        value_parser = BoolishValueParser::new(),
+        hide_possible_values = true
+    )]
+    pub dns_tunnel_only: Option<bool>,
+    /// IPv6 handling outside the tunnel
+    #[arg(long, value_enum)]
+    pub ipv6: Option<Ipv6Choice>,
+    /// Allow a network, e.g. a printer subnet (repeatable)
+    #[arg(long, value_name = "CIDR")]
+    pub allow: Vec<String>,
+    /// Allow a port on local networks: tcp/631, udp/5353 or 631 for both
+    /// (repeatable)
+    #[arg(long, value_name = "[PROTO/]PORT")]
+    pub allow_port: Vec<String>,
+    /// Captive portal probe URL (plain HTTP), or "off"
+    #[arg(long, value_name = "URL|off")]
+    pub portal_url: Option<String>,
+    /// Length of a captive portal window in seconds
+    #[arg(long, value_name = "SECS")]
+    pub portal_window: Option<u64>,
+    /// Remove an allowed network or port (repeatable)
+    #[arg(long, value_name = "CIDR|PORT")]
+    pub remove: Vec<String>,
+    /// Go back to the default policy first
+    #[arg(long)]
+    pub reset: bool,
+}
+
+/// IPv6 handling while the kill switch blocks.
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum Ipv6Choice {
+    /// IPv6 only through the tunnel
+    Block,
+    /// Also link-local and unique local addresses on the LAN
+    Lan,
+    /// Do not filter IPv6
+    Allow,
+}
+
+/// Split tunnel rules of a profile.
+///
+/// Targets are networks (`10.0.0.0/8`), addresses or hostnames; hostnames
+/// are resolved each time the profile connects.
+#[derive(Subcommand, Debug, Clone)]
+pub enum SplitAction {
+    /// Show the rules of a profile
+    Show {
+        /// Profile name
+        profile: String,
+    },
+    /// Send only these targets through the tunnel
+    Include {
+        /// Profile name
+        profile: String,
+        /// Networks, addresses or hostnames
+        #[arg(required = true)]
+        targets: Vec<String>,
+    },
+    /// Keep these targets off the tunnel
+    Exclude {
+        /// Profile name
+        profile: String,
+        /// Networks, addresses or hostnames
+        #[arg(required = true)]
+        targets: Vec<String>,
+    },
+    /// Remove targets from both lists
+    Remove {
+        /// Profile name
+        profile: String,
+        /// Networks, addresses or hostnames
+        #[arg(required = true)]
+        targets: Vec<String>,
+    },
+    /// Remove all rules of a profile
+    Clear {
+        /// Profile name
+        profile: String,
+    },
+}
+
+/// Subscription management.
+///
+/// A subscription URL serves either a single config or an index listing
+/// config URLs, one per line.
+#[derive(Subcommand, Debug, Clone)]
+pub enum SubscriptionAction {
+    /// Register a subscription URL and import its profiles
+    Add {
+        /// Config or index URL
+        url: String,
+        /// Name of the subscription (defaults to the URL's host)
+        #[arg(long)]
+        name: Option<String>,
+        /// Refresh automatically every N hours while the TUI is running
+        #[arg(long, value_name = "HOURS")]
+        every: Option<u64>,
+        /// Allow fetching the subscription over plain HTTP
+        #[arg(long)]
+        insecure: bool,
+    },
+    /// List subscriptions
+    List,
+    /// Fetch changes and update the affected profiles in place
+    Refresh {
+        /// Subscription to refresh (all when omitted)
+        name: Option<String>,
+        /// Only show what would change
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Unregister a subscription (its profiles are kept)
+    Remove {
+        /// Subscription name
+        name: String,
+        /// Also delete the profiles it imported
+        #[arg(long)]
+        delete_profiles: bool,
+    },
+}
+
+/// Conflict handling when importing a bundle.
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum OnConflict {
+    /// Keep the existing profile
+    Skip,
+    /// Replace the existing profile
+    Overwrite,
+    /// Import under a new name
+    KeepBoth,
+}
+
+/// Profile vault actions. The passphrase is read from
+/// `VORTIX_VAULT_PASSPHRASE` or prompted for on the terminal.
+#[derive(Subcommand, Debug, Clone, Copy)]
+pub enum VaultAction {
+    /// Encrypt all stored profiles with a passphrase
+    Enable,
+    /// Decrypt all stored profiles and turn the vault off
+    Disable,
+    /// Show whether the vault is enabled
+    Status,
 }
@@ -1,18 +1,58 @@
 //! CLI command handlers.
 
-use crate::cli::args::Commands;
+use crate::cli::args::{
+    AuditFormat, Commands, Ipv6Choice, KillSwitchAction, OnConflict, PolicyArgs, SplitAction,
+    SubscriptionAction, VaultAction,
+};
+use crate::core::archive;
+use crate::core::bundle;
+use crate::core::downloader::Verification;
+use crate::core::duplicates::{self, Duplicate};
+use crate::core::importer::{Conflict, Outcome, Summary};
 use color_eyre::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroize;
 
 /// Handles CLI commands that don't require the TUI.
 ///
 /// Returns `true` if the command was handled and the program should exit,
 /// or `false` if the TUI should be started.
-#[allow(clippy::unnecessary_wraps)]
+#[allow(clippy::unnecessary_wraps, clippy::too_many_lines)]
 pub fn handle_command(command: &Commands) -> Result<bool> {
     match command {
-        Commands::Import { file } => {
-            handle_import(file);
+        Commands::Import {
+            file,
+            on_conflict,
+            include,
+            exclude,
+            dry_run,
+            sha256,
+            pubkey,
+            signature,
+            insecure,
+        } => {
+            let verification = Verification {
+                sha256: sha256.clone(),
+                public_key: pubkey.clone().filter(|key| !key.trim().is_empty()),
+                signature: signature.clone(),
+                insecure: *insecure,
+            };
+            handle_import(
+                file,
+                on_conflict.map(conflict_choice),
+                include,
+                exclude,
+                *dry_run,
+                &verification,
+            );
+            Ok(true)
+        }
+        Commands::Export {
+            file,
+            profiles,
+            encrypt,
+        } => {
+            handle_export(file, profiles, *encrypt);
             Ok(true)
         }
         Commands::Update => {
@@ -23,34 +63,166 @@ pub fn handle_command(command: &Commands) -> Result<bool> {
             handle_release_killswitch();
             Ok(true)
         }
+        Commands::New {
+            name,
+            address,
+            dns,
+            peer_key,
+            endpoint,
+            allowed_ips,
+            keepalive,
+        } => {
+            let spec = crate::vpn::wireguard::NewProfile {
+                address: address
+                    .clone()
+                    .unwrap_or_else(|| ask(constants::CLI_PROMPT_NEW_ADDRESS)),
+                dns: dns
+                    .clone()
+                    .unwrap_or_else(|| ask(constants::CLI_PROMPT_NEW_DNS)),
+                peer_public_key: peer_key
+                    .clone()
+                    .unwrap_or_else(|| ask(constants::CLI_PROMPT_NEW_PEER_KEY)),
+                endpoint: endpoint
+                    .clone()
+                    .unwrap_or_else(|| ask(constants::CLI_PROMPT_NEW_ENDPOINT)),
+                allowed_ips: allowed_ips.clone(),
+                keepalive: keepalive.clone(),
+            };
+            handle_new(name, &spec);
+            Ok(true)
+        }
+        Commands::Qr {
+            profile,
+            exclude_hooks,
+            yes,
+        } => {
+            handle_qr(profile, *exclude_hooks, *yes);
+            Ok(true)
+        }
+        Commands::Vault { action } => {
+            handle_vault(*action);
+            Ok(true)
+        }
+        Commands::Subscription { action } => {
+            handle_subscription(action);
+            Ok(true)
+        }
+        Commands::Tag {
+            profile,
+            tags,
+            remove,
+        } => {
+            handle_tag(profile, tags, *remove);
+            Ok(true)
+        }
+        Commands::Split { action } => {
+            handle_split(action);
+            Ok(true)
+        }
+        Commands::KillSwitch { action } => {
+            handle_killswitch(action);
+            Ok(true)
+        }
+        Commands::Audit { format, output } => {
+            handle_audit(*format, output.as_deref());
+            Ok(true)
+        }
     }
 }
 
 use crate::constants;
 
+/// Maps the CLI conflict flag onto the import choice.
+fn conflict_choice(on_conflict: OnConflict) -> Conflict {
+    match on_conflict {
+        OnConflict::Skip => Conflict::Skip,
+        OnConflict::Overwrite => Conflict::Overwrite,
+        OnConflict::KeepBoth => Conflict::KeepBoth,
+    }
+}
+
 /// Imports a VPN profile from the specified file path or directory.
-fn handle_import(file: &str) {
-    use crate::core::importer::{resolve_target, ImportTarget};
+///
+/// Duplicates of stored profiles are handled per `on_conflict`, or asked
+/// about when it is `None`. Directories are searched recursively; they and
+/// archives are narrowed down by the `include`/`exclude` globs. With
+/// `dry_run` nothing is written. URLs are downloaded over HTTPS and checked
+/// against `verification` first.
+fn handle_import(
+    file: &str,
+    on_conflict: Option<Conflict>,
+    include: &[String],
+    exclude: &[String],
+    dry_run: bool,
+    verification: &Verification,
+) {
+    use crate::core::importer::{resolve_target, Filter, ImportTarget};
+
+    if let Err(e) = unlock_vault() {
+        eprintln!("{}{}", constants::CLI_MSG_IMPORT_FAILED, e);
+        std::process::exit(1);
+    }
+    let filter = Filter::new(include, exclude).unwrap_or_else(|e| {
+        eprintln!("{}{}", constants::CLI_MSG_ERROR, e);
+        std::process::exit(1);
+    });
+
+    let target = resolve_target(file);
+    if verification.sha256.is_some() || verification.signature.is_some() {
+        if let Ok(target) = &target {
+            if !matches!(target, ImportTarget::Url(_)) {
+                eprintln!("{}", constants::CLI_MSG_VERIFY_URL_ONLY);
+                std::process::exit(1);
+            }
+        }
+    }
 
-    match resolve_target(file) {
+    match target {
         Ok(ImportTarget::Url(url)) => {
             println!("{}", constants::CLI_MSG_DOWNLOADING);
-            match crate::core::downloader::download_profile(&url) {
+            match crate::core::downloader::download_profile(&url, verification) {
+                // The downloaded path is a temp file
+                Ok(downloaded_path) if archive::is_archive(&downloaded_path) => {
+                    let extracted = archive::extract(&downloaded_path, &filter);
+                    let _ = std::fs::remove_file(&downloaded_path);
+                    import_extracted(extracted, on_conflict, dry_run);
+                }
+                Ok(downloaded_path) if dry_run => {
+                    let parent = downloaded_path.parent().unwrap_or(Path::new("."));
+                    let ok = print_previews(std::slice::from_ref(&downloaded_path), parent);
+                    let _ = std::fs::remove_file(&downloaded_path);
+                    exit_unless(ok);
+                }
                 Ok(downloaded_path) => {
-                    // The downloaded path is a temp file
-                    import_single_file(&downloaded_path);
+                    import_single_file(&downloaded_path, on_conflict);
                 }
                 Err(e) => {
-                    eprintln!("{}{}", constants::CLI_MSG_IMPORT_FAILED, e);
+                    eprintln!("{}{}", constants::MSG_DOWNLOAD_FAILED, e);
                     std::process::exit(1);
                 }
             }
         }
+        Ok(ImportTarget::File(path)) if bundle::is_bundle(&path) => {
+            if dry_run {
+                eprintln!("{}", constants::CLI_MSG_DRY_RUN_BUNDLE);
+                std::process::exit(1);
+            }
+            import_bundle(&path, on_conflict);
+        }
+        Ok(ImportTarget::File(path)) if dry_run => {
+            exit_unless(print_previews(
+                std::slice::from_ref(&path),
+                path.parent().unwrap_or(Path::new(".")),
+            ));
+        }
         Ok(ImportTarget::File(path)) => {
-            import_single_file(&path);
+            import_single_file(&path, on_conflict);
         }
         Ok(ImportTarget::Directory(path)) => {
-            import_from_directory(&path);
+            import_from_directory(&path, on_conflict, &filter, dry_run);
+        }
+        Ok(ImportTarget::Archive(path)) => {
+            import_extracted(archive::extract(&path, &filter), on_conflict, dry_run);
         }
         Err(e) => {
             eprintln!("{}{}", constants::CLI_MSG_ERROR, e);
@@ -59,10 +231,16 @@ fn handle_import(file: &str) {
     }
 }
 
+fn exit_unless(ok: bool) {
+    if !ok {
+        std::process::exit(1);
+    }
+}
+
 /// Import a single VPN profile file
-fn import_single_file(path: &Path) {
-    match crate::vpn::import_profile(path) {
-        Ok(profile) => {
+fn import_single_file(path: &Path, on_conflict: Option<Conflict>) {
+    match import_files(&[path.to_path_buf()], on_conflict).pop() {
+        Some(Outcome::Imported(profile, warnings) | Outcome::Replaced(profile, warnings)) => {
             println!("{}{}", constants::CLI_MSG_IMPORT_SUCCESS, profile.name);
             println!(
                 "{}{}",
@@ -79,59 +257,685 @@ fn import_single_file(path: &Path) {
                 constants::CLI_MSG_IMPORT_DETAILS_PATH,
                 profile.config_path.display()
             );
+            for warning in warnings {
+                eprintln!("{}{}", constants::CLI_MSG_IMPORT_WARNING, warning);
+            }
         }
-        Err(e) => {
+        Some(Outcome::Skipped(name)) => {
+            println!("{}{}", constants::CLI_MSG_IMPORT_SKIPPED, name);
+        }
+        Some(Outcome::Failed(_, e)) => {
             eprintln!("{}{}", constants::CLI_MSG_IMPORT_FAILED, e);
             std::process::exit(1);
         }
+        None => {}
     }
 }
 
-/// Bulk import all .conf and .ovpn files from a directory
-fn import_from_directory(dir_path: &Path) {
-    let mut imported = 0;
-    let mut failed = 0;
+/// Import config files, checking each one against the stored profiles (and
+/// the files imported before it) for duplicates.
+fn import_files(paths: &[PathBuf], on_conflict: Option<Conflict>) -> Vec<Outcome> {
+    let mut index = duplicates::Index::of(&crate::vpn::load_profiles());
+    let mut policy = on_conflict;
+    let mut outcomes = Vec::new();
 
-    match std::fs::read_dir(dir_path) {
-        Ok(entries) => {
-            for entry in entries.flatten() {
-                let path = entry.path();
-
-                if path.is_file()
-                    && path
-                        .extension()
-                        .is_some_and(|ext| ext == "conf" || ext == "ovpn")
-                {
-                    match crate::vpn::import_profile(&path) {
-                        Ok(profile) => {
-                            println!("  ✅ {}", profile.name); // Keeping checkmark list item simple
-                            imported += 1;
-                        }
-                        Err(e) => {
-                            eprintln!("  ❌ {} - {}", path.display(), e);
-                            failed += 1;
-                        }
-                    }
+    for path in paths {
+        let duplicate = index.find_duplicate(path);
+        let resolution = duplicate.as_ref().map(|duplicate| {
+            let choice = policy.unwrap_or_else(|| {
+                let (choice, apply_to_all) = ask_duplicate(path, duplicate);
+                if apply_to_all {
+                    policy = Some(choice);
                 }
-            }
+                choice
+            });
+            (duplicate, choice)
+        });
+        outcomes.push(index.import(path, resolution));
+    }
+    outcomes
+}
+
+/// Ask what to do about a duplicate; an upper-case answer applies to the
+/// rest of the batch. Without a terminal to ask on, duplicates are skipped.
+fn ask_duplicate(source: &Path, duplicate: &Duplicate) -> (Conflict, bool) {
+    use std::io::IsTerminal;
 
-            // Show summary
-            println!("{}", constants::CLI_MSG_SUMMARY_HEADER);
-            println!("{}{}", constants::CLI_MSG_SUMMARY_IMPORTED, imported);
-            if failed > 0 {
-                println!("{}{}", constants::CLI_MSG_SUMMARY_FAILED, failed);
+    eprintln!(
+        "⚠ {} duplicates '{}' ({})",
+        source.display(),
+        duplicate.name,
+        duplicate.kind.describe()
+    );
+    if !std::io::stdin().is_terminal() {
+        eprintln!("{}", constants::CLI_MSG_DUPLICATE_NOT_ASKED);
+        return (Conflict::Skip, false);
+    }
+
+    loop {
+        let answer = ask(constants::CLI_PROMPT_DUPLICATE);
+        let choice = match answer.to_lowercase().as_str() {
+            "" | "s" => Conflict::Skip,
+            "o" => Conflict::Overwrite,
+            "k" => Conflict::KeepBoth,
+            _ => continue,
+        };
+        return (choice, answer.chars().any(char::is_uppercase));
+    }
+}
+
+/// Print one line per outcome and the summary. Returns `false` if any
+/// profile failed to import.
+fn report_outcomes(outcomes: &[Outcome]) -> bool {
+    for outcome in outcomes {
+        match outcome {
+            Outcome::Imported(profile, warnings) | Outcome::Replaced(profile, warnings) => {
+                println!("  ✅ {}", profile.name);
+                for warning in warnings {
+                    eprintln!("  {}{}", constants::CLI_MSG_IMPORT_WARNING, warning);
+                }
             }
+            Outcome::Skipped(name) => println!("  ⏭  {name}"),
+            Outcome::Failed(name, e) => eprintln!("  ❌ {name} - {e}"),
+        }
+    }
 
-            if imported == 0 {
-                eprintln!("{}", constants::CLI_MSG_NO_FILES);
+    let summary = Summary::of(outcomes);
+    println!("{}", constants::CLI_MSG_SUMMARY_HEADER);
+    println!(
+        "{}{}",
+        constants::CLI_MSG_SUMMARY_IMPORTED,
+        summary.imported
+    );
+    if summary.replaced > 0 {
+        println!(
+            "{}{}",
+            constants::CLI_MSG_SUMMARY_REPLACED,
+            summary.replaced
+        );
+    }
+    if summary.skipped > 0 {
+        println!("{}{}", constants::CLI_MSG_SUMMARY_SKIPPED, summary.skipped);
+    }
+    if summary.failed > 0 {
+        println!("{}{}", constants::CLI_MSG_SUMMARY_FAILED, summary.failed);
+    }
+    summary.failed == 0
+}
+
+/// Import every profile from an exported bundle
+fn import_bundle(path: &Path, on_conflict: Option<Conflict>) {
+    let passphrase = if bundle::is_encrypted(path) {
+        match read_passphrase(
+            constants::CLI_PROMPT_BUNDLE_PASSPHRASE,
+            constants::ENV_BUNDLE_PASSPHRASE,
+        ) {
+            Ok(passphrase) => Some(passphrase),
+            Err(e) => {
+                eprintln!("{}{}", constants::CLI_MSG_IMPORT_FAILED, e);
                 std::process::exit(1);
             }
         }
+    } else {
+        None
+    };
+
+    let profiles = match bundle::read(path, passphrase.as_deref().map(String::as_str)) {
+        Ok(profiles) => profiles,
         Err(e) => {
-            eprintln!("{}{}", constants::CLI_MSG_DIR_ERROR, e);
+            eprintln!("{}{}", constants::CLI_MSG_IMPORT_FAILED, e);
             std::process::exit(1);
         }
+    };
+
+    let outcomes = bundle::import(&profiles, on_conflict.unwrap_or_default());
+    exit_unless(report_outcomes(&outcomes));
+}
+
+/// Exports the named profiles (all when none are given) into a bundle.
+fn handle_export(file: &str, names: &[String], encrypt: bool) {
+    let fail = |e: String| -> ! {
+        eprintln!("{}{}", constants::CLI_MSG_EXPORT_FAILED, e);
+        std::process::exit(1);
+    };
+
+    unlock_vault().unwrap_or_else(|e| fail(e));
+
+    let mut profiles = crate::vpn::load_profiles();
+    if !names.is_empty() {
+        if let Some(unknown) = names
+            .iter()
+            .find(|name| !profiles.iter().any(|p| &p.name == *name))
+        {
+            fail(format!("{}{}", constants::CLI_MSG_UNKNOWN_PROFILE, unknown));
+        }
+        profiles.retain(|p| names.contains(&p.name));
+    }
+
+    let passphrase = if encrypt {
+        Some(read_new_passphrase(constants::ENV_BUNDLE_PASSPHRASE).unwrap_or_else(|e| fail(e)))
+    } else {
+        None
+    };
+
+    let dest = crate::core::importer::expand_home(file);
+    match bundle::export(&profiles, &dest, passphrase.as_deref().map(String::as_str)) {
+        Ok(count) => {
+            println!("{}{}", constants::CLI_MSG_EXPORT_SUCCESS, count);
+            println!(
+                "{}{}",
+                constants::CLI_MSG_EXPORT_DETAILS_PATH,
+                dest.display()
+            );
+        }
+        Err(e) => fail(e),
+    }
+}
+
+/// Unlock the profile vault (if enabled) so profiles can be read and written.
+fn unlock_vault() -> Result<(), String> {
+    if !crate::core::vault::is_enabled() || crate::core::vault::is_unlocked() {
+        return Ok(());
+    }
+    let passphrase = read_passphrase(
+        constants::CLI_PROMPT_VAULT_PASSPHRASE,
+        constants::ENV_VAULT_PASSPHRASE,
+    )?;
+    crate::core::vault::unlock(&passphrase)
+}
+
+/// Bulk import all .conf and .ovpn files below a directory
+fn import_from_directory(
+    dir_path: &Path,
+    on_conflict: Option<Conflict>,
+    filter: &crate::core::importer::Filter,
+    dry_run: bool,
+) {
+    let paths = crate::core::importer::find_configs(dir_path, filter).unwrap_or_else(|e| {
+        eprintln!("{}{}", constants::CLI_MSG_DIR_ERROR, e);
+        std::process::exit(1);
+    });
+    exit_unless(import_batch(&paths, dir_path, on_conflict, dry_run));
+}
+
+/// Import (or preview) the configs unpacked from an archive. The unpacked
+/// files are removed before exiting.
+fn import_extracted(
+    extracted: Result<archive::Extracted, String>,
+    on_conflict: Option<Conflict>,
+    dry_run: bool,
+) {
+    let extracted = extracted.unwrap_or_else(|e| {
+        eprintln!("{}{}", constants::CLI_MSG_IMPORT_FAILED, e);
+        std::process::exit(1);
+    });
+    let ok = import_batch(
+        &extracted.configs,
+        &extracted.dir.path,
+        on_conflict,
+        dry_run,
+    );
+    drop(extracted);
+    exit_unless(ok);
+}
+
+/// Import `paths` found below `base`, or with `dry_run` only show what
+/// would be imported. Returns `false` if anything failed.
+fn import_batch(
+    paths: &[PathBuf],
+    base: &Path,
+    on_conflict: Option<Conflict>,
+    dry_run: bool,
+) -> bool {
+    if paths.is_empty() {
+        eprintln!("{}", constants::CLI_MSG_NO_FILES);
+        return false;
+    }
+    if dry_run {
+        return print_previews(paths, base);
+    }
+    report_outcomes(&import_files(paths, on_conflict))
+}
+
+/// Print what importing each file would do, with paths shown relative to
+/// `base`. Returns `false` if any file could not be imported.
+fn print_previews(paths: &[PathBuf], base: &Path) -> bool {
+    let index = duplicates::Index::of(&crate::vpn::load_profiles());
+    let mut importable = 0;
+
+    println!("{}", constants::CLI_MSG_DRY_RUN_HEADER);
+    for path in paths {
+        let shown = path.strip_prefix(base).unwrap_or(path).display();
+        match crate::vpn::preview_profile(path) {
+            Ok(preview) => {
+                importable += 1;
+                println!(
+                    "  ✅ {shown} → {} ({}, {})",
+                    preview.name, preview.protocol, preview.location
+                );
+                if let Some(duplicate) = index.find_duplicate(path) {
+                    println!(
+                        "     ⏭  duplicates '{}' ({})",
+                        duplicate.name,
+                        duplicate.kind.describe()
+                    );
+                }
+                for warning in &preview.warnings {
+                    eprintln!("  {}{}", constants::CLI_MSG_IMPORT_WARNING, warning);
+                }
+            }
+            Err(e) => eprintln!("  ❌ {shown} - {e}"),
+        }
     }
+
+    println!(
+        "{}{importable} of {}",
+        constants::CLI_MSG_DRY_RUN_SUMMARY,
+        paths.len()
+    );
+    importable == paths.len()
+}
+
+/// Creates a `WireGuard` profile with a generated key pair.
+fn handle_new(name: &str, spec: &crate::vpn::wireguard::NewProfile) {
+    if let Err(e) = unlock_vault() {
+        eprintln!("{}{}", constants::CLI_MSG_NEW_FAILED, e);
+        std::process::exit(1);
+    }
+
+    match crate::vpn::create_wireguard_profile(name, spec) {
+        Ok((profile, public_key, warnings)) => {
+            println!("{}{}", constants::CLI_MSG_NEW_SUCCESS, profile.name);
+            println!(
+                "{}{}",
+                constants::CLI_MSG_IMPORT_DETAILS_PATH,
+                profile.config_path.display()
+            );
+            println!("{}{}", constants::CLI_MSG_NEW_PUBLIC_KEY, public_key);
+            println!("{}", constants::CLI_MSG_NEW_REGISTER);
+            for warning in warnings {
+                eprintln!("{}{}", constants::CLI_MSG_IMPORT_WARNING, warning);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}{}", constants::CLI_MSG_NEW_FAILED, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Ask for a value on stdin (empty on EOF).
+fn ask(prompt: &str) -> String {
+    use std::io::Write;
+
+    eprint!("{prompt}");
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    let _ = std::io::stdin().read_line(&mut answer);
+    answer.trim().to_string()
+}
+
+/// Prints a `WireGuard` profile as a QR code after a private key warning.
+fn handle_qr(name: &str, exclude_hooks: bool, yes: bool) {
+    let fail = |e: String| -> ! {
+        eprintln!("{}{}", constants::CLI_MSG_ERROR, e);
+        std::process::exit(1);
+    };
+
+    unlock_vault().unwrap_or_else(|e| fail(e));
+    let profile = crate::vpn::load_profiles()
+        .into_iter()
+        .find(|p| p.name == name)
+        .unwrap_or_else(|| fail(format!("{}{}", constants::CLI_MSG_UNKNOWN_PROFILE, name)));
+    let mut payload =
+        crate::core::qr::profile_payload(&profile, exclude_hooks).unwrap_or_else(|e| fail(e));
+
+    eprintln!("{}", constants::CLI_MSG_QR_WARNING);
+    if !yes {
+        eprint!("{}", constants::CLI_PROMPT_QR_CONFIRM);
+        let mut answer = String::new();
+        let _ = std::io::stdin().read_line(&mut answer);
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            payload.zeroize();
+            eprintln!("{}", constants::CLI_MSG_QR_ABORTED);
+            std::process::exit(1);
+        }
+    }
+
+    let rows = crate::core::qr::render(&payload);
+    payload.zeroize();
+    let rows = rows.unwrap_or_else(|e| fail(e));
+
+    println!("\n{}", profile.name);
+    for row in rows {
+        // Black on white so the code scans on dark terminals too
+        println!("\x1b[30;47m{row}\x1b[0m");
+    }
+}
+
+/// Handles `vortix vault enable|disable|status`.
+fn handle_vault(action: VaultAction) {
+    use crate::core::vault;
+
+    let result = match action {
+        VaultAction::Status => {
+            if vault::is_enabled() {
+                println!("{}", constants::CLI_MSG_VAULT_STATUS_ON);
+            } else {
+                println!("{}", constants::CLI_MSG_VAULT_STATUS_OFF);
+            }
+            return;
+        }
+        VaultAction::Enable => read_new_passphrase(constants::ENV_VAULT_PASSPHRASE)
+            .and_then(|passphrase| vault::enable(&passphrase))
+            .map(|count| println!("{}{}", constants::CLI_MSG_VAULT_ENABLED, count)),
+        VaultAction::Disable => read_passphrase(
+            constants::CLI_PROMPT_VAULT_PASSPHRASE,
+            constants::ENV_VAULT_PASSPHRASE,
+        )
+        .and_then(|passphrase| vault::disable(&passphrase))
+        .map(|count| println!("{}{}", constants::CLI_MSG_VAULT_DISABLED, count)),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}{}", constants::CLI_MSG_ERROR, e);
+        std::process::exit(1);
+    }
+}
+
+/// Manages subscription URLs.
+fn handle_subscription(action: &SubscriptionAction) {
+    use crate::core::subscription;
+
+    let mut subscriptions = subscription::load();
+    let find = |subscriptions: &[subscription::Subscription], name: &str| {
+        subscriptions
+            .iter()
+            .position(|s| s.name == name)
+            .ok_or_else(|| format!("{}{name}", constants::CLI_MSG_SUB_UNKNOWN))
+    };
+
+    let result = match action {
+        SubscriptionAction::Add {
+            url,
+            name,
+            every,
+            insecure,
+        } => subscription::add(&mut subscriptions, url, name.as_deref(), *every, *insecure)
+            .and_then(|index| {
+                subscription::save(&subscriptions)?;
+                println!(
+                    "{}{}",
+                    constants::CLI_MSG_SUB_ADDED,
+                    subscriptions[index].name
+                );
+                refresh_subscriptions(&mut subscriptions, Some(index), false)
+            }),
+        SubscriptionAction::List => {
+            if subscriptions.is_empty() {
+                println!("{}", constants::CLI_MSG_SUB_NONE);
+            }
+            for sub in &subscriptions {
+                let schedule = sub
+                    .interval_hours
+                    .map_or_else(|| "on demand".to_string(), |h| format!("every {h}h"));
+                let refreshed = sub.refreshed_at.map_or_else(
+                    || "never".to_string(),
+                    |secs| {
+                        crate::utils::format_relative_time(
+                            std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs),
+                        )
+                    },
+                );
+                println!(
+                    "{}  {}  ({} profiles, {schedule}, last refresh {refreshed})",
+                    sub.name,
+                    sub.url,
+                    sub.profile_count()
+                );
+            }
+            return;
+        }
+        SubscriptionAction::Refresh { name, dry_run } => name
+            .as_deref()
+            .map(|name| find(&subscriptions, name))
+            .transpose()
+            .and_then(|index| refresh_subscriptions(&mut subscriptions, index, *dry_run)),
+        SubscriptionAction::Remove {
+            name,
+            delete_profiles,
+        } => find(&subscriptions, name).and_then(|index| {
+            let removed = subscriptions.remove(index);
+            if *delete_profiles {
+                let profiles_dir = crate::vpn::get_profiles_dir()?;
+                for file_name in removed.profile_files() {
+                    crate::vpn::remove_profile(&profiles_dir.join(file_name));
+                }
+            }
+            subscription::save(&subscriptions)?;
+            println!("{}{}", constants::CLI_MSG_SUB_REMOVED, removed.name);
+            Ok(())
+        }),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}{}", constants::CLI_MSG_ERROR, e);
+        std::process::exit(1);
+    }
+}
+
+/// Handles `vortix tag <profile> [tags...] [--remove]`.
+fn handle_tag(name: &str, tags: &[String], remove: bool) {
+    let fail = |e: String| -> ! {
+        eprintln!("{}{}", constants::CLI_MSG_ERROR, e);
+        std::process::exit(1);
+    };
+
+    let profile = crate::vpn::load_profiles()
+        .into_iter()
+        .find(|p| p.name == name)
+        .unwrap_or_else(|| fail(format!("{}{}", constants::CLI_MSG_UNKNOWN_PROFILE, name)));
+    let tags: Vec<&str> = tags.iter().map(|t| t.trim()).collect();
+    if tags
+        .iter()
+        .any(|t| t.is_empty() || t.contains(char::is_whitespace))
+    {
+        fail(constants::CLI_MSG_TAG_INVALID.to_string());
+    }
+
+    let mut metadata = crate::utils::load_profile_metadata().unwrap_or_else(|e| fail(e));
+    let key = profile.config_path.to_string_lossy().to_string();
+    let current = &mut metadata.entry(key).or_default().tags;
+    if remove {
+        current.retain(|t| !tags.contains(&t.as_str()));
+    } else {
+        for tag in tags.iter().copied() {
+            if !current.iter().any(|t| t == tag) {
+                current.push(tag.to_string());
+            }
+        }
+    }
+
+    if current.is_empty() {
+        println!("{}: {}", profile.name, constants::CLI_MSG_TAG_NONE);
+    } else {
+        println!("{}: {}", profile.name, current.join(", "));
+    }
+    if !tags.is_empty() {
+        crate::utils::save_profile_metadata(&metadata).unwrap_or_else(|e| fail(e));
+    }
+}
+
+/// Handles `vortix split show|include|exclude|remove|clear`.
+fn handle_split(action: &SplitAction) {
+    use crate::core::split_tunnel;
+
+    let fail = |e: String| -> ! {
+        eprintln!("{}{}", constants::CLI_MSG_ERROR, e);
+        std::process::exit(1);
+    };
+
+    let (SplitAction::Show { profile: name }
+    | SplitAction::Include { profile: name, .. }
+    | SplitAction::Exclude { profile: name, .. }
+    | SplitAction::Remove { profile: name, .. }
+    | SplitAction::Clear { profile: name }) = action;
+    let profile = crate::vpn::load_profiles()
+        .into_iter()
+        .find(|p| &p.name == name)
+        .unwrap_or_else(|| fail(format!("{}{}", constants::CLI_MSG_UNKNOWN_PROFILE, name)));
+    let mut metadata = crate::utils::load_profile_metadata().unwrap_or_else(|e| fail(e));
+    let key = profile.config_path.to_string_lossy().to_string();
+    let entry = metadata.entry(key).or_default();
+    let split = &mut entry.split_tunnel;
+
+    let targets = match action {
+        SplitAction::Include { targets, .. }
+        | SplitAction::Exclude { targets, .. }
+        | SplitAction::Remove { targets, .. } => targets
+            .iter()
+            .map(|t| split_tunnel::parse_target(t))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_or_else(|e| fail(e)),
+        SplitAction::Show { .. } | SplitAction::Clear { .. } => Vec::new(),
+    };
+    // A target lives in one list at most
+    split.include.retain(|t| !targets.contains(t));
+    split.exclude.retain(|t| !targets.contains(t));
+    match action {
+        SplitAction::Include { .. } => split.include.extend(targets),
+        SplitAction::Exclude { .. } => split.exclude.extend(targets),
+        SplitAction::Clear { .. } => *split = split_tunnel::SplitTunnel::default(),
+        SplitAction::Show { .. } | SplitAction::Remove { .. } => {}
+    }
+
+    println!("{}", profile.name);
+    if split.is_empty() {
+        println!("{}", constants::CLI_MSG_SPLIT_NONE);
+    }
+    for (label, list) in [("include", &split.include), ("exclude", &split.exclude)] {
+        if !list.is_empty() {
+            println!("   {label}: {}", list.join(", "));
+        }
+    }
+
+    if !matches!(action, SplitAction::Show { .. }) {
+        crate::utils::save_profile_metadata(&metadata).unwrap_or_else(|e| fail(e));
+        println!("{}", constants::CLI_MSG_SPLIT_RECONNECT);
+    }
+}
+
+/// Refresh one subscription (or all), print the diff and save the result.
+fn refresh_subscriptions(
+    subscriptions: &mut [crate::core::subscription::Subscription],
+    only: Option<usize>,
+    dry_run: bool,
+) -> Result<(), String> {
+    if !dry_run {
+        unlock_vault()?;
+    }
+    let in_use = profiles_in_use();
+
+    let mut failed = 0;
+    for (index, sub) in subscriptions.iter_mut().enumerate() {
+        if only.is_some_and(|only| only != index) {
+            continue;
+        }
+        match crate::core::subscription::refresh(sub, dry_run, &in_use) {
+            Ok(plan) => {
+                println!("{}: {}", sub.name, plan.summary());
+                for line in plan.lines() {
+                    println!("  {line}");
+                }
+                failed += plan.failed();
+            }
+            Err(e) => {
+                eprintln!("{}: {e}", sub.name);
+                failed += 1;
+            }
+        }
+    }
+
+    if dry_run {
+        println!("{}", constants::CLI_MSG_SUB_DRY_RUN);
+        return Ok(());
+    }
+    crate::core::subscription::save(subscriptions)?;
+    if failed > 0 {
+        return Err(format!("{failed}{}", constants::CLI_MSG_SUB_FAILED));
+    }
+    Ok(())
+}
+
+/// Config paths of profiles that are currently connected.
+fn profiles_in_use() -> Vec<PathBuf> {
+    let profiles = crate::vpn::load_profiles();
+    let active = crate::core::scanner::get_active_profiles(&profiles);
+    profiles
+        .into_iter()
+        .filter(|p| active.iter().any(|a| a.name == p.name))
+        .map(|p| p.config_path)
+        .collect()
+}
+
+/// A new passphrase (vault or bundle), confirmed twice when typed
+/// interactively. An existing vault's passphrase is only asked once.
+fn read_new_passphrase(env: &str) -> Result<zeroize::Zeroizing<String>, String> {
+    let is_vault = env == constants::ENV_VAULT_PASSPHRASE;
+    let prompt = if is_vault {
+        constants::CLI_PROMPT_VAULT_PASSPHRASE
+    } else {
+        constants::CLI_PROMPT_BUNDLE_PASSPHRASE
+    };
+    if (is_vault && crate::core::vault::is_enabled()) || std::env::var_os(env).is_some() {
+        return read_passphrase(prompt, env);
+    }
+    let first = read_passphrase(prompt, env)?;
+    let second = read_passphrase(constants::CLI_PROMPT_VAULT_CONFIRM, env)?;
+    if *first != *second {
+        return Err(constants::CLI_MSG_VAULT_MISMATCH.to_string());
+    }
+    Ok(first)
+}
+
+/// Read a passphrase from the `env` variable, or prompt for it on the
+/// terminal without echo.
+fn read_passphrase(prompt: &str, env: &str) -> Result<zeroize::Zeroizing<String>, String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal;
+    use std::io::Write;
+
+    if let Ok(passphrase) = std::env::var(env) {
+        return Ok(zeroize::Zeroizing::new(passphrase));
+    }
+
+    eprint!("{prompt}");
+    let _ = std::io::stderr().flush();
+    terminal::enable_raw_mode().map_err(|e| format!("Cannot read passphrase: {e}"))?;
+
+    let mut passphrase = zeroize::Zeroizing::new(String::new());
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err("Cancelled".to_string());
+                }
+                KeyCode::Esc => break Err("Cancelled".to_string()),
+                KeyCode::Backspace => {
+                    passphrase.pop();
+                }
+                KeyCode::Char(c) => passphrase.push(c),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(format!("Cannot read passphrase: {e}")),
+        }
+    };
+
+    let _ = terminal::disable_raw_mode();
+    eprintln!();
+    result.map(|()| passphrase)
 }
 
 /// Handles the update command by running cargo install.
@@ -162,6 +966,342 @@ fn handle_update() {
 
 /// Handles the release-killswitch command.
 /// Emergency release of kill switch firewall rules.
+/// Handles `vortix killswitch policy`.
+fn handle_killswitch_policy(args: &PolicyArgs) {
+    use crate::core::killswitch::{self, Ipv6Policy, KillSwitchPolicy};
+
+    let fail = |e: String| -> ! {
+        eprintln!("{}{}", constants::CLI_MSG_ERROR, e);
+        std::process::exit(1);
+    };
+
+    let PolicyArgs {
+        lan,
+        dhcp,
+        dns_tunnel_only,
+        ipv6,
+        allow,
+        allow_port,
+        portal_url,
+        portal_window,
+        remove,
+        reset,
+    } = args;
+
+    let mut policy = if *reset {
+        KillSwitchPolicy::default()
+    } else {
+        killswitch::load_policy()
+    };
+    let before = policy.clone();
+
+    policy.allow_lan = lan.unwrap_or(policy.allow_lan);
+    policy.allow_dhcp = dhcp.unwrap_or(policy.allow_dhcp);
+    policy.dns_tunnel_only = dns_tunnel_only.unwrap_or(policy.dns_tunnel_only);
+    if let Some(ipv6) = ipv6 {
+        policy.ipv6 = match ipv6 {
+            Ipv6Choice::Block => Ipv6Policy::Block,
+            Ipv6Choice::Lan => Ipv6Policy::Lan,
+            Ipv6Choice::Allow => Ipv6Policy::Allow,
+        };
+    }
+    for net in allow {
+        let net = net
+            .parse::<crate::vpn::wireguard::Cidr>()
+            .unwrap_or_else(|e| fail(e))
+            .to_string();
+        if !policy.allowed_cidrs.contains(&net) {
+            policy.allowed_cidrs.push(net);
+        }
+    }
+    for port in allow_port {
+        let port = killswitch::parse_port_rule(port).unwrap_or_else(|e| fail(e));
+        if !policy.allowed_ports.contains(&port) {
+            policy.allowed_ports.push(port);
+        }
+    }
+    if let Some(url) = portal_url {
+        policy.portal_url = match url.trim() {
+            "" | "off" => String::new(),
+            url if url.starts_with("http://") => url.to_string(),
+            url => fail(format!(
+                "'{url}' must be a plain http:// URL (portals cannot redirect HTTPS)"
+            )),
+        };
+    }
+    if let Some(secs) = portal_window {
+        if !(1..=constants::PORTAL_WINDOW_MAX_SECS).contains(secs) {
+            fail(format!(
+                "portal window must be 1-{} seconds",
+                constants::PORTAL_WINDOW_MAX_SECS
+            ));
+        }
+        policy.portal_window_secs = *secs;
+    }
+    for target in remove {
+        let target = target.trim().to_ascii_lowercase();
+        let net = target
+            .parse::<crate::vpn::wireguard::Cidr>()
+            .map_or_else(|_| target.clone(), |c| c.to_string());
+        policy.allowed_cidrs.retain(|c| *c != net);
+        policy.allowed_ports.retain(|p| *p != target);
+    }
+
+    if policy != before || *reset {
+        killswitch::save_policy(&policy).unwrap_or_else(|e| fail(e.to_string()));
+        println!("{}", constants::CLI_MSG_KS_POLICY_SAVED);
+    }
+    println!("{}{}", constants::CLI_MSG_KS_POLICY, policy.summary());
+    for (label, list) in [
+        ("networks", &policy.allowed_cidrs),
+        ("ports", &policy.allowed_ports),
+    ] {
+        if !list.is_empty() {
+            println!("   allowed {label}: {}", list.join(", "));
+        }
+    }
+    println!(
+        "   captive portal: {}, window {}s",
+        if policy.portal_url.is_empty() {
+            "probe off"
+        } else {
+            &policy.portal_url
+        },
+        policy.portal_window_secs
+    );
+
+    println!();
+    print!("{}", current_rules(&policy).rules);
+}
+
+/// Handles `vortix killswitch <action>`.
+fn handle_killswitch(action: &KillSwitchAction) {
+    match action {
+        KillSwitchAction::Show { dry_run } => handle_killswitch_show(*dry_run),
+        KillSwitchAction::Policy(args) => handle_killswitch_policy(args),
+        KillSwitchAction::Status => handle_killswitch_status(),
+        KillSwitchAction::Portal => handle_killswitch_portal(),
+    }
+}
+
+/// Handles `vortix killswitch show [--dry-run]`.
+fn handle_killswitch_show(dry_run: bool) {
+    let mut preview = current_rules(&crate::core::killswitch::load_policy());
+    print!("{}", preview.rules);
+    if dry_run {
+        match preview.dry_run() {
+            Ok(()) => println!("\n{}", constants::MSG_KS_RULES_VALID),
+            Err(e) => {
+                eprintln!("\n{}{}", constants::CLI_MSG_ERROR, e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Handles `vortix killswitch status`.
+fn handle_killswitch_status() {
+    use crate::core::killswitch;
+
+    let Some(state) = killswitch::load_state() else {
+        println!("{}", constants::CLI_MSG_KS_NO_STATE);
+        return;
+    };
+    println!("Kill switch: {:?} (mode {:?})", state.state, state.mode);
+    let session = match state.pid {
+        Some(pid) if state.owner_gone() => format!("PID {pid}, not running"),
+        Some(pid) => format!("PID {pid}, running"),
+        None => "unknown".to_string(),
+    };
+    println!("   session:   {session}");
+    if let Some(interface) = &state.vpn_interface {
+        println!("   interface: {interface}");
+    }
+    if !state.endpoints.is_empty() {
+        let hosts: Vec<_> = state.endpoints.iter().map(|e| e.host.as_str()).collect();
+        println!(
+            "   servers:   {} ({})",
+            killswitch::server_addresses(&state.endpoints).join(", "),
+            hosts.join(", ")
+        );
+    }
+    println!("   policy:    {}", killswitch::load_policy().summary());
+    if state.is_stale() {
+        println!("\n{}", constants::CLI_MSG_KS_STALE);
+    }
+}
+
+/// Handles `vortix killswitch portal`.
+fn handle_killswitch_portal() {
+    use crate::core::portal::{self, Probe};
+
+    let policy = crate::core::killswitch::load_policy();
+    if policy.portal_url.is_empty() {
+        println!("{}", constants::MSG_PORTAL_PROBE_OFF);
+        return;
+    }
+    println!("Probing {} ...", policy.portal_url);
+    match portal::probe(&policy.portal_url) {
+        Probe::Open => println!("{}", constants::CLI_MSG_PORTAL_OPEN),
+        Probe::Portal { location } => println!(
+            "{}{}",
+            constants::CLI_MSG_PORTAL_FOUND,
+            location.as_deref().unwrap_or("unknown")
+        ),
+        Probe::Unreachable(reason) => println!("{}{reason}", constants::CLI_MSG_PORTAL_UNREACHABLE),
+    }
+    match portal::gateway() {
+        Some(gateway) => println!(
+            "   window would allow: HTTP/HTTPS to {}, DNS to {} on {}, for {}s",
+            gateway.network, gateway.address, gateway.interface, policy.portal_window_secs
+        ),
+        None => println!("   {}", constants::MSG_PORTAL_NO_GATEWAY),
+    }
+}
+
+/// The rules the kill switch would install for the active connection (or
+/// the default interface), after a line saying which one that is.
+fn current_rules(
+    policy: &crate::core::killswitch::KillSwitchPolicy,
+) -> crate::core::killswitch::RulePreview {
+    use crate::core::killswitch;
+
+    let profiles = crate::vpn::load_profiles();
+    let session = crate::core::scanner::get_active_profiles(&profiles)
+        .into_iter()
+        .next();
+    let cached = killswitch::load_state()
+        .map(|s| s.endpoints)
+        .unwrap_or_default();
+    let Some(session) = session else {
+        println!(
+            "{}{})",
+            constants::CLI_MSG_KS_PREVIEW_IDLE,
+            killswitch::DEFAULT_VPN_INTERFACE
+        );
+        return killswitch::RulePreview::new(
+            killswitch::DEFAULT_VPN_INTERFACE,
+            &killswitch::server_addresses(&cached),
+            None,
+            policy,
+        );
+    };
+
+    println!(
+        "{}{} on {}, server {})",
+        constants::CLI_MSG_KS_PREVIEW,
+        session.name,
+        session.interface,
+        session.endpoint
+    );
+    let profile = profiles.iter().find(|p| p.name == session.name);
+    let hosts = profile.map(crate::vpn::server_hosts).unwrap_or_default();
+    let mut servers = killswitch::server_addresses(&killswitch::resolve_endpoints(&hosts, &cached));
+    let current = session.endpoint.split(':').next().unwrap_or("");
+    if !current.is_empty() && !servers.iter().any(|s| s == current) {
+        servers.push(current.to_string());
+    }
+    let split = profile
+        .filter(|p| !p.split_tunnel.is_empty())
+        .and_then(|p| {
+            p.split_tunnel
+                .resolve()
+                .map_err(|e| eprintln!("{}{e}", constants::CLI_MSG_WARNING))
+                .ok()
+        });
+    killswitch::RulePreview::new(&session.interface, &servers, split.as_ref(), policy)
+}
+
+/// Handles `vortix audit`: runs every leak check against the active
+/// connection and prints or writes the report. Exits with status 1 when a
+/// check fails.
+fn handle_audit(format: AuditFormat, output: Option<&Path>) {
+    use crate::core::{audit, killswitch, leaks, routes, telemetry};
+
+    let profiles = crate::vpn::load_profiles();
+    let session = crate::core::scanner::get_active_profiles(&profiles)
+        .into_iter()
+        .next();
+    let profile = session
+        .as_ref()
+        .and_then(|s| profiles.iter().find(|p| p.name == s.name));
+
+    let public_ip = telemetry::fetch_public_ip();
+    let real_ip = if session.is_none() {
+        if let Some(ip) = &public_ip {
+            audit::save_real_ip(ip);
+        }
+        public_ip.clone()
+    } else {
+        audit::load_real_ip()
+    };
+
+    let (interface, endpoint) = session
+        .as_ref()
+        .map_or(("", ""), |s| (s.interface.as_str(), s.endpoint.as_str()));
+    let dns = profile.map(|p| leaks::check_dns(interface, &crate::vpn::expected_dns(p)));
+    let ipv6 = session.as_ref().map(|_| leaks::check_ipv6(interface));
+    let route_table = routes::inspect(interface, endpoint);
+    let allowed_ips = profile.and_then(crate::vpn::allowed_ips);
+    let state = killswitch::load_state();
+    let wireguard = profile.is_some_and(|p| p.protocol == crate::state::Protocol::WireGuard);
+    // Read last: the checks above sent traffic through the tunnel, which
+    // makes an idle peer handshake again
+    let handshake = session.as_ref().filter(|_| wireguard).map(|s| {
+        crate::core::scanner::get_active_profiles(&profiles)
+            .into_iter()
+            .find(|a| a.name == s.name)
+            .map_or_else(|| s.latest_handshake.clone(), |a| a.latest_handshake)
+    });
+
+    let inputs = audit::Inputs {
+        interface,
+        exit_ip: public_ip.as_deref(),
+        real_ip: real_ip.as_deref(),
+        dns: dns.as_ref(),
+        ipv6: ipv6.as_ref(),
+        routes: &route_table,
+        split_tunnel: profile.is_some_and(|p| !p.split_tunnel.is_empty()),
+        killswitch_mode: state.as_ref().map_or_else(Default::default, |s| s.mode),
+        killswitch_state: state.as_ref().map_or_else(Default::default, |s| s.state),
+        killswitch_stale: state
+            .as_ref()
+            .is_some_and(killswitch::PersistedState::is_stale),
+        allowed_ips: allowed_ips.as_deref(),
+        handshake: handshake.as_deref(),
+        keepalive: profile.is_some_and(crate::vpn::has_keepalive),
+    };
+    let report = audit::Report::new(
+        profile.map(|p| p.name.as_str()),
+        profile.map(|p| p.protocol.to_string()).as_deref(),
+        &inputs,
+        crate::utils::format_utc_timestamp(),
+    );
+
+    let rendered = match format {
+        AuditFormat::Text => report.to_text(),
+        AuditFormat::Json => report.to_json() + "\n",
+        AuditFormat::Markdown => report.to_markdown(),
+    };
+    match output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, &rendered) {
+                eprintln!(
+                    "{}Failed to write {}: {e}",
+                    constants::CLI_MSG_ERROR,
+                    path.display()
+                );
+                std::process::exit(2);
+            }
+            println!("{}{}", constants::CLI_MSG_AUDIT_WRITTEN, path.display());
+        }
+        None => print!("{rendered}"),
+    }
+    if !report.passed {
+        std::process::exit(1);
+    }
+}
+
 fn handle_release_killswitch() {
     println!("Releasing kill switch...");
 
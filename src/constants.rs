@@ -32,6 +32,18 @@ pub const PROFILES_DIR_NAME: &str = "profiles";
 pub const LOGS_DIR_NAME: &str = "logs";
 /// Name of the profile metadata file.
 pub const METADATA_FILE_NAME: &str = "metadata.json";
+/// Name of the sidebar preferences file (grouping, sort, collapsed groups).
+pub const SIDEBAR_PREFS_FILE_NAME: &str = "sidebar.json";
+/// Kill switch allow-list (LAN, extra networks and ports, DNS, IPv6).
+pub const KILLSWITCH_POLICY_FILE_NAME: &str = "killswitch.json";
+/// Registered subscription URLs and their sync state.
+pub const SUBSCRIPTIONS_FILE_NAME: &str = "subscriptions.json";
+/// Public IP last seen while disconnected, compared by `vortix audit`.
+pub const REAL_IP_FILE_NAME: &str = "real_ip";
+/// Name of the saved `OpenVPN` credentials subdirectory.
+pub const CREDENTIALS_DIR_NAME: &str = "credentials";
+/// Name of the vault header file (its presence enables encrypted profiles).
+pub const VAULT_FILE_NAME: &str = "vault.json";
 
 // === Telemetry API Endpoints ===
 
@@ -44,7 +56,11 @@ pub const IP_API_FALLBACK_2: &str = "https://icanhazip.com";
 /// Fallback API 3: ifconfig.me (IP only).
 pub const IP_API_FALLBACK_3: &str = "https://ifconfig.me/ip";
 
-/// IPv6 leak detection endpoints (any success = leak).
+/// Public IPv6 address used for route lookups in the IPv6 leak check (no
+/// traffic is sent to it).
+pub const IPV6_PROBE_ADDR: &str = "2001:4860:4860::8888";
+
+/// Optional remote IPv6 leak confirmation endpoints (any success = leak).
 pub const IPV6_CHECK_APIS: [&str; 3] = [
     "https://ipv6.icanhazip.com",
     "https://v6.ident.me",
@@ -63,8 +79,29 @@ pub const PING_TARGETS: [&str; 4] = [
 pub const API_TIMEOUT_SECS: u8 = 5;
 /// Timeout for file downloads in seconds.
 pub const HTTP_TIMEOUT_SECS: u64 = 10;
+/// Largest profile or archive accepted from a URL, in bytes.
+pub const MAX_DOWNLOAD_BYTES: u64 = 16 * 1024 * 1024;
 /// Timeout for ping commands in seconds.
 pub const PING_TIMEOUT_SECS: u8 = 2;
+/// How often the TUI looks for subscriptions due for a refresh, in seconds.
+pub const SUBSCRIPTION_CHECK_INTERVAL_SECS: u64 = 300;
+/// How often latency measured while connected is written to
+/// `metadata.json`, in seconds (it is also written on disconnect and quit).
+pub const LATENCY_SAVE_INTERVAL_SECS: u64 = 300;
+/// How often the kill switch's VPN server hostnames are resolved again, in
+/// seconds.
+pub const ENDPOINT_REFRESH_INTERVAL_SECS: u64 = 600;
+/// Default captive portal probe: answers 200 on an open network, and is
+/// redirected to the login page behind a portal.
+pub const PORTAL_PROBE_URL: &str = "http://captive.apple.com/hotspot-detect.html";
+/// Default length of a captive portal window, in seconds.
+pub const PORTAL_WINDOW_SECS: u64 = 120;
+/// Longest portal window `vortix killswitch policy` accepts, in seconds.
+pub const PORTAL_WINDOW_MAX_SECS: u64 = 900;
+/// Timeout of a portal probe in seconds.
+pub const PORTAL_PROBE_TIMEOUT_SECS: u64 = 5;
+/// How often the portal is probed while a window is open, in seconds.
+pub const PORTAL_RECHECK_SECS: u64 = 5;
 /// Delay between retry attempts in milliseconds.
 pub const RETRY_DELAY_MS: u64 = 500;
 /// Number of retry attempts per API/target.
@@ -90,6 +127,16 @@ pub const MSG_DETECTING: &str = "Detecting...";
 pub const MSG_FETCHING: &str = "Fetching...";
 /// No data available placeholder.
 pub const MSG_NO_DATA: &str = "---";
+/// Security Guard placeholder until the first DNS route check finishes.
+pub const MSG_DNS_CHECKING: &str = "  Checking resolver routes...";
+/// Security Guard note for a profile DNS server the system does not use.
+pub const MSG_DNS_UNUSED: &str = "profile DNS not in use";
+/// Security Guard note when only the remote IPv6 check saw a leak.
+pub const MSG_IPV6_REMOTE_LEAK: &str = "an IPv6-only site answered (remote check)";
+/// Routes panel summary while no tunnel is up.
+pub const MSG_ROUTES_NO_TUNNEL: &str = "No tunnel";
+/// Resolver findings listed in the Security Guard before "+N more".
+pub const DNS_FINDINGS_SHOWN: usize = 3;
 
 // === Cryptographic Defaults ===
 
@@ -104,13 +151,98 @@ pub const DEFAULT_IMPORTED_FILENAME: &str = "imported_profile.conf";
 
 pub const TITLE_IMPORT_PROFILE: &str = " Import VPN Profile ";
 pub const TITLE_IMPORT_FOOTER: &str = " [Enter] Import  [Esc] Cancel ";
+pub const TITLE_EXPORT_PROFILES: &str = " 📦 Export Profiles ";
+pub const TITLE_EXPORT_FOOTER: &str = " [Tab] Switch Field  [Enter] Export  [Esc] Cancel ";
+pub const LABEL_EXPORT_PATH: &str = "Bundle file";
+pub const LABEL_EXPORT_PASSPHRASE: &str = "Passphrase";
+pub const HINT_EXPORT_PASSPHRASE: &str = "💡 Leave the passphrase empty for an unencrypted bundle";
+pub const DEFAULT_EXPORT_FILE: &str = "~/vortix-profiles";
+pub const TITLE_QR_WARNING: &str = " ⚠ Private Key Exposure ";
+pub const TITLE_QR_WARNING_FOOTER: &str = " [Enter] Show QR  [h] Toggle Hooks  [Esc] Cancel ";
+pub const TITLE_QR_FOOTER: &str = " [Esc] Close ";
+pub const TITLE_KS_PREVIEW_FOOTER: &str = " [Esc] Close  [↑/↓] Scroll  [v] Validate (dry run) ";
+pub const MSG_KS_PREVIEW_HINT: &str = "Not applied. Press v to check the rules with pfctl -n.";
+pub const MSG_KS_RULES_VALID: &str = "✓ pfctl accepts these rules (dry run, nothing applied)";
+pub const TITLE_KS_STALE: &str = " ⚠ Kill Switch Still Blocking ";
+pub const TITLE_KS_STALE_FOOTER: &str = " [r/Enter] Restore  [x] Release ";
+pub const MSG_KS_STALE: &str = "A previous vortix session ended while the kill switch was blocking. Non-VPN traffic may still be cut off.";
+pub const MSG_KS_STALE_CHOICES: &str =
+    "Restore keeps blocking under this session; Release restores normal network access.";
+pub const TITLE_PORTAL: &str = " 🌐 Captive Portal Window ";
+pub const TITLE_PORTAL_FOOTER: &str = " [Enter/y] Open Window  [Esc/n] Cancel ";
+pub const MSG_PORTAL_BLOCKED: &str = "The connection did not come up while the kill switch blocks. If this network has a login page (hotel, airport), open a portal window to reach it.";
+pub const MSG_PORTAL_MANUAL: &str =
+    "Let a captive portal's login page through the kill switch for a limited time.";
+pub const MSG_PORTAL_REARM: &str =
+    "Full blocking returns when the time is up or the login is detected. The exception is logged.";
+pub const MSG_PORTAL_HINT: &str = "[c] Captive portal window";
+pub const MSG_PORTAL_NOT_BLOCKING: &str =
+    "Kill switch is not blocking; checking for a captive portal";
+pub const MSG_PORTAL_NO_GATEWAY: &str = "No default gateway found for a portal window";
+pub const MSG_PORTAL_ROOT: &str = "A portal window requires root privileges";
+pub const MSG_PORTAL_PROBE_OFF: &str =
+    "Captive portal probe is off (vortix killswitch policy --portal-url)";
+pub const CLI_MSG_PORTAL_OPEN: &str = "No captive portal: the probe answered directly";
+pub const CLI_MSG_PORTAL_FOUND: &str = "⚠ Captive portal detected, login page: ";
+pub const CLI_MSG_PORTAL_UNREACHABLE: &str = "Probe got no answer: ";
+pub const QR_WARNING_TEXT: &str = "The QR code contains this profile's private key. Anyone who can see or photograph your screen can impersonate this peer. Make sure nobody is watching and no screen sharing or recording is active.";
+pub const LABEL_QR_EXCLUDE_HOOKS: &str = "Exclude PostUp/PostDown";
+pub const MSG_QR_WIREGUARD_ONLY: &str = "QR codes are only available for WireGuard profiles";
+pub const TITLE_NEW_PROFILE: &str = " ✨ New WireGuard Profile ";
+pub const TITLE_NEW_PROFILE_FOOTER: &str =
+    " [Tab/↑↓] Switch Field  [Ctrl+U] Clear  [Enter] Create  [Esc] Cancel ";
+pub const HINT_NEW_PROFILE: &str =
+    "💡 A fresh key pair is generated; the private key never leaves this machine";
+pub const TITLE_PROFILE_CREATED: &str = " ✓ Profile Created ";
+pub const TITLE_PROFILE_CREATED_FOOTER: &str = " [Enter/Esc] Close ";
+pub const MSG_PROFILE_CREATED_HINT: &str =
+    "Add this public key as a peer on the server before connecting:";
+pub const TITLE_IMPORT_CONFIRM: &str = " 📥 Import Profile ";
+pub const TITLE_IMPORT_CONFIRM_FOOTER: &str =
+    " [Tab/↑↓] Switch Field  [Ctrl+U] Clear  [Enter] Save  [Esc] Cancel ";
+pub const LABEL_ROUTES_FROM_SERVER: &str = "pushed by the server";
+pub const TITLE_IMPORT_PREVIEW: &str = " 📂 Import Preview ";
+pub const TITLE_IMPORT_PREVIEW_FOOTER: &str =
+    " [↑↓] Move  [Space] Toggle  [a] All/None  [Enter] Import  [Esc] Cancel ";
+pub const MSG_IMPORT_PREVIEW_OK: &str = "✓ Ready to import";
+pub const TITLE_DUPLICATE: &str = " ⚠ Duplicate Profile ";
+pub const TITLE_DUPLICATE_FOOTER: &str =
+    " [s] Skip  [o] Overwrite  [k] Keep Both  [a] Apply to All  [Esc] Stop ";
+pub const LABEL_DUPLICATE_APPLY_ALL: &str = "Apply to remaining duplicates";
+pub const TITLE_BUNDLE_UNLOCK: &str = " 📦 Encrypted Bundle ";
+pub const TITLE_BUNDLE_UNLOCK_FOOTER: &str = " [Enter] Import  [Esc] Cancel ";
+pub const PROMPT_BUNDLE_PASSPHRASE: &str = "This bundle is encrypted. Enter its passphrase:";
 pub const PROMPT_IMPORT_PATH: &str = "Enter path to file, directory, or URL:";
 pub const HINT_IMPORT_BULK: &str = "💡 Tip: Enter a directory to bulk import all profiles";
 pub const LABEL_SUPPORTED_FORMATS: &str = "Supported formats:";
 pub const EXT_CONF: &str = ".conf";
 pub const EXT_OVPN: &str = ".ovpn";
+pub const EXT_ZIP: &str = ".zip";
+/// Most entries read from an imported ZIP archive
+pub const MAX_ARCHIVE_ENTRIES: usize = 10_000;
+/// Most bytes unpacked from an imported ZIP archive
+pub const MAX_ARCHIVE_BYTES: u64 = 64 * 1024 * 1024;
+/// Deepest folder level searched below an imported directory
+pub const MAX_IMPORT_DEPTH: usize = 8;
 pub const PROTO_WIREGUARD: &str = "WireGuard";
 pub const PROTO_OPENVPN: &str = "OpenVPN";
+pub const TITLE_PROFILES: &str = " Profiles ";
+pub const MSG_NO_SEARCH_MATCHES: &str = "No matching profiles";
+pub const HINT_SEARCH_CLEAR: &str = "  [Esc] clear";
+pub const MSG_GROUP_BY: &str = "Group by: ";
+pub const MSG_SORT_BY: &str = "Sort by: ";
+pub const TITLE_CREDENTIALS: &str = " 🔑 Credentials: ";
+pub const TITLE_CREDENTIALS_FOOTER: &str =
+    " [Tab] Next  [Space] Save option  [Enter] Connect  [Esc] Cancel ";
+pub const LABEL_SAVE_CREDENTIALS: &str = "Remember";
+pub const MSG_CREDENTIALS_CANCELLED: &str = "Connection cancelled";
+pub const MSG_CREDENTIALS_FORGOTTEN: &str = "Forgot saved credentials for ";
+pub const MSG_NO_SAVED_CREDENTIALS: &str = "No saved credentials for ";
+pub const MSG_VAULT_LOCKED: &str = "Vault is locked: unlock it with the vault passphrase first";
+pub const MSG_VAULT_UNLOCKED: &str = "Vault unlocked";
+pub const TITLE_VAULT_UNLOCK: &str = " 🔒 Unlock Profile Vault ";
+pub const TITLE_VAULT_UNLOCK_FOOTER: &str = " [Enter] Unlock  [Esc] Skip ";
+pub const PROMPT_VAULT_PASSPHRASE: &str = "Profiles are encrypted. Enter the vault passphrase:";
 
 // === Messages: General (Toast/Logs) ===
 
@@ -118,6 +250,15 @@ pub const MSG_DOWNLOADING: &str = "Downloading profile...";
 pub const MSG_DOWNLOAD_FAILED: &str = "Download failed: ";
 pub const MSG_IMPORT_SUCCESS: &str = "Imported: ";
 pub const MSG_IMPORT_ERROR: &str = "Error: ";
+pub const MSG_EXPORT_SUCCESS: &str = "Exported profiles: ";
+pub const MSG_EXPORT_ERROR: &str = "Export failed: ";
+pub const MSG_NOTHING_TO_EXPORT: &str = "No profiles to export";
+pub const MSG_SUBS_REFRESHING: &str = "Refreshing subscriptions...";
+pub const MSG_SUBS_BUSY: &str = "Subscriptions are already refreshing";
+pub const MSG_SUBS_NONE: &str = "No subscriptions. Add one with: vortix subscription add <url>";
+pub const MSG_SUBS_UPDATED: &str = "Subscriptions updated";
+pub const MSG_SUBS_UP_TO_DATE: &str = "Subscriptions are up to date";
+pub const MSG_SUBS_FAILED: &str = "Subscription refresh failed (see logs)";
 pub const MSG_PATH_NOT_FOUND: &str = "Path not found: ";
 pub const MSG_INVALID_PATH_TYPE: &str = "Invalid path type";
 pub const MSG_NO_FILES_FOUND: &str = "No .conf or .ovpn files found";
@@ -129,19 +270,104 @@ pub const MSG_BATCH_LOG_TEMPLATE: &str = "IMPORT: Batch imported {} profiles fro
 // === Messages: CLI Output ===
 
 pub const CLI_MSG_DOWNLOADING: &str = "Downloading profile from URL...";
+pub const CLI_MSG_VERIFY_URL_ONLY: &str =
+    "Error: --sha256 and --signature only apply to URL imports";
 pub const CLI_MSG_IMPORT_SUCCESS: &str = "Imported profile: ";
 pub const CLI_MSG_IMPORT_DETAILS_PROTO: &str = "   Protocol: ";
 pub const CLI_MSG_IMPORT_DETAILS_LOC: &str = "   Location: ";
 pub const CLI_MSG_IMPORT_DETAILS_PATH: &str = "   Saved to: ";
 pub const CLI_MSG_IMPORT_FAILED: &str = "Import failed: ";
+pub const CLI_MSG_IMPORT_WARNING: &str = "   ⚠ Warning: ";
 pub const CLI_MSG_PATH_NOT_FOUND: &str = "Path not found: ";
 pub const CLI_MSG_INVALID_PATH: &str = "Invalid path type";
 pub const CLI_MSG_SUMMARY_HEADER: &str = "\nImport Summary:";
 pub const CLI_MSG_SUMMARY_IMPORTED: &str = "   Imported: ";
 pub const CLI_MSG_SUMMARY_FAILED: &str = "   Failed: ";
-pub const CLI_MSG_NO_FILES: &str = "\nNo .conf or .ovpn files found in directory";
+pub const CLI_MSG_NO_FILES: &str = "\nNo matching .conf or .ovpn files found";
+pub const CLI_MSG_DRY_RUN_HEADER: &str = "Dry run - nothing will be imported:";
+pub const CLI_MSG_DRY_RUN_SUMMARY: &str = "\nWould import: ";
+pub const CLI_MSG_DRY_RUN_BUNDLE: &str = "--dry-run is not supported for bundles";
 pub const CLI_MSG_DIR_ERROR: &str = "Error reading directory: ";
 pub const CLI_MSG_ERROR: &str = "Error: ";
+pub const CLI_MSG_WARNING: &str = "Warning: ";
+
+// Bundle Messages
+pub const CLI_PROMPT_BUNDLE_PASSPHRASE: &str = "Bundle passphrase: ";
+pub const CLI_MSG_EXPORT_SUCCESS: &str = "📦 Exported profiles: ";
+pub const CLI_MSG_EXPORT_DETAILS_PATH: &str = "   Saved to: ";
+pub const CLI_MSG_EXPORT_FAILED: &str = "Export failed: ";
+pub const CLI_MSG_UNKNOWN_PROFILE: &str = "Unknown profile: ";
+pub const CLI_MSG_SUMMARY_REPLACED: &str = "   Replaced: ";
+pub const CLI_MSG_SUMMARY_SKIPPED: &str = "   Skipped (already exist): ";
+pub const CLI_MSG_IMPORT_SKIPPED: &str = "⏭  Skipped duplicate: ";
+pub const CLI_PROMPT_DUPLICATE: &str =
+    "  [s]kip, [o]verwrite or [k]eep both? (S/O/K = same for all remaining) ";
+pub const CLI_MSG_DUPLICATE_NOT_ASKED: &str =
+    "  Skipped (not a terminal; pass --on-conflict to choose)";
+pub const ENV_BUNDLE_PASSPHRASE: &str = "VORTIX_BUNDLE_PASSPHRASE";
+
+// New Profile Messages
+pub const CLI_PROMPT_NEW_ADDRESS: &str = "Interface address (e.g. 10.0.0.2/32): ";
+pub const CLI_PROMPT_NEW_DNS: &str = "DNS servers (optional): ";
+pub const CLI_PROMPT_NEW_PEER_KEY: &str = "Server public key: ";
+pub const CLI_PROMPT_NEW_ENDPOINT: &str = "Server endpoint (host:port): ";
+pub const CLI_MSG_NEW_SUCCESS: &str = "🔑 Created profile: ";
+pub const CLI_MSG_NEW_PUBLIC_KEY: &str = "   Public key: ";
+pub const CLI_MSG_NEW_REGISTER: &str =
+    "   Register this public key as a peer on the server before connecting.";
+pub const CLI_MSG_NEW_FAILED: &str = "Failed to create profile: ";
+
+// Subscription Messages
+pub const CLI_MSG_SUB_ADDED: &str = "🔗 Subscribed: ";
+pub const CLI_MSG_SUB_REMOVED: &str = "Removed subscription: ";
+pub const CLI_MSG_SUB_UNKNOWN: &str = "Unknown subscription: ";
+pub const CLI_MSG_SUB_NONE: &str = "No subscriptions. Add one with: vortix subscription add <url>";
+pub const CLI_MSG_SUB_DRY_RUN: &str = "Dry run: no profiles were changed.";
+pub const CLI_MSG_SUB_FAILED: &str = " change(s) could not be applied";
+
+// Tag Messages
+pub const CLI_MSG_TAG_NONE: &str = "no tags";
+pub const CLI_MSG_TAG_INVALID: &str = "Tags must be non-empty and contain no spaces";
+
+// Split Tunnel Messages
+pub const CLI_MSG_SPLIT_NONE: &str =
+    "   No split tunnel rules: all traffic follows the profile's routes";
+pub const CLI_MSG_SPLIT_RECONNECT: &str = "Changes apply the next time the profile connects.";
+
+// Kill Switch Messages
+pub const CLI_MSG_KS_POLICY: &str = "Kill switch policy: ";
+pub const CLI_MSG_KS_POLICY_SAVED: &str =
+    "🛡 Kill switch policy saved; it applies the next time the kill switch blocks.";
+pub const CLI_MSG_KS_PREVIEW: &str = "# Rules for the active connection (";
+pub const CLI_MSG_KS_PREVIEW_IDLE: &str = "# Rules while disconnected (default interface ";
+pub const CLI_MSG_KS_NO_STATE: &str = "Kill switch: no saved state (never enabled)";
+pub const CLI_MSG_KS_STALE: &str = "⚠ Left blocking by a vortix that is no longer running. Start vortix to restore it, or run `vortix release-kill-switch`.";
+pub const CLI_MSG_AUDIT_WRITTEN: &str = "Audit report written to ";
+pub const MSG_AUDIT_NO_PROFILE: &str = "none (not connected)";
+pub const MSG_GUARD_KEPT: &str = "🛡 Kill switch (strict mode) keeps blocking non-VPN traffic. Run `vortix release-kill-switch` to restore network access.";
+pub const MSG_GUARD_RELEASED: &str = "Kill switch released; network access restored.";
+pub const MSG_GUARD_BLOCK_FAILED: &str = "⚠ Kill switch (strict mode) could not block: ";
+pub const MSG_GUARD_RELEASE_FAILED: &str =
+    "⚠ Kill switch could not be released (run `vortix release-kill-switch`): ";
+
+// QR Messages
+pub const CLI_MSG_QR_WARNING: &str = "⚠ The QR code contains the private key of this profile.\n  Anyone who can see your screen can copy it. Check that nobody is watching\n  and no screen sharing or recording is active.";
+pub const CLI_PROMPT_QR_CONFIRM: &str = "Show the QR code? [y/N] ";
+pub const CLI_MSG_QR_ABORTED: &str = "Aborted.";
+
+// Vault Messages
+pub const CLI_PROMPT_VAULT_PASSPHRASE: &str = "Vault passphrase: ";
+pub const CLI_PROMPT_VAULT_CONFIRM: &str = "Confirm passphrase: ";
+pub const CLI_MSG_VAULT_MISMATCH: &str = "Passphrases do not match";
+pub const CLI_MSG_VAULT_ENABLED: &str = "🔒 Vault enabled. Profile files encrypted: ";
+pub const CLI_MSG_VAULT_DISABLED: &str = "🔓 Vault disabled. Profile files decrypted: ";
+pub const CLI_MSG_VAULT_STATUS_ON: &str = "Vault: enabled (profiles are encrypted at rest)";
+pub const CLI_MSG_VAULT_STATUS_OFF: &str = "Vault: disabled (profiles are stored as plain files)";
+pub const ENV_VAULT_PASSPHRASE: &str = "VORTIX_VAULT_PASSPHRASE";
+/// Pinned minisign public key that downloads must be signed with
+pub const ENV_SIGNING_KEY: &str = "VORTIX_SIGNING_KEY";
+/// Set to `1` to confirm IPv6 leaks by contacting `IPV6_CHECK_APIS`
+pub const ENV_IPV6_REMOTE_CHECK: &str = "VORTIX_IPV6_REMOTE_CHECK";
 
 pub const CLI_MSG_UPDATE_START: &str = "🔄 Updating vortix...\n";
 pub const CLI_MSG_UPDATE_SUCCESS: &str = "Successfully updated vortix!";
@@ -157,6 +383,11 @@ pub const ERR_HTML_CONTENT: &str =
     "URL returned HTML content. Did you mean to use the 'raw' version of the link?";
 pub const ERR_EMPTY_CONTENT: &str = "Downloaded content is empty";
 pub const ERR_SERVER_ERROR: &str = "Server returned error: ";
+pub const ERR_INSECURE_URL: &str =
+    "Refusing to download over plain HTTP; use an https:// URL (or pass --insecure)";
+pub const ERR_DOWNLOAD_TOO_LARGE: &str = "Download exceeds the size limit of ";
+pub const ERR_CHECKSUM_MISMATCH: &str = "SHA-256 checksum does not match";
+pub const ERR_SIGNATURE_INVALID: &str = "Signature verification failed: ";
 pub const ERR_HTTP_CLIENT_BUILD_FAILED: &str = "Failed to build HTTP client";
 pub const ERR_NETWORK_REQUEST_FAILED: &str = "Network request failed";
 pub const ERR_READ_CONTENT_FAILED: &str = "Failed to read content";
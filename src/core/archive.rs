@@ -0,0 +1,164 @@
+//! Provider ZIP archives.
+//!
+//! An archive is unpacked into a private scratch directory with its folder
+//! layout intact, so relative `ca`/`cert`/`tls-auth` references in the
+//! configs resolve against the files shipped next to them.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::importer::{is_config_file, Filter};
+use super::vault::{self, ScratchDir};
+use crate::constants;
+
+/// Local file header signature of a ZIP archive.
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// An unpacked archive. The files are removed when this is dropped.
+pub struct Extracted {
+    pub dir: ScratchDir,
+    /// Configs to import, sorted by their path inside the archive.
+    pub configs: Vec<PathBuf>,
+}
+
+/// Whether `path` is a ZIP archive, by extension or content (downloads do
+/// not always keep their name).
+pub fn is_archive(path: &Path) -> bool {
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+    {
+        return true;
+    }
+    let mut magic = [0u8; 4];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok()
+        && magic == ZIP_MAGIC
+}
+
+/// Unpack `path` and list the `.conf`/`.ovpn` files selected by `filter`.
+///
+/// Entries that would land outside the scratch directory are rejected, and
+/// the total size is capped so a hostile archive cannot fill the disk.
+pub fn extract(path: &Path, filter: &Filter) -> Result<Extracted, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Not a valid ZIP archive: {e}"))?;
+    if archive.len() > constants::MAX_ARCHIVE_ENTRIES {
+        return Err(format!(
+            "Archive has too many entries ({}, limit {})",
+            archive.len(),
+            constants::MAX_ARCHIVE_ENTRIES
+        ));
+    }
+
+    let dir = ScratchDir::new("archive")?;
+    let mut configs = Vec::new();
+    let mut budget = constants::MAX_ARCHIVE_BYTES;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read archive entry: {e}"))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(relative) = entry.enclosed_name() else {
+            return Err(format!("Unsafe path in archive: {}", entry.name()));
+        };
+        if is_metadata(&relative) {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        (&mut entry)
+            .take(budget + 1)
+            .read_to_end(&mut data)
+            .map_err(|e| format!("Failed to extract {}: {e}", relative.display()))?;
+        budget = budget.checked_sub(data.len() as u64).ok_or_else(|| {
+            format!(
+                "Archive is larger than {} MiB unpacked",
+                constants::MAX_ARCHIVE_BYTES >> 20
+            )
+        })?;
+
+        let target = dir.path.join(&relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to extract {}: {e}", relative.display()))?;
+        }
+        vault::write_private(&target, &data)
+            .map_err(|e| format!("Failed to extract {}: {e}", relative.display()))?;
+
+        if is_config_file(&relative) && filter.matches(&relative) {
+            configs.push(relative);
+        }
+    }
+
+    configs.sort();
+    let configs = configs.iter().map(|r| dir.path.join(r)).collect();
+    Ok(Extracted { dir, configs })
+}
+
+/// Resource forks and folders added by macOS' archiver.
+fn is_metadata(path: &Path) -> bool {
+    path.components().any(|c| c.as_os_str() == "__MACOSX")
+        || path
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with("._"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_zip(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_extract_keeps_layout_and_filters() {
+        let scratch = ScratchDir::new("test-archive").unwrap();
+        let zip_path = scratch.path.join("provider.zip");
+        write_zip(
+            &zip_path,
+            &[
+                ("ovpn/de-fra.ovpn", "remote de 1194\nca certs/ca.crt\n"),
+                ("ovpn/certs/ca.crt", "CA"),
+                ("wg/us-nyc.conf", "[Interface]\n"),
+                ("__MACOSX/ovpn/._de-fra.ovpn", "junk"),
+                ("README.txt", "hi"),
+            ],
+        );
+        assert!(is_archive(&zip_path));
+
+        let all = extract(&zip_path, &Filter::default()).unwrap();
+        let names: Vec<_> = all
+            .configs
+            .iter()
+            .map(|p| p.strip_prefix(&all.dir.path).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                PathBuf::from("ovpn/de-fra.ovpn"),
+                PathBuf::from("wg/us-nyc.conf")
+            ]
+        );
+        assert!(all.dir.path.join("ovpn/certs/ca.crt").is_file());
+
+        let filter = Filter::new(&["de-*".to_string()], &[]).unwrap();
+        let filtered = extract(&zip_path, &filter).unwrap();
+        assert_eq!(filtered.configs.len(), 1);
+        assert!(filtered.configs[0].ends_with("ovpn/de-fra.ovpn"));
+    }
+}
@@ -0,0 +1,542 @@
+//! Leak-test audit (`vortix audit`).
+//!
+//! Runs every check vortix knows against the active connection and reports
+//! pass/fail, as evidence for security reviews: exit IP against the real
+//! IP, IPv6 and DNS leaks, the default route, the kill switch, `AllowedIPs`
+//! coverage and handshake freshness. The checks are evaluated from
+//! collected [`Inputs`], so the Security Guard panel shows the same verdicts
+//! from the state the TUI already has.
+//!
+//! The real IP is the public address seen while disconnected; it is kept in
+//! the config directory so a later audit can compare against it.
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use super::leaks::{DnsReport, Ipv6Report, Verdict};
+use super::routes::{RouteKind, RouteTable};
+use crate::constants;
+use crate::state::{KillSwitchMode, KillSwitchState};
+use crate::utils;
+use crate::vpn::wireguard::Cidr;
+
+/// Handshakes older than this mean the session has expired (`WireGuard`
+/// rejects a session after 180 seconds without rekeying). An idle peer
+/// without `PersistentKeepalive` only handshakes again once traffic flows.
+const HANDSHAKE_MAX_AGE_SECS: u64 = 180;
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+    /// Not applicable or not measurable right now.
+    Skip,
+}
+
+impl Status {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+            Self::Skip => "SKIP",
+        }
+    }
+}
+
+/// One audited property.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    /// Stable identifier for JSON consumers.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+}
+
+/// A complete audit.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    /// UTC, RFC 3339.
+    pub timestamp: String,
+    pub profile: Option<String>,
+    pub protocol: Option<String>,
+    pub interface: Option<String>,
+    /// No check failed.
+    pub passed: bool,
+    pub checks: Vec<Check>,
+}
+
+/// What the checks are evaluated from.
+#[derive(Debug, Clone, Copy)]
+pub struct Inputs<'a> {
+    /// Tunnel interface; empty when not connected.
+    pub interface: &'a str,
+    /// Public IP seen now.
+    pub exit_ip: Option<&'a str>,
+    /// Public IP seen while disconnected.
+    pub real_ip: Option<&'a str>,
+    pub dns: Option<&'a DnsReport>,
+    pub ipv6: Option<&'a Ipv6Report>,
+    pub routes: &'a RouteTable,
+    /// The profile has split tunnel rules.
+    pub split_tunnel: bool,
+    pub killswitch_mode: KillSwitchMode,
+    pub killswitch_state: KillSwitchState,
+    /// The kill switch state was left by a session that is gone.
+    pub killswitch_stale: bool,
+    /// `AllowedIPs` of every peer (`WireGuard` only).
+    pub allowed_ips: Option<&'a [Cidr]>,
+    /// `latest handshake` as printed by `wg show` (`WireGuard` only).
+    pub handshake: Option<&'a str>,
+    /// A peer sets `PersistentKeepalive`, so the handshake stays fresh
+    /// even while idle.
+    pub keepalive: bool,
+}
+
+impl Report {
+    /// Evaluate every check and stamp the result.
+    pub fn new(
+        profile: Option<&str>,
+        protocol: Option<&str>,
+        inputs: &Inputs,
+        timestamp: String,
+    ) -> Self {
+        let checks = evaluate(inputs);
+        Self {
+            timestamp,
+            profile: profile.map(str::to_string),
+            protocol: protocol.map(str::to_string),
+            interface: (!inputs.interface.is_empty()).then(|| inputs.interface.to_string()),
+            passed: checks.iter().all(|c| c.status != Status::Fail),
+            checks,
+        }
+    }
+
+    fn counts(&self) -> String {
+        let count = |status| self.checks.iter().filter(|c| c.status == status).count();
+        format!(
+            "{} passed, {} warning(s), {} failed, {} skipped",
+            count(Status::Pass),
+            count(Status::Warn),
+            count(Status::Fail),
+            count(Status::Skip)
+        )
+    }
+
+    fn verdict(&self) -> &'static str {
+        if self.passed {
+            "PASS"
+        } else {
+            "FAIL"
+        }
+    }
+
+    fn subject(&self) -> String {
+        match (&self.profile, &self.protocol, &self.interface) {
+            (Some(profile), Some(protocol), Some(interface)) => {
+                format!("{profile} ({protocol} on {interface})")
+            }
+            (Some(profile), _, _) => profile.clone(),
+            _ => constants::MSG_AUDIT_NO_PROFILE.to_string(),
+        }
+    }
+
+    /// Plain text for the terminal.
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "Vortix audit, {}\nProfile: {}\n\n",
+            self.timestamp,
+            self.subject()
+        );
+        for check in &self.checks {
+            writeln!(
+                out,
+                "{}  {:<15} {}",
+                check.status.label(),
+                check.name,
+                check.detail
+            )
+            .unwrap();
+        }
+        writeln!(out, "\nResult: {} ({})", self.verdict(), self.counts()).unwrap();
+        out
+    }
+
+    /// A Markdown section for review documents.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "## Vortix audit\n\n- **Time:** {}\n- **Profile:** {}\n- **Result:** {} ({})\n\n\
+             | Check | Result | Details |\n|---|---|---|\n",
+            self.timestamp,
+            self.subject(),
+            self.verdict(),
+            self.counts()
+        );
+        for check in &self.checks {
+            writeln!(
+                out,
+                "| {} | {} | {} |",
+                check.name,
+                check.status.label(),
+                check.detail.replace('|', "\\|")
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    /// Pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Run every check on `inputs`, in report order.
+pub fn evaluate(inputs: &Inputs) -> Vec<Check> {
+    let connected = !inputs.interface.is_empty();
+    let check = |id, name, (status, detail)| Check {
+        id,
+        name,
+        status,
+        detail,
+    };
+    let skip = || (Status::Skip, "not connected".to_string());
+    let when_connected = |result: (Status, String)| if connected { result } else { skip() };
+
+    vec![
+        check(
+            "tunnel",
+            "Tunnel",
+            if connected {
+                (Status::Pass, format!("up on {}", inputs.interface))
+            } else {
+                (Status::Fail, "not connected to a VPN".to_string())
+            },
+        ),
+        check("exit_ip", "Exit IP", when_connected(exit_ip(inputs))),
+        check("ipv6", "IPv6 leak", when_connected(ipv6(inputs.ipv6))),
+        check("dns", "DNS path", when_connected(dns(inputs.dns))),
+        check(
+            "default_route",
+            "Default route",
+            when_connected(default_route(inputs)),
+        ),
+        check("kill_switch", "Kill switch", kill_switch(inputs)),
+        check(
+            "allowed_ips",
+            "AllowedIPs",
+            when_connected(allowed_ips(inputs)),
+        ),
+        check(
+            "handshake",
+            "Handshake",
+            when_connected(handshake(inputs.handshake, inputs.keepalive)),
+        ),
+    ]
+}
+
+fn exit_ip(inputs: &Inputs) -> (Status, String) {
+    match (inputs.exit_ip, inputs.real_ip) {
+        (None, _) => (Status::Warn, "exit IP could not be fetched".to_string()),
+        (Some(exit), Some(real)) if exit == real => (
+            Status::Fail,
+            format!("{exit} is the real IP: traffic is not leaving through the VPN"),
+        ),
+        (Some(exit), Some(real)) => (Status::Pass, format!("{exit} (real IP {real} hidden)")),
+        (Some(exit), None) => (
+            Status::Warn,
+            format!("{exit}; real IP unknown (run vortix once while disconnected)"),
+        ),
+    }
+}
+
+fn ipv6(report: Option<&Ipv6Report>) -> (Status, String) {
+    let Some(report) = report else {
+        return (Status::Skip, "not checked".to_string());
+    };
+    let status = match report.verdict {
+        Verdict::Tunnel => Status::Pass,
+        Verdict::Leak => Status::Fail,
+        Verdict::Local | Verdict::Unknown => Status::Warn,
+    };
+    (status, report.reason.clone())
+}
+
+fn dns(report: Option<&DnsReport>) -> (Status, String) {
+    let Some(report) = report else {
+        return (Status::Skip, "not checked".to_string());
+    };
+    if report.resolvers.is_empty() {
+        return (Status::Warn, "no resolvers found".to_string());
+    }
+    let status = if report.is_leaking() {
+        Status::Fail
+    } else if report.is_secure() {
+        Status::Pass
+    } else {
+        Status::Warn
+    };
+    let detail = report
+        .resolvers
+        .iter()
+        .filter(|r| status == Status::Pass || r.verdict != Verdict::Tunnel)
+        .map(|r| format!("{}: {}", r.server, r.reason))
+        .collect::<Vec<_>>()
+        .join("; ");
+    (status, detail)
+}
+
+fn default_route(inputs: &Inputs) -> (Status, String) {
+    let bypass: Vec<&str> = inputs
+        .routes
+        .routes
+        .iter()
+        .filter(|r| r.kind == RouteKind::Bypass)
+        .map(|r| r.destination.as_str())
+        .collect();
+    if inputs.routes.full_tunnel && bypass.is_empty() {
+        (
+            Status::Pass,
+            format!("all traffic via {}", inputs.interface),
+        )
+    } else if inputs.routes.full_tunnel {
+        (
+            Status::Warn,
+            format!("bypassing the tunnel: {}", bypass.join(", ")),
+        )
+    } else if inputs.split_tunnel {
+        (
+            Status::Warn,
+            "split tunnel: only selected networks use the tunnel".to_string(),
+        )
+    } else {
+        (
+            Status::Fail,
+            format!("default route does not go through {}", inputs.interface),
+        )
+    }
+}
+
+fn kill_switch(inputs: &Inputs) -> (Status, String) {
+    match (inputs.killswitch_mode, inputs.killswitch_state) {
+        (KillSwitchMode::Off, _) => (Status::Fail, "off".to_string()),
+        (mode, KillSwitchState::Blocking) if inputs.killswitch_stale => (
+            Status::Warn,
+            format!("{mode:?}: blocking, left by a session that is no longer running"),
+        ),
+        (mode, KillSwitchState::Blocking) => (
+            Status::Pass,
+            format!("{mode:?}: blocking all traffic outside the tunnel"),
+        ),
+        (mode, _) => (
+            Status::Pass,
+            format!("{mode:?}: armed, blocks traffic outside the tunnel if it drops"),
+        ),
+    }
+}
+
+fn allowed_ips(inputs: &Inputs) -> (Status, String) {
+    let Some(allowed) = inputs.allowed_ips else {
+        return (
+            Status::Skip,
+            "OpenVPN: routes come from the config and the server".to_string(),
+        );
+    };
+    let covers = |default: &str, halves: [&str; 2]| {
+        let has = |net: &str| allowed.iter().any(|c| c.to_string() == net);
+        has(default) || halves.iter().all(|half| has(half))
+    };
+    let v4 = covers("0.0.0.0/0", ["0.0.0.0/1", "128.0.0.0/1"]);
+    let v6 = covers("::/0", ["::/1", "8000::/1"]);
+    let list = allowed
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    match (v4, v6) {
+        _ if inputs.split_tunnel => (
+            Status::Warn,
+            format!("{list}, narrowed by split tunnel rules"),
+        ),
+        (true, true) => (Status::Pass, format!("{list} (full tunnel)")),
+        (true, false) => (Status::Warn, format!("{list}: IPv6 is not routed")),
+        _ => (
+            Status::Fail,
+            format!("{list}: not all IPv4 traffic is routed"),
+        ),
+    }
+}
+
+fn handshake(latest: Option<&str>, keepalive: bool) -> (Status, String) {
+    let Some(latest) = latest else {
+        return (
+            Status::Skip,
+            "OpenVPN: checked by its own keepalive".to_string(),
+        );
+    };
+    let (status, detail) = match handshake_age(latest) {
+        Some(age) if age <= HANDSHAKE_MAX_AGE_SECS => return (Status::Pass, latest.to_string()),
+        Some(_) => (
+            Status::Fail,
+            format!(
+                "{latest}, older than {} minutes",
+                HANDSHAKE_MAX_AGE_SECS / 60
+            ),
+        ),
+        None => (Status::Fail, "no handshake yet".to_string()),
+    };
+    if keepalive {
+        (status, detail)
+    } else {
+        // Nothing to tell an idle tunnel from a dead one
+        (
+            Status::Warn,
+            format!("{detail} (idle? no PersistentKeepalive)"),
+        )
+    }
+}
+
+/// Seconds since the handshake, from `wg show`'s wording
+/// (`1 minute, 5 seconds ago`, `Now`).
+fn handshake_age(latest: &str) -> Option<u64> {
+    let latest = latest.trim();
+    if latest.eq_ignore_ascii_case("now") {
+        return Some(0);
+    }
+    let latest = latest.strip_suffix(" ago")?;
+    latest.split(',').try_fold(0, |total, part| {
+        let mut words = part.split_whitespace();
+        let value: u64 = words.next()?.parse().ok()?;
+        let unit = match words.next()?.trim_end_matches('s') {
+            "second" => 1,
+            "minute" => 60,
+            "hour" => 3600,
+            "day" => 86_400,
+            "year" => 31_536_000,
+            _ => return None,
+        };
+        Some(total + value * unit)
+    })
+}
+
+fn real_ip_path() -> Option<std::path::PathBuf> {
+    utils::get_app_config_dir()
+        .ok()
+        .map(|dir| dir.join(constants::REAL_IP_FILE_NAME))
+}
+
+/// The public IP last seen while disconnected.
+pub fn load_real_ip() -> Option<String> {
+    let ip = std::fs::read_to_string(real_ip_path()?).ok()?;
+    Some(ip.trim().to_string()).filter(|ip| !ip.is_empty())
+}
+
+/// Remember the public IP seen while disconnected.
+pub fn save_real_ip(ip: &str) {
+    if let Some(path) = real_ip_path() {
+        let _ = std::fs::write(path, ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs<'a>(routes: &'a RouteTable, allowed: &'a [Cidr]) -> Inputs<'a> {
+        Inputs {
+            interface: "wg0",
+            exit_ip: Some("203.0.113.9"),
+            real_ip: Some("198.51.100.2"),
+            dns: None,
+            ipv6: None,
+            routes,
+            split_tunnel: false,
+            killswitch_mode: KillSwitchMode::Auto,
+            killswitch_state: KillSwitchState::Armed,
+            killswitch_stale: false,
+            allowed_ips: Some(allowed),
+            handshake: Some("1 minute, 5 seconds ago"),
+            keepalive: true,
+        }
+    }
+
+    #[test]
+    fn test_handshake_age() {
+        assert_eq!(handshake_age("1 minute, 5 seconds ago"), Some(65));
+        assert_eq!(handshake_age("2 hours, 1 second ago"), Some(7201));
+        assert_eq!(handshake_age("Now"), Some(0));
+        assert_eq!(handshake_age("(none)"), None);
+    }
+
+    #[test]
+    fn test_evaluate_full_tunnel_and_leaks() {
+        let routes = RouteTable {
+            full_tunnel: true,
+            ..RouteTable::default()
+        };
+        let allowed: Vec<Cidr> = vec!["0.0.0.0/0".parse().unwrap(), "::/0".parse().unwrap()];
+        let report = Report::new(
+            Some("de-lin"),
+            Some("WireGuard"),
+            &inputs(&routes, &allowed),
+            "2026-10-18T12:00:00Z".to_string(),
+        );
+        let statuses: Vec<_> = report.checks.iter().map(|c| (c.id, c.status)).collect();
+        assert_eq!(
+            statuses,
+            [
+                ("tunnel", Status::Pass),
+                ("exit_ip", Status::Pass),
+                ("ipv6", Status::Skip),
+                ("dns", Status::Skip),
+                ("default_route", Status::Pass),
+                ("kill_switch", Status::Pass),
+                ("allowed_ips", Status::Pass),
+                ("handshake", Status::Pass),
+            ]
+        );
+        assert!(report.passed);
+        assert!(report
+            .to_markdown()
+            .contains("| Exit IP | PASS | 203.0.113.9 (real IP 198.51.100.2 hidden) |"));
+
+        let v4_only: Vec<Cidr> = vec!["0.0.0.0/1".parse().unwrap(), "128.0.0.0/1".parse().unwrap()];
+        let mut leaky = inputs(&routes, &v4_only);
+        leaky.exit_ip = Some("198.51.100.2");
+        leaky.handshake = Some("5 minutes, 2 seconds ago");
+        leaky.killswitch_mode = KillSwitchMode::Off;
+        let checks = evaluate(&leaky);
+        let status = |id| checks.iter().find(|c| c.id == id).unwrap().status;
+        assert_eq!(status("exit_ip"), Status::Fail);
+        assert_eq!(status("allowed_ips"), Status::Warn);
+        assert_eq!(status("handshake"), Status::Fail);
+        assert_eq!(status("kill_switch"), Status::Fail);
+
+        // Without a keepalive an old handshake may just be an idle tunnel
+        let idle = Inputs {
+            keepalive: false,
+            ..inputs(&routes, &allowed)
+        };
+        let idle = |handshake| Inputs { handshake, ..idle };
+        for stale in ["5 minutes, 2 seconds ago", "(none)"] {
+            let checks = evaluate(&idle(Some(stale)));
+            let check = checks.iter().find(|c| c.id == "handshake").unwrap();
+            assert_eq!(check.status, Status::Warn, "{stale}");
+            assert!(check.detail.contains("no PersistentKeepalive"));
+        }
+
+        let disconnected = Inputs {
+            interface: "",
+            ..leaky
+        };
+        let report = Report::new(None, None, &disconnected, String::new());
+        assert!(!report.passed);
+        assert_eq!(report.checks[1].status, Status::Skip);
+        assert!(report.to_text().contains("FAIL  Tunnel"));
+    }
+}
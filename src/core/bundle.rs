@@ -0,0 +1,459 @@
+//! Profile bundles: a single file holding several profiles for moving them
+//! between machines.
+//!
+//! A bundle is JSON with a `vortix_bundle` version marker. Each entry carries
+//! the profile's config, its bundled `OpenVPN` key/cert files and its
+//! `metadata.json` entry. The profile list can optionally be sealed with a
+//! passphrase (see [`crate::core::crypto`]).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use super::crypto::{self, SealedBox};
+use super::duplicates;
+use super::importer::{Conflict, Outcome};
+use super::vault::{self, ScratchDir};
+use crate::state::{Protocol, VpnProfile};
+use crate::utils::{self, base64_decode, base64_encode, ProfileMetadata};
+use crate::vpn::{self, openvpn};
+
+/// Format version written into every bundle.
+const BUNDLE_VERSION: u8 = 1;
+
+/// Conventional file extension for bundles.
+pub const BUNDLE_EXTENSION: &str = "vortix";
+
+/// On-disk bundle. Exactly one of `profiles` / `encrypted` is set.
+#[derive(Serialize, Deserialize)]
+struct BundleFile {
+    vortix_bundle: u8,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    profiles: Vec<BundledProfile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encrypted: Option<SealedBox>,
+}
+
+/// One exported profile.
+#[derive(Serialize, Deserialize)]
+pub struct BundledProfile {
+    /// Stored file name, e.g. `work.ovpn`.
+    pub file_name: String,
+    /// Config text; bundled file references are bare names from `files`.
+    pub config: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<BundledFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ProfileMetadata>,
+}
+
+impl BundledProfile {
+    /// Profile name (file name without extension).
+    pub fn name(&self) -> &str {
+        Path::new(&self.file_name)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(&self.file_name)
+    }
+}
+
+impl Drop for BundledProfile {
+    fn drop(&mut self) {
+        self.config.zeroize();
+    }
+}
+
+/// A key/cert file referenced by a bundled `OpenVPN` profile.
+#[derive(Serialize, Deserialize)]
+pub struct BundledFile {
+    pub name: String,
+    /// Base64 file contents.
+    pub data: String,
+}
+
+impl Drop for BundledFile {
+    fn drop(&mut self) {
+        self.data.zeroize();
+    }
+}
+
+/// Whether `path` looks like a bundle (by content, not extension).
+pub fn is_bundle(path: &Path) -> bool {
+    peek(path).is_some()
+}
+
+/// Whether the bundle at `path` needs a passphrase to read.
+pub fn is_encrypted(path: &Path) -> bool {
+    peek(path).is_some_and(|bundle| bundle.encrypted.is_some())
+}
+
+fn peek(path: &Path) -> Option<BundleFile> {
+    let data = fs::read(path).ok()?;
+    if data.first() != Some(&b'{') {
+        return None;
+    }
+    serde_json::from_slice(&data).ok()
+}
+
+// === Export ===
+
+/// Write `profiles` (with their files and metadata) into a bundle at `dest`.
+/// Returns the number of profiles written.
+pub fn export(
+    profiles: &[VpnProfile],
+    dest: &Path,
+    passphrase: Option<&str>,
+) -> Result<usize, String> {
+    if profiles.is_empty() {
+        return Err("No profiles to export".to_string());
+    }
+    let metadata = utils::load_profile_metadata().unwrap_or_default();
+
+    let mut entries = Vec::with_capacity(profiles.len());
+    for profile in profiles {
+        let mut entry = bundle_profile(profile)
+            .map_err(|e| format!("Failed to export '{}': {e}", profile.name))?;
+        entry.metadata = metadata
+            .get(profile.config_path.to_string_lossy().as_ref())
+            .cloned();
+        entries.push(entry);
+    }
+    let count = entries.len();
+    write(entries, dest, passphrase)?;
+    Ok(count)
+}
+
+/// Serialize bundle entries to `dest` (0600), sealed when a passphrase is given.
+fn write(
+    entries: Vec<BundledProfile>,
+    dest: &Path,
+    passphrase: Option<&str>,
+) -> Result<(), String> {
+    let file = match passphrase {
+        Some(passphrase) => {
+            let mut json =
+                serde_json::to_vec(&entries).map_err(|e| format!("Failed to encode: {e}"))?;
+            let sealed = crypto::seal(passphrase, &json);
+            json.zeroize();
+            BundleFile {
+                vortix_bundle: BUNDLE_VERSION,
+                profiles: Vec::new(),
+                encrypted: Some(sealed?),
+            }
+        }
+        None => BundleFile {
+            vortix_bundle: BUNDLE_VERSION,
+            profiles: entries,
+            encrypted: None,
+        },
+    };
+
+    let mut json =
+        serde_json::to_vec_pretty(&file).map_err(|e| format!("Failed to encode: {e}"))?;
+    let written = vault::write_private(dest, &json);
+    json.zeroize();
+    written.map_err(|e| format!("Failed to write {}: {e}", dest.display()))
+}
+
+/// Read one stored profile into a bundle entry, turning references to its
+/// companion files into bare names.
+///
+/// Files referenced from elsewhere on disk (e.g. `/etc/openvpn/ca.crt`) are
+/// bundled too, so the profile works on the target machine; a referenced
+/// file that does not exist fails the export.
+fn bundle_profile(profile: &VpnProfile) -> Result<BundledProfile, String> {
+    let config = vault::read_config(&profile.config_path)?;
+    let file_name = profile
+        .config_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("profile")
+        .to_string();
+
+    if profile.protocol != Protocol::OpenVPN {
+        return Ok(BundledProfile {
+            file_name,
+            config,
+            files: Vec::new(),
+            metadata: None,
+        });
+    }
+
+    let files_dir = vpn::companion_dir(&profile.config_path);
+    let config_dir = profile
+        .config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    let (parsed, _) = openvpn::parse(&config);
+    // Bundled name of each source file, in the order they were added
+    let mut sources: Vec<(PathBuf, String)> = Vec::new();
+    let mut files: Vec<BundledFile> = Vec::new();
+    let mut replacements = Vec::new();
+    for file_ref in &parsed.file_refs {
+        let path = config_dir.join(&file_ref.path);
+        let name = if let Some((_, name)) = sources.iter().find(|(p, _)| *p == path) {
+            name.clone()
+        } else {
+            if !path.is_file() {
+                return Err(format!(
+                    "line {}: {} file '{}' does not exist",
+                    file_ref.line, file_ref.directive, file_ref.path
+                ));
+            }
+            let name = path
+                .strip_prefix(&files_dir)
+                .ok()
+                .and_then(|p| p.to_str())
+                .map_or_else(|| unique_name(&path, &files), str::to_string);
+            let mut data = vault::open_bytes(
+                fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?,
+            )?;
+            files.push(BundledFile {
+                name: name.clone(),
+                data: base64_encode(&data),
+            });
+            data.zeroize();
+            sources.push((path, name.clone()));
+            name
+        };
+        replacements.push((file_ref.line, file_ref.path.clone(), name));
+    }
+
+    Ok(BundledProfile {
+        file_name,
+        config: openvpn::rewrite_file_refs(&config, &replacements),
+        files,
+        metadata: None,
+    })
+}
+
+/// Bundle name for a file from outside the companion directory: its file
+/// name, numbered when another bundled file already uses it.
+fn unique_name(path: &Path, files: &[BundledFile]) -> String {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("file");
+    let (stem, extension) = file_name
+        .rsplit_once('.')
+        .filter(|(stem, _)| !stem.is_empty())
+        .map_or((file_name, String::new()), |(stem, ext)| {
+            (stem, format!(".{ext}"))
+        });
+    let mut name = file_name.to_string();
+    let mut counter = 1;
+    while files.iter().any(|f| f.name == name) {
+        name = format!("{stem}({counter}){extension}");
+        counter += 1;
+    }
+    name
+}
+
+// === Import ===
+
+/// Read the profiles from a bundle. `passphrase` is required for encrypted
+/// bundles.
+pub fn read(path: &Path, passphrase: Option<&str>) -> Result<Vec<BundledProfile>, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let file: BundleFile =
+        serde_json::from_slice(&data).map_err(|e| format!("Not a vortix bundle: {e}"))?;
+    if file.vortix_bundle != BUNDLE_VERSION {
+        return Err(format!(
+            "Unsupported bundle format version {}",
+            file.vortix_bundle
+        ));
+    }
+
+    let Some(sealed) = file.encrypted else {
+        return Ok(file.profiles);
+    };
+    let passphrase = passphrase.ok_or("Bundle is encrypted: a passphrase is required")?;
+    let mut json = crypto::open(passphrase, &sealed)?;
+    let profiles = serde_json::from_slice(&json).map_err(|e| format!("Corrupted bundle: {e}"));
+    json.zeroize();
+    profiles
+}
+
+/// Import bundled profiles into the profiles directory.
+///
+/// Each profile goes through the regular [`vpn::import_profile`] validation;
+/// its files are staged next to it in a private scratch directory first.
+/// Stored profiles are matched by content like any other import (see
+/// [`duplicates::Index`]), and `conflict` decides what happens to them. An
+/// overwritten profile is only replaced (and its metadata dropped) once the
+/// bundled one has been validated and stored.
+pub fn import(profiles: &[BundledProfile], conflict: Conflict) -> Vec<Outcome> {
+    let mut index = duplicates::Index::of(&vpn::load_profiles());
+    let mut metadata = utils::load_profile_metadata().unwrap_or_default();
+    let mut metadata_changed = false;
+
+    let outcomes = profiles
+        .iter()
+        .map(|entry| {
+            let name = entry.name().to_string();
+            let (_staging, config_path) = match stage_entry(entry) {
+                Ok(staged) => staged,
+                Err(e) => return Outcome::Failed(name, e),
+            };
+            let duplicate = index.find_duplicate(&config_path);
+            let outcome = index.import(
+                &config_path,
+                duplicate.as_ref().map(|duplicate| (duplicate, conflict)),
+            );
+
+            if let Outcome::Imported(profile, _) | Outcome::Replaced(profile, _) = &outcome {
+                if let (Outcome::Replaced(..), Some(duplicate)) = (&outcome, &duplicate) {
+                    metadata.remove(duplicate.existing.to_string_lossy().as_ref());
+                }
+                if let Some(meta) = &entry.metadata {
+                    metadata.insert(
+                        profile.config_path.to_string_lossy().into_owned(),
+                        meta.clone(),
+                    );
+                }
+                metadata_changed = true;
+            }
+            match outcome {
+                // Report the bundled name, not the staged file's
+                Outcome::Failed(_, e) => Outcome::Failed(name, e),
+                Outcome::Skipped(_) => Outcome::Skipped(name),
+                outcome => outcome,
+            }
+        })
+        .collect();
+
+    if metadata_changed {
+        let _ = utils::save_profile_metadata(&metadata);
+    }
+    outcomes
+}
+
+/// Write one bundled profile and its files into a private scratch
+/// directory. Returns the directory (removed on drop) and the config path.
+fn stage_entry(entry: &BundledProfile) -> Result<(ScratchDir, PathBuf), String> {
+    let is_plain_name = |name: &str| {
+        !name.is_empty()
+            && !name.starts_with('.')
+            && Path::new(name).file_name().and_then(|n| n.to_str()) == Some(name)
+    };
+    if !is_plain_name(&entry.file_name) {
+        return Err(format!("Invalid profile file name '{}'", entry.file_name));
+    }
+
+    let staging = ScratchDir::new("bundle")?;
+    for file in &entry.files {
+        if !is_plain_name(&file.name) {
+            return Err(format!("Invalid file name '{}'", file.name));
+        }
+        let mut data = base64_decode(&file.data)
+            .ok_or_else(|| format!("Corrupted file '{}' in bundle", file.name))?;
+        let written = vault::write_private(&staging.path.join(&file.name), &data);
+        data.zeroize();
+        written.map_err(|e| format!("Failed to stage '{}': {e}", file.name))?;
+    }
+
+    let config_path = staging.path.join(&entry.file_name);
+    vault::write_private(&config_path, entry.config.as_bytes())
+        .map_err(|e| format!("Failed to stage config: {e}"))?;
+    Ok((staging, config_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("vortix-{name}-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_bundle_profile_uses_bare_file_names() {
+        let root = temp_root("export");
+        let config_path = root.join("work.ovpn");
+        let files_dir = vpn::companion_dir(&config_path);
+        fs::create_dir_all(&files_dir).unwrap();
+        fs::write(files_dir.join("ca.crt"), "ca").unwrap();
+        let ca = files_dir.join("ca.crt");
+        // Referenced from outside the companion directory, with a name
+        // that is already taken
+        let external = root.join("external");
+        fs::create_dir_all(&external).unwrap();
+        fs::write(external.join("ca.crt"), "cert").unwrap();
+        fs::write(
+            &config_path,
+            format!(
+                "remote a\nca {}\ncert {}\n",
+                ca.display(),
+                external.join("ca.crt").display()
+            ),
+        )
+        .unwrap();
+
+        let profile = VpnProfile {
+            name: "work".to_string(),
+            protocol: Protocol::OpenVPN,
+            location: String::new(),
+            custom_location: None,
+            config_path,
+            last_used: None,
+            tags: Vec::new(),
+            last_latency_ms: None,
+            connect_count: 0,
+            split_tunnel: crate::core::split_tunnel::SplitTunnel::default(),
+        };
+        let entry = bundle_profile(&profile).unwrap();
+        assert_eq!(entry.file_name, "work.ovpn");
+        assert_eq!(entry.config, "remote a\nca ca.crt\ncert ca(1).crt\n");
+        assert_eq!(entry.files.len(), 2);
+        assert_eq!(entry.files[0].name, "ca.crt");
+        assert_eq!(base64_decode(&entry.files[0].data).unwrap(), b"ca");
+        assert_eq!(entry.files[1].name, "ca(1).crt");
+        assert_eq!(base64_decode(&entry.files[1].data).unwrap(), b"cert");
+
+        // A reference to a missing file fails the export
+        fs::write(
+            &profile.config_path,
+            "remote a\ncert /nonexistent/client.crt\n",
+        )
+        .unwrap();
+        let err = bundle_profile(&profile).err().unwrap();
+        assert!(err.contains("/nonexistent/client.crt"), "{err}");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_encrypted_bundle_roundtrip() {
+        let root = temp_root("bundle-file");
+        let entry = || BundledProfile {
+            file_name: "home.conf".to_string(),
+            config: "[Interface]\n".to_string(),
+            files: Vec::new(),
+            metadata: None,
+        };
+
+        let plain = root.join("plain.vortix");
+        write(vec![entry()], &plain, None).unwrap();
+        assert!(is_bundle(&plain));
+        assert!(!is_encrypted(&plain));
+        assert_eq!(read(&plain, None).unwrap()[0].name(), "home");
+
+        let sealed = root.join("sealed.vortix");
+        write(vec![entry()], &sealed, Some("pw")).unwrap();
+        assert!(is_encrypted(&sealed));
+        assert!(!fs::read_to_string(&sealed).unwrap().contains("Interface"));
+        assert!(read(&sealed, None).is_err());
+        assert!(read(&sealed, Some("wrong")).is_err());
+        assert_eq!(
+            read(&sealed, Some("pw")).unwrap()[0].config,
+            "[Interface]\n"
+        );
+
+        let config = root.join("home.conf");
+        fs::write(&config, "[Interface]\n").unwrap();
+        assert!(!is_bundle(&config));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
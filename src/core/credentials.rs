@@ -0,0 +1,559 @@
+//! Credentials for `OpenVPN` profiles that cannot start unattended.
+//!
+//! `openvpn --daemon` has no terminal to prompt on, so profiles using a bare
+//! `auth-user-pass`, a `static-challenge` (TOTP) or an encrypted private key
+//! need their secrets collected by the TUI. This module works out what a
+//! profile needs, optionally remembers credentials per profile (in a
+//! `chmod 600` file or passphrase-encrypted), and hands them to `OpenVPN`
+//! either through short-lived 0600 files or over the management interface.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use super::crypto::{self, SealedBox};
+use super::vault::ScratchDir;
+use crate::logger::{self, LogLevel};
+use crate::state::CredentialStore;
+use crate::utils::base64_encode;
+use crate::vpn::openvpn::{self, AuthUserPass, StaticChallenge};
+
+/// How long to wait for `OpenVPN` to ask for and accept credentials over the
+/// management interface.
+const MANAGEMENT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Username/password and key passphrase for one profile.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_passphrase: Option<String>,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Credentials {
+    fn drop(&mut self) {
+        self.password.zeroize();
+        if let Some(passphrase) = self.key_passphrase.as_mut() {
+            passphrase.zeroize();
+        }
+    }
+}
+
+/// What a profile needs from the user before it can connect.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CredentialNeeds {
+    /// `auth-user-pass` without a file (or with a static challenge).
+    pub user_pass: bool,
+    /// The private key is encrypted and no `askpass` file is set.
+    pub key_passphrase: bool,
+    pub challenge: Option<StaticChallenge>,
+}
+
+impl CredentialNeeds {
+    /// Inspect an `.ovpn` config. Relative key paths resolve next to it.
+    pub fn for_config(content: &str, config_path: &Path) -> Self {
+        let (config, _) = openvpn::parse(content);
+        let base_dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+        // A static challenge is asked together with the password, so the
+        // username/password must come from us even when a file is configured
+        let challenge = config
+            .static_challenge
+            .clone()
+            .filter(|_| config.auth_user_pass.is_some());
+
+        Self {
+            user_pass: config.auth_user_pass == Some(AuthUserPass::Prompt) || challenge.is_some(),
+            key_passphrase: config.needs_key_passphrase(base_dir),
+            challenge,
+        }
+    }
+
+    /// Whether anything has to be asked for.
+    pub fn any(&self) -> bool {
+        self.user_pass || self.key_passphrase || self.challenge.is_some()
+    }
+
+    /// Whether saved credentials cover everything except a challenge response.
+    pub fn satisfied_by(&self, credentials: &Credentials) -> bool {
+        (!self.user_pass || !credentials.username.is_empty())
+            && (!self.key_passphrase || credentials.key_passphrase.is_some())
+    }
+}
+
+/// Everything handed to `OpenVPN` for one connection attempt.
+#[derive(Debug, Clone)]
+pub struct OpenVpnAuth {
+    pub needs: CredentialNeeds,
+    pub credentials: Credentials,
+    pub challenge_response: Option<String>,
+}
+
+impl OpenVpnAuth {
+    /// A static challenge needs the management interface; plain
+    /// username/password and key passphrases go through 0600 files in memory.
+    fn uses_management(&self) -> bool {
+        self.needs.challenge.is_some()
+    }
+
+    /// Password as sent to `OpenVPN`, in `SCRV1` format when a challenge
+    /// response is included.
+    fn password(&self) -> String {
+        match &self.challenge_response {
+            Some(response) => format!(
+                "SCRV1:{}:{}",
+                base64_encode(self.credentials.password.as_bytes()),
+                base64_encode(response.as_bytes())
+            ),
+            None => self.credentials.password.clone(),
+        }
+    }
+}
+
+// === Storage ===
+
+/// On-disk format of `credentials/<profile>.json`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum StoredFile {
+    Plain {
+        #[serde(flatten)]
+        credentials: Credentials,
+    },
+    Encrypted {
+        sealed: SealedBox,
+    },
+}
+
+/// Saved credentials for a profile.
+#[derive(Debug)]
+pub enum Stored {
+    /// Usable as-is.
+    Plain(Credentials),
+    /// Needs the passphrase (see [`unlock`]).
+    Locked(SealedBox),
+}
+
+fn credentials_path(profile: &str) -> Result<PathBuf, String> {
+    let dir = crate::utils::get_app_config_dir()
+        .map_err(|e| format!("Failed to get config dir: {e}"))?
+        .join(crate::constants::CREDENTIALS_DIR_NAME);
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(&dir)
+        .map_err(|e| format!("Failed to create credentials dir: {e}"))?;
+    Ok(dir.join(format!("{profile}.json")))
+}
+
+/// Write `data` to a fresh file only the owner can read.
+fn write_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    // Remove first so an existing file with looser permissions is not reused
+    let _ = fs::remove_file(path);
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(data)
+}
+
+/// Load saved credentials for a profile, if any.
+pub fn load(profile: &str) -> Option<Stored> {
+    let content = fs::read_to_string(credentials_path(profile).ok()?).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(StoredFile::Plain { credentials }) => Some(Stored::Plain(credentials)),
+        Ok(StoredFile::Encrypted { sealed }) => Some(Stored::Locked(sealed)),
+        Err(e) => {
+            crate::logger::log(
+                crate::logger::LogLevel::Warning,
+                "AUTH",
+                format!("Ignoring unreadable saved credentials for '{profile}': {e}"),
+            );
+            None
+        }
+    }
+}
+
+/// Decrypt credentials saved with [`CredentialStore::Encrypted`].
+pub fn unlock(sealed: &SealedBox, passphrase: &str) -> Result<Credentials, String> {
+    let mut plain = crypto::open(passphrase, sealed)?;
+    let credentials =
+        serde_json::from_slice(&plain).map_err(|e| format!("Saved credentials are corrupted: {e}"));
+    plain.zeroize();
+    credentials
+}
+
+/// Save credentials for a profile. `passphrase` is required for
+/// [`CredentialStore::Encrypted`]; [`CredentialStore::None`] saves nothing.
+pub fn save(
+    profile: &str,
+    credentials: &Credentials,
+    store: CredentialStore,
+    passphrase: Option<&str>,
+) -> Result<(), String> {
+    let file = match store {
+        CredentialStore::None => return Ok(()),
+        CredentialStore::File => StoredFile::Plain {
+            credentials: credentials.clone(),
+        },
+        CredentialStore::Encrypted => {
+            let passphrase = passphrase
+                .filter(|p| !p.is_empty())
+                .ok_or("A passphrase is required to encrypt credentials")?;
+            let mut plain = serde_json::to_vec(credentials)
+                .map_err(|e| format!("Failed to serialize credentials: {e}"))?;
+            let sealed = crypto::seal(passphrase, &plain);
+            plain.zeroize();
+            StoredFile::Encrypted { sealed: sealed? }
+        }
+    };
+
+    let mut json = serde_json::to_vec_pretty(&file)
+        .map_err(|e| format!("Failed to serialize credentials: {e}"))?;
+    let result = write_private(&credentials_path(profile)?, &json)
+        .map_err(|e| format!("Failed to save credentials: {e}"));
+    json.zeroize();
+    result
+}
+
+/// Delete saved credentials for a profile. Returns whether any existed.
+pub fn forget(profile: &str) -> bool {
+    credentials_path(profile).is_ok_and(|path| fs::remove_file(path).is_ok())
+}
+
+// === Handoff to OpenVPN ===
+
+/// Private directory in memory-backed storage holding the credential files
+/// or management socket for one `openvpn` connection. Removed (with its
+/// contents) on drop.
+pub struct Handoff {
+    dir: ScratchDir,
+    args: Vec<String>,
+    socket: Option<PathBuf>,
+}
+
+impl Handoff {
+    /// Create the files/socket path and the matching `openvpn` arguments.
+    pub fn prepare(auth: &OpenVpnAuth) -> Result<Self, String> {
+        let dir = ScratchDir::in_memory("auth")?;
+
+        let mut handoff = Self {
+            dir,
+            args: Vec::new(),
+            socket: None,
+        };
+
+        if auth.uses_management() {
+            let socket = handoff.dir.path.join("mgmt.sock");
+            handoff.args = vec![
+                "--management".to_string(),
+                socket.to_string_lossy().into_owned(),
+                "unix".to_string(),
+                "--management-query-passwords".to_string(),
+            ];
+            handoff.socket = Some(socket);
+            return Ok(handoff);
+        }
+
+        // OpenVPN reads these files again when it reconnects with
+        // `auth-nocache` or `auth-retry`, so they must outlive the launch
+        // ([`hold`])
+        if auth.needs.user_pass {
+            let path = handoff.dir.path.join("auth");
+            let mut content = format!(
+                "{}\n{}\n",
+                auth.credentials.username, auth.credentials.password
+            );
+            let written = write_private(&path, content.as_bytes());
+            content.zeroize();
+            written.map_err(|e| format!("Failed to write credential file: {e}"))?;
+            handoff.args.push("--auth-user-pass".to_string());
+            handoff.args.push(path.to_string_lossy().into_owned());
+        }
+        if let Some(passphrase) = auth.credentials.key_passphrase.as_ref() {
+            if auth.needs.key_passphrase {
+                let path = handoff.dir.path.join("askpass");
+                write_private(&path, format!("{passphrase}\n").as_bytes())
+                    .map_err(|e| format!("Failed to write credential file: {e}"))?;
+                handoff.args.push("--askpass".to_string());
+                handoff.args.push(path.to_string_lossy().into_owned());
+            }
+        }
+        Ok(handoff)
+    }
+
+    /// Extra arguments for the `openvpn` command line.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// After `openvpn --daemon` has started, answer its password queries over
+    /// the management interface and wait until the credentials are accepted.
+    /// Queries after that (on reconnects) are answered in the background
+    /// until the daemon exits. Does nothing for file-based handoff.
+    pub fn finish(&self, auth: &OpenVpnAuth) -> Result<(), String> {
+        let Some(socket) = &self.socket else {
+            return Ok(());
+        };
+
+        let deadline = Instant::now() + MANAGEMENT_TIMEOUT;
+        let mut stream = loop {
+            match UnixStream::connect(socket) {
+                Ok(stream) => break stream,
+                Err(_) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(200));
+                }
+                Err(e) => return Err(format!("OpenVPN management interface unavailable: {e}")),
+            }
+        };
+        stream
+            .set_read_timeout(Some(MANAGEMENT_TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+
+        send(&mut stream, "state on")?;
+        if !answer_queries(&mut reader, &mut stream, auth, true)? {
+            return Err("OpenVPN closed the management interface".to_string());
+        }
+
+        // OpenVPN asks again on reconnects (auth-nocache, auth-retry,
+        // ping-restart); keep answering until it closes the interface
+        stream.set_read_timeout(None).map_err(|e| e.to_string())?;
+        let auth = auth.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = answer_queries(&mut reader, &mut stream, &auth, false) {
+                logger::log(LogLevel::Warning, "AUTH", format!("OpenVPN reconnect: {e}"));
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Send one management interface command.
+fn send(stream: &mut UnixStream, line: &str) -> Result<(), String> {
+    stream
+        .write_all(format!("{line}\n").as_bytes())
+        .map_err(|e| format!("Failed to talk to OpenVPN: {e}"))
+}
+
+/// Answer password queries from the management interface. With
+/// `until_connected`, returns `true` once the connection is up; otherwise
+/// keeps going until `OpenVPN` closes the interface (`false`).
+fn answer_queries(
+    reader: &mut impl BufRead,
+    stream: &mut UnixStream,
+    auth: &OpenVpnAuth,
+    until_connected: bool,
+) -> Result<bool, String> {
+    for line in reader.lines() {
+        let line = line.map_err(|e| format!("No answer from OpenVPN: {e}"))?;
+        match management_reply(&line, auth) {
+            ManagementEvent::Reply(mut commands) => {
+                let result = commands.iter().try_for_each(|c| send(stream, c));
+                commands.iter_mut().for_each(Zeroize::zeroize);
+                result?;
+            }
+            ManagementEvent::Connected if until_connected => return Ok(true),
+            ManagementEvent::Failed(reason) => {
+                // Don't leave a daemon waiting for credentials we can't give
+                let _ = send(stream, "signal SIGTERM");
+                return Err(reason);
+            }
+            ManagementEvent::Connected | ManagementEvent::Ignore => {}
+        }
+    }
+    Ok(false)
+}
+
+/// Credential handoffs kept for running daemons, by stored config path.
+fn held_handoffs() -> &'static Mutex<HashMap<PathBuf, Handoff>> {
+    static HELD: OnceLock<Mutex<HashMap<PathBuf, Handoff>>> = OnceLock::new();
+    HELD.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Keep the credential files or management socket alive for the
+/// connection of `config_path`, which `OpenVPN` may use again to reconnect.
+pub fn hold(config_path: &Path, handoff: Handoff) {
+    if let Ok(mut held) = held_handoffs().lock() {
+        held.insert(config_path.to_path_buf(), handoff);
+    }
+}
+
+/// Remove the handoff kept for `config_path` once its connection is down.
+pub fn release(config_path: &Path) {
+    if let Ok(mut held) = held_handoffs().lock() {
+        held.remove(config_path);
+    }
+}
+
+/// Remove every kept handoff (no connection is up any more).
+pub fn release_all() {
+    if let Ok(mut held) = held_handoffs().lock() {
+        held.clear();
+    }
+}
+
+/// What to do with one line from the management interface.
+#[derive(Debug, PartialEq, Eq)]
+enum ManagementEvent {
+    Reply(Vec<String>),
+    Connected,
+    Failed(String),
+    Ignore,
+}
+
+/// Quote a value for a management interface command.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn management_reply(line: &str, auth: &OpenVpnAuth) -> ManagementEvent {
+    if line.starts_with(">PASSWORD:Verification Failed") {
+        return ManagementEvent::Failed(
+            "Authentication failed (check username, password or code)".to_string(),
+        );
+    }
+    if line.starts_with(">PASSWORD:Need 'Auth'") {
+        return ManagementEvent::Reply(vec![
+            format!("username \"Auth\" {}", quote(&auth.credentials.username)),
+            format!("password \"Auth\" {}", quote(&auth.password())),
+        ]);
+    }
+    if line.starts_with(">PASSWORD:Need 'Private Key'") {
+        return match &auth.credentials.key_passphrase {
+            Some(passphrase) => ManagementEvent::Reply(vec![format!(
+                "password \"Private Key\" {}",
+                quote(passphrase)
+            )]),
+            None => ManagementEvent::Failed("OpenVPN asked for a key passphrase".to_string()),
+        };
+    }
+    if let Some(fatal) = line.strip_prefix(">FATAL:") {
+        return ManagementEvent::Failed(format!("OpenVPN: {fatal}"));
+    }
+    if line.starts_with(">STATE:") && line.contains(",CONNECTED,") {
+        return ManagementEvent::Connected;
+    }
+    ManagementEvent::Ignore
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth(challenge: bool) -> OpenVpnAuth {
+        OpenVpnAuth {
+            needs: CredentialNeeds {
+                user_pass: true,
+                key_passphrase: false,
+                challenge: challenge.then(|| StaticChallenge {
+                    text: "OTP".to_string(),
+                    echo: false,
+                }),
+            },
+            credentials: Credentials {
+                username: "alice".to_string(),
+                password: "pa\"ss".to_string(),
+                key_passphrase: None,
+            },
+            challenge_response: challenge.then(|| "123456".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_needs_for_config() {
+        let path = Path::new("/nonexistent/p.ovpn");
+        let needs = CredentialNeeds::for_config("remote a\nauth-user-pass\n", path);
+        assert!(needs.user_pass && !needs.key_passphrase && needs.challenge.is_none());
+
+        let needs = CredentialNeeds::for_config("remote a\nauth-user-pass /x/creds\n", path);
+        assert!(!needs.any());
+
+        let needs = CredentialNeeds::for_config(
+            "remote a\nauth-user-pass /x/creds\nstatic-challenge \"OTP\" 0\n",
+            path,
+        );
+        assert!(needs.user_pass && needs.challenge.is_some());
+
+        let saved = Credentials {
+            username: "alice".to_string(),
+            password: String::new(),
+            key_passphrase: None,
+        };
+        assert!(needs.satisfied_by(&saved));
+    }
+
+    #[test]
+    fn test_management_replies() {
+        let plain = auth(false);
+        assert_eq!(
+            management_reply(">PASSWORD:Need 'Auth' username/password", &plain),
+            ManagementEvent::Reply(vec![
+                "username \"Auth\" \"alice\"".to_string(),
+                "password \"Auth\" \"pa\\\"ss\"".to_string(),
+            ])
+        );
+
+        let challenged = auth(true);
+        let ManagementEvent::Reply(commands) = management_reply(
+            ">PASSWORD:Need 'Auth' username/password SC:0,OTP",
+            &challenged,
+        ) else {
+            panic!("expected a reply");
+        };
+        // SCRV1:base64("pa\"ss"):base64("123456")
+        assert_eq!(commands[1], "password \"Auth\" \"SCRV1:cGEic3M=:MTIzNDU2\"");
+
+        assert!(matches!(
+            management_reply(">PASSWORD:Verification Failed: 'Auth'", &plain),
+            ManagementEvent::Failed(_)
+        ));
+        assert_eq!(
+            management_reply(
+                ">STATE:1700000000,CONNECTED,SUCCESS,10.8.0.2,1.2.3.4",
+                &plain
+            ),
+            ManagementEvent::Connected
+        );
+        assert_eq!(
+            management_reply(">INFO:OpenVPN Management Interface", &plain),
+            ManagementEvent::Ignore
+        );
+    }
+
+    #[test]
+    fn test_handoff_files_are_private_and_removed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let handoff = Handoff::prepare(&auth(false)).unwrap();
+        assert_eq!(handoff.args()[0], "--auth-user-pass");
+        let file = PathBuf::from(&handoff.args()[1]);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "alice\npa\"ss\n");
+        let mode = fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        drop(handoff);
+        assert!(!file.exists());
+
+        let handoff = Handoff::prepare(&auth(true)).unwrap();
+        assert_eq!(handoff.args()[0], "--management");
+        assert_eq!(handoff.args()[3], "--management-query-passwords");
+    }
+}
@@ -0,0 +1,137 @@
+//! Passphrase-based encryption for secrets kept on disk.
+//!
+//! Keys are derived with Argon2id (default parameters) from the passphrase and
+//! a random salt; data is sealed with XChaCha20-Poly1305 so a wrong passphrase
+//! or any tampering is detected on open.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use crate::utils::{base64_decode, base64_encode};
+
+/// Format version written into every sealed box.
+const SEALED_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+/// Encrypted data plus everything needed to decrypt it with the passphrase.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedBox {
+    pub version: u8,
+    /// Base64 Argon2id salt.
+    pub salt: String,
+    /// Base64 `XChaCha20` nonce.
+    pub nonce: String,
+    /// Base64 ciphertext including the Poly1305 tag.
+    pub ciphertext: String,
+}
+
+/// A derived 256-bit key, wiped from memory on drop.
+pub struct SecretKey([u8; KEY_LEN]);
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    let mut buf = [0u8; N];
+    getrandom::getrandom(&mut buf).map_err(|e| format!("Failed to get random bytes: {e}"))?;
+    Ok(buf)
+}
+
+/// A fresh random salt for [`derive_key`].
+pub fn random_salt() -> Result<Vec<u8>, String> {
+    random_bytes::<SALT_LEN>().map(|salt| salt.to_vec())
+}
+
+/// Derive a key from a passphrase with Argon2id.
+pub fn derive_key(passphrase: &str, salt: &[u8]) -> Result<SecretKey, String> {
+    let mut key = SecretKey([0u8; KEY_LEN]);
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key.0)
+        .map_err(|e| format!("Key derivation failed: {e}"))?;
+    Ok(key)
+}
+
+/// Encrypt with an already derived key. Returns `(nonce, ciphertext)`.
+pub fn encrypt(key: &SecretKey, plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let nonce: [u8; NONCE_LEN] = random_bytes()?;
+    let ciphertext = XChaCha20Poly1305::new((&key.0).into())
+        .encrypt(XNonce::from_slice(&nonce), plaintext)
+        .map_err(|_| "Encryption failed".to_string())?;
+    Ok((nonce.to_vec(), ciphertext))
+}
+
+/// Decrypt data produced by [`encrypt`] with the same key.
+pub fn decrypt(key: &SecretKey, nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+    if nonce.len() != NONCE_LEN {
+        return Err("Encrypted data is corrupted".to_string());
+    }
+    XChaCha20Poly1305::new((&key.0).into())
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Wrong passphrase or corrupted data".to_string())
+}
+
+/// Encrypt `plaintext` with a key derived from `passphrase`.
+pub fn seal(passphrase: &str, plaintext: &[u8]) -> Result<SealedBox, String> {
+    let salt = random_salt()?;
+    let key = derive_key(passphrase, &salt)?;
+    let (nonce, ciphertext) = encrypt(&key, plaintext)?;
+
+    Ok(SealedBox {
+        version: SEALED_VERSION,
+        salt: base64_encode(&salt),
+        nonce: base64_encode(&nonce),
+        ciphertext: base64_encode(&ciphertext),
+    })
+}
+
+/// Decrypt a sealed box. Fails on a wrong passphrase or modified data.
+pub fn open(passphrase: &str, sealed: &SealedBox) -> Result<Vec<u8>, String> {
+    if sealed.version != SEALED_VERSION {
+        return Err(format!(
+            "Unsupported encryption format version {}",
+            sealed.version
+        ));
+    }
+    let corrupt = || "Encrypted data is corrupted".to_string();
+    let salt = base64_decode(&sealed.salt).ok_or_else(corrupt)?;
+    let nonce = base64_decode(&sealed.nonce).ok_or_else(corrupt)?;
+    let ciphertext = base64_decode(&sealed.ciphertext).ok_or_else(corrupt)?;
+
+    let key = derive_key(passphrase, &salt)?;
+    decrypt(&key, &nonce, &ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let sealed = seal("correct horse", b"secret").unwrap();
+        assert_eq!(open("correct horse", &sealed).unwrap(), b"secret");
+        assert!(open("wrong", &sealed)
+            .unwrap_err()
+            .contains("Wrong passphrase"));
+
+        // Same input never produces the same ciphertext
+        let again = seal("correct horse", b"secret").unwrap();
+        assert_ne!(sealed.ciphertext, again.ciphertext);
+    }
+
+    #[test]
+    fn test_open_rejects_tampering() {
+        let mut sealed = seal("pw", b"secret").unwrap();
+        let mut bytes = base64_decode(&sealed.ciphertext).unwrap();
+        bytes[0] ^= 1;
+        sealed.ciphertext = base64_encode(&bytes);
+        assert!(open("pw", &sealed).is_err());
+    }
+}
@@ -3,28 +3,92 @@
 //! Uses curl command for HTTP requests to avoid heavy dependencies.
 
 use crate::constants;
+use crate::core::vault::ScratchDir;
 use crate::logger::{self, LogLevel};
 use crate::utils;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
 use std::path::PathBuf;
 use std::process::Command;
 
+/// HTTP cache validators remembered between conditional requests.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+/// Result of a conditional request.
+#[derive(Debug)]
+pub enum Fetch {
+    /// The server answered 304: the cached copy is still current.
+    NotModified,
+    /// New content together with the validators for the next request.
+    Body {
+        data: String,
+        validators: Validators,
+    },
+}
+
+/// Checks a downloaded profile must pass before it is imported.
+#[derive(Clone, Debug, Default)]
+pub struct Verification {
+    /// Expected SHA-256 of the file, hex encoded.
+    pub sha256: Option<String>,
+    /// Pinned minisign public key (the base64 line of `minisign.pub`, or
+    /// the whole file) the download must be signed with.
+    pub public_key: Option<String>,
+    /// URL or local path of the signature; `<url>.sig` by default.
+    pub signature: Option<String>,
+    /// Allow plain-HTTP URLs.
+    pub insecure: bool,
+}
+
+impl Verification {
+    /// Defaults for downloads started from the TUI: HTTPS only, and signed
+    /// by the key in `VORTIX_SIGNING_KEY` when that is set.
+    pub fn from_env() -> Self {
+        Self {
+            public_key: std::env::var(constants::ENV_SIGNING_KEY)
+                .ok()
+                .filter(|key| !key.trim().is_empty()),
+            ..Self::default()
+        }
+    }
+}
+
 /// Downloads a VPN profile from a given URL and saves it to the profiles directory.
 ///
 /// # Arguments
 ///
 /// * `url` - The direct URL to download the config from.
+/// * `verification` - Scheme, checksum and signature requirements.
 ///
 /// # Returns
 ///
-/// The `PathBuf` of the saved file, or an Error string.
+/// The `PathBuf` of the saved file, or an Error string. A file that fails
+/// verification is deleted again.
 #[allow(clippy::too_many_lines)]
-pub fn download_profile(url: &str) -> Result<PathBuf, String> {
+pub fn download_profile(url: &str, verification: &Verification) -> Result<PathBuf, String> {
     logger::log(
         LogLevel::Info,
         "DOWNLOAD",
         format!("Fetching profile from URL: {url}"),
     );
 
+    if !verification.insecure && !url.trim().starts_with("https://") {
+        logger::log(LogLevel::Error, "DOWNLOAD", constants::ERR_INSECURE_URL);
+        return Err(constants::ERR_INSECURE_URL.to_string());
+    }
+    let expected_sha256 = verification
+        .sha256
+        .as_deref()
+        .map(parse_sha256)
+        .transpose()?;
+
     // Extract filename from URL path
     let filename = extract_filename_from_url(url);
     logger::log(
@@ -37,36 +101,35 @@ pub fn download_profile(url: &str) -> Result<PathBuf, String> {
     let profiles_dir = std::env::temp_dir();
     let target_path = utils::get_unique_path(&profiles_dir, &filename);
 
-    // Use curl to download directly to file
+    // Use curl to download the body to stdout
     // -f: Fail silently on HTTP errors (returns exit code)
     // -L: Follow redirects
     // -s: Silent mode
     // -S: Show errors even in silent mode
     // --max-time: Timeout
-    // -o: Output file
-    let output = Command::new("curl")
-        .args([
-            "-f",
-            "-L",
-            "-s",
-            "-S",
-            "--max-time",
-            &constants::HTTP_TIMEOUT_SECS.to_string(),
-            "-A",
-            &format!("{}/{}", constants::APP_NAME, constants::APP_VERSION),
-            "-o",
-            target_path.to_str().unwrap_or(""),
-            url,
-        ])
-        .output()
-        .map_err(|e| {
-            logger::log(
-                LogLevel::Error,
-                "DOWNLOAD",
-                format!("Failed to execute curl: {e}"),
-            );
-            format!("{}: {e}", constants::ERR_HTTP_CLIENT_BUILD_FAILED)
-        })?;
+    // --max-filesize: Early refusal when the server announces the size;
+    //   the cap itself is enforced while reading (`run_capped`)
+    // --proto/--proto-redir: No plain HTTP, not even after a redirect
+    let mut command = Command::new("curl");
+    command.args([
+        "-f",
+        "-L",
+        "-s",
+        "-S",
+        "--max-time",
+        &constants::HTTP_TIMEOUT_SECS.to_string(),
+        "--max-filesize",
+        &constants::MAX_DOWNLOAD_BYTES.to_string(),
+        "-A",
+        &format!("{}/{}", constants::APP_NAME, constants::APP_VERSION),
+    ]);
+    if !verification.insecure {
+        command.args(["--proto", "=https", "--proto-redir", "=https"]);
+    }
+    let output = run_capped(command.arg(url)).map_err(|e| {
+        logger::log(LogLevel::Error, "DOWNLOAD", format!("Download failed: {e}"));
+        e
+    })?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -75,67 +138,28 @@ pub fn download_profile(url: &str) -> Result<PathBuf, String> {
             "DOWNLOAD",
             format!("curl failed: {stderr}"),
         );
-
-        // Clean up partial download if it exists
-        if target_path.exists() {
-            let _ = std::fs::remove_file(&target_path);
-        }
-
-        // Parse curl error for user-friendly message
-        if stderr.contains("Could not resolve host") {
-            return Err(format!(
-                "{}: Could not resolve host",
-                constants::ERR_NETWORK_REQUEST_FAILED
-            ));
-        } else if stderr.contains("Connection refused") || stderr.contains("Connection timed out") {
-            return Err(format!(
-                "{}: Connection failed",
-                constants::ERR_NETWORK_REQUEST_FAILED
-            ));
-        } else if stderr.contains("The requested URL returned error") {
-            return Err(format!(
-                "{}: {}",
-                constants::ERR_SERVER_ERROR,
-                stderr.trim()
-            ));
-        }
-        return Err(format!(
-            "{}: {}",
-            constants::ERR_NETWORK_REQUEST_FAILED,
-            stderr.trim()
-        ));
+        return Err(curl_error(&stderr));
     }
 
-    // Verify the downloaded file exists and has content
-    let metadata = std::fs::metadata(&target_path).map_err(|e| {
+    if output.body.is_empty() {
+        logger::log(LogLevel::Error, "DOWNLOAD", "Downloaded file is empty");
+        return Err(constants::ERR_EMPTY_CONTENT.to_string());
+    }
+    std::fs::write(&target_path, &output.body).map_err(|e| {
         logger::log(
             LogLevel::Error,
             "DOWNLOAD",
-            format!("Failed to read downloaded file: {e}"),
+            format!("Failed to save downloaded file: {e}"),
         );
-        format!("Failed to verify download: {e}")
+        format!("Failed to save download: {e}")
     })?;
 
-    if metadata.len() == 0 {
-        logger::log(LogLevel::Error, "DOWNLOAD", "Downloaded file is empty");
-        let _ = std::fs::remove_file(&target_path);
-        return Err(constants::ERR_EMPTY_CONTENT.to_string());
-    }
-
     // Check if we accidentally downloaded HTML (common with GitHub web links)
     let content_preview = std::fs::read_to_string(&target_path)
         .map(|s| s.chars().take(100).collect::<String>())
         .unwrap_or_default();
 
-    if content_preview
-        .trim_start()
-        .to_lowercase()
-        .starts_with("<!doctype")
-        || content_preview
-            .trim_start()
-            .to_lowercase()
-            .starts_with("<html")
-    {
+    if looks_like_html(&content_preview) {
         logger::log(
             LogLevel::Error,
             "DOWNLOAD",
@@ -145,13 +169,23 @@ pub fn download_profile(url: &str) -> Result<PathBuf, String> {
         return Err(constants::ERR_HTML_CONTENT.to_string());
     }
 
+    if let Err(e) = verify(&target_path, url, expected_sha256, verification) {
+        logger::log(
+            LogLevel::Error,
+            "DOWNLOAD",
+            format!("Verification failed for {url}: {e}"),
+        );
+        let _ = std::fs::remove_file(&target_path);
+        return Err(e);
+    }
+
     logger::log(
         LogLevel::Info,
         "DOWNLOAD",
         format!(
             "✓ Downloaded {} ({} bytes) → {}",
             filename,
-            metadata.len(),
+            output.body.len(),
             target_path.display()
         ),
     );
@@ -159,6 +193,270 @@ pub fn download_profile(url: &str) -> Result<PathBuf, String> {
     Ok(target_path)
 }
 
+/// Check a downloaded file against the expected checksum and signature.
+fn verify(
+    path: &std::path::Path,
+    url: &str,
+    expected_sha256: Option<String>,
+    verification: &Verification,
+) -> Result<(), String> {
+    let data = std::fs::read(path).map_err(|e| format!("Failed to verify download: {e}"))?;
+
+    if let Some(expected) = expected_sha256 {
+        let actual = sha256_hex(&data);
+        if actual != expected {
+            return Err(format!(
+                "{} (got {actual})",
+                constants::ERR_CHECKSUM_MISMATCH
+            ));
+        }
+        logger::log(LogLevel::Info, "DOWNLOAD", "✓ SHA-256 checksum verified");
+    }
+
+    if let Some(public_key) = &verification.public_key {
+        let signature = match &verification.signature {
+            Some(source) if is_url(source) => fetch_signature(source, verification.insecure)?,
+            Some(source) => std::fs::read_to_string(super::importer::expand_home(source))
+                .map_err(|e| format!("Failed to read signature {source}: {e}"))?,
+            None => fetch_signature(&format!("{url}.sig"), verification.insecure)?,
+        };
+        verify_signature(&data, public_key, &signature)?;
+        logger::log(LogLevel::Info, "DOWNLOAD", "✓ Signature verified");
+    }
+    Ok(())
+}
+
+/// Check a minisign signature (which is ed25519 underneath) over `data`.
+fn verify_signature(data: &[u8], public_key: &str, signature: &str) -> Result<(), String> {
+    let invalid = |e: minisign_verify::Error| format!("{}{e}", constants::ERR_SIGNATURE_INVALID);
+    let public_key = if public_key.contains('\n') {
+        minisign_verify::PublicKey::decode(public_key)
+    } else {
+        minisign_verify::PublicKey::from_base64(public_key.trim())
+    }
+    .map_err(invalid)?;
+    let signature = minisign_verify::Signature::decode(signature).map_err(invalid)?;
+    // Legacy (non-prehashed) signatures are plain ed25519 over the file
+    public_key.verify(data, &signature, true).map_err(invalid)
+}
+
+fn fetch_signature(url: &str, insecure: bool) -> Result<String, String> {
+    if !insecure && !url.starts_with("https://") {
+        return Err(constants::ERR_INSECURE_URL.to_string());
+    }
+    match fetch(url, &Validators::default(), insecure) {
+        Ok(Fetch::Body { data, .. }) => Ok(data),
+        Ok(Fetch::NotModified) => Err(format!("No signature at {url}")),
+        Err(e) => Err(format!("Failed to fetch signature {url}: {e}")),
+    }
+}
+
+/// Normalize a hex SHA-256 digest as given on the command line.
+fn parse_sha256(hex: &str) -> Result<String, String> {
+    let hex = hex.trim();
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "Invalid SHA-256 '{hex}': expected 64 hex characters"
+        ));
+    }
+    Ok(hex.to_ascii_lowercase())
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    let mut hex = String::new();
+    for byte in Sha256::digest(data) {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
+fn is_url(source: &str) -> bool {
+    source.starts_with("https://") || source.starts_with("http://")
+}
+
+fn too_large() -> String {
+    format!(
+        "{}{} MiB",
+        constants::ERR_DOWNLOAD_TOO_LARGE,
+        constants::MAX_DOWNLOAD_BYTES >> 20
+    )
+}
+
+/// Fetch `url` as text, sending `If-None-Match`/`If-Modified-Since` from
+/// `validators` so unchanged content costs a 304 instead of a download.
+///
+/// Follows the same policy as [`download_profile`]: HTTPS only (also after
+/// redirects) unless `insecure`, and at most
+/// [`constants::MAX_DOWNLOAD_BYTES`].
+pub fn fetch(url: &str, validators: &Validators, insecure: bool) -> Result<Fetch, String> {
+    logger::log(LogLevel::Debug, "DOWNLOAD", format!("Checking {url}"));
+
+    if !insecure && !url.trim().starts_with("https://") {
+        return Err(constants::ERR_INSECURE_URL.to_string());
+    }
+
+    let scratch = ScratchDir::new("fetch")?;
+    let headers_path = scratch.path.join("headers");
+
+    let mut command = Command::new("curl");
+    command.args([
+        "-f",
+        "-L",
+        "-s",
+        "-S",
+        "--max-time",
+        &constants::HTTP_TIMEOUT_SECS.to_string(),
+        "--max-filesize",
+        &constants::MAX_DOWNLOAD_BYTES.to_string(),
+        "-A",
+        &format!("{}/{}", constants::APP_NAME, constants::APP_VERSION),
+        "-D",
+        headers_path.to_str().unwrap_or(""),
+    ]);
+    if !insecure {
+        command.args(["--proto", "=https", "--proto-redir", "=https"]);
+    }
+    if let Some(etag) = &validators.etag {
+        command.args(["-H", &format!("If-None-Match: {etag}")]);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        command.args(["-H", &format!("If-Modified-Since: {last_modified}")]);
+    }
+    let output = run_capped(command.arg(url))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        logger::log(
+            LogLevel::Error,
+            "DOWNLOAD",
+            format!("curl failed for {url}: {}", stderr.trim()),
+        );
+        return Err(curl_error(&stderr));
+    }
+
+    let headers = std::fs::read_to_string(&headers_path).unwrap_or_default();
+    if last_response(&headers).split_whitespace().nth(1) == Some("304") {
+        return Ok(Fetch::NotModified);
+    }
+
+    let data = String::from_utf8(output.body)
+        .map_err(|e| format!("Failed to read response from {url}: {e}"))?;
+    if data.trim().is_empty() {
+        return Err(constants::ERR_EMPTY_CONTENT.to_string());
+    }
+    if looks_like_html(&data) {
+        return Err(constants::ERR_HTML_CONTENT.to_string());
+    }
+
+    Ok(Fetch::Body {
+        data,
+        validators: parse_validators(&headers),
+    })
+}
+
+/// Body and status of a curl run whose output stayed within the cap.
+struct Capped {
+    status: std::process::ExitStatus,
+    body: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+/// Run `command` (curl writing the body to stdout) and read at most
+/// [`constants::MAX_DOWNLOAD_BYTES`] of its output. A larger body stops
+/// curl mid-transfer, whether or not the server announced its size.
+fn run_capped(command: &mut Command) -> Result<Capped, String> {
+    use std::io::Read;
+
+    let mut child = command
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("{}: {e}", constants::ERR_HTTP_CLIENT_BUILD_FAILED))?;
+    let mut body = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        stdout
+            .take(constants::MAX_DOWNLOAD_BYTES + 1)
+            .read_to_end(&mut body)
+            .map_err(|e| format!("{}: {e}", constants::ERR_NETWORK_REQUEST_FAILED))?;
+    }
+    if body.len() as u64 > constants::MAX_DOWNLOAD_BYTES {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(too_large());
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("{}: {e}", constants::ERR_NETWORK_REQUEST_FAILED))?;
+    Ok(Capped {
+        status: output.status,
+        body,
+        stderr: output.stderr,
+    })
+}
+
+/// The last response of a curl header dump. With redirects the dump holds
+/// one block per response.
+fn last_response(headers: &str) -> &str {
+    headers
+        .split("\r\n\r\n")
+        .flat_map(|block| block.split("\n\n"))
+        .filter(|block| block.trim_start().starts_with("HTTP/"))
+        .last()
+        .unwrap_or_default()
+        .trim_start()
+}
+
+/// Pick the cache validators out of a curl header dump; only the last
+/// response counts.
+fn parse_validators(headers: &str) -> Validators {
+    let mut validators = Validators::default();
+    for line in last_response(headers).lines() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+        if name.trim().eq_ignore_ascii_case("etag") {
+            validators.etag = value;
+        } else if name.trim().eq_ignore_ascii_case("last-modified") {
+            validators.last_modified = value;
+        }
+    }
+    validators
+}
+
+/// Turn curl's stderr into a user-facing message.
+fn curl_error(stderr: &str) -> String {
+    if stderr.contains("Could not resolve host") {
+        format!(
+            "{}: Could not resolve host",
+            constants::ERR_NETWORK_REQUEST_FAILED
+        )
+    } else if stderr.contains("Connection refused") || stderr.contains("Connection timed out") {
+        format!(
+            "{}: Connection failed",
+            constants::ERR_NETWORK_REQUEST_FAILED
+        )
+    } else if stderr.contains("Maximum file size exceeded") {
+        too_large()
+    } else if stderr.contains("Protocol \"http\" disabled") || stderr.contains("not supported") {
+        constants::ERR_INSECURE_URL.to_string()
+    } else if stderr.contains("The requested URL returned error") {
+        format!("{}: {}", constants::ERR_SERVER_ERROR, stderr.trim())
+    } else {
+        format!(
+            "{}: {}",
+            constants::ERR_NETWORK_REQUEST_FAILED,
+            stderr.trim()
+        )
+    }
+}
+
+/// Whether a response is an HTML page (common with GitHub web links).
+fn looks_like_html(content: &str) -> bool {
+    let start = content.trim_start().to_lowercase();
+    start.starts_with("<!doctype") || start.starts_with("<html")
+}
+
 /// Extract filename from URL path
 ///
 /// # Limitations
@@ -168,7 +466,7 @@ pub fn download_profile(url: &str) -> Result<PathBuf, String> {
 /// it defaults to `.ovpn`, otherwise `.conf`. This may incorrectly classify
 /// URLs like `https://example.com/openvpn/download` as needing `.ovpn` when
 /// the actual content might be `WireGuard`.
-fn extract_filename_from_url(url: &str) -> String {
+pub fn extract_filename_from_url(url: &str) -> String {
     // Try to extract filename from URL path
     // e.g., "https://example.com/configs/us-east.conf" -> "us-east.conf"
 
@@ -180,7 +478,8 @@ fn extract_filename_from_url(url: &str) -> String {
     if let Some(last_segment) = url_path.rsplit('/').next() {
         if !last_segment.is_empty()
             && (last_segment.ends_with(constants::EXT_OVPN)
-                || last_segment.ends_with(constants::EXT_CONF))
+                || last_segment.ends_with(constants::EXT_CONF)
+                || last_segment.ends_with(constants::EXT_ZIP))
         {
             return last_segment.to_string();
         }
@@ -200,6 +499,60 @@ fn extract_filename_from_url(url: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_sha256() {
+        let hex = sha256_hex(b"test");
+        assert_eq!(parse_sha256(&hex.to_uppercase()), Ok(hex.clone()));
+        assert!(parse_sha256(&hex[..62]).is_err());
+        assert!(parse_sha256(&format!("{}zz", &hex[..62])).is_err());
+    }
+
+    #[test]
+    fn test_download_refuses_plain_http() {
+        let err = download_profile("http://example.com/a.conf", &Verification::default());
+        assert_eq!(err, Err(constants::ERR_INSECURE_URL.to_string()));
+    }
+
+    #[test]
+    fn test_fetch_refuses_plain_http() {
+        let err = fetch("http://example.com/index", &Validators::default(), false).err();
+        assert_eq!(err, Some(constants::ERR_INSECURE_URL.to_string()));
+    }
+
+    #[test]
+    fn test_run_capped_stops_oversized_output() {
+        // Endless output without an announced size is cut off at the cap
+        assert_eq!(
+            run_capped(&mut Command::new("yes")).err(),
+            Some(too_large())
+        );
+
+        let output = run_capped(Command::new("printf").arg("[Interface]")).unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.body, b"[Interface]");
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        // Test vector from minisign
+        let public_key = "untrusted comment: minisign public key E7620F1842B4E81F\n\
+                          RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+        let signature = "untrusted comment: signature from minisign secret key\n\
+            RWQf6LRCGA9i59SLOFxz6NxvASXDJeRtuZykwQepbDEGt87ig1BNpWaVWuNrm73YiIiJbq71Wi+dP9eKL8OC351vwIasSSbXxwA=\n\
+            trusted comment: timestamp:1555779966\tfile:test\n\
+            QtKMXWyYcwdpZAlPF7tE2ENJkRd1ujvKjlj1m9RtHTBnZPa5WKU5uWRs5GoP5M/VqE81QFuMKI5k/SfNQUaOAA==";
+        assert!(verify_signature(b"test", public_key, signature).is_ok());
+        assert!(verify_signature(
+            b"test",
+            "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3",
+            signature
+        )
+        .is_ok());
+        assert!(verify_signature(b"Test", public_key, signature)
+            .unwrap_err()
+            .starts_with(constants::ERR_SIGNATURE_INVALID));
+    }
+
     #[test]
     fn test_extract_filename_conf() {
         assert_eq!(
@@ -249,6 +602,19 @@ mod tests {
         assert!(result.ends_with(".conf"));
     }
 
+    #[test]
+    fn test_parse_validators_uses_last_response() {
+        let headers = "HTTP/1.1 302 Found\r\nLocation: /b\r\nETag: \"old\"\r\n\r\n\
+                       HTTP/2 200\r\netag: \"abc\"\r\nLast-Modified: Tue, 01 Sep 2026 10:00:00 GMT\r\n\r\n";
+        assert_eq!(
+            parse_validators(headers),
+            Validators {
+                etag: Some("\"abc\"".to_string()),
+                last_modified: Some("Tue, 01 Sep 2026 10:00:00 GMT".to_string()),
+            }
+        );
+    }
+
     #[test]
     fn test_extract_filename_ovpn_in_url() {
         // Should use .ovpn if mentioned in URL
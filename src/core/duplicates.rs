@@ -0,0 +1,295 @@
+//! Content-based duplicate detection for imports.
+//!
+//! Two configs are duplicates when their normalised content is the same
+//! (comments, blank lines, spacing and where referenced files live are
+//! ignored) or when they connect to the same endpoint with the same client
+//! key. Re-importing a provider's configs then finds the stored profiles
+//! instead of creating `name(1)` clones.
+
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use super::importer::{Conflict, Outcome};
+use super::vault;
+use crate::state::{Protocol, VpnProfile};
+use crate::vpn::{self, openvpn, wireguard};
+
+/// Inline blocks / file directives that identify an `OpenVPN` client, in
+/// order of preference.
+const OPENVPN_KEY_DIRECTIVES: &[&str] = &["key", "cert", "pkcs12", "secret"];
+
+/// How an imported config matches a stored profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Identical,
+    SameEndpointAndKey,
+}
+
+impl Kind {
+    /// Short explanation for prompts.
+    pub const fn describe(self) -> &'static str {
+        match self {
+            Self::Identical => "identical content",
+            Self::SameEndpointAndKey => "same endpoint and key",
+        }
+    }
+}
+
+/// A stored profile that importing a file would duplicate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Duplicate {
+    /// Config path of the stored profile.
+    pub existing: PathBuf,
+    /// Name of the stored profile.
+    pub name: String,
+    pub kind: Kind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Fingerprint {
+    protocol: Protocol,
+    content: [u8; 32],
+    identity: Option<[u8; 32]>,
+}
+
+/// Fingerprints of the stored profiles. Kept current while a batch is
+/// imported, so duplicates within the batch are caught too.
+pub struct Index {
+    entries: Vec<(PathBuf, Fingerprint)>,
+}
+
+impl Index {
+    /// Fingerprint the stored profiles (unreadable ones are left out).
+    pub fn of(profiles: &[VpnProfile]) -> Self {
+        let entries = profiles
+            .iter()
+            .filter_map(|profile| {
+                let content = vault::read_config(&profile.config_path).ok()?;
+                let base = profile.config_path.parent().unwrap_or(Path::new("."));
+                let fingerprint = fingerprint(profile.protocol, &content, base);
+                Some((profile.config_path.clone(), fingerprint))
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// The stored profile `source` duplicates, if any. Identical content
+    /// wins over a matching endpoint and key.
+    pub fn find_duplicate(&self, source: &Path) -> Option<Duplicate> {
+        let wanted = source_fingerprint(source)?;
+        let same_protocol = || {
+            self.entries
+                .iter()
+                .filter(move |(_, f)| f.protocol == wanted.protocol)
+        };
+
+        let (path, kind) = same_protocol()
+            .find(|(_, f)| f.content == wanted.content)
+            .map(|(path, _)| (path, Kind::Identical))
+            .or_else(|| {
+                wanted.identity?;
+                same_protocol()
+                    .find(|(_, f)| f.identity == wanted.identity)
+                    .map(|(path, _)| (path, Kind::SameEndpointAndKey))
+            })?;
+
+        Some(Duplicate {
+            existing: path.clone(),
+            name: file_stem(path),
+            kind,
+        })
+    }
+
+    /// Import `source`. `resolution` is the duplicate found by
+    /// [`Index::find_duplicate`] together with what to do about it.
+    pub fn import(&mut self, source: &Path, resolution: Option<(&Duplicate, Conflict)>) -> Outcome {
+        match resolution {
+            Some((_, Conflict::Skip)) => Outcome::Skipped(file_stem(source)),
+            Some((duplicate, Conflict::Overwrite)) => self.replace(&duplicate.existing, source),
+            Some((_, Conflict::KeepBoth)) | None => {
+                let result = vpn::import_profile(source)
+                    .map(|(profile, warnings)| Outcome::Imported(profile, warnings));
+                self.record(source, result)
+            }
+        }
+    }
+
+    /// Replace the stored profile at `existing` with `source` (see
+    /// [`vpn::replace_profile`]).
+    pub fn replace(&mut self, existing: &Path, source: &Path) -> Outcome {
+        let result = vpn::replace_profile(existing, source).map(|(profile, warnings)| {
+            self.entries.retain(|(path, _)| path != existing);
+            Outcome::Replaced(profile, warnings)
+        });
+        self.record(source, result)
+    }
+
+    /// Add a stored profile's fingerprint, so later files in the batch are
+    /// checked against it.
+    fn record(&mut self, source: &Path, result: Result<Outcome, String>) -> Outcome {
+        match result {
+            Ok(outcome) => {
+                if let Outcome::Imported(profile, _) | Outcome::Replaced(profile, _) = &outcome {
+                    if let Some(fingerprint) = source_fingerprint(source) {
+                        self.entries
+                            .push((profile.config_path.clone(), fingerprint));
+                    }
+                }
+                outcome
+            }
+            Err(e) => Outcome::Failed(file_stem(source), e),
+        }
+    }
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map_or_else(String::new, |s| s.to_string_lossy().into_owned())
+}
+
+fn source_fingerprint(source: &Path) -> Option<Fingerprint> {
+    let protocol = match source.extension()?.to_str()?.to_lowercase().as_str() {
+        "conf" => Protocol::WireGuard,
+        "ovpn" => Protocol::OpenVPN,
+        _ => return None,
+    };
+    let content = std::fs::read_to_string(source).ok()?;
+    let base = source.parent().unwrap_or(Path::new("."));
+    Some(fingerprint(protocol, &content, base))
+}
+
+/// `base` is the directory relative file references are resolved against.
+fn fingerprint(protocol: Protocol, content: &str, base: &Path) -> Fingerprint {
+    Fingerprint {
+        protocol,
+        content: Sha256::digest(normalise(protocol, content).as_bytes()).into(),
+        identity: identity(protocol, content, base)
+            .map(|identity| Sha256::digest(identity.as_bytes()).into()),
+    }
+}
+
+/// Config text without comments, blank lines and incidental spacing.
+/// `OpenVPN` file references are reduced to the file name, since importing
+/// moves the files next to the stored profile.
+fn normalise(protocol: Protocol, content: &str) -> String {
+    let file_refs = match protocol {
+        Protocol::OpenVPN => openvpn::parse(content).0.file_refs,
+        Protocol::WireGuard => Vec::new(),
+    };
+
+    let mut normalised = String::new();
+    for (idx, raw) in content.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        let line = match protocol {
+            Protocol::WireGuard => match line.split_once('=') {
+                Some((key, value)) => {
+                    format!("{}={}", key.trim().to_ascii_lowercase(), value.trim())
+                }
+                None => line.to_ascii_lowercase(),
+            },
+            Protocol::OpenVPN => match file_refs.iter().find(|r| r.line == idx + 1) {
+                Some(file_ref) => {
+                    let file_name = Path::new(&file_ref.path)
+                        .file_name()
+                        .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+                    format!("{} {file_name}", file_ref.directive)
+                }
+                None => line.split_whitespace().collect::<Vec<_>>().join(" "),
+            },
+        };
+        normalised.push_str(&line);
+        normalised.push('\n');
+    }
+    normalised
+}
+
+/// Endpoint and client key, when the config has both.
+fn identity(protocol: Protocol, content: &str, base: &Path) -> Option<String> {
+    match protocol {
+        Protocol::WireGuard => {
+            let (config, _) = wireguard::parse(content);
+            let key = config.interface.private_key?;
+            let endpoint = config.peers.iter().find_map(|p| p.endpoint.as_ref())?;
+            Some(format!(
+                "wireguard {}:{} {key}",
+                endpoint.host.to_ascii_lowercase(),
+                endpoint.port
+            ))
+        }
+        Protocol::OpenVPN => {
+            let (config, _) = openvpn::parse(content);
+            let remote = config.remotes.first()?;
+            let key = OPENVPN_KEY_DIRECTIVES.iter().find_map(|directive| {
+                config
+                    .inline_blocks
+                    .iter()
+                    .find(|block| block.tag == *directive)
+                    .map(|block| block.content.trim().to_string())
+                    .or_else(|| {
+                        let file_ref = config
+                            .file_refs
+                            .iter()
+                            .find(|r| r.directive == *directive)?;
+                        vault::read_config(&base.join(&file_ref.path))
+                            .ok()
+                            .map(|key| key.trim().to_string())
+                    })
+            })?;
+            Some(format!(
+                "openvpn {}:{} {key}",
+                remote.host.to_ascii_lowercase(),
+                remote.port
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WG: &str = "[Interface]\nPrivateKey = aGVsbG8=\nAddress = 10.0.0.2/32\n\n\
+                      [Peer]\nPublicKey = d29ybGQ=\nEndpoint = vpn.example.com:51820\n\
+                      AllowedIPs = 0.0.0.0/0\n";
+
+    #[test]
+    fn test_formatting_does_not_change_fingerprint() {
+        let reformatted = "# exported by provider\n[interface]\nPrivateKey=aGVsbG8=\n\
+                           Address =  10.0.0.2/32\n[Peer]\nPublicKey = d29ybGQ=\n\
+                           Endpoint = vpn.example.com:51820\nAllowedIPs = 0.0.0.0/0\n";
+        let base = Path::new(".");
+        assert_eq!(
+            fingerprint(Protocol::WireGuard, WG, base),
+            fingerprint(Protocol::WireGuard, reformatted, base)
+        );
+    }
+
+    #[test]
+    fn test_same_endpoint_and_key_differs_in_content_only() {
+        let base = Path::new(".");
+        let other_dns = format!("{WG}DNS = 1.1.1.1\n");
+        let a = fingerprint(Protocol::WireGuard, WG, base);
+        let b = fingerprint(Protocol::WireGuard, &other_dns, base);
+        assert_ne!(a.content, b.content);
+        assert!(a.identity.is_some());
+        assert_eq!(a.identity, b.identity);
+
+        let other_server = WG.replace("vpn.example.com", "other.example.com");
+        let c = fingerprint(Protocol::WireGuard, &other_server, base);
+        assert_ne!(a.identity, c.identity);
+    }
+
+    #[test]
+    fn test_openvpn_file_refs_compare_by_file_name() {
+        let source = "client\nremote vpn.example.com 1194\nca ca.crt\n";
+        let stored = "client\nremote vpn.example.com 1194\nca /home/u/.config/vortix/profiles/work.files/ca.crt\n";
+        assert_eq!(
+            normalise(Protocol::OpenVPN, source),
+            normalise(Protocol::OpenVPN, stored)
+        );
+    }
+}
@@ -0,0 +1,333 @@
+//! Grouping and sorting for the profile sidebar tree.
+//!
+//! The sidebar is built in two passes: `sort_profiles` orders `App::profiles`
+//! in place, then `build_rows` turns the (filtered) profiles into a flat list
+//! of group headers and profile rows that the table widget can render.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use super::search::ProfileMatch;
+use crate::state::{GroupBy, SortBy, VpnProfile};
+
+/// Group for profiles whose country cannot be determined.
+pub const UNKNOWN_GROUP: &str = "Unknown";
+/// Group for profiles without any tags.
+pub const UNTAGGED_GROUP: &str = "Untagged";
+
+/// Country codes used in `City, CC` locations, mapped to the names used by
+/// the bare-country locations so both end up in the same group.
+const COUNTRY_CODES: &[(&str, &str)] = &[
+    ("AU", "Australia"),
+    ("CA", "Canada"),
+    ("CH", "Switzerland"),
+    ("DE", "Germany"),
+    ("ES", "Spain"),
+    ("FR", "France"),
+    ("GB", "United Kingdom"),
+    ("IT", "Italy"),
+    ("JP", "Japan"),
+    ("NL", "Netherlands"),
+    ("SE", "Sweden"),
+    ("SG", "Singapore"),
+    ("US", "United States"),
+];
+
+/// A single row in the sidebar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SidebarRow {
+    /// Collapsible group header.
+    Group {
+        /// Group name (country, protocol or tag).
+        key: String,
+        /// Number of profiles in the group that pass the filter.
+        members: usize,
+        /// Whether the members are hidden.
+        collapsed: bool,
+    },
+    /// A profile entry.
+    Profile(ProfileMatch),
+}
+
+/// Extract the country from a profile location.
+///
+/// Handles both `"Frankfurt, DE"` and `"Germany"` style locations.
+pub fn country_of(location: &str) -> String {
+    if let Some((_, code)) = location.rsplit_once(", ") {
+        let code = code.trim();
+        return COUNTRY_CODES
+            .iter()
+            .find(|(c, _)| c.eq_ignore_ascii_case(code))
+            .map_or_else(|| code.to_string(), |(_, name)| (*name).to_string());
+    }
+
+    let location = location.trim();
+    if location.is_empty() {
+        UNKNOWN_GROUP.to_string()
+    } else {
+        location.to_string()
+    }
+}
+
+/// Group keys a profile belongs to under the given grouping.
+pub fn group_keys(profile: &VpnProfile, group_by: GroupBy) -> Vec<String> {
+    match group_by {
+        GroupBy::None => Vec::new(),
+        GroupBy::Country => vec![country_of(&profile.location)],
+        GroupBy::Protocol => vec![profile.protocol.to_string()],
+        GroupBy::Tag => {
+            let mut tags = profile.tags.clone();
+            tags.sort();
+            tags.dedup();
+            if tags.is_empty() {
+                vec![UNTAGGED_GROUP.to_string()]
+            } else {
+                tags
+            }
+        }
+    }
+}
+
+fn is_fallback_group(key: &str) -> bool {
+    key == UNKNOWN_GROUP || key == UNTAGGED_GROUP
+}
+
+/// Compare two profiles for the given sort order. Ties fall back to the name.
+pub fn compare_profiles(a: &VpnProfile, b: &VpnProfile, sort_by: SortBy) -> Ordering {
+    let primary = match sort_by {
+        SortBy::Name => Ordering::Equal,
+        // Most recent first, never-used last
+        SortBy::LastUsed => b.last_used.cmp(&a.last_used),
+        // Fastest first, unmeasured last
+        SortBy::Latency => match (a.last_latency_ms, b.last_latency_ms) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+        SortBy::Usage => b.connect_count.cmp(&a.connect_count),
+    };
+    primary.then_with(|| a.name.cmp(&b.name))
+}
+
+/// Sort profiles in place for the given sort order.
+pub fn sort_profiles(profiles: &mut [VpnProfile], sort_by: SortBy) {
+    profiles.sort_by(|a, b| compare_profiles(a, b, sort_by));
+}
+
+/// Build the sidebar rows from already ordered filter matches.
+///
+/// Groups appear in the order of their first member, except for name sorting
+/// where groups are alphabetical. The `Unknown`/`Untagged` groups always come
+/// last. `expand_all` ignores `collapsed` (used while a filter is active so
+/// matches are never hidden).
+pub fn build_rows(
+    profiles: &[VpnProfile],
+    matches: &[ProfileMatch],
+    group_by: GroupBy,
+    sort_by: SortBy,
+    collapsed: &HashSet<String>,
+    expand_all: bool,
+) -> Vec<SidebarRow> {
+    if group_by == GroupBy::None {
+        return matches.iter().cloned().map(SidebarRow::Profile).collect();
+    }
+
+    let mut groups: Vec<(String, Vec<&ProfileMatch>)> = Vec::new();
+    for m in matches {
+        for key in group_keys(&profiles[m.index], group_by) {
+            if let Some((_, members)) = groups.iter_mut().find(|(k, _)| *k == key) {
+                members.push(m);
+            } else {
+                groups.push((key, vec![m]));
+            }
+        }
+    }
+
+    if sort_by == SortBy::Name {
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+    groups.sort_by_key(|(key, _)| is_fallback_group(key));
+
+    let mut rows = Vec::new();
+    for (key, members) in groups {
+        let is_collapsed = !expand_all && collapsed.contains(&key);
+        rows.push(SidebarRow::Group {
+            key,
+            members: members.len(),
+            collapsed: is_collapsed,
+        });
+        if !is_collapsed {
+            rows.extend(members.into_iter().cloned().map(SidebarRow::Profile));
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::search::filter_profiles;
+    use crate::state::Protocol;
+    use std::path::PathBuf;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn profile(name: &str, location: &str, protocol: Protocol, tags: &[&str]) -> VpnProfile {
+        VpnProfile {
+            name: name.to_string(),
+            protocol,
+            location: location.to_string(),
+            custom_location: None,
+            config_path: PathBuf::from(format!("/tmp/{name}.conf")),
+            last_used: None,
+            tags: tags.iter().map(ToString::to_string).collect(),
+            last_latency_ms: None,
+            connect_count: 0,
+            split_tunnel: crate::core::split_tunnel::SplitTunnel::default(),
+        }
+    }
+
+    fn group_headers(rows: &[SidebarRow]) -> Vec<(String, usize, bool)> {
+        rows.iter()
+            .filter_map(|r| match r {
+                SidebarRow::Group {
+                    key,
+                    members,
+                    collapsed,
+                    ..
+                } => Some((key.clone(), *members, *collapsed)),
+                SidebarRow::Profile(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_country_of() {
+        assert_eq!(country_of("Frankfurt, DE"), "Germany");
+        assert_eq!(country_of("Germany"), "Germany");
+        assert_eq!(country_of("Somewhere, XX"), "XX");
+        assert_eq!(country_of(""), UNKNOWN_GROUP);
+    }
+
+    #[test]
+    fn test_group_keys_tags() {
+        let tagged = profile(
+            "a",
+            "Unknown",
+            Protocol::WireGuard,
+            &["work", "home", "work"],
+        );
+        assert_eq!(group_keys(&tagged, GroupBy::Tag), vec!["home", "work"]);
+
+        let untagged = profile("b", "Unknown", Protocol::WireGuard, &[]);
+        assert_eq!(group_keys(&untagged, GroupBy::Tag), vec![UNTAGGED_GROUP]);
+        assert!(group_keys(&untagged, GroupBy::None).is_empty());
+    }
+
+    #[test]
+    fn test_sort_by_latency_and_last_used() {
+        let mut fast = profile("b-fast", "Unknown", Protocol::WireGuard, &[]);
+        fast.last_latency_ms = Some(20);
+        let mut slow = profile("c-slow", "Unknown", Protocol::WireGuard, &[]);
+        slow.last_latency_ms = Some(90);
+        slow.last_used = Some(UNIX_EPOCH + Duration::from_secs(100));
+        let unmeasured = profile("a-new", "Unknown", Protocol::WireGuard, &[]);
+
+        let mut profiles = vec![unmeasured.clone(), slow.clone(), fast.clone()];
+        sort_profiles(&mut profiles, SortBy::Latency);
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["b-fast", "c-slow", "a-new"]);
+
+        sort_profiles(&mut profiles, SortBy::LastUsed);
+        assert_eq!(profiles[0].name, "c-slow");
+
+        sort_profiles(&mut profiles, SortBy::Name);
+        assert_eq!(profiles[0].name, "a-new");
+    }
+
+    #[test]
+    fn test_build_rows_groups_by_country() {
+        let profiles = vec![
+            profile("de-01", "Germany", Protocol::WireGuard, &[]),
+            profile("frankfurt", "Frankfurt, DE", Protocol::OpenVPN, &[]),
+            profile("misc", "Unknown", Protocol::WireGuard, &[]),
+            profile("nl-01", "Netherlands", Protocol::WireGuard, &[]),
+        ];
+        let matches = filter_profiles(&profiles, "");
+        let rows = build_rows(
+            &profiles,
+            &matches,
+            GroupBy::Country,
+            SortBy::Name,
+            &HashSet::new(),
+            false,
+        );
+
+        assert_eq!(
+            group_headers(&rows),
+            vec![
+                ("Germany".to_string(), 2, false),
+                ("Netherlands".to_string(), 1, false),
+                ("Unknown".to_string(), 1, false),
+            ]
+        );
+        assert_eq!(rows.len(), 7);
+    }
+
+    #[test]
+    fn test_build_rows_collapsed_groups() {
+        let profiles = vec![
+            profile("a", "Unknown", Protocol::WireGuard, &[]),
+            profile("b", "Unknown", Protocol::OpenVPN, &[]),
+        ];
+        let matches = filter_profiles(&profiles, "");
+        let collapsed: HashSet<String> = std::iter::once("OpenVPN".to_string()).collect();
+
+        let rows = build_rows(
+            &profiles,
+            &matches,
+            GroupBy::Protocol,
+            SortBy::Name,
+            &collapsed,
+            false,
+        );
+        // OpenVPN header (collapsed, no members) + WireGuard header + 1 member
+        assert_eq!(rows.len(), 3);
+
+        let expanded = build_rows(
+            &profiles,
+            &matches,
+            GroupBy::Protocol,
+            SortBy::Name,
+            &collapsed,
+            true,
+        );
+        assert_eq!(expanded.len(), 4);
+    }
+
+    #[test]
+    fn test_build_rows_flat() {
+        let profiles = vec![profile("a", "Unknown", Protocol::WireGuard, &["x", "y"])];
+        let matches = filter_profiles(&profiles, "");
+        let rows = build_rows(
+            &profiles,
+            &matches,
+            GroupBy::None,
+            SortBy::Name,
+            &HashSet::new(),
+            false,
+        );
+        assert_eq!(rows, vec![SidebarRow::Profile(matches[0].clone())]);
+
+        // With tag grouping a profile with two tags shows up twice
+        let rows = build_rows(
+            &profiles,
+            &matches,
+            GroupBy::Tag,
+            SortBy::Name,
+            &HashSet::new(),
+            false,
+        );
+        assert_eq!(rows.len(), 4);
+    }
+}
@@ -0,0 +1,104 @@
+//! Crash-safe kill switch guard.
+//!
+//! A panic or a SIGTERM/SIGINT/SIGHUP would otherwise leave the firewall
+//! however vortix had it, possibly cutting the user off with nothing left
+//! to release it. The guard restores the terminal and settles the kill
+//! switch by its mode before the process ends: strict mode (`AlwaysOn`)
+//! keeps blocking, reloading the rules so no captive portal window
+//! outlives the session, and the other modes release.
+//! The next start finds the dead PID in `killswitch.state` and offers to
+//! restore or release whatever is still loaded.
+
+use crate::constants;
+use crate::core::killswitch;
+use crate::logger::{self, LogLevel};
+use crate::state::{KillSwitchMode, KillSwitchState};
+
+/// Install the panic hook and signal handlers. `restore_terminal` runs
+/// first so the outcome is printed on a usable terminal.
+pub fn install(restore_terminal: fn()) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Background workers may panic without taking the app down
+        if std::thread::current().name() == Some("main") {
+            restore_terminal();
+            if let Some(outcome) = settle_kill_switch() {
+                eprintln!("{outcome}");
+            }
+        }
+        previous(info);
+    }));
+
+    #[cfg(unix)]
+    {
+        use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+
+        match signal_hook::iterator::Signals::new([SIGTERM, SIGINT, SIGHUP]) {
+            Ok(mut signals) => {
+                std::thread::spawn(move || {
+                    if let Some(signal) = signals.forever().next() {
+                        restore_terminal();
+                        if let Some(outcome) = settle_kill_switch() {
+                            eprintln!("{outcome}");
+                        }
+                        std::process::exit(128 + signal);
+                    }
+                });
+            }
+            Err(e) => logger::log(
+                LogLevel::Warning,
+                "GUARD",
+                format!("Failed to install signal handlers: {e}"),
+            ),
+        }
+    }
+}
+
+/// Apply the kill switch mode's policy for a vortix that is going away.
+/// Returns what was done, or `None` when there was nothing to do.
+pub fn settle_kill_switch() -> Option<String> {
+    let persisted = killswitch::load_state()?;
+    let interface = persisted.vpn_interface.as_deref();
+
+    match persisted.mode {
+        KillSwitchMode::AlwaysOn => {
+            if let Err(e) = killswitch::enable_blocking(
+                interface.unwrap_or(killswitch::DEFAULT_VPN_INTERFACE),
+                &killswitch::server_addresses(&persisted.endpoints),
+                persisted.split.as_ref(),
+                &killswitch::load_policy(),
+            ) {
+                return Some(format!("{}{e}", constants::MSG_GUARD_BLOCK_FAILED));
+            }
+            let _ = killswitch::save_state(
+                persisted.mode,
+                KillSwitchState::Blocking,
+                interface,
+                &persisted.endpoints,
+                persisted.split.as_ref(),
+            );
+            Some(constants::MSG_GUARD_KEPT.to_string())
+        }
+        KillSwitchMode::Auto | KillSwitchMode::Off => {
+            if !persisted.state.is_blocking() {
+                return None;
+            }
+            if let Err(e) = killswitch::disable_blocking() {
+                return Some(format!("{}{e}", constants::MSG_GUARD_RELEASE_FAILED));
+            }
+            let state = if persisted.mode == KillSwitchMode::Off {
+                KillSwitchState::Disabled
+            } else {
+                KillSwitchState::Armed
+            };
+            let _ = killswitch::save_state(
+                persisted.mode,
+                state,
+                interface,
+                &persisted.endpoints,
+                persisted.split.as_ref(),
+            );
+            Some(constants::MSG_GUARD_RELEASED.to_string())
+        }
+    }
+}
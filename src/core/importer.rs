@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+use crate::constants;
+use crate::state::VpnProfile;
+use std::path::{Path, PathBuf};
 
 /// Target for an import operation
 #[derive(Debug, Clone)]
@@ -6,6 +8,142 @@ pub enum ImportTarget {
     Url(String),
     File(PathBuf),
     Directory(PathBuf),
+    /// A ZIP archive of configs, possibly in nested folders
+    Archive(PathBuf),
+}
+
+/// What to do when an imported profile already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Conflict {
+    /// Leave the existing profile alone.
+    #[default]
+    Skip,
+    /// Replace the existing profile.
+    Overwrite,
+    /// Import under a new name, e.g. `work(1)`.
+    KeepBoth,
+}
+
+/// Result of importing one profile.
+pub enum Outcome {
+    Imported(VpnProfile, Vec<String>),
+    Replaced(VpnProfile, Vec<String>),
+    Skipped(String),
+    Failed(String, String),
+}
+
+/// Tally of an import, for summaries.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub imported: usize,
+    pub replaced: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+impl Summary {
+    pub fn of(outcomes: &[Outcome]) -> Self {
+        let mut summary = Self::default();
+        for outcome in outcomes {
+            summary.add(outcome);
+        }
+        summary
+    }
+
+    /// Count one more outcome.
+    pub fn add(&mut self, outcome: &Outcome) {
+        match outcome {
+            Outcome::Imported(..) => self.imported += 1,
+            Outcome::Replaced(..) => self.replaced += 1,
+            Outcome::Skipped(_) => self.skipped += 1,
+            Outcome::Failed(..) => self.failed += 1,
+        }
+    }
+
+    /// Number of files processed.
+    pub fn total(&self) -> usize {
+        self.imported + self.replaced + self.skipped + self.failed
+    }
+}
+
+/// Include/exclude globs selecting configs in a directory or archive.
+///
+/// A pattern matches either the file name or the path relative to the
+/// imported folder, so `de-*` and `wireguard/*.conf` both work.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+}
+
+impl Filter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, String> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| glob::Pattern::new(p).map_err(|e| format!("Invalid pattern '{p}': {e}")))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    /// Whether the config at `relative` is selected: it matches an include
+    /// pattern (or there are none) and no exclude pattern.
+    pub fn matches(&self, relative: &Path) -> bool {
+        let name = relative
+            .file_name()
+            .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        let hit = |p: &glob::Pattern| p.matches(&name) || p.matches_path(relative);
+        (self.include.is_empty() || self.include.iter().any(hit)) && !self.exclude.iter().any(hit)
+    }
+}
+
+/// Whether `path` has a config extension (`.conf` or `.ovpn`).
+pub fn is_config_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("conf") || ext.eq_ignore_ascii_case("ovpn"))
+}
+
+/// All configs below `dir` selected by `filter`, sorted by path.
+///
+/// Hidden directories are skipped and symlinked directories are not
+/// followed, so a link back up the tree cannot loop. Folders more than
+/// [`constants::MAX_IMPORT_DEPTH`] levels down are not searched.
+pub fn find_configs(dir: &Path, filter: &Filter) -> Result<Vec<PathBuf>, String> {
+    let mut configs = Vec::new();
+    let mut pending = vec![(dir.to_path_buf(), 0)];
+
+    while let Some((current, depth)) = pending.pop() {
+        let entries = match std::fs::read_dir(&current) {
+            Ok(entries) => entries,
+            Err(e) if current == dir => return Err(e.to_string()),
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if !entry.file_name().to_string_lossy().starts_with('.')
+                    && depth < constants::MAX_IMPORT_DEPTH
+                {
+                    pending.push((path, depth + 1));
+                }
+            } else if path.is_file()
+                && is_config_file(&path)
+                && filter.matches(path.strip_prefix(dir).unwrap_or(&path))
+            {
+                configs.push(path);
+            }
+        }
+    }
+
+    configs.sort();
+    Ok(configs)
 }
 
 /// Helper to expand paths with ~ to standard `PathBuf`
@@ -19,7 +157,7 @@ pub fn expand_home(path_str: &str) -> PathBuf {
 }
 
 /// Basic URL validation - checks structure without external dependencies
-fn is_valid_url(url: &str) -> bool {
+pub fn is_valid_url(url: &str) -> bool {
     // Must start with http:// or https://
     let url = url.trim();
     if !url.starts_with("http://") && !url.starts_with("https://") {
@@ -66,7 +204,9 @@ pub fn resolve_target(input: &str) -> Result<ImportTarget, String> {
     }
 
     // 4. Determine Type
-    if path.is_file() {
+    if path.is_file() && super::archive::is_archive(&path) {
+        Ok(ImportTarget::Archive(path))
+    } else if path.is_file() {
         Ok(ImportTarget::File(path))
     } else if path.is_dir() {
         Ok(ImportTarget::Directory(path))
@@ -79,6 +219,85 @@ pub fn resolve_target(input: &str) -> Result<ImportTarget, String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_find_configs_recurses_and_filters() {
+        let scratch = crate::core::vault::ScratchDir::new("test-find").unwrap();
+        let root = &scratch.path;
+        for file in [
+            "de-fra.conf",
+            "nested/de-ber.ovpn",
+            "nested/deeper/us-nyc.conf",
+            "nested/notes.txt",
+            ".git/hooks.conf",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        let relative = |filter: &Filter| -> Vec<String> {
+            find_configs(root, filter)
+                .unwrap()
+                .iter()
+                .map(|p| p.strip_prefix(root).unwrap().display().to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            relative(&Filter::default()),
+            [
+                "de-fra.conf",
+                "nested/de-ber.ovpn",
+                "nested/deeper/us-nyc.conf"
+            ]
+        );
+        let filter = Filter::new(&["de-*".into()], &["*.ovpn".into()]).unwrap();
+        assert_eq!(relative(&filter), ["de-fra.conf"]);
+        let filter = Filter::new(&["nested/*".into()], &[]).unwrap();
+        assert_eq!(
+            relative(&filter),
+            ["nested/de-ber.ovpn", "nested/deeper/us-nyc.conf"]
+        );
+    }
+
+    #[test]
+    fn test_find_configs_skips_hidden_links_and_deep_dirs() {
+        let scratch = crate::core::vault::ScratchDir::new("test-find-limits").unwrap();
+        let root = &scratch.path;
+        let deepest = (1..=constants::MAX_IMPORT_DEPTH)
+            .map(|level| format!("l{level}"))
+            .collect::<Vec<_>>()
+            .join("/");
+        for file in [
+            "top.conf".to_string(),
+            ".hidden/secret.conf".to_string(),
+            "nested/.cache/cached.ovpn".to_string(),
+            format!("{deepest}/deepest.conf"),
+            format!("{deepest}/too-deep/skipped.conf"),
+            "outside/linked.conf".to_string(),
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        // A link to another folder and one back up the tree
+        std::os::unix::fs::symlink(root.join("outside"), root.join("nested/link")).unwrap();
+        std::os::unix::fs::symlink(root, root.join("nested/loop")).unwrap();
+
+        let found: Vec<String> = find_configs(root, &Filter::default())
+            .unwrap()
+            .iter()
+            .map(|p| p.strip_prefix(root).unwrap().display().to_string())
+            .collect();
+        assert_eq!(
+            found,
+            [
+                format!("{deepest}/deepest.conf"),
+                "outside/linked.conf".to_string(),
+                "top.conf".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn test_is_valid_url_https() {
         assert!(is_valid_url("https://example.com/test.conf"));
@@ -7,18 +7,26 @@
 //! This module modifies system firewall rules and requires root privileges.
 //! Firewall rules are designed to:
 //! - Always allow loopback traffic
-//! - Always allow local network (RFC1918) traffic
-//! - Allow VPN server IP for reconnection
+//! - Allow local network (RFC1918) traffic and DHCP, unless the
+//!   [`KillSwitchPolicy`] says otherwise
+//! - Allow the policy's extra networks and ports
+//! - Allow every address of the profile's VPN servers for reconnection,
+//!   resolved ahead of time ([`resolve_endpoints`])
 //! - Allow all traffic on VPN interface
+//! - During a captive portal window ([`enable_portal_window`]), allow
+//!   HTTP/HTTPS to the gateway's network so the login page loads
 
+use crate::core::split_tunnel::Policy;
 use crate::logger::{self, LogLevel};
 use crate::state::{KillSwitchMode, KillSwitchState};
 use crate::utils;
 use std::fmt::Write as FmtWrite;
 use std::fs;
 use std::io::{self, Write as IoWrite};
+use std::net::{IpAddr, ToSocketAddrs};
 use std::path::PathBuf;
 use std::process::Command;
+use std::sync::OnceLock;
 
 /// State file path for kill switch persistence
 const STATE_FILE: &str = "killswitch.state";
@@ -61,18 +69,165 @@ impl From<io::Error> for KillSwitchError {
     }
 }
 
-/// Generate pf rules that block all traffic except VPN
-fn generate_pf_rules(vpn_interface: &str, vpn_server_ip: Option<&str>) -> String {
-    let mut rules = format!(
-        r"# Vortix Kill Switch Rules - Auto-generated
-# DO NOT EDIT - Will be overwritten
+/// How IPv6 is treated while the kill switch blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ipv6Policy {
+    /// IPv6 only through the tunnel.
+    #[default]
+    Block,
+    /// Also link-local, unique local and multicast neighbours, so IPv6
+    /// devices on the LAN stay reachable.
+    Lan,
+    /// IPv6 is not filtered (for tunnels without IPv6 on networks where it
+    /// must keep working).
+    Allow,
+}
 
-# Default: block all
-block all
+impl std::fmt::Display for Ipv6Policy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Block => write!(f, "block"),
+            Self::Lan => write!(f, "lan"),
+            Self::Allow => write!(f, "allow"),
+        }
+    }
+}
 
-# Allow loopback
-pass quick on lo0 all
+/// What the kill switch lets through besides the tunnel, stored in
+/// `killswitch.json`. The default matches the original fixed rules.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct KillSwitchPolicy {
+    /// Allow the private (RFC1918) ranges outside the tunnel.
+    pub allow_lan: bool,
+    /// Allow DHCP (and `DHCPv6` unless IPv6 is blocked).
+    pub allow_dhcp: bool,
+    /// Networks always allowed, e.g. a printer or NAS subnet.
+    pub allowed_cidrs: Vec<String>,
+    /// Ports always allowed: `tcp/631`, `udp/5353` or `631` for both.
+    pub allowed_ports: Vec<String>,
+    /// DNS (53, 853) only through the tunnel, even to LAN resolvers.
+    pub dns_tunnel_only: bool,
+    pub ipv6: Ipv6Policy,
+    /// Plain HTTP URL probed for captive portals (empty: no probing).
+    pub portal_url: String,
+    /// How long a confirmed portal window lasts.
+    pub portal_window_secs: u64,
+}
+
+impl Default for KillSwitchPolicy {
+    fn default() -> Self {
+        Self {
+            allow_lan: true,
+            allow_dhcp: true,
+            allowed_cidrs: Vec::new(),
+            allowed_ports: Vec::new(),
+            dns_tunnel_only: false,
+            ipv6: Ipv6Policy::default(),
+            portal_url: crate::constants::PORTAL_PROBE_URL.to_string(),
+            portal_window_secs: crate::constants::PORTAL_WINDOW_SECS,
+        }
+    }
+}
+
+impl KillSwitchPolicy {
+    /// Drop allowed networks and ports that do not parse, normalising the
+    /// rest. Returns a message for each dropped entry.
+    ///
+    /// The policy file can be edited by hand, and its entries are written
+    /// into the firewall rules verbatim.
+    pub fn sanitize(&mut self) -> Vec<String> {
+        let mut dropped = Vec::new();
+        let cidrs = std::mem::take(&mut self.allowed_cidrs);
+        for net in cidrs {
+            match net.parse::<crate::vpn::wireguard::Cidr>() {
+                Ok(cidr) if !self.allowed_cidrs.contains(&cidr.to_string()) => {
+                    self.allowed_cidrs.push(cidr.to_string());
+                }
+                Ok(_) => {}
+                Err(e) => dropped.push(format!("allowed network {net:?}: {e}")),
+            }
+        }
+        let ports = std::mem::take(&mut self.allowed_ports);
+        for port in ports {
+            match parse_port_rule(&port) {
+                Ok(rule) if !self.allowed_ports.contains(&rule) => self.allowed_ports.push(rule),
+                Ok(_) => {}
+                Err(e) => dropped.push(format!("allowed port {port:?}: {e}")),
+            }
+        }
+        dropped
+    }
+
+    /// `LAN allowed, DNS tunnel only, +2 nets, IPv6 blocked`
+    pub fn summary(&self) -> String {
+        let mut parts = vec![if self.allow_lan {
+            "LAN allowed".to_string()
+        } else {
+            "LAN blocked".to_string()
+        }];
+        if self.dns_tunnel_only {
+            parts.push("DNS tunnel only".to_string());
+        }
+        if !self.allowed_cidrs.is_empty() {
+            parts.push(format!("+{} nets", self.allowed_cidrs.len()));
+        }
+        if !self.allowed_ports.is_empty() {
+            parts.push(format!("+{} ports", self.allowed_ports.len()));
+        }
+        if self.portal_url.is_empty() {
+            parts.push("portal probe off".to_string());
+        }
+        parts.push(match self.ipv6 {
+            Ipv6Policy::Block => "IPv6 blocked".to_string(),
+            Ipv6Policy::Lan => "IPv6 LAN".to_string(),
+            Ipv6Policy::Allow => "IPv6 open".to_string(),
+        });
+        parts.join(", ")
+    }
+}
+
+/// Check an allowed port and return it as `tcp/N`, `udp/N` or `N`.
+pub fn parse_port_rule(value: &str) -> std::result::Result<String, String> {
+    let value = value.trim().to_ascii_lowercase();
+    let (proto, port) = match value.split_once('/') {
+        Some((proto @ ("tcp" | "udp"), port)) => (Some(proto), port),
+        Some(_) => return Err(format!("'{value}' must be tcp/PORT, udp/PORT or PORT")),
+        None => (None, value.as_str()),
+    };
+    match port.parse::<u16>() {
+        Ok(0) | Err(_) => Err(format!("'{value}' is not a valid port (1-65535)")),
+        Ok(port) => Ok(proto.map_or_else(|| port.to_string(), |p| format!("{p}/{port}"))),
+    }
+}
+
+/// Where allowed ports may be reached: the private ranges and multicast
+/// (printers, NAS, mDNS), never the internet.
+const PORT_NETWORKS: &[&str] = &[
+    "10.0.0.0/8",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "224.0.0.0/4",
+];
 
+/// Their IPv6 counterparts, unless IPv6 is blocked.
+const PORT_NETWORKS_V6: &[&str] = &["fe80::/10", "fc00::/7", "ff00::/8"];
+
+/// `tcp/631` → `proto tcp to { 10.0.0.0/8 … } port 631`
+fn port_rule(rule: &str, networks: &str) -> Option<String> {
+    let rule = parse_port_rule(rule).ok()?;
+    Some(match rule.split_once('/') {
+        Some((proto, port)) => format!("proto {proto} to {{ {networks} }} port {port}"),
+        None => format!("proto {{ tcp udp }} to {{ {networks} }} port {rule}"),
+    })
+}
+
+/// Rules for what the policy allows outside the tunnel.
+fn push_allowed(rules: &mut String, policy: &KillSwitchPolicy) {
+    if policy.allow_lan {
+        rules.push_str(
+            r"
 # Allow local network (RFC1918)
 pass out quick to 192.168.0.0/16
 pass in quick from 192.168.0.0/16
@@ -80,49 +235,218 @@ pass out quick to 10.0.0.0/8
 pass in quick from 10.0.0.0/8
 pass out quick to 172.16.0.0/12
 pass in quick from 172.16.0.0/12
+",
+        );
+    }
+    if policy.ipv6 == Ipv6Policy::Lan {
+        rules.push_str(
+            r"
+# Allow local IPv6 (link-local, unique local, multicast)
+pass out quick to fe80::/10
+pass in quick from fe80::/10
+pass out quick to fc00::/7
+pass in quick from fc00::/7
+pass out quick to ff00::/8
+",
+        );
+    }
 
+    if policy.allow_dhcp {
+        rules.push_str(
+            r"
 # Allow DHCP
 pass out quick proto udp from any port 68 to any port 67
 pass in quick proto udp from any port 67 to any port 68
+",
+        );
+        if policy.ipv6 != Ipv6Policy::Block {
+            rules.push_str(
+                "pass out quick inet6 proto udp from any port 546 to any port 547\n\
+                 pass in quick inet6 proto udp from any port 547 to any port 546\n",
+            );
+        }
+    }
 
-# Allow all traffic on VPN interface
-pass quick on {vpn_interface} all
-"
+    if !policy.allowed_cidrs.is_empty() {
+        rules.push_str("\n# Allowed networks\n");
+        for net in &policy.allowed_cidrs {
+            writeln!(rules, "pass out quick to {net}\npass in quick from {net}").unwrap();
+        }
+    }
+    // Local destinations only: a port opened for a NAS must not open it
+    // to the whole internet outside the tunnel
+    let mut networks: Vec<&str> = PORT_NETWORKS.to_vec();
+    if policy.ipv6 != Ipv6Policy::Block {
+        networks.extend(PORT_NETWORKS_V6);
+    }
+    networks.extend(policy.allowed_cidrs.iter().map(String::as_str));
+    let networks = networks.join(" ");
+    let ports: Vec<String> = policy
+        .allowed_ports
+        .iter()
+        .filter_map(|p| port_rule(p, &networks))
+        .collect();
+    if !ports.is_empty() {
+        rules.push_str("\n# Allowed ports (local networks only)\n");
+        for port in ports {
+            writeln!(rules, "pass out quick {port}").unwrap();
+        }
+    }
+}
+
+/// Generate pf rules that block all traffic except VPN, plus what the
+/// `policy` allows.
+///
+/// With split tunnel rules, excluded networks may always leave outside the
+/// tunnel; with an include list only the included networks are held to the
+/// tunnel and everything else is let through.
+pub fn generate_pf_rules(
+    vpn_interface: &str,
+    servers: &[String],
+    split: Option<&Policy>,
+    policy: &KillSwitchPolicy,
+) -> String {
+    generate_rules(vpn_interface, servers, split, policy, None)
+}
+
+/// [`generate_pf_rules`] with the exceptions of a captive portal `window`,
+/// placed ahead of the policy's blocks so `dns_tunnel_only` does not stop
+/// DNS to the gateway.
+fn generate_rules(
+    vpn_interface: &str,
+    servers: &[String],
+    split: Option<&Policy>,
+    policy: &KillSwitchPolicy,
+    window: Option<&PortalWindow>,
+) -> String {
+    let mut rules = String::from(
+        r"# Vortix Kill Switch Rules - Auto-generated
+# DO NOT EDIT - Will be overwritten
+
+# Default: block all
+block all
+
+# Allow loopback
+pass quick on lo0 all
+",
     );
 
-    // Allow VPN server IP if known (for reconnection)
-    if let Some(ip) = vpn_server_ip {
-        // Using writeln! to avoid clippy::write_with_newline and handling the result
-        writeln!(
-            rules,
-            "\n# Allow VPN server for reconnection\npass out quick proto udp to {ip}\npass out quick proto tcp to {ip}"
-        )
-        .unwrap();
+    let include = split.map_or(&[][..], |p| p.include.as_slice());
+    if !include.is_empty() {
+        // Ahead of the local network rules, which could cover them
+        rules.push_str("\n# Split tunnel: included networks only through the VPN\n");
+        for net in include {
+            writeln!(rules, "block out quick on ! {vpn_interface} to {net}").unwrap();
+        }
+    }
+
+    writeln!(
+        rules,
+        "\n# Allow all traffic on VPN interface\npass quick on {vpn_interface} all"
+    )
+    .unwrap();
+
+    if let Some(window) = window {
+        rules.push_str(&generate_portal_rules(window));
+    }
+
+    // Everything below applies outside the tunnel only
+    if policy.dns_tunnel_only {
+        rules.push_str(
+            "\n# DNS only through the VPN\nblock out quick proto { tcp udp } to any port { 53 853 }\n",
+        );
+    }
+
+    push_allowed(&mut rules, policy);
+
+    // Every address the profile may (re)connect to
+    if !servers.is_empty() {
+        rules.push_str("\n# Allow VPN servers for reconnection\n");
+        for ip in servers {
+            writeln!(
+                rules,
+                "pass out quick proto udp to {ip}\npass out quick proto tcp to {ip}"
+            )
+            .unwrap();
+        }
+    }
+
+    if let Some(split) = split {
+        if !split.exclude.is_empty() {
+            rules.push_str("\n# Split tunnel: excluded networks bypass the VPN\n");
+            for net in &split.exclude {
+                writeln!(rules, "pass out quick to {net}").unwrap();
+            }
+        }
+    }
+
+    if policy.ipv6 == Ipv6Policy::Allow {
+        rules.push_str("\n# IPv6 is not filtered\npass quick inet6 all\n");
+    }
+
+    if !include.is_empty() {
+        rules.push_str("\n# Split tunnel: other traffic bypasses the VPN\npass out all\n");
     }
 
     rules
 }
 
+/// Temporary exception for logging in to a captive portal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortalWindow {
+    /// Network of the default gateway, e.g. `10.12.0.0/22`.
+    pub network: String,
+    /// The gateway itself, which usually answers DNS for the portal.
+    pub gateway: String,
+    /// Addresses of the portal probe URL.
+    pub probe_addresses: Vec<String>,
+}
+
+/// Rules of a portal window: HTTP/HTTPS to the gateway's network, DNS to
+/// the gateway and HTTP to the probe URL.
+fn generate_portal_rules(window: &PortalWindow) -> String {
+    let mut rules = format!(
+        "\n# Captive portal window (temporary)\n\
+         pass out quick proto tcp to {network} port {{ 80 443 }}\n\
+         pass out quick proto {{ tcp udp }} to {gateway} port 53\n",
+        network = window.network,
+        gateway = window.gateway
+    );
+    for ip in &window.probe_addresses {
+        writeln!(rules, "pass out quick proto tcp to {ip} port 80").unwrap();
+    }
+    rules
+}
+
 /// Enable kill switch by loading restrictive pf rules.
 ///
 /// # Arguments
 ///
 /// * `vpn_interface` - The VPN tunnel interface (e.g., "utun3", "tun0")
-/// * `vpn_server_ip` - Optional VPN server IP to allow for reconnection
+/// * `servers` - VPN server addresses to allow for reconnection
+/// * `split` - Split tunnel rules of the connection, if any
+/// * `policy` - What is allowed besides the tunnel
 ///
 /// # Errors
 ///
 /// Returns error if not running as root or pf commands fail.
-pub fn enable_blocking(vpn_interface: &str, vpn_server_ip: Option<&str>) -> Result<()> {
+pub fn enable_blocking(
+    vpn_interface: &str,
+    servers: &[String],
+    split: Option<&Policy>,
+    policy: &KillSwitchPolicy,
+) -> Result<()> {
     logger::log(
         LogLevel::Info,
         "FIREWALL",
         format!(
             "Enabling kill switch on interface '{}'{}",
             vpn_interface,
-            vpn_server_ip
-                .map(|ip| format!(", server: {ip}"))
-                .unwrap_or_default()
+            if servers.is_empty() {
+                String::new()
+            } else {
+                format!(", servers: {}", servers.join(" "))
+            }
         ),
     );
 
@@ -135,8 +459,51 @@ pub fn enable_blocking(vpn_interface: &str, vpn_server_ip: Option<&str>) -> Resu
         return Err(KillSwitchError::NotRoot);
     }
 
-    // Generate and write pf rules
-    let rules = generate_pf_rules(vpn_interface, vpn_server_ip);
+    load_rules(&generate_pf_rules(vpn_interface, servers, split, policy))?;
+
+    logger::log(
+        LogLevel::Info,
+        "FIREWALL",
+        "✓ Kill switch ACTIVE - blocking non-VPN traffic",
+    );
+    Ok(())
+}
+
+/// Block like [`enable_blocking`], but let the captive portal `window`
+/// through until the rules are loaded again.
+///
+/// # Errors
+///
+/// Returns error if not running as root or pf commands fail.
+pub fn enable_portal_window(
+    vpn_interface: &str,
+    servers: &[String],
+    split: Option<&Policy>,
+    policy: &KillSwitchPolicy,
+    window: &PortalWindow,
+) -> Result<()> {
+    logger::log(
+        LogLevel::Warning,
+        "FIREWALL",
+        format!(
+            "Opening captive portal window: HTTP/HTTPS to {}, DNS to {}",
+            window.network, window.gateway
+        ),
+    );
+    if !crate::utils::is_root() {
+        return Err(KillSwitchError::NotRoot);
+    }
+    load_rules(&generate_rules(
+        vpn_interface,
+        servers,
+        split,
+        policy,
+        Some(window),
+    ))
+}
+
+/// Write `rules` and load them into pf, enabling it.
+fn load_rules(rules: &str) -> Result<()> {
     let mut file = fs::File::create(PF_CONF_PATH)?;
     file.write_all(rules.as_bytes())?;
     logger::log(
@@ -174,15 +541,83 @@ pub fn enable_blocking(vpn_interface: &str, vpn_server_ip: Option<&str>) -> Resu
             return Err(KillSwitchError::CommandFailed(stderr.to_string()));
         }
     }
-
-    logger::log(
-        LogLevel::Info,
-        "FIREWALL",
-        "✓ Kill switch ACTIVE - blocking non-VPN traffic",
-    );
     Ok(())
 }
 
+/// Check `rules` with `pfctl -n`, which parses them without loading
+/// anything.
+///
+/// # Errors
+///
+/// Returns the parser's complaints, or an error if `pfctl` is unavailable.
+pub fn validate_rules(rules: &str) -> Result<()> {
+    let mut child = Command::new("pfctl")
+        .args(["-n", "-f", "-"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => KillSwitchError::CommandFailed(
+                "pfctl not found (the kill switch uses macOS pf)".to_string(),
+            ),
+            _ => KillSwitchError::Io(e),
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(rules.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(KillSwitchError::CommandFailed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// Rules the kill switch would install, shown before anything is loaded.
+///
+/// Generating and dry-running a preview never touches pf or the rules
+/// file; only [`validate_rules`] runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RulePreview {
+    /// Generated pf rules.
+    pub rules: String,
+    /// Result of the last dry run (`None` until requested).
+    pub validation: Option<std::result::Result<(), String>>,
+}
+
+impl RulePreview {
+    /// Generate the rules for `vpn_interface` with the given servers,
+    /// split tunnel and policy.
+    #[must_use]
+    pub fn new(
+        vpn_interface: &str,
+        servers: &[String],
+        split: Option<&Policy>,
+        policy: &KillSwitchPolicy,
+    ) -> Self {
+        Self {
+            rules: generate_pf_rules(vpn_interface, servers, split, policy),
+            validation: None,
+        }
+    }
+
+    /// Check the rules with `pfctl -n` and keep the result.
+    pub fn dry_run(&mut self) -> &std::result::Result<(), String> {
+        self.dry_run_with(validate_rules)
+    }
+
+    fn dry_run_with(
+        &mut self,
+        validate: impl FnOnce(&str) -> Result<()>,
+    ) -> &std::result::Result<(), String> {
+        self.validation
+            .insert(validate(&self.rules).map_err(|e| e.to_string()))
+    }
+}
+
 /// Disable kill switch by flushing pf rules.
 ///
 /// # Errors
@@ -235,13 +670,193 @@ fn get_state_path() -> Option<PathBuf> {
     utils::home_dir().map(|h| h.join(".config").join("vortix").join(STATE_FILE))
 }
 
+fn get_policy_path() -> Option<PathBuf> {
+    utils::home_dir().map(|h| {
+        h.join(".config")
+            .join("vortix")
+            .join(crate::constants::KILLSWITCH_POLICY_FILE_NAME)
+    })
+}
+
+/// Load the kill switch policy, falling back to the default rules.
+///
+/// Invalid allowed networks and ports are dropped with a warning (see
+/// [`KillSwitchPolicy::sanitize`]).
+pub fn load_policy() -> KillSwitchPolicy {
+    let Some(content) = get_policy_path().and_then(|p| fs::read_to_string(p).ok()) else {
+        return KillSwitchPolicy::default();
+    };
+    let mut policy: KillSwitchPolicy = serde_json::from_str(&content).unwrap_or_else(|e| {
+        logger::log(
+            LogLevel::Warning,
+            "FIREWALL",
+            format!("Failed to parse kill switch policy: {e}. Using defaults."),
+        );
+        KillSwitchPolicy::default()
+    });
+    for dropped in policy.sanitize() {
+        logger::log(
+            LogLevel::Warning,
+            "FIREWALL",
+            format!("Ignoring invalid kill switch policy entry: {dropped}"),
+        );
+    }
+    policy
+}
+
+/// Save the kill switch policy.
+pub fn save_policy(policy: &KillSwitchPolicy) -> Result<()> {
+    let Some(path) = get_policy_path() else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(policy).map_err(io::Error::other)?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// A VPN server host and the addresses it resolved to.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ResolvedEndpoint {
+    pub host: String,
+    pub addresses: Vec<String>,
+}
+
+/// Resolve every server host to its IPv4 and IPv6 addresses, so the rules
+/// can let reconnection through without DNS. A host that does not resolve
+/// (the kill switch may be blocking DNS) keeps its `cached` addresses.
+///
+/// Hosts are looked up in parallel, so resolving the servers of every
+/// profile takes as long as the slowest lookup.
+pub fn resolve_endpoints(hosts: &[String], cached: &[ResolvedEndpoint]) -> Vec<ResolvedEndpoint> {
+    let lookups: Vec<Vec<String>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = hosts
+            .iter()
+            .map(|host| scope.spawn(move || lookup(host)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap_or_default())
+            .collect()
+    });
+    hosts
+        .iter()
+        .zip(lookups)
+        .filter_map(|(host, addresses)| {
+            if !addresses.is_empty() {
+                return Some(ResolvedEndpoint {
+                    host: host.clone(),
+                    addresses,
+                });
+            }
+            let previous = cached.iter().find(|e| e.host == *host).cloned();
+            logger::log(
+                LogLevel::Warning,
+                "FIREWALL",
+                if previous.is_some() {
+                    format!("Could not resolve {host}; keeping its cached addresses")
+                } else {
+                    format!(
+                        "Could not resolve {host}; it will be blocked while the kill switch is on"
+                    )
+                },
+            );
+            previous
+        })
+        .collect()
+}
+
+/// Addresses of `host`, sorted and without duplicates; empty if it does not
+/// resolve.
+fn lookup(host: &str) -> Vec<String> {
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return vec![ip.to_string()];
+    }
+    let mut addresses: Vec<String> = (host, 0)
+        .to_socket_addrs()
+        .map(|addrs| addrs.map(|a| a.ip().to_string()).collect())
+        .unwrap_or_default();
+    addresses.sort();
+    addresses.dedup();
+    addresses
+}
+
+/// All addresses of `endpoints`, without duplicates.
+pub fn server_addresses(endpoints: &[ResolvedEndpoint]) -> Vec<String> {
+    let mut addresses = Vec::new();
+    for address in endpoints.iter().flat_map(|e| &e.addresses) {
+        if !addresses.contains(address) {
+            addresses.push(address.clone());
+        }
+    }
+    addresses
+}
+
 /// Persistent state for recovery after crashes.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct PersistedState {
     pub mode: KillSwitchMode,
     pub state: KillSwitchState,
     pub vpn_interface: Option<String>,
-    pub vpn_server_ip: Option<String>,
+    /// Server addresses of the last profile, for when DNS is unavailable.
+    #[serde(default)]
+    pub endpoints: Vec<ResolvedEndpoint>,
+    /// Split tunnel rules of the connection, so the guard reloads the same
+    /// rules.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split: Option<Policy>,
+    /// Process that wrote the state.
+    #[serde(default)]
+    pub pid: Option<u32>,
+    /// When that process started ([`utils::process_start_time`]), so a
+    /// reused PID is not taken for it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid_started: Option<String>,
+}
+
+impl PersistedState {
+    /// Whether the process that wrote the state is gone (or unknown, for
+    /// files written before the PID was recorded).
+    pub fn owner_gone(&self) -> bool {
+        self.owner_gone_with(utils::is_process_alive, utils::process_start_time)
+    }
+
+    fn owner_gone_with(
+        &self,
+        is_alive: impl Fn(u32) -> bool,
+        start_time: impl Fn(u32) -> Option<String>,
+    ) -> bool {
+        let Some(pid) = self.pid else {
+            return true;
+        };
+        if !is_alive(pid) {
+            return true;
+        }
+        // Alive, but possibly another process that was given the same PID
+        match (&self.pid_started, start_time(pid)) {
+            (Some(recorded), Some(current)) => *recorded != current,
+            _ => false,
+        }
+    }
+
+    /// Left blocking by a vortix that is no longer running.
+    pub fn is_stale(&self) -> bool {
+        self.state.is_blocking() && self.owner_gone()
+    }
+}
+
+/// Kill switch mode after the stale kill switch prompt: restoring keeps the
+/// previous session's mode (Auto if it was somehow Off), releasing turns
+/// it off. Either way the rules are taken over as blocking.
+#[must_use]
+pub fn takeover_mode(previous: KillSwitchMode, restore: bool) -> KillSwitchMode {
+    match (restore, previous) {
+        (false, _) => KillSwitchMode::Off,
+        (true, KillSwitchMode::Off) => KillSwitchMode::Auto,
+        (true, mode) => mode,
+    }
 }
 
 /// Load kill switch state from persistence file.
@@ -274,7 +889,8 @@ pub fn save_state(
     mode: KillSwitchMode,
     state: KillSwitchState,
     vpn_interface: Option<&str>,
-    vpn_server_ip: Option<&str>,
+    endpoints: &[ResolvedEndpoint],
+    split: Option<&Policy>,
 ) -> Result<()> {
     let Some(path) = get_state_path() else {
         return Ok(()); // Silently skip if no home dir
@@ -289,7 +905,10 @@ pub fn save_state(
         mode,
         state,
         vpn_interface: vpn_interface.map(String::from),
-        vpn_server_ip: vpn_server_ip.map(String::from),
+        endpoints: endpoints.to_vec(),
+        split: split.cloned(),
+        pid: Some(std::process::id()),
+        pid_started: own_start_time(),
     };
 
     let content = serde_json::to_string_pretty(&persisted).map_err(io::Error::other)?;
@@ -298,6 +917,14 @@ pub fn save_state(
     Ok(())
 }
 
+/// When this process started, read once rather than on every save.
+fn own_start_time() -> Option<String> {
+    static STARTED: OnceLock<Option<String>> = OnceLock::new();
+    STARTED
+        .get_or_init(|| utils::process_start_time(std::process::id()))
+        .clone()
+}
+
 /// Clear the persisted state file.
 pub fn clear_state() {
     if let Some(path) = get_state_path() {
@@ -311,19 +938,291 @@ mod tests {
 
     #[test]
     fn test_generate_pf_rules_with_server() {
-        let rules = generate_pf_rules("utun3", Some("1.2.3.4"));
+        let servers = ["1.2.3.4".to_string(), "2001:db8::7".to_string()];
+        let rules = generate_pf_rules("utun3", &servers, None, &KillSwitchPolicy::default());
         assert!(rules.contains("block all"));
         assert!(rules.contains("pass quick on lo0"));
         assert!(rules.contains("192.168.0.0/16"));
         assert!(rules.contains("pass out quick proto udp to 1.2.3.4"));
+        assert!(rules.contains("pass out quick proto tcp to 2001:db8::7"));
         assert!(rules.contains("pass quick on utun3"));
     }
 
     #[test]
     fn test_generate_pf_rules_without_server() {
-        let rules = generate_pf_rules("utun3", None);
+        let rules = generate_pf_rules("utun3", &[], None, &KillSwitchPolicy::default());
         assert!(rules.contains("block all"));
         assert!(rules.contains("pass quick on utun3"));
         assert!(!rules.contains("1.2.3.4"));
     }
+
+    #[test]
+    fn test_generate_pf_rules_split_tunnel() {
+        let cidr = |s: &str| s.parse().unwrap();
+        let exclude = Policy {
+            include: Vec::new(),
+            exclude: vec![cidr("198.51.100.0/24")],
+        };
+        let rules = generate_pf_rules("utun3", &[], Some(&exclude), &KillSwitchPolicy::default());
+        assert!(rules.contains("pass out quick to 198.51.100.0/24"));
+        assert!(!rules.contains("pass out all"));
+
+        let include = Policy {
+            include: vec![cidr("10.20.0.0/16")],
+            exclude: Vec::new(),
+        };
+        let rules = generate_pf_rules("utun3", &[], Some(&include), &KillSwitchPolicy::default());
+        let block = rules
+            .find("block out quick on ! utun3 to 10.20.0.0/16")
+            .unwrap();
+        // Must come before the RFC1918 pass rules
+        assert!(block < rules.find("pass out quick to 10.0.0.0/8").unwrap());
+        assert!(rules.ends_with("pass out all\n"));
+    }
+
+    #[test]
+    fn test_portal_window_rules() {
+        let window = PortalWindow {
+            network: "10.12.0.0/22".to_string(),
+            gateway: "10.12.0.1".to_string(),
+            probe_addresses: vec!["17.253.109.201".to_string()],
+        };
+        let rules = generate_portal_rules(&window);
+        assert!(rules.contains("pass out quick proto tcp to 10.12.0.0/22 port { 80 443 }"));
+        assert!(rules.contains("pass out quick proto { tcp udp } to 10.12.0.1 port 53"));
+        assert!(rules.contains("pass out quick proto tcp to 17.253.109.201 port 80"));
+        assert!(!rules.contains("to any"));
+
+        // pf takes the first quick match: the window must come before the
+        // DNS block of a tunnel-only policy
+        let policy = KillSwitchPolicy {
+            dns_tunnel_only: true,
+            ..KillSwitchPolicy::default()
+        };
+        let rules = generate_rules("utun3", &[], None, &policy, Some(&window));
+        let gateway_dns = rules
+            .find("pass out quick proto { tcp udp } to 10.12.0.1 port 53")
+            .unwrap();
+        let dns_block = rules.find("to any port { 53 853 }").unwrap();
+        assert!(gateway_dns < dns_block);
+        assert!(
+            rules.find("to 17.253.109.201 port 80").unwrap() < dns_block,
+            "{rules}"
+        );
+        assert_eq!(
+            generate_rules("utun3", &[], None, &policy, None),
+            generate_pf_rules("utun3", &[], None, &policy)
+        );
+    }
+
+    #[test]
+    fn test_generate_pf_rules_policy() {
+        let hotel = KillSwitchPolicy {
+            allow_lan: false,
+            allowed_cidrs: vec!["192.168.50.0/24".to_string()],
+            allowed_ports: vec!["tcp/631".to_string(), "5353".to_string()],
+            dns_tunnel_only: true,
+            ..KillSwitchPolicy::default()
+        };
+        let rules = generate_pf_rules("utun3", &[], None, &hotel);
+        assert!(!rules.contains("pass out quick to 10.0.0.0/8"));
+        assert!(rules.contains("pass out quick to 192.168.50.0/24"));
+        assert!(rules.contains(
+            "pass out quick proto tcp to { 10.0.0.0/8 172.16.0.0/12 192.168.0.0/16 \
+             224.0.0.0/4 192.168.50.0/24 } port 631"
+        ));
+        assert!(rules.contains("pass out quick proto { tcp udp } to { 10.0.0.0/8"));
+        assert!(rules.contains("192.168.50.0/24 } port 5353"));
+        // Never to the internet
+        assert!(!rules.contains("to any port 631"));
+        assert!(!rules.contains("inet6"));
+        // DNS is blocked only after tunnel traffic has been passed
+        let dns = rules.find("to any port { 53 853 }").unwrap();
+        assert!(rules.find("pass quick on utun3 all").unwrap() < dns);
+        assert!(dns < rules.find("pass out quick to 192.168.50.0/24").unwrap());
+
+        let lan_v6 = KillSwitchPolicy {
+            ipv6: Ipv6Policy::Lan,
+            ..KillSwitchPolicy::default()
+        };
+        let rules = generate_pf_rules("utun3", &[], None, &lan_v6);
+        assert!(rules.contains("pass out quick to fe80::/10"));
+        assert!(rules.contains("port 546 to any port 547"));
+
+        assert_eq!(parse_port_rule("TCP/631").unwrap(), "tcp/631");
+        assert_eq!(parse_port_rule("5353").unwrap(), "5353");
+        assert!(parse_port_rule("icmp/1").is_err());
+        assert!(parse_port_rule("udp/0").is_err());
+    }
+
+    #[test]
+    fn test_policy_sanitize_drops_invalid_entries() {
+        let mut policy = KillSwitchPolicy {
+            allowed_cidrs: vec![
+                "192.168.50.0/24".to_string(),
+                "192.168.50.0/24".to_string(),
+                "10.0.0.0/8\npass out quick all".to_string(),
+                "printer".to_string(),
+            ],
+            allowed_ports: vec![
+                "TCP/631".to_string(),
+                "tcp/631".to_string(),
+                "53\npass all".to_string(),
+                "icmp/1".to_string(),
+            ],
+            ..KillSwitchPolicy::default()
+        };
+        let dropped = policy.sanitize();
+        assert_eq!(dropped.len(), 4, "{dropped:?}");
+        assert_eq!(policy.allowed_cidrs, ["192.168.50.0/24"]);
+        assert_eq!(policy.allowed_ports, ["tcp/631"]);
+
+        let rules = generate_pf_rules("utun3", &[], None, &policy);
+        assert!(!rules.contains("pass out quick all"));
+        assert!(!rules.contains("pass all"));
+    }
+
+    #[test]
+    fn test_rule_preview_uses_policy_and_split_tunnel() {
+        let policy = KillSwitchPolicy {
+            allow_lan: false,
+            allowed_ports: vec!["tcp/631".to_string()],
+            dns_tunnel_only: true,
+            ..KillSwitchPolicy::default()
+        };
+        let split = Policy {
+            include: Vec::new(),
+            exclude: vec!["198.51.100.0/24".parse().unwrap()],
+        };
+        let servers = ["1.2.3.4".to_string()];
+
+        let preview = RulePreview::new("utun3", &servers, Some(&split), &policy);
+        assert_eq!(
+            preview.rules,
+            generate_pf_rules("utun3", &servers, Some(&split), &policy)
+        );
+        assert!(preview.rules.contains("pass out quick to 198.51.100.0/24"));
+        assert!(preview.rules.contains("to any port { 53 853 }"));
+        assert!(!preview.rules.contains("pass out quick to 10.0.0.0/8"));
+        assert_eq!(preview.validation, None);
+    }
+
+    #[test]
+    fn test_rule_preview_dry_run_only_validates() {
+        let conf = std::path::Path::new(PF_CONF_PATH);
+        let before = fs::metadata(conf).and_then(|m| m.modified()).ok();
+
+        let mut preview = RulePreview::new("utun3", &[], None, &KillSwitchPolicy::default());
+        let expected = preview.rules.clone();
+        let mut calls = 0;
+        let result = preview.dry_run_with(|rules| {
+            calls += 1;
+            assert_eq!(rules, expected);
+            Err(KillSwitchError::CommandFailed("syntax error".to_string()))
+        });
+        assert_eq!(result, &Err("pf command failed: syntax error".to_string()));
+        assert_eq!(calls, 1);
+        assert_eq!(preview.rules, expected);
+
+        assert_eq!(preview.dry_run_with(|_| Ok(())), &Ok(()));
+        // Nothing was written for pfctl -f to load
+        assert_eq!(fs::metadata(conf).and_then(|m| m.modified()).ok(), before);
+    }
+
+    #[test]
+    fn test_resolve_endpoints_falls_back_to_cache() {
+        let cached = [ResolvedEndpoint {
+            host: "vpn.invalid".to_string(),
+            addresses: vec!["203.0.113.9".to_string()],
+        }];
+        let hosts = [
+            "198.51.100.7".to_string(),
+            "vpn.invalid".to_string(),
+            "gone.invalid".to_string(),
+        ];
+        let resolved = resolve_endpoints(&hosts, &cached);
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved[0].addresses, ["198.51.100.7"]);
+        assert_eq!(resolved[1], cached[0]);
+
+        let mut twice = resolved.clone();
+        twice.push(resolved[0].clone());
+        assert_eq!(server_addresses(&twice), ["198.51.100.7", "203.0.113.9"]);
+    }
+
+    #[test]
+    fn test_stale_state_needs_dead_owner() {
+        let state = |state, pid| PersistedState {
+            mode: KillSwitchMode::AlwaysOn,
+            state,
+            vpn_interface: None,
+            endpoints: Vec::new(),
+            split: None,
+            pid,
+            pid_started: None,
+        };
+        let ours = Some(std::process::id());
+        assert!(!state(KillSwitchState::Blocking, ours).is_stale());
+        assert!(!state(KillSwitchState::Armed, None).is_stale());
+        assert!(state(KillSwitchState::Blocking, None).is_stale());
+        // Not a valid PID, so never running
+        assert!(state(KillSwitchState::Blocking, Some(u32::MAX)).is_stale());
+    }
+
+    #[test]
+    fn test_persisted_state_keeps_split_policy() {
+        let split = Policy {
+            include: vec!["10.20.0.0/16".parse().unwrap()],
+            exclude: vec!["2001:db8::/32".parse().unwrap()],
+        };
+        let state = PersistedState {
+            mode: KillSwitchMode::AlwaysOn,
+            state: KillSwitchState::Blocking,
+            vpn_interface: Some("utun3".to_string()),
+            endpoints: Vec::new(),
+            split: Some(split.clone()),
+            pid: None,
+            pid_started: None,
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.contains("\"10.20.0.0/16\""), "{json}");
+        let loaded: PersistedState = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.split, Some(split));
+
+        // Files written before the split was recorded still load
+        let old = r#"{"mode":"AlwaysOn","state":"Blocking","vpn_interface":null}"#;
+        assert_eq!(
+            serde_json::from_str::<PersistedState>(old).unwrap().split,
+            None
+        );
+    }
+
+    #[test]
+    fn test_owner_gone_when_pid_reused() {
+        let recorded = PersistedState {
+            mode: KillSwitchMode::AlwaysOn,
+            state: KillSwitchState::Blocking,
+            vpn_interface: None,
+            endpoints: Vec::new(),
+            split: None,
+            pid: Some(4242),
+            pid_started: Some("Sat Oct 17 09:00:00 2026".to_string()),
+        };
+        let started = |at: &'static str| move |_| Some(at.to_string());
+
+        assert!(!recorded.owner_gone_with(|_| true, started("Sat Oct 17 09:00:00 2026")));
+        // Same PID, different process
+        assert!(recorded.owner_gone_with(|_| true, started("Sun Oct 18 08:00:00 2026")));
+        assert!(recorded.owner_gone_with(|_| false, started("Sat Oct 17 09:00:00 2026")));
+        // Start time unavailable: trust the PID
+        assert!(!recorded.owner_gone_with(|_| true, |_| None));
+
+        // This process, as recorded by save_state
+        let ours = PersistedState {
+            pid: Some(std::process::id()),
+            pid_started: own_start_time(),
+            ..recorded
+        };
+        assert!(!ours.owner_gone());
+    }
 }
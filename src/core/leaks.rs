@@ -0,0 +1,536 @@
+//! Local leak checks for the Security Guard panel.
+//!
+//! DNS: every active resolver is looked up in the routing table. A resolver
+//! reached through the tunnel interface is fine; one reached through the
+//! physical interface is a leak, whether or not it is a private address.
+//! Resolvers are also compared with the servers the profile configures.
+//!
+//! IPv6: global addresses and IPv6 default routes are read locally
+//! (`/proc/net/if_inet6` and `/proc/net/ipv6_route` on Linux, `ifconfig`
+//! and `netstat` on macOS), and a route lookup for a public IPv6 address
+//! shows whether IPv6 would leave outside the tunnel. Nothing is sent.
+
+use std::process::Command;
+
+use super::routes;
+use crate::{constants, utils};
+
+/// How a resolver is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Through the tunnel interface.
+    Tunnel,
+    /// Through another interface, outside the tunnel.
+    Leak,
+    /// A stub on this machine whose upstream servers are unknown.
+    Local,
+    /// No route or no tunnel interface to compare with.
+    Unknown,
+}
+
+/// One active resolver and how it is reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolver {
+    pub server: String,
+    /// Interface the route lookup returned.
+    pub interface: Option<String>,
+    /// Whether the profile configures this server.
+    pub from_profile: bool,
+    pub verdict: Verdict,
+    /// Explanation shown in the Security Guard panel.
+    pub reason: String,
+}
+
+/// Result of a DNS leak check.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DnsReport {
+    pub resolvers: Vec<Resolver>,
+    /// Servers the profile configures that the system does not use.
+    pub unused: Vec<String>,
+}
+
+impl DnsReport {
+    /// Any resolver reached outside the tunnel.
+    pub fn is_leaking(&self) -> bool {
+        self.resolvers.iter().any(|r| r.verdict == Verdict::Leak)
+    }
+
+    /// Every resolver is confirmed to go through the tunnel.
+    pub fn is_secure(&self) -> bool {
+        !self.resolvers.is_empty() && self.resolvers.iter().all(|r| r.verdict == Verdict::Tunnel)
+    }
+}
+
+/// Result of the local IPv6 leak check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ipv6Report {
+    /// Global-scope addresses as `(address, interface)`.
+    pub addresses: Vec<(String, String)>,
+    /// Interfaces that carry an IPv6 default route.
+    pub default_routes: Vec<String>,
+    /// `Tunnel` when IPv6 cannot leave outside the tunnel.
+    pub verdict: Verdict,
+    /// Explanation shown in the Security Guard panel.
+    pub reason: String,
+}
+
+impl Ipv6Report {
+    pub fn is_leaking(&self) -> bool {
+        self.verdict == Verdict::Leak
+    }
+}
+
+/// Check whether IPv6 traffic could be routed outside `tunnel`, from the
+/// local addresses and routing table only.
+pub fn check_ipv6(tunnel: &str) -> Ipv6Report {
+    let (addresses, default_routes) = if cfg!(target_os = "macos") {
+        (
+            run("ifconfig", &[])
+                .map(|out| parse_ifconfig_inet6(&out))
+                .unwrap_or_default(),
+            run("netstat", &["-rn", "-f", "inet6"])
+                .map(|out| parse_netstat_inet6_defaults(&out))
+                .unwrap_or_default(),
+        )
+    } else {
+        (
+            std::fs::read_to_string("/proc/net/if_inet6")
+                .map(|content| parse_if_inet6(&content))
+                .unwrap_or_default(),
+            std::fs::read_to_string("/proc/net/ipv6_route")
+                .map(|content| parse_ipv6_default_routes(&content))
+                .unwrap_or_default(),
+        )
+    };
+    let route = routes::interface_for(constants::IPV6_PROBE_ADDR);
+    assess_ipv6(addresses, default_routes, route, tunnel)
+}
+
+fn assess_ipv6(
+    addresses: Vec<(String, String)>,
+    default_routes: Vec<String>,
+    route: Option<String>,
+    tunnel: &str,
+) -> Ipv6Report {
+    let outside = addresses.iter().find(|(_, iface)| iface != tunnel);
+    let bypass = default_routes.iter().find(|iface| *iface != tunnel);
+
+    let (verdict, reason) = match (outside, route) {
+        _ if tunnel.is_empty() => (
+            Verdict::Unknown,
+            "tunnel interface not detected yet".to_string(),
+        ),
+        (None, _) => (
+            Verdict::Tunnel,
+            "no global IPv6 address outside the tunnel".to_string(),
+        ),
+        (Some(_), Some(iface)) if iface == tunnel => {
+            (Verdict::Tunnel, format!("IPv6 routed through {iface}"))
+        }
+        (Some((addr, _)), Some(iface)) => (
+            Verdict::Leak,
+            format!("{addr} reachable via {iface}, outside the tunnel"),
+        ),
+        // No route for the probe (or no lookup tool): fall back to the table
+        (Some((addr, _)), None) => match bypass {
+            Some(iface) => (
+                Verdict::Leak,
+                format!("{addr} with a default route via {iface}"),
+            ),
+            None => (
+                Verdict::Tunnel,
+                "no IPv6 default route outside the tunnel".to_string(),
+            ),
+        },
+    };
+    Ipv6Report {
+        addresses,
+        default_routes,
+        verdict,
+        reason,
+    }
+}
+
+/// Global-scope entries of `/proc/net/if_inet6`:
+/// `address ifindex prefixlen scope flags name`.
+fn parse_if_inet6(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [hex, _, _, scope, _, iface] = fields[..] else {
+                return None;
+            };
+            if scope != "00" {
+                return None;
+            }
+            Some((parse_proc_ipv6(hex)?, iface.to_string()))
+        })
+        .collect()
+}
+
+/// Interfaces with a usable `::/0` route in `/proc/net/ipv6_route`
+/// (the kernel's unreachable default on `lo` is skipped).
+fn parse_ipv6_default_routes(content: &str) -> Vec<String> {
+    const RTF_REJECT: u32 = 0x0200;
+    let mut interfaces = Vec::new();
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [dest, dest_len, .., flags, iface] = fields[..] else {
+            continue;
+        };
+        let reject = u32::from_str_radix(flags, 16).map_or(true, |f| f & RTF_REJECT != 0);
+        if dest.bytes().all(|b| b == b'0') && dest_len == "00" && !reject && iface != "lo" {
+            push_unique(&mut interfaces, iface);
+        }
+    }
+    interfaces
+}
+
+/// `20010db8...0001` → `2001:db8::1`
+fn parse_proc_ipv6(hex: &str) -> Option<String> {
+    u128::from_str_radix(hex, 16)
+        .ok()
+        .filter(|_| hex.len() == 32)
+        .map(|bits| std::net::Ipv6Addr::from(bits).to_string())
+}
+
+/// `inet6` lines of `ifconfig`, without link-local and loopback addresses.
+fn parse_ifconfig_inet6(output: &str) -> Vec<(String, String)> {
+    let mut addresses = Vec::new();
+    let mut iface = "";
+    for line in output.lines() {
+        if !line.starts_with([' ', '\t']) {
+            iface = line.split(':').next().unwrap_or("");
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        if words.next() != Some("inet6") {
+            continue;
+        }
+        let addr = words.next().unwrap_or("");
+        let global = addr
+            .parse::<std::net::Ipv6Addr>()
+            .is_ok_and(|ip| !ip.is_loopback() && ip.segments()[0] & 0xffc0 != 0xfe80);
+        if global {
+            addresses.push((addr.to_string(), iface.to_string()));
+        }
+    }
+    addresses
+}
+
+/// `default  fe80::1%en0  UGcg  en0` lines of `netstat -rn -f inet6`.
+fn parse_netstat_inet6_defaults(output: &str) -> Vec<String> {
+    let mut interfaces = Vec::new();
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if let ["default", _, flags, iface, ..] = fields[..] {
+            if !flags.contains('R') && !iface.starts_with("lo") {
+                push_unique(&mut interfaces, iface);
+            }
+        }
+    }
+    interfaces
+}
+
+/// Check the system's resolvers against the `tunnel` interface and the
+/// profile's `expected` DNS servers.
+pub fn check_dns(tunnel: &str, expected: &[String]) -> DnsReport {
+    assess_dns(&active_resolvers(), expected, tunnel, routes::interface_for)
+}
+
+fn assess_dns(
+    resolvers: &[String],
+    expected: &[String],
+    tunnel: &str,
+    route: impl Fn(&str) -> Option<String>,
+) -> DnsReport {
+    let resolvers: Vec<Resolver> = resolvers
+        .iter()
+        .map(|server| {
+            let from_profile = expected.contains(server);
+            let interface = route(server);
+            let (verdict, reason) = if is_loopback(server) {
+                (
+                    Verdict::Local,
+                    "local stub resolver, upstream servers unknown".to_string(),
+                )
+            } else {
+                match &interface {
+                    _ if tunnel.is_empty() => (
+                        Verdict::Unknown,
+                        "tunnel interface not detected yet".to_string(),
+                    ),
+                    None => (Verdict::Unknown, "no route found".to_string()),
+                    Some(iface) if iface == tunnel && from_profile => {
+                        (Verdict::Tunnel, format!("profile DNS via {iface}"))
+                    }
+                    Some(iface) if iface == tunnel => (
+                        Verdict::Tunnel,
+                        format!("via {iface}, not set by the profile"),
+                    ),
+                    Some(iface) => {
+                        let kind = if from_profile {
+                            "profile DNS"
+                        } else if utils::is_private_ip(server) {
+                            "local network resolver"
+                        } else {
+                            "public resolver"
+                        };
+                        (
+                            Verdict::Leak,
+                            format!("{kind} via {iface}, outside the tunnel"),
+                        )
+                    }
+                }
+            };
+            Resolver {
+                server: server.clone(),
+                interface,
+                from_profile,
+                verdict,
+                reason,
+            }
+        })
+        .collect();
+
+    let unused = expected
+        .iter()
+        .filter(|server| !resolvers.iter().any(|r| &r.server == *server))
+        .cloned()
+        .collect();
+    DnsReport { resolvers, unused }
+}
+
+/// Resolvers the system currently uses, in order. A loopback stub
+/// (systemd-resolved) is replaced by the servers it forwards to.
+pub fn active_resolvers() -> Vec<String> {
+    let mut servers = Vec::new();
+    if cfg!(target_os = "macos") {
+        if let Some(out) = run("scutil", &["--dns"]) {
+            servers = parse_scutil_dns(&out);
+        }
+    } else {
+        servers = std::fs::read_to_string("/etc/resolv.conf")
+            .map(|content| parse_resolv_conf(&content))
+            .unwrap_or_default();
+        if servers.iter().all(|s| is_loopback(s)) {
+            if let Some(upstream) = run("resolvectl", &["dns"])
+                .map(|out| parse_resolvectl_dns(&out))
+                .filter(|upstream| !upstream.is_empty())
+            {
+                servers = upstream;
+            }
+        }
+    }
+    servers
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn push_unique(servers: &mut Vec<String>, server: &str) {
+    if !server.is_empty() && !servers.iter().any(|s| s == server) {
+        servers.push(server.to_string());
+    }
+}
+
+fn parse_resolv_conf(content: &str) -> Vec<String> {
+    let mut servers = Vec::new();
+    for line in content.lines() {
+        let mut words = line.split_whitespace();
+        if words.next() == Some("nameserver") {
+            push_unique(&mut servers, words.next().unwrap_or(""));
+        }
+    }
+    servers
+}
+
+/// `Global: 1.1.1.1` / `Link 3 (wg0): 10.0.0.1 fd00::1`
+fn parse_resolvectl_dns(output: &str) -> Vec<String> {
+    let mut servers = Vec::new();
+    for line in output.lines() {
+        if let Some((_, list)) = line.split_once(": ") {
+            for server in list.split_whitespace() {
+                push_unique(&mut servers, server);
+            }
+        }
+    }
+    servers
+}
+
+/// `nameserver[0] : 1.1.1.1` entries of `scutil --dns`.
+fn parse_scutil_dns(output: &str) -> Vec<String> {
+    let mut servers = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("nameserver[") {
+            if let Some((_, server)) = line.split_once(" : ") {
+                push_unique(&mut servers, server.trim());
+            }
+        }
+    }
+    servers
+}
+
+fn is_loopback(server: &str) -> bool {
+    server
+        .parse::<std::net::IpAddr>()
+        .is_ok_and(|ip| ip.is_loopback())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_assess_dns_uses_routes_not_address_ranges() {
+        let route = |ip: &str| match ip {
+            "10.64.0.1" | "1.1.1.1" => Some("wg0".to_string()),
+            "192.168.1.1" | "8.8.8.8" => Some("eth0".to_string()),
+            _ => None,
+        };
+        let report = assess_dns(
+            &strings(&[
+                "10.64.0.1",
+                "1.1.1.1",
+                "192.168.1.1",
+                "8.8.8.8",
+                "127.0.0.53",
+            ]),
+            &strings(&["10.64.0.1", "10.64.0.2"]),
+            "wg0",
+            route,
+        );
+        let verdicts: Vec<_> = report.resolvers.iter().map(|r| r.verdict).collect();
+        assert_eq!(
+            verdicts,
+            [
+                Verdict::Tunnel,
+                Verdict::Tunnel,
+                Verdict::Leak,
+                Verdict::Leak,
+                Verdict::Local
+            ]
+        );
+        assert!(report.resolvers[0].from_profile);
+        assert!(report.resolvers[2]
+            .reason
+            .starts_with("local network resolver via eth0"));
+        assert_eq!(report.unused, ["10.64.0.2"]);
+        assert!(report.is_leaking());
+        assert!(!report.is_secure());
+
+        let tunnel_only = assess_dns(&strings(&["10.64.0.1"]), &[], "wg0", route);
+        assert!(tunnel_only.is_secure());
+    }
+
+    #[test]
+    fn test_parse_resolver_lists() {
+        assert_eq!(
+            parse_resolv_conf(
+                "# generated\nnameserver 127.0.0.53\noptions edns0\nnameserver 127.0.0.53\n"
+            ),
+            ["127.0.0.53"]
+        );
+        assert_eq!(
+            parse_resolvectl_dns(
+                "Global:\nLink 2 (eth0): 192.168.1.1 fe80::1%eth0\nLink 5 (wg0): 10.64.0.1\n"
+            ),
+            ["192.168.1.1", "fe80::1%eth0", "10.64.0.1"]
+        );
+        assert_eq!(
+            parse_scutil_dns("resolver #1\n  nameserver[0] : 10.64.0.1\n  nameserver[1] : 1.1.1.1\nresolver #2\n  nameserver[0] : 10.64.0.1\n"),
+            ["10.64.0.1", "1.1.1.1"]
+        );
+    }
+
+    #[test]
+    fn test_assess_ipv6_prefers_route_lookup() {
+        let addresses = vec![("2001:db8::2".to_string(), "eth0".to_string())];
+        let defaults = strings(&["eth0", "wg0"]);
+
+        // wg-quick full tunnel: main table still has eth0, the policy rule wins
+        let tunnelled = assess_ipv6(
+            addresses.clone(),
+            defaults.clone(),
+            Some("wg0".into()),
+            "wg0",
+        );
+        assert_eq!(tunnelled.verdict, Verdict::Tunnel);
+
+        let leaking = assess_ipv6(
+            addresses.clone(),
+            defaults.clone(),
+            Some("eth0".into()),
+            "wg0",
+        );
+        assert!(leaking.is_leaking());
+        assert_eq!(
+            leaking.reason,
+            "2001:db8::2 reachable via eth0, outside the tunnel"
+        );
+
+        let table_only = assess_ipv6(addresses, defaults, None, "wg0");
+        assert!(table_only.is_leaking());
+
+        let no_address = assess_ipv6(Vec::new(), strings(&["eth0"]), Some("eth0".into()), "wg0");
+        assert_eq!(no_address.verdict, Verdict::Tunnel);
+    }
+
+    #[test]
+    fn test_parse_proc_ipv6_tables() {
+        let if_inet6 = "\
+00000000000000000000000000000001 01 80 10 80       lo
+fe8000000000000000fc00fffe000001 04 40 20 80     eth0
+20010db8000000000000000000000002 04 40 00 82     eth0
+fd000000000000000000000000000002 05 80 00 80      wg0
+";
+        assert_eq!(
+            parse_if_inet6(if_inet6),
+            [
+                ("2001:db8::2".to_string(), "eth0".to_string()),
+                ("fd00::2".to_string(), "wg0".to_string())
+            ]
+        );
+
+        let ipv6_route = "\
+fd000000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 00000003     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 00000000000000000000000000000000 ffffffff 00000001 00000000 00200200       lo
+";
+        assert_eq!(parse_ipv6_default_routes(ipv6_route), ["eth0"]);
+    }
+
+    #[test]
+    fn test_parse_macos_ipv6_output() {
+        let ifconfig = "\
+lo0: flags=8049<UP,LOOPBACK,RUNNING,MULTICAST> mtu 16384
+\tinet6 ::1 prefixlen 128
+en0: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500
+\tinet6 fe80::1c2a:3bff:fe4d:5e6f%en0 prefixlen 64 secured scopeid 0x6
+\tinet6 2001:db8::1234 prefixlen 64 autoconf secured
+";
+        assert_eq!(
+            parse_ifconfig_inet6(ifconfig),
+            [("2001:db8::1234".to_string(), "en0".to_string())]
+        );
+        let netstat = "\
+Internet6:
+Destination                             Gateway                         Flags         Netif Expire
+default                                 fe80::1%en0                     UGcg            en0
+default                                 fe80::%utun0                    UGcIg         utun0
+::1                                     ::1                             UHL             lo0
+";
+        assert_eq!(parse_netstat_inet6_defaults(netstat), ["en0", "utun0"]);
+    }
+}
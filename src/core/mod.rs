@@ -4,14 +4,46 @@
 //! - `scanner`: Detects active VPN connections on the system
 //! - `telemetry`: Collects network telemetry (IP, latency, ISP, etc.)
 //! - `killswitch`: macOS pf firewall control for traffic blocking
+//! - `search`: Fuzzy matching for the profile sidebar filter
+//! - `grouping`: Grouping and sorting for the profile sidebar tree
+//! - `crypto`: Passphrase-based encryption for secrets on disk
+//! - `credentials`: `OpenVPN` credential prompts, storage and handoff
+//! - `vault`: Optional encrypted-at-rest profile storage
+//! - `archive`: Provider ZIP archives unpacked for import
+//! - `bundle`: Export/import of profile sets as a single file
+//! - `duplicates`: Content-based duplicate detection for imports
+//! - `qr`: Terminal QR codes for `WireGuard` profiles
+//! - `subscription`: Provider URLs that keep imported profiles up to date
+//! - `routes`: Route lookups for destinations
+//! - `leaks`: Route-based DNS leak checks
+//! - `split_tunnel`: Per-profile include/exclude routing rules
+//! - `guard`: Settles the kill switch when vortix panics or is killed
+//! - `audit`: Leak-test checks and the `vortix audit` report
+//! - `portal`: Captive portal detection for the kill switch's portal window
 
 #![allow(unused_imports)]
 
+pub mod archive;
+pub mod audit;
+pub mod bundle;
+pub mod credentials;
+pub mod crypto;
 pub mod downloader;
+pub mod duplicates;
+pub mod grouping;
+pub mod guard;
 pub mod importer;
 pub mod killswitch;
+pub mod leaks;
+pub mod portal;
+pub mod qr;
+pub mod routes;
 pub mod scanner;
+pub mod search;
+pub mod split_tunnel;
+pub mod subscription;
 pub mod telemetry;
+pub mod vault;
 
 // Re-export commonly used items
 pub use scanner::{get_active_profiles, ActiveSession};
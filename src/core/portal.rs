@@ -0,0 +1,264 @@
+//! Captive portal detection.
+//!
+//! Hotel and airport networks hold every connection until the user logs in
+//! on a web page, which the kill switch blocks. A plain HTTP probe to a
+//! known URL (`KillSwitchPolicy::portal_url`) tells an open network from
+//! one that redirects to a login page. The gateway lookup finds the network
+//! the login page lives on, for a time-limited portal window in the kill
+//! switch rules ([`crate::core::killswitch::enable_portal_window`]).
+
+use std::net::Ipv4Addr;
+use std::process::Command;
+
+use crate::constants;
+use crate::core::killswitch::{self, ResolvedEndpoint};
+use crate::vpn::wireguard::Endpoint;
+
+/// What the probe found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Probe {
+    /// The probe URL answered directly: no login needed.
+    Open,
+    /// The network redirected the probe, usually to its login page.
+    Portal { location: Option<String> },
+    /// No answer (offline, or blocked by the kill switch).
+    Unreachable(String),
+}
+
+/// The default route outside the tunnel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gateway {
+    pub interface: String,
+    pub address: String,
+    /// Network of the interface the gateway is on, e.g. `10.12.0.0/22`.
+    pub network: String,
+}
+
+/// Request `url` without following redirects.
+pub fn probe(url: &str) -> Probe {
+    let timeout = constants::PORTAL_PROBE_TIMEOUT_SECS.to_string();
+    let output = Command::new("curl")
+        .args([
+            "-s",
+            "-o",
+            "/dev/null",
+            "--max-time",
+            &timeout,
+            "-w",
+            "%{http_code} %{redirect_url}",
+            url,
+        ])
+        .output();
+    match output {
+        Ok(output) => classify(&String::from_utf8_lossy(&output.stdout)),
+        Err(e) => Probe::Unreachable(format!("curl failed: {e}")),
+    }
+}
+
+/// `302 http://login.example/` (curl's `%{http_code} %{redirect_url}`).
+fn classify(output: &str) -> Probe {
+    let (code, location) = output.trim().split_once(' ').unwrap_or((output.trim(), ""));
+    match code.parse::<u16>() {
+        Ok(300..=399) => Probe::Portal {
+            location: Some(location.trim().to_string()).filter(|l| !l.is_empty()),
+        },
+        Ok(100..=299) => Probe::Open,
+        Ok(code @ 400..=599) => Probe::Unreachable(format!("HTTP {code}")),
+        _ => Probe::Unreachable("no response".to_string()),
+    }
+}
+
+/// Host of the probe URL (`http://host[:port]/path`, IPv6 in brackets),
+/// read like a `WireGuard` endpoint.
+pub fn probe_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let authority = if authority.ends_with(']') || !authority.contains(':') {
+        format!("{authority}:80")
+    } else {
+        authority.to_string()
+    };
+    let endpoint = authority.parse::<Endpoint>().ok()?;
+    // An IPv6 address without brackets cannot be told from its port
+    (!endpoint.host.contains(':') || authority.starts_with('[')).then_some(endpoint.host)
+}
+
+/// Resolve the probe URL's host so a portal window can let the probe
+/// through. Done ahead of time: once the kill switch blocks DNS the host
+/// keeps its `cached` addresses.
+pub fn resolve_probe(url: &str, cached: &[ResolvedEndpoint]) -> Vec<ResolvedEndpoint> {
+    probe_host(url).map_or_else(Vec::new, |host| {
+        killswitch::resolve_endpoints(&[host], cached)
+    })
+}
+
+/// The IPv4 default gateway and the network it is reached on.
+pub fn gateway() -> Option<Gateway> {
+    let (address, interface) = if cfg!(target_os = "macos") {
+        parse_route_get_default(&run("route", &["-n", "get", "default"])?)?
+    } else {
+        parse_ip_default_route(&run("ip", &["-4", "route", "show", "default"])?)?
+    };
+    let network = if cfg!(target_os = "macos") {
+        parse_ifconfig(&run("ifconfig", &[&interface])?)?
+    } else {
+        parse_ip_addr(&run(
+            "ip",
+            &["-o", "-4", "addr", "show", "dev", &interface],
+        )?)?
+    };
+    Some(Gateway {
+        interface,
+        address,
+        network,
+    })
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `default via 10.12.0.1 dev wlan0 ...` → gateway and interface. Tunnel
+/// default routes (without `via`) are skipped.
+fn parse_ip_default_route(output: &str) -> Option<(String, String)> {
+    output.lines().find_map(|line| {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let value = |key: &str| {
+            words
+                .iter()
+                .position(|w| *w == key)
+                .and_then(|i| words.get(i + 1))
+                .map(|v| (*v).to_string())
+        };
+        Some((value("via")?, value("dev")?))
+    })
+}
+
+/// The `gateway:` and `interface:` lines of `route -n get default`.
+fn parse_route_get_default(output: &str) -> Option<(String, String)> {
+    let field = |name: &str| {
+        output.lines().find_map(|line| {
+            line.trim()
+                .strip_prefix(name)
+                .map(|value| value.trim().to_string())
+        })
+    };
+    Some((field("gateway:")?, field("interface:")?))
+}
+
+/// `2: wlan0    inet 10.12.1.40/22 brd ...` → `10.12.0.0/22`
+fn parse_ip_addr(output: &str) -> Option<String> {
+    let mut words = output.split_whitespace();
+    words.find(|w| *w == "inet")?;
+    let (addr, prefix) = words.next()?.split_once('/')?;
+    network(addr.parse().ok()?, prefix.parse().ok()?)
+}
+
+/// `inet 10.12.1.40 netmask 0xfffffc00 broadcast ...` → `10.12.0.0/22`
+fn parse_ifconfig(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let [_, addr, "netmask", mask, ..] = words[..] else {
+            return None;
+        };
+        if words[0] != "inet" {
+            return None;
+        }
+        let mask = u32::from_str_radix(mask.trim_start_matches("0x"), 16).ok()?;
+        let prefix = u8::try_from(mask.count_ones()).ok()?;
+        network(addr.parse().ok()?, prefix)
+    })
+}
+
+fn network(addr: Ipv4Addr, prefix: u8) -> Option<String> {
+    if prefix > 32 {
+        return None;
+    }
+    let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+    Some(format!(
+        "{}/{prefix}",
+        Ipv4Addr::from(u32::from(addr) & mask)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_probe() {
+        assert_eq!(classify("204 "), Probe::Open);
+        assert_eq!(classify("200 "), Probe::Open);
+        assert_eq!(
+            classify("302 http://10.12.0.1/login?orig=x"),
+            Probe::Portal {
+                location: Some("http://10.12.0.1/login?orig=x".to_string())
+            }
+        );
+        assert_eq!(classify("307 "), Probe::Portal { location: None });
+        assert!(matches!(classify("000 "), Probe::Unreachable(_)));
+        assert!(matches!(classify(""), Probe::Unreachable(_)));
+    }
+
+    #[test]
+    fn test_probe_host() {
+        let host = |url| probe_host(url);
+        assert_eq!(
+            host("http://captive.apple.com/hotspot-detect.html").as_deref(),
+            Some("captive.apple.com")
+        );
+        assert_eq!(
+            host("http://example.com:8080?x=1").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(host("http://user@10.0.0.1/").as_deref(), Some("10.0.0.1"));
+        assert_eq!(
+            host("http://[2001:db8::1]/probe").as_deref(),
+            Some("2001:db8::1")
+        );
+        assert_eq!(
+            host("http://[2001:db8::1]:8080/").as_deref(),
+            Some("2001:db8::1")
+        );
+        assert_eq!(host("http://2001:db8::1/"), None);
+        assert_eq!(host(""), None);
+
+        // Literal addresses need no DNS and come back sorted, once each
+        let resolved = resolve_probe("http://[2001:db8::1]/", &[]);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].addresses, ["2001:db8::1"]);
+    }
+
+    #[test]
+    fn test_gateway_parsing() {
+        assert_eq!(
+            parse_ip_default_route(
+                "default dev wg0 scope link\ndefault via 10.12.0.1 dev wlan0 proto dhcp metric 600\n"
+            ),
+            Some(("10.12.0.1".to_string(), "wlan0".to_string()))
+        );
+        assert_eq!(
+            parse_ip_addr("3: wlan0    inet 10.12.1.40/22 brd 10.12.3.255 scope global wlan0"),
+            Some("10.12.0.0/22".to_string())
+        );
+        assert_eq!(
+            parse_route_get_default(
+                "   route to: default\ndestination: default\n    gateway: 172.20.4.1\n  interface: en0\n"
+            ),
+            Some(("172.20.4.1".to_string(), "en0".to_string()))
+        );
+        assert_eq!(
+            parse_ifconfig(
+                "en0: flags=8863<UP>\n\tinet6 fe80::1%en0 prefixlen 64\n\tinet 172.20.5.9 netmask 0xfffff800 broadcast 172.20.7.255\n"
+            ),
+            Some("172.20.0.0/21".to_string())
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Terminal QR codes for moving `WireGuard` profiles to a phone.
+//!
+//! Codes are drawn with Unicode half-blocks, two modules per character row,
+//! and must be shown dark-on-light (see [`render`]) to scan reliably.
+
+use qrcode::{Color, EcLevel, QrCode};
+
+use crate::state::{Protocol, VpnProfile};
+
+/// Light border around the code, in modules.
+const QUIET_ZONE: usize = 2;
+
+/// Config text to encode for `profile`, optionally without hook lines.
+pub fn profile_payload(profile: &VpnProfile, exclude_hooks: bool) -> Result<String, String> {
+    if profile.protocol != Protocol::WireGuard {
+        return Err(crate::constants::MSG_QR_WIREGUARD_ONLY.to_string());
+    }
+    let content = crate::core::vault::read_config(&profile.config_path)?;
+    Ok(if exclude_hooks {
+        strip_hooks(&content)
+    } else {
+        content
+    })
+}
+
+/// Remove `PostUp`/`PostDown` lines, which are host shell commands that mobile
+/// clients can't (or shouldn't) run.
+pub fn strip_hooks(content: &str) -> String {
+    let mut stripped = String::with_capacity(content.len());
+    for line in content.lines() {
+        let key = line.split('=').next().unwrap_or("").trim();
+        if !key.eq_ignore_ascii_case("PostUp") && !key.eq_ignore_ascii_case("PostDown") {
+            stripped.push_str(line);
+            stripped.push('\n');
+        }
+    }
+    stripped
+}
+
+/// Encode `data` as a QR code and draw it as text rows.
+///
+/// `█`, `▀` and `▄` mark dark modules; callers paint the rows dark on a light
+/// background.
+pub fn render(data: &str) -> Result<Vec<String>, String> {
+    let code = QrCode::with_error_correction_level(data.as_bytes(), EcLevel::L)
+        .map_err(|e| format!("Config is too large for a QR code: {e}"))?;
+    let width = code.width();
+    let colors = code.to_colors();
+
+    let size = width + 2 * QUIET_ZONE;
+    let dark = |x: usize, y: usize| {
+        let (Some(x), Some(y)) = (x.checked_sub(QUIET_ZONE), y.checked_sub(QUIET_ZONE)) else {
+            return false;
+        };
+        x < width && y < width && colors[y * width + x] == Color::Dark
+    };
+
+    Ok((0..size)
+        .step_by(2)
+        .map(|y| {
+            (0..size)
+                .map(|x| match (dark(x, y), dark(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_hooks() {
+        let config = "[Interface]\nPrivateKey = k\nPostUp = iptables -A x\npostdown=iptables -D x\n\n[Peer]\nEndpoint = a:1\n";
+        assert_eq!(
+            strip_hooks(config),
+            "[Interface]\nPrivateKey = k\n\n[Peer]\nEndpoint = a:1\n"
+        );
+    }
+
+    #[test]
+    fn test_render_dimensions_and_finder() {
+        let rows = render("[Interface]\nPrivateKey = k\n").unwrap();
+        let code =
+            QrCode::with_error_correction_level(b"[Interface]\nPrivateKey = k\n", EcLevel::L)
+                .unwrap();
+        let size = code.width() + 2 * QUIET_ZONE;
+
+        assert_eq!(rows.len(), size.div_ceil(2));
+        assert!(rows.iter().all(|r| r.chars().count() == size));
+        // Quiet zone on top, then the top two rows of the finder pattern
+        assert!(rows[0].trim().is_empty());
+        let finder: String = rows[1].chars().skip(QUIET_ZONE).take(7).collect();
+        assert_eq!(finder, "█▀▀▀▀▀█");
+    }
+}
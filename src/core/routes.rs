@@ -0,0 +1,371 @@
+//! Route lookups and the routing table inspector.
+//!
+//! Asks the kernel which interface a destination would leave through
+//! (`ip route get` on Linux, `route -n get` on macOS), so checks can tell
+//! traffic inside the tunnel from traffic that bypasses it. The inspector
+//! reads the whole table (`ip route show table all` / `netstat -rn`) plus
+//! policy rules, and marks each route relative to the tunnel.
+
+use std::process::Command;
+
+/// Interface the system would use to reach `ip`, or `None` when there is no
+/// route or the lookup tool is unavailable.
+pub fn interface_for(ip: &str) -> Option<String> {
+    // Link-local IPv6 resolvers carry their scope (`fe80::1%en0`)
+    let ip = ip.split('%').next().unwrap_or(ip);
+
+    if cfg!(target_os = "macos") {
+        let family = if ip.contains(':') { "-inet6" } else { "-inet" };
+        let output = Command::new("route")
+            .args(["-n", "get", family, ip])
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| parse_route_get(&String::from_utf8_lossy(&output.stdout)))
+            .flatten()
+    } else {
+        let output = Command::new("ip")
+            .args(["route", "get", ip])
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| parse_ip_route_get(&String::from_utf8_lossy(&output.stdout)))
+            .flatten()
+    }
+}
+
+/// How a route relates to the tunnel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteKind {
+    /// Through the tunnel interface.
+    Tunnel,
+    /// A default route outside the tunnel.
+    Default,
+    /// Host route to the VPN server, which must stay outside the tunnel.
+    Endpoint,
+    /// Directly connected network (no gateway).
+    Local,
+    /// More specific than the tunnel's full-tunnel routes, via a gateway
+    /// outside it: this traffic skips the VPN.
+    Bypass,
+    /// Outside the tunnel while only some traffic is tunnelled.
+    Other,
+}
+
+/// One routing table entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// `default` or a prefix as printed by the system.
+    pub destination: String,
+    pub gateway: Option<String>,
+    pub interface: String,
+    /// Routing table when it is not `main` (Linux).
+    pub table: Option<String>,
+    pub kind: RouteKind,
+}
+
+/// Snapshot for the Routes panel.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteTable {
+    pub routes: Vec<Route>,
+    /// Non-default policy rules, such as `WireGuard`'s fwmark rules.
+    pub rules: Vec<String>,
+    /// The tunnel carries a default route (or both `/1` halves).
+    pub full_tunnel: bool,
+}
+
+/// Read the routing table and policy rules, marking routes relative to the
+/// `tunnel` interface and the VPN server `endpoint` (`host:port`).
+pub fn inspect(tunnel: &str, endpoint: &str) -> RouteTable {
+    let (routes, rules) = if cfg!(target_os = "macos") {
+        let routes = ["inet", "inet6"]
+            .iter()
+            .filter_map(|family| run("netstat", &["-rn", "-f", family]))
+            .flat_map(|out| parse_netstat_routes(&out))
+            .collect();
+        (routes, Vec::new())
+    } else {
+        let routes = ["-4", "-6"]
+            .iter()
+            .filter_map(|family| run("ip", &[family, "route", "show", "table", "all"]))
+            .flat_map(|out| out.lines().filter_map(parse_ip_route).collect::<Vec<_>>())
+            .collect();
+        let mut rules = Vec::new();
+        for out in ["-4", "-6"]
+            .iter()
+            .filter_map(|family| run("ip", &[family, "rule", "show"]))
+        {
+            for rule in parse_ip_rules(&out) {
+                if !rules.contains(&rule) {
+                    rules.push(rule);
+                }
+            }
+        }
+        (routes, rules)
+    };
+    classify(routes, rules, tunnel, endpoint_host(endpoint))
+}
+
+fn classify(
+    mut routes: Vec<Route>,
+    rules: Vec<String>,
+    tunnel: &str,
+    endpoint: &str,
+) -> RouteTable {
+    let via_tunnel = |route: &Route| !tunnel.is_empty() && route.interface == tunnel;
+    let covers = |halves: [&[&str]; 2]| {
+        halves.iter().all(|names| {
+            routes
+                .iter()
+                .any(|r| via_tunnel(r) && names.contains(&r.destination.as_str()))
+        })
+    };
+    let full_tunnel = routes
+        .iter()
+        .any(|r| via_tunnel(r) && is_default(&r.destination))
+        || covers([&["0.0.0.0/1", "0/1"], &["128.0.0.0/1", "128.0/1", "128/1"]])
+        || covers([&["::/1"], &["8000::/1"]]);
+
+    for route in &mut routes {
+        let host = route.destination.split('/').next().unwrap_or("");
+        route.kind = if via_tunnel(route) {
+            RouteKind::Tunnel
+        } else if is_default(&route.destination) {
+            RouteKind::Default
+        } else if !endpoint.is_empty() && host == endpoint {
+            RouteKind::Endpoint
+        } else if route.gateway.is_none() {
+            RouteKind::Local
+        } else if full_tunnel {
+            RouteKind::Bypass
+        } else {
+            RouteKind::Other
+        };
+    }
+    RouteTable {
+        routes,
+        rules,
+        full_tunnel,
+    }
+}
+
+fn is_default(destination: &str) -> bool {
+    matches!(destination, "default" | "0.0.0.0/0" | "::/0")
+}
+
+/// `1.2.3.4:51820` / `[2001:db8::1]:51820` → the address.
+fn endpoint_host(endpoint: &str) -> &str {
+    let host = endpoint
+        .rsplit_once(':')
+        .filter(|(host, _)| !host.contains(':') || host.starts_with('['))
+        .map_or(endpoint, |(host, _)| host);
+    host.trim_start_matches('[').trim_end_matches(']')
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// One line of `ip route show table all`. Local/broadcast entries and
+/// reject routes are left out.
+fn parse_ip_route(line: &str) -> Option<Route> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let destination = *words.first()?;
+    if matches!(
+        destination,
+        "local"
+            | "broadcast"
+            | "anycast"
+            | "multicast"
+            | "unreachable"
+            | "blackhole"
+            | "prohibit"
+            | "throw"
+    ) {
+        return None;
+    }
+    let value = |key: &str| {
+        words
+            .iter()
+            .position(|w| *w == key)
+            .and_then(|i| words.get(i + 1))
+            .map(|v| (*v).to_string())
+    };
+    let table = value("table");
+    if table.as_deref() == Some("local") {
+        return None;
+    }
+    Some(Route {
+        destination: destination.to_string(),
+        gateway: value("via"),
+        interface: value("dev")?,
+        table,
+        kind: RouteKind::Other,
+    })
+}
+
+/// Rules of `ip rule show` other than the three every system has.
+fn parse_ip_rules(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (priority, rule) = line.split_once(':')?;
+            let rule = rule.split_whitespace().collect::<Vec<_>>().join(" ");
+            let standard = matches!(
+                rule.as_str(),
+                "from all lookup local" | "from all lookup main" | "from all lookup default"
+            );
+            (!standard).then(|| format!("{}: {rule}", priority.trim()))
+        })
+        .collect()
+}
+
+/// Routes of `netstat -rn -f inet|inet6` (macOS), without loopback.
+fn parse_netstat_routes(output: &str) -> Vec<Route> {
+    output
+        .lines()
+        .skip_while(|line| !line.starts_with("Destination"))
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [destination, gateway, _flags, interface, ..] = fields[..] else {
+                return None;
+            };
+            if interface.starts_with("lo") {
+                return None;
+            }
+            Some(Route {
+                destination: destination.to_string(),
+                gateway: (!gateway.starts_with("link#")).then(|| gateway.to_string()),
+                interface: interface.to_string(),
+                table: None,
+                kind: RouteKind::Other,
+            })
+        })
+        .collect()
+}
+
+/// `1.1.1.1 via 192.168.1.1 dev eth0 src 192.168.1.20 uid 0` → `eth0`
+fn parse_ip_route_get(output: &str) -> Option<String> {
+    let mut words = output.split_whitespace();
+    words.find(|w| *w == "dev")?;
+    words.next().map(str::to_string)
+}
+
+/// The `interface: en0` line of `route -n get`.
+fn parse_route_get(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("interface:")
+            .map(|iface| iface.trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_route_lookups() {
+        assert_eq!(
+            parse_ip_route_get(
+                "1.1.1.1 via 192.168.1.1 dev eth0 src 192.168.1.20 uid 0\n    cache\n"
+            ),
+            Some("eth0".to_string())
+        );
+        assert_eq!(
+            parse_ip_route_get("10.0.0.1 dev wg0 table 51820 src 10.0.0.2 uid 0"),
+            Some("wg0".to_string())
+        );
+        assert_eq!(
+            parse_ip_route_get("RTNETLINK answers: Network is unreachable"),
+            None
+        );
+        assert_eq!(
+            parse_route_get(
+                "   route to: 1.1.1.1\ndestination: default\n    gateway: 10.0.0.1\n  interface: utun4\n"
+            ),
+            Some("utun4".to_string())
+        );
+    }
+
+    #[test]
+    fn test_inspect_wg_quick_full_tunnel() {
+        let ip_route = "\
+default via 192.168.1.1 dev eth0 proto dhcp metric 100
+10.20.0.0/16 via 192.168.1.1 dev eth0
+192.168.1.0/24 dev eth0 proto kernel scope link src 192.168.1.20
+default dev wg0 table 51820 scope link
+local 192.168.1.20 dev eth0 table local proto kernel scope host src 192.168.1.20
+unreachable default dev lo proto kernel metric 4294967295 pref medium";
+        let routes = ip_route.lines().filter_map(parse_ip_route).collect();
+        let rules = parse_ip_rules(
+            "0:\tfrom all lookup local\n32764:\tfrom all lookup main suppress_prefixlength 0\n\
+             32765:\tnot from all fwmark 0xca6c lookup 51820\n32766:\tfrom all lookup main\n",
+        );
+        assert_eq!(
+            rules,
+            [
+                "32764: from all lookup main suppress_prefixlength 0",
+                "32765: not from all fwmark 0xca6c lookup 51820"
+            ]
+        );
+
+        let table = classify(routes, rules, "wg0", endpoint_host("203.0.113.5:51820"));
+        assert!(table.full_tunnel);
+        let kinds: Vec<_> = table.routes.iter().map(|r| r.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                RouteKind::Default,
+                RouteKind::Bypass,
+                RouteKind::Local,
+                RouteKind::Tunnel
+            ]
+        );
+        assert_eq!(table.routes[3].table.as_deref(), Some("51820"));
+    }
+
+    #[test]
+    fn test_inspect_openvpn_def1_on_macos() {
+        let netstat = "\
+Routing tables
+
+Internet:
+Destination        Gateway            Flags               Netif Expire
+0/1                10.8.0.1           UGScg               utun4
+default            192.168.1.1        UGScg                 en0
+127                127.0.0.1          UCS                   lo0
+128.0/1            10.8.0.1           UGSc                utun4
+198.51.100.7/32    192.168.1.1        UGSc                  en0
+192.168.1          link#6             UCS                   en0      !
+";
+        let table = classify(
+            parse_netstat_routes(netstat),
+            Vec::new(),
+            "utun4",
+            endpoint_host("198.51.100.7:1194"),
+        );
+        assert!(table.full_tunnel);
+        let kinds: Vec<_> = table.routes.iter().map(|r| r.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                RouteKind::Tunnel,
+                RouteKind::Default,
+                RouteKind::Tunnel,
+                RouteKind::Endpoint,
+                RouteKind::Local
+            ]
+        );
+        assert_eq!(endpoint_host("[2001:db8::1]:51820"), "2001:db8::1");
+    }
+}
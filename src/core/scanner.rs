@@ -405,7 +405,7 @@ fn check_openvpn_by_pid(pid: u32, config_path: &Path) -> ActiveSession {
 
     // If no endpoint from args, try parsing the config file
     if session.endpoint.is_empty() {
-        if let Ok(content) = std::fs::read_to_string(config_path) {
+        if let Ok(content) = crate::core::vault::read_config(config_path) {
             for line in content.lines() {
                 let line = line.trim();
                 if line.to_lowercase().starts_with("remote ") {
@@ -425,7 +425,7 @@ fn check_openvpn_by_pid(pid: u32, config_path: &Path) -> ActiveSession {
     session.public_key = "OpenVPN".to_string(); // Use this field to indicate protocol
 
     // Try to get cipher from config
-    if let Ok(content) = std::fs::read_to_string(config_path) {
+    if let Ok(content) = crate::core::vault::read_config(config_path) {
         for line in content.lines() {
             let line = line.trim();
             if line.to_lowercase().starts_with("cipher ") {
@@ -0,0 +1,281 @@
+//! Fuzzy matching for the profile sidebar filter.
+//!
+//! Matching is a case-insensitive subsequence search (fzf-style): every
+//! character of the pattern must appear in the candidate in order, with
+//! bonuses for consecutive runs and word starts so that `deam` ranks
+//! `de-amsterdam` above `dev-team-main`.
+
+use crate::state::VpnProfile;
+
+/// Score awarded for every matched character.
+const SCORE_MATCH: i64 = 16;
+/// Bonus when a match directly follows the previous match.
+const BONUS_CONSECUTIVE: i64 = 12;
+/// Bonus when a match starts a word (after `-`, `_`, space, etc.).
+const BONUS_WORD_START: i64 = 10;
+/// Penalty per unmatched character between two matches.
+const PENALTY_GAP: i64 = 1;
+
+/// Result of matching a pattern against a single string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better.
+    pub score: i64,
+    /// Char indices of the matched characters in the candidate.
+    pub positions: Vec<usize>,
+}
+
+/// A profile that passed the sidebar filter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileMatch {
+    /// Index into `App::profiles`.
+    pub index: usize,
+    /// Combined score across all query terms.
+    pub score: i64,
+    /// Char indices in the profile name to highlight.
+    pub name_positions: Vec<usize>,
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn is_word_start(chars: &[char], idx: usize) -> bool {
+    if idx == 0 {
+        return true;
+    }
+    let prev = chars[idx - 1];
+    !prev.is_alphanumeric() || (prev.is_lowercase() && chars[idx].is_uppercase())
+}
+
+/// Fuzzy-match `pattern` against `text`.
+///
+/// Returns `None` if the pattern is not a subsequence of the text. Every
+/// possible start position of the first pattern character is tried and the
+/// best-scoring alignment is kept.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().map(fold).collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    let original: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = original.iter().copied().map(fold).collect();
+
+    let mut best: Option<FuzzyMatch> = None;
+
+    for start in 0..folded.len() {
+        if folded[start] != pattern[0] {
+            continue;
+        }
+
+        let mut positions = vec![start];
+        let mut cursor = start + 1;
+        for &pc in &pattern[1..] {
+            match folded[cursor..].iter().position(|&c| c == pc) {
+                Some(offset) => {
+                    positions.push(cursor + offset);
+                    cursor += offset + 1;
+                }
+                None => break,
+            }
+        }
+        if positions.len() != pattern.len() {
+            // Later starts only have less text to work with
+            break;
+        }
+
+        let score = score_positions(&original, &positions);
+        if best.as_ref().map_or(true, |b| score > b.score) {
+            best = Some(FuzzyMatch { score, positions });
+        }
+    }
+
+    best
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn score_positions(chars: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    let mut prev: Option<usize> = None;
+
+    for &pos in positions {
+        score += SCORE_MATCH;
+        if is_word_start(chars, pos) {
+            score += BONUS_WORD_START;
+        }
+        match prev {
+            Some(p) if pos == p + 1 => score += BONUS_CONSECUTIVE,
+            Some(p) => score -= (pos - p - 1) as i64 * PENALTY_GAP,
+            None => score -= pos as i64 * PENALTY_GAP,
+        }
+        prev = Some(pos);
+    }
+
+    score
+}
+
+/// Filter profiles by a whitespace-separated query.
+///
+/// Every term must match at least one of the profile's name, location,
+/// protocol or tags. Results are sorted by descending score; ties keep the
+/// input order so an empty query returns the list unchanged.
+pub fn filter_profiles(profiles: &[VpnProfile], query: &str) -> Vec<ProfileMatch> {
+    let terms: Vec<&str> = query.split_whitespace().collect();
+
+    let mut matches: Vec<ProfileMatch> = profiles
+        .iter()
+        .enumerate()
+        .filter_map(|(index, profile)| {
+            let mut score = 0;
+            let mut name_positions = Vec::new();
+
+            for term in &terms {
+                let name = fuzzy_match(term, &profile.name);
+                let other = std::iter::once(profile.location.clone())
+                    .chain(std::iter::once(profile.protocol.to_string()))
+                    .chain(profile.tags.iter().cloned())
+                    .filter_map(|field| fuzzy_match(term, &field))
+                    .map(|m| m.score)
+                    .max();
+
+                match (name, other) {
+                    (Some(n), Some(o)) if o > n.score => score += o,
+                    (Some(n), _) => {
+                        score += n.score;
+                        name_positions.extend(n.positions);
+                    }
+                    (None, Some(o)) => score += o,
+                    (None, None) => return None,
+                }
+            }
+
+            name_positions.sort_unstable();
+            name_positions.dedup();
+
+            Some(ProfileMatch {
+                index,
+                score,
+                name_positions,
+            })
+        })
+        .collect();
+
+    matches.sort_by_key(|m| std::cmp::Reverse(m.score));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Protocol;
+    use std::path::PathBuf;
+
+    fn profile(name: &str, location: &str, protocol: Protocol, tags: &[&str]) -> VpnProfile {
+        VpnProfile {
+            name: name.to_string(),
+            protocol,
+            location: location.to_string(),
+            custom_location: None,
+            config_path: PathBuf::from(format!("/tmp/{name}.conf")),
+            last_used: None,
+            tags: tags.iter().map(ToString::to_string).collect(),
+            last_latency_ms: None,
+            connect_count: 0,
+            split_tunnel: crate::core::split_tunnel::SplitTunnel::default(),
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_match_subsequence() {
+        let m = fuzzy_match("dams", "de-amsterdam").unwrap();
+        assert_eq!(m.positions, vec![0, 3, 4, 5]);
+        assert!(fuzzy_match("xyz", "de-amsterdam").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_match_case_insensitive() {
+        assert!(fuzzy_match("NL", "nl-01").is_some());
+        assert!(fuzzy_match("nl", "NL-01").is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_match_empty_pattern() {
+        let m = fuzzy_match("", "anything").unwrap();
+        assert_eq!(m.score, 0);
+        assert!(m.positions.is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_match_prefers_word_starts() {
+        let tight = fuzzy_match("ams", "nl-amsterdam").unwrap();
+        let loose = fuzzy_match("ams", "panama-staging").unwrap();
+        assert!(tight.score > loose.score);
+    }
+
+    #[test]
+    fn test_fuzzy_match_picks_best_alignment() {
+        // The first 'a' gives a scattered match; the later "ams" is contiguous
+        let m = fuzzy_match("ams", "a-x-ams").unwrap();
+        assert_eq!(m.positions, vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn test_filter_profiles_empty_query_keeps_order() {
+        let profiles = vec![
+            profile("b", "Unknown", Protocol::WireGuard, &[]),
+            profile("a", "Unknown", Protocol::OpenVPN, &[]),
+        ];
+        let result = filter_profiles(&profiles, "  ");
+        let indices: Vec<usize> = result.iter().map(|m| m.index).collect();
+        assert_eq!(indices, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_filter_profiles_by_location_protocol_and_tags() {
+        let profiles = vec![
+            profile("de-01", "Germany", Protocol::WireGuard, &["work"]),
+            profile("nl-01", "Netherlands", Protocol::OpenVPN, &[]),
+            profile(
+                "us-01",
+                "United States",
+                Protocol::WireGuard,
+                &["streaming"],
+            ),
+        ];
+
+        let by_location: Vec<usize> = filter_profiles(&profiles, "netherl")
+            .iter()
+            .map(|m| m.index)
+            .collect();
+        assert_eq!(by_location, vec![1]);
+
+        let by_protocol: Vec<usize> = filter_profiles(&profiles, "openvpn")
+            .iter()
+            .map(|m| m.index)
+            .collect();
+        assert_eq!(by_protocol, vec![1]);
+
+        let by_tag: Vec<usize> = filter_profiles(&profiles, "stream")
+            .iter()
+            .map(|m| m.index)
+            .collect();
+        assert_eq!(by_tag, vec![2]);
+    }
+
+    #[test]
+    fn test_filter_profiles_all_terms_must_match() {
+        let profiles = vec![
+            profile("de-01", "Germany", Protocol::WireGuard, &[]),
+            profile("de-02", "Germany", Protocol::OpenVPN, &[]),
+        ];
+        let result = filter_profiles(&profiles, "de wireguard");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].index, 0);
+        // Only the name term contributes highlight positions
+        assert_eq!(result[0].name_positions, vec![0, 1]);
+    }
+}
@@ -0,0 +1,472 @@
+//! Split tunnelling rules.
+//!
+//! A profile can name networks and hostnames that are the only traffic sent
+//! through the tunnel (`include`) or that must stay off it (`exclude`).
+//! Hostnames are resolved when connecting. For `WireGuard` the rules are
+//! turned into each peer's `AllowedIPs`; for `OpenVPN` into `route`
+//! options. The kill switch reads the same [`Policy`] so it does not block
+//! traffic the rules send around the tunnel.
+
+use std::fmt::Write as _;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
+
+use crate::vpn::wireguard::{self, Cidr};
+
+/// Include/exclude lists as configured (stored in `metadata.json`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SplitTunnel {
+    /// Networks and hosts that go through the tunnel; everything else
+    /// bypasses it. Empty means all traffic.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub include: Vec<String>,
+    /// Networks and hosts that always bypass the tunnel.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub exclude: Vec<String>,
+}
+
+impl SplitTunnel {
+    /// No rules: the profile's own routing applies.
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Resolve hostnames into host routes.
+    pub fn resolve(&self) -> Result<Policy, String> {
+        Ok(Policy {
+            include: resolve_targets(&self.include)?,
+            exclude: resolve_targets(&self.exclude)?,
+        })
+    }
+
+    /// One-line description for the connection details.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.include.is_empty() {
+            parts.push(format!("only {}", list_summary(&self.include)));
+        }
+        if !self.exclude.is_empty() {
+            parts.push(format!("bypass {}", list_summary(&self.exclude)));
+        }
+        if parts.is_empty() {
+            "all traffic".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// `10.0.0.0/8` or `10.0.0.0/8 +2`
+fn list_summary(targets: &[String]) -> String {
+    match targets {
+        [] => String::new(),
+        [first] => first.clone(),
+        [first, rest @ ..] => format!("{first} +{}", rest.len()),
+    }
+}
+
+/// Check a rule target and return it in canonical form: networks are
+/// masked to their prefix (`10.1.2.3/8` → `10.0.0.0/8`), bare addresses
+/// become host routes and hostnames are lowercased.
+pub fn parse_target(target: &str) -> Result<String, String> {
+    let target = target.trim();
+    if let Ok(cidr) = target.parse::<Cidr>() {
+        return Ok(network(cidr).to_string());
+    }
+    if is_hostname(target) {
+        return Ok(target.to_ascii_lowercase());
+    }
+    Err(format!(
+        "'{target}' is not a network (CIDR), address or hostname"
+    ))
+}
+
+fn is_hostname(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 253
+        && value.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        // A dotted string of digits is a mistyped address, not a host
+        && !value.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+fn resolve_targets(targets: &[String]) -> Result<Vec<Cidr>, String> {
+    let mut nets = Vec::new();
+    for target in targets {
+        if let Ok(cidr) = target.parse::<Cidr>() {
+            nets.push(network(cidr));
+            continue;
+        }
+        let addrs = resolve_host(target);
+        if addrs.is_empty() {
+            return Err(format!("Failed to resolve split tunnel host '{target}'"));
+        }
+        nets.extend(addrs.into_iter().map(host_route));
+    }
+    Ok(dedupe(nets))
+}
+
+fn resolve_host(host: &str) -> Vec<IpAddr> {
+    let mut addrs: Vec<IpAddr> = (host, 0)
+        .to_socket_addrs()
+        .map(|addrs| addrs.map(|a| a.ip()).collect())
+        .unwrap_or_default();
+    addrs.sort();
+    addrs.dedup();
+    addrs
+}
+
+/// Split tunnel rules with every hostname resolved, as applied to a
+/// connection.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Policy {
+    pub include: Vec<Cidr>,
+    pub exclude: Vec<Cidr>,
+}
+
+impl Policy {
+    /// A peer's `AllowedIPs` narrowed to the included networks, minus the
+    /// excluded ones.
+    ///
+    /// Once a family no longer has a `/0`, `wg-quick` stops routing the
+    /// endpoint around the tunnel through its fwmark table, so the peer
+    /// `endpoints` are carved out as well to avoid a routing loop.
+    pub fn allowed_ips(&self, allowed: &[Cidr], endpoints: &[IpAddr]) -> Vec<Cidr> {
+        let mut nets: Vec<Cidr> = allowed.iter().copied().map(network).collect();
+        if !self.include.is_empty() {
+            nets = nets
+                .iter()
+                .flat_map(|net| self.include.iter().filter_map(|inc| intersect(*net, *inc)))
+                .collect();
+        }
+        for hole in &self.exclude {
+            nets = nets.into_iter().flat_map(|n| subtract(n, *hole)).collect();
+        }
+        for ip in endpoints {
+            let keeps_default = nets
+                .iter()
+                .any(|n| n.is_default_route() && n.addr.is_ipv4() == ip.is_ipv4());
+            if !keeps_default {
+                let hole = host_route(*ip);
+                nets = nets.into_iter().flat_map(|n| subtract(n, hole)).collect();
+            }
+        }
+        dedupe(nets)
+    }
+
+    /// `openvpn` options for the policy: included networks replace the
+    /// server's routes (`--route-nopull`), excluded ones are routed via the
+    /// original gateway. `OpenVPN` has no gateway keyword for IPv6, so IPv6
+    /// exclusions are left out (see [`Policy::openvpn_unsupported`]).
+    pub fn openvpn_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if !self.include.is_empty() {
+            args.push("--route-nopull".to_string());
+        }
+        for (nets, gateway) in [
+            (&self.include, "vpn_gateway"),
+            (&self.exclude, "net_gateway"),
+        ] {
+            for net in nets {
+                match net.addr {
+                    IpAddr::V4(addr) => args.extend([
+                        "--route".to_string(),
+                        addr.to_string(),
+                        Ipv4Addr::from(mask_v4(net.prefix)).to_string(),
+                        gateway.to_string(),
+                    ]),
+                    IpAddr::V6(_) if gateway == "vpn_gateway" => {
+                        args.extend(["--route-ipv6".to_string(), net.to_string()]);
+                    }
+                    IpAddr::V6(_) => {}
+                }
+            }
+        }
+        args
+    }
+
+    /// Excluded IPv6 networks that [`Policy::openvpn_args`] cannot express.
+    pub fn openvpn_unsupported(&self) -> Vec<Cidr> {
+        self.exclude
+            .iter()
+            .filter(|net| net.addr.is_ipv6())
+            .copied()
+            .collect()
+    }
+}
+
+/// Rewrite a `wg-quick` config so each peer's `AllowedIPs` follows the
+/// policy. Peer endpoints given as hostnames are resolved here.
+pub fn apply_wireguard(content: &str, policy: &Policy) -> String {
+    rewrite_allowed_ips(content, policy, &resolve_host)
+}
+
+fn rewrite_allowed_ips(
+    content: &str,
+    policy: &Policy,
+    lookup: &dyn Fn(&str) -> Vec<IpAddr>,
+) -> String {
+    let (config, _) = wireguard::parse(content);
+    let mut peers = config.peers.iter();
+    let mut in_peer = false;
+    let mut out = String::new();
+
+    for line in content.lines() {
+        let trimmed = line.split('#').next().unwrap_or("").trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            in_peer = trimmed.eq_ignore_ascii_case("[Peer]");
+            let _ = writeln!(out, "{line}");
+            if in_peer {
+                let Some(peer) = peers.next() else { continue };
+                let endpoints = peer.endpoint.as_ref().map_or_else(Vec::new, |e| {
+                    e.host
+                        .parse::<IpAddr>()
+                        .map_or_else(|_| lookup(&e.host), |ip| vec![ip])
+                });
+                let allowed = policy.allowed_ips(&peer.allowed_ips, &endpoints);
+                if !allowed.is_empty() {
+                    let list: Vec<String> = allowed.iter().map(ToString::to_string).collect();
+                    let _ = writeln!(out, "AllowedIPs = {}", list.join(", "));
+                }
+            }
+            continue;
+        }
+        let is_allowed_ips = trimmed
+            .split_once('=')
+            .is_some_and(|(key, _)| key.trim().eq_ignore_ascii_case("AllowedIPs"));
+        if in_peer && is_allowed_ips {
+            continue;
+        }
+        let _ = writeln!(out, "{line}");
+    }
+    out
+}
+
+/// Address as bits, right-aligned, and the family's width.
+fn bits(addr: IpAddr) -> (u128, u8) {
+    match addr {
+        IpAddr::V4(v4) => (u128::from(u32::from(v4)), 32),
+        IpAddr::V6(v6) => (u128::from(v6), 128),
+    }
+}
+
+fn from_bits(v4: bool, bits: u128) -> IpAddr {
+    if v4 {
+        #[allow(clippy::cast_possible_truncation)]
+        IpAddr::V4(Ipv4Addr::from(bits as u32))
+    } else {
+        IpAddr::V6(Ipv6Addr::from(bits))
+    }
+}
+
+fn mask(width: u8, prefix: u8) -> u128 {
+    if prefix == 0 {
+        return 0;
+    }
+    let all = u128::MAX >> (128 - u32::from(width));
+    all & !((1u128 << (width - prefix)) - 1)
+}
+
+fn mask_v4(prefix: u8) -> u32 {
+    #[allow(clippy::cast_possible_truncation)]
+    let mask = mask(32, prefix) as u32;
+    mask
+}
+
+fn host_route(addr: IpAddr) -> Cidr {
+    let prefix = if addr.is_ipv4() { 32 } else { 128 };
+    Cidr { addr, prefix }
+}
+
+/// Clear the host bits (`10.1.2.3/8` → `10.0.0.0/8`).
+fn network(cidr: Cidr) -> Cidr {
+    let (value, width) = bits(cidr.addr);
+    Cidr {
+        addr: from_bits(width == 32, value & mask(width, cidr.prefix)),
+        prefix: cidr.prefix,
+    }
+}
+
+/// Whether `outer` covers all of `inner`.
+fn contains(outer: Cidr, inner: Cidr) -> bool {
+    let (outer_bits, width) = bits(outer.addr);
+    let (inner_bits, inner_width) = bits(inner.addr);
+    width == inner_width
+        && outer.prefix <= inner.prefix
+        && inner_bits & mask(width, outer.prefix) == outer_bits & mask(width, outer.prefix)
+}
+
+fn intersect(a: Cidr, b: Cidr) -> Option<Cidr> {
+    if contains(a, b) {
+        Some(b)
+    } else if contains(b, a) {
+        Some(a)
+    } else {
+        None
+    }
+}
+
+/// `net` without `hole`, as the fewest prefixes.
+fn subtract(net: Cidr, hole: Cidr) -> Vec<Cidr> {
+    if contains(hole, net) {
+        return Vec::new();
+    }
+    if !contains(net, hole) {
+        return vec![net];
+    }
+    let (value, width) = bits(net.addr);
+    let prefix = net.prefix + 1;
+    let high = value | (1u128 << (width - prefix));
+    [value, high]
+        .into_iter()
+        .flat_map(|half| {
+            subtract(
+                Cidr {
+                    addr: from_bits(width == 32, half),
+                    prefix,
+                },
+                hole,
+            )
+        })
+        .collect()
+}
+
+/// Drop networks covered by another one, keeping the original order.
+fn dedupe(nets: Vec<Cidr>) -> Vec<Cidr> {
+    let mut kept: Vec<Cidr> = Vec::new();
+    for net in nets {
+        if kept.iter().any(|k| contains(*k, net)) {
+            continue;
+        }
+        kept.retain(|k| !contains(net, *k));
+        kept.push(net);
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cidrs(list: &[&str]) -> Vec<Cidr> {
+        list.iter().map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(parse_target("10.1.2.3/8").unwrap(), "10.0.0.0/8");
+        assert_eq!(parse_target("192.0.2.7").unwrap(), "192.0.2.7/32");
+        assert_eq!(
+            parse_target(" Intranet.Corp.example ").unwrap(),
+            "intranet.corp.example"
+        );
+        assert!(parse_target("10.0.0.0/33").is_err());
+        assert!(parse_target("10.0.0.300").is_err());
+        assert!(parse_target("bad host").is_err());
+        assert!(parse_target("-bad.example").is_err());
+    }
+
+    #[test]
+    fn test_allowed_ips_exclude_and_include() {
+        let full = cidrs(&["0.0.0.0/0", "::/0"]);
+        let exclude = Policy {
+            include: Vec::new(),
+            exclude: cidrs(&["10.0.0.0/8"]),
+        };
+        let endpoint: IpAddr = "203.0.113.5".parse().unwrap();
+        let allowed = exclude.allowed_ips(&full, &[endpoint]);
+        // IPv6 keeps its /0, so wg-quick still handles the endpoint there
+        assert!(allowed.contains(&"::/0".parse().unwrap()));
+        let v4: Vec<Cidr> = allowed
+            .iter()
+            .filter(|c| c.addr.is_ipv4())
+            .copied()
+            .collect();
+        assert!(v4
+            .iter()
+            .all(|c| !contains(*c, "10.1.2.3/32".parse().unwrap())));
+        assert!(v4.iter().all(|c| !contains(*c, host_route(endpoint))));
+        assert!(v4
+            .iter()
+            .any(|c| contains(*c, "8.8.8.8/32".parse().unwrap())));
+        assert!(v4
+            .iter()
+            .any(|c| contains(*c, "203.0.113.4/32".parse().unwrap())));
+        // Seven pieces around 10.0.0.0/8, plus 31 replacing the /1 that
+        // held the endpoint
+        assert_eq!(v4.len(), 7 + 31);
+
+        let include = Policy {
+            include: cidrs(&["10.20.0.0/16", "198.51.100.7/32", "2001:db8::/32"]),
+            exclude: cidrs(&["10.20.5.0/24"]),
+        };
+        let allowed = include.allowed_ips(&cidrs(&["0.0.0.0/0"]), &[endpoint]);
+        assert!(allowed.contains(&"198.51.100.7/32".parse().unwrap()));
+        assert!(allowed.iter().all(|c| c.addr.is_ipv4()));
+        assert!(allowed
+            .iter()
+            .all(|c| !contains(*c, "10.20.5.1/32".parse().unwrap())));
+        assert!(allowed
+            .iter()
+            .any(|c| contains(*c, "10.20.6.1/32".parse().unwrap())));
+
+        // A peer that only carries its own subnet is narrowed, not widened
+        let narrow = include.allowed_ips(&cidrs(&["10.20.0.0/24"]), &[]);
+        assert_eq!(narrow, cidrs(&["10.20.0.0/24"]));
+    }
+
+    #[test]
+    fn test_rewrite_wireguard_and_openvpn_args() {
+        let config = "\
+[Interface]
+PrivateKey = x
+Address = 10.0.0.2/32
+
+[Peer]
+PublicKey = y
+AllowedIPs = 0.0.0.0/0
+AllowedIPs = ::/0
+Endpoint = vpn.example:51820
+";
+        let policy = Policy {
+            include: cidrs(&["10.20.0.0/16", "2001:db8::/32"]),
+            exclude: Vec::new(),
+        };
+        let rewritten = rewrite_allowed_ips(config, &policy, &|host| {
+            assert_eq!(host, "vpn.example");
+            vec!["203.0.113.5".parse().unwrap()]
+        });
+        assert_eq!(
+            rewritten,
+            "[Interface]\nPrivateKey = x\nAddress = 10.0.0.2/32\n\n[Peer]\n\
+             AllowedIPs = 10.20.0.0/16, 2001:db8::/32\nPublicKey = y\n\
+             Endpoint = vpn.example:51820\n"
+        );
+
+        let policy = Policy {
+            include: cidrs(&["10.20.0.0/16", "2001:db8::/32"]),
+            exclude: cidrs(&["192.0.2.0/24", "2001:db8:1::/48"]),
+        };
+        assert_eq!(
+            policy.openvpn_args(),
+            [
+                "--route-nopull",
+                "--route",
+                "10.20.0.0",
+                "255.255.0.0",
+                "vpn_gateway",
+                "--route-ipv6",
+                "2001:db8::/32",
+                "--route",
+                "192.0.2.0",
+                "255.255.255.0",
+                "net_gateway"
+            ]
+        );
+        assert_eq!(policy.openvpn_unsupported(), cidrs(&["2001:db8:1::/48"]));
+    }
+}
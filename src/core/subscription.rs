@@ -0,0 +1,689 @@
+//! Subscription URLs that keep imported profiles in sync with a provider.
+//!
+//! A subscription points either at a single config or at an index listing
+//! config URLs, one per line (relative URLs are resolved against the index).
+//! Refreshing sends conditional requests, compares what came back with what
+//! was imported last time and updates the affected profiles in place, so
+//! their metadata (tags, usage stats) survives server rotations.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::downloader::{self, Fetch, Validators};
+use super::duplicates;
+use super::importer::{self, Conflict, Outcome};
+use super::vault::{self, ScratchDir};
+use crate::constants;
+use crate::logger::{self, LogLevel};
+use crate::state::VpnProfile;
+use crate::utils;
+use crate::vpn;
+
+/// A registered subscription and what it imported last time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Subscription {
+    pub name: String,
+    pub url: String,
+    /// Hours between automatic refreshes while the TUI is running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_hours: Option<u64>,
+    /// Unix time of the last refresh that was applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refreshed_at: Option<u64>,
+    /// Plain HTTP was explicitly allowed when the subscription was added.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure: bool,
+    #[serde(default)]
+    validators: Validators,
+    /// Config URLs listed by the index (just `url` for a single config).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    entries: Vec<String>,
+    /// Imported profiles by config URL.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    profiles: BTreeMap<String, Tracked>,
+}
+
+/// A profile imported from one config URL.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Tracked {
+    file_name: String,
+    sha256: String,
+    #[serde(default)]
+    validators: Validators,
+}
+
+impl Subscription {
+    /// Number of profiles this subscription manages.
+    pub fn profile_count(&self) -> usize {
+        self.profiles.len()
+    }
+
+    /// Stored file names of the profiles this subscription manages.
+    pub fn profile_files(&self) -> impl Iterator<Item = &str> {
+        self.profiles.values().map(|t| t.file_name.as_str())
+    }
+
+    /// Whether an automatic refresh is due at `now` (Unix seconds).
+    pub fn is_due(&self, now: u64) -> bool {
+        self.interval_hours.is_some_and(|hours| {
+            self.refreshed_at
+                .map_or(true, |last| now.saturating_sub(last) >= hours * 3600)
+        })
+    }
+}
+
+/// What a refresh does to one profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Changed,
+    Removed,
+    Unchanged,
+}
+
+/// One profile's entry in a refresh plan.
+#[derive(Clone, Debug)]
+pub struct ProfileChange {
+    pub change: Change,
+    /// Profile name (file name without extension).
+    pub name: String,
+    /// Line diff for changed profiles, with key material fingerprinted.
+    pub diff: Vec<String>,
+    /// Why applying this change failed, if it did.
+    pub error: Option<String>,
+    source: String,
+    file_name: String,
+    content: Option<String>,
+    validators: Validators,
+}
+
+impl ProfileChange {
+    fn new(change: Change, source: &str, file_name: String) -> Self {
+        Self {
+            change,
+            name: stem(&file_name),
+            diff: Vec::new(),
+            error: None,
+            source: source.to_string(),
+            file_name,
+            content: None,
+            validators: Validators::default(),
+        }
+    }
+}
+
+/// Everything a refresh does (or would do) to one subscription.
+#[derive(Clone, Debug)]
+pub struct Plan {
+    pub changes: Vec<ProfileChange>,
+    /// Validators of the subscription URL; `None` when it answered 304.
+    validators: Option<Validators>,
+    entries: Vec<String>,
+}
+
+impl Plan {
+    /// Number of profiles with the given change.
+    pub fn count(&self, change: Change) -> usize {
+        self.changes.iter().filter(|c| c.change == change).count()
+    }
+
+    /// Number of changes that could not be applied.
+    pub fn failed(&self) -> usize {
+        self.changes.iter().filter(|c| c.error.is_some()).count()
+    }
+
+    /// Whether anything was added, changed or removed.
+    pub fn has_changes(&self) -> bool {
+        self.changes.iter().any(|c| c.change != Change::Unchanged)
+    }
+
+    /// One-line summary, e.g. `1 added, 2 changed, 0 removed`.
+    pub fn summary(&self) -> String {
+        if !self.has_changes() {
+            return "up to date".to_string();
+        }
+        let mut summary = format!(
+            "{} added, {} changed, {} removed",
+            self.count(Change::Added),
+            self.count(Change::Changed),
+            self.count(Change::Removed)
+        );
+        if self.failed() > 0 {
+            let _ = write!(summary, ", {} failed", self.failed());
+        }
+        summary
+    }
+
+    /// Human-readable diff: one line per affected profile, followed by the
+    /// changed config lines of updated profiles.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for change in &self.changes {
+            let marker = match change.change {
+                Change::Added => "+",
+                Change::Changed => "~",
+                Change::Removed => "-",
+                Change::Unchanged => continue,
+            };
+            match &change.error {
+                Some(error) => lines.push(format!("{marker} {} ✗ {error}", change.name)),
+                None => lines.push(format!("{marker} {}", change.name)),
+            }
+            lines.extend(change.diff.iter().map(|line| format!("    {line}")));
+        }
+        lines
+    }
+}
+
+fn store_path() -> Result<PathBuf, String> {
+    Ok(utils::get_app_config_dir()
+        .map_err(|e| format!("Failed to get config dir: {e}"))?
+        .join(constants::SUBSCRIPTIONS_FILE_NAME))
+}
+
+/// Load registered subscriptions.
+pub fn load() -> Vec<Subscription> {
+    let Ok(path) = store_path() else {
+        return Vec::new();
+    };
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        logger::log(
+            LogLevel::Warning,
+            "CONFIG",
+            format!(
+                "Failed to parse {}: {e}",
+                constants::SUBSCRIPTIONS_FILE_NAME
+            ),
+        );
+        Vec::new()
+    })
+}
+
+/// Save subscriptions. The file is private since URLs often carry tokens.
+pub fn save(subscriptions: &[Subscription]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(subscriptions)
+        .map_err(|e| format!("Failed to serialize subscriptions: {e}"))?;
+    vault::write_private(&store_path()?, json.as_bytes())
+        .map_err(|e| format!("Failed to write subscriptions: {e}"))
+}
+
+/// Register a subscription and return its index. The name defaults to the
+/// URL's host.
+///
+/// Plain `http://` URLs are refused unless `insecure` is set; the choice is
+/// kept for every later refresh.
+pub fn add(
+    subscriptions: &mut Vec<Subscription>,
+    url: &str,
+    name: Option<&str>,
+    interval_hours: Option<u64>,
+    insecure: bool,
+) -> Result<usize, String> {
+    let url = url.trim();
+    if !importer::is_valid_url(url) {
+        return Err(format!("Invalid URL: {url}"));
+    }
+    if !insecure && !url.starts_with("https://") {
+        return Err(constants::ERR_INSECURE_URL.to_string());
+    }
+    let name = name.map_or_else(|| host(url).to_string(), |n| n.trim().to_string());
+    if name.is_empty() {
+        return Err("Subscription name is empty".to_string());
+    }
+    if interval_hours == Some(0) {
+        return Err("Refresh interval must be at least one hour".to_string());
+    }
+    if let Some(existing) = subscriptions
+        .iter()
+        .find(|s| s.name == name || s.url == url)
+    {
+        return Err(format!("Subscription '{}' already exists", existing.name));
+    }
+
+    subscriptions.push(Subscription {
+        name,
+        url: url.to_string(),
+        interval_hours,
+        refreshed_at: None,
+        insecure,
+        validators: Validators::default(),
+        entries: Vec::new(),
+        profiles: BTreeMap::new(),
+    });
+    Ok(subscriptions.len() - 1)
+}
+
+/// Fetch a subscription and work out what changed. Nothing is written.
+///
+/// Fails as a whole if any listed config cannot be fetched, so a flaky
+/// server never looks like removed profiles.
+pub fn check(sub: &Subscription) -> Result<Plan, String> {
+    let profiles_dir = vpn::get_profiles_dir()?;
+    let present = |t: &&Tracked| profiles_dir.join(&t.file_name).exists();
+
+    // A profile deleted by hand is imported again, so skip the cache then
+    let complete = sub
+        .entries
+        .iter()
+        .all(|entry| sub.profiles.get(entry).is_some_and(|t| present(&t)));
+    let cached = if complete {
+        sub.validators.clone()
+    } else {
+        Validators::default()
+    };
+
+    let (validators, entries, mut own_body) =
+        match downloader::fetch(&sub.url, &cached, sub.insecure)? {
+            Fetch::NotModified => (None, sub.entries.clone(), None),
+            Fetch::Body { data, validators } => match parse_index(&sub.url, &data) {
+                Some(entries) => (Some(validators), entries, None),
+                None => (Some(validators), vec![sub.url.clone()], Some(data)),
+            },
+        };
+
+    let mut changes = Vec::new();
+    for source in &entries {
+        let tracked = sub.profiles.get(source).filter(present);
+        let fetched = if *source == sub.url {
+            match (own_body.take(), &validators) {
+                (Some(data), Some(validators)) => Fetch::Body {
+                    data,
+                    validators: validators.clone(),
+                },
+                _ => Fetch::NotModified,
+            }
+        } else {
+            let cached = tracked.map(|t| t.validators.clone()).unwrap_or_default();
+            downloader::fetch(source, &cached, sub.insecure)
+                .map_err(|e| format!("{source}: {e}"))?
+        };
+        changes.push(plan_entry(source, tracked, fetched, &profiles_dir));
+    }
+
+    for (source, tracked) in &sub.profiles {
+        if !entries.contains(source) {
+            changes.push(ProfileChange::new(
+                Change::Removed,
+                source,
+                tracked.file_name.clone(),
+            ));
+        }
+    }
+
+    Ok(Plan {
+        changes,
+        validators,
+        entries,
+    })
+}
+
+fn plan_entry(
+    source: &str,
+    tracked: Option<&Tracked>,
+    fetched: Fetch,
+    profiles_dir: &Path,
+) -> ProfileChange {
+    let (data, validators) = match fetched {
+        Fetch::NotModified => {
+            let file_name = tracked.map_or_else(
+                || downloader::extract_filename_from_url(source),
+                |t| t.file_name.clone(),
+            );
+            let mut change = ProfileChange::new(Change::Unchanged, source, file_name);
+            change.validators = tracked.map(|t| t.validators.clone()).unwrap_or_default();
+            return change;
+        }
+        Fetch::Body { data, validators } => (data, validators),
+    };
+
+    let mut change = match tracked {
+        Some(t) if t.sha256 == sha256_hex(&data) => {
+            ProfileChange::new(Change::Unchanged, source, t.file_name.clone())
+        }
+        Some(t) => {
+            let current = vault::read_config(&profiles_dir.join(&t.file_name)).unwrap_or_default();
+            let mut change = ProfileChange::new(Change::Changed, source, t.file_name.clone());
+            change.diff = diff_lines(&current, &data);
+            change.content = Some(data);
+            change
+        }
+        None => {
+            let file_name = downloader::extract_filename_from_url(source);
+            let mut change = ProfileChange::new(Change::Added, source, file_name);
+            change.content = Some(data);
+            change
+        }
+    };
+    change.validators = validators;
+    change
+}
+
+/// Carry out a plan and record the result in `sub`. Profiles whose config
+/// path is in `in_use` are kept even if the provider dropped them.
+pub fn apply(sub: &mut Subscription, plan: &mut Plan, in_use: &[PathBuf]) -> Result<(), String> {
+    let profiles_dir = vpn::get_profiles_dir()?;
+    let mut metadata = utils::load_profile_metadata().unwrap_or_default();
+    let mut metadata_changed = false;
+    let mut index = duplicates::Index::of(&vpn::load_profiles());
+
+    for change in &mut plan.changes {
+        let path = profiles_dir.join(&change.file_name);
+        match change.change {
+            Change::Unchanged => {
+                if let Some(tracked) = sub.profiles.get_mut(&change.source) {
+                    tracked.validators = change.validators.clone();
+                }
+            }
+            Change::Added | Change::Changed => {
+                let content = change.content.take().unwrap_or_default();
+                let replace = (change.change == Change::Changed).then_some(path.as_path());
+                match import_staged(&mut index, &change.file_name, &content, replace) {
+                    Ok(profile) => {
+                        change.name.clone_from(&profile.name);
+                        let file_name = profile
+                            .config_path
+                            .file_name()
+                            .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+                        sub.profiles.insert(
+                            change.source.clone(),
+                            Tracked {
+                                file_name,
+                                sha256: sha256_hex(&content),
+                                validators: change.validators.clone(),
+                            },
+                        );
+                    }
+                    Err(e) => change.error = Some(e),
+                }
+            }
+            Change::Removed => {
+                if in_use.contains(&path) {
+                    change.error = Some("in use, kept until the next refresh".to_string());
+                    continue;
+                }
+                vpn::remove_profile(&path);
+                metadata_changed |= metadata.remove(path.to_string_lossy().as_ref()).is_some();
+                sub.profiles.remove(&change.source);
+            }
+        }
+    }
+
+    if metadata_changed {
+        let _ = utils::save_profile_metadata(&metadata);
+    }
+
+    sub.entries = std::mem::take(&mut plan.entries);
+    if plan.failed() == 0 {
+        if let Some(validators) = plan.validators.take() {
+            sub.validators = validators;
+        }
+    } else {
+        // Make the next refresh fetch everything again
+        sub.validators = Validators::default();
+    }
+    sub.refreshed_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs());
+
+    logger::log(
+        LogLevel::Info,
+        "SUBSCRIPTION",
+        format!("{}: {}", sub.name, plan.summary()),
+    );
+    Ok(())
+}
+
+/// Check a subscription and, unless `dry_run`, apply the result.
+pub fn refresh(sub: &mut Subscription, dry_run: bool, in_use: &[PathBuf]) -> Result<Plan, String> {
+    let mut plan = check(sub)?;
+    if !dry_run {
+        apply(sub, &mut plan, in_use)?;
+    }
+    Ok(plan)
+}
+
+/// Import `content` as `file_name` through a private staging directory,
+/// replacing `replace` in place when given.
+///
+/// A new config that duplicates a stored profile (by content, see
+/// [`duplicates::Index`]) updates and adopts that profile instead of adding
+/// a copy under a numbered name.
+fn import_staged(
+    index: &mut duplicates::Index,
+    file_name: &str,
+    content: &str,
+    replace: Option<&Path>,
+) -> Result<VpnProfile, String> {
+    let staging = ScratchDir::new("subscription")?;
+    let staged = staging.path.join(file_name);
+    vault::write_private(&staged, content.as_bytes())
+        .map_err(|e| format!("Failed to stage config: {e}"))?;
+    let outcome = if let Some(existing) = replace {
+        index.replace(existing, &staged)
+    } else {
+        let duplicate = index.find_duplicate(&staged);
+        index.import(
+            &staged,
+            duplicate.as_ref().map(|d| (d, Conflict::Overwrite)),
+        )
+    };
+    match outcome {
+        Outcome::Imported(profile, _) | Outcome::Replaced(profile, _) => Ok(profile),
+        Outcome::Failed(_, e) => Err(e),
+        Outcome::Skipped(name) => Err(format!("{name} was skipped")),
+    }
+}
+
+/// Parse an index into absolute config URLs, or `None` when the body is not
+/// an index (i.e. it is a config itself).
+fn parse_index(base: &str, body: &str) -> Option<Vec<String>> {
+    let mut entries = Vec::new();
+    for line in body.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let path = line.split(['?', '#']).next().unwrap_or(line).to_lowercase();
+        let is_config = path.ends_with(constants::EXT_CONF) || path.ends_with(constants::EXT_OVPN);
+        if !is_config || line.contains(char::is_whitespace) {
+            return None;
+        }
+        let url = resolve_url(base, line);
+        if !entries.contains(&url) {
+            entries.push(url);
+        }
+    }
+    (!entries.is_empty()).then_some(entries)
+}
+
+/// Resolve an index entry against the index URL.
+fn resolve_url(base: &str, reference: &str) -> String {
+    if reference.starts_with("http://") || reference.starts_with("https://") {
+        return reference.to_string();
+    }
+    let base = base.split(['?', '#']).next().unwrap_or(base);
+    let authority = base.find("://").map_or(0, |i| i + 3);
+    let rest = &base[authority..];
+    if reference.starts_with('/') {
+        let host_end = rest.find('/').map_or(base.len(), |i| authority + i);
+        format!("{}{reference}", &base[..host_end])
+    } else {
+        let dir_end = rest.rfind('/').map_or(base.len(), |i| authority + i);
+        format!("{}/{reference}", &base[..dir_end])
+    }
+}
+
+/// Host part of a URL, used as the default subscription name.
+fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let authority = authority.rsplit('@').next().unwrap_or(authority);
+    authority.split(':').next().unwrap_or(authority)
+}
+
+fn stem(file_name: &str) -> String {
+    Path::new(file_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file_name)
+        .to_string()
+}
+
+fn sha256_hex(data: &str) -> String {
+    let mut hex = String::new();
+    for byte in Sha256::digest(data.as_bytes()) {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
+/// Short fingerprint printed in place of key material.
+fn fingerprint(data: &str) -> String {
+    sha256_hex(data)[..8].to_string()
+}
+
+/// Lines removed (`-`) and added (`+`) between two configs.
+fn diff_lines(old: &str, new: &str) -> Vec<String> {
+    let old = redacted_lines(old);
+    let new = redacted_lines(new);
+    let mut diff: Vec<String> = old
+        .iter()
+        .filter(|line| !new.contains(line))
+        .map(|line| format!("- {line}"))
+        .collect();
+    diff.extend(
+        new.iter()
+            .filter(|line| !old.contains(line))
+            .map(|line| format!("+ {line}")),
+    );
+    diff
+}
+
+/// Significant config lines with private/preshared keys and inline
+/// `OpenVPN` blocks replaced by fingerprints, so diffs are safe to print.
+fn redacted_lines(config: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut block: Option<(String, String)> = None;
+    for line in config.lines().map(str::trim) {
+        if let Some((tag, contents)) = &mut block {
+            if line.eq_ignore_ascii_case(&format!("</{tag}>")) {
+                lines.push(format!("<{tag}> ({})", fingerprint(contents)));
+                block = None;
+            } else {
+                contents.push_str(line);
+                contents.push('\n');
+            }
+            continue;
+        }
+        if let Some(tag) = line
+            .strip_prefix('<')
+            .and_then(|l| l.strip_suffix('>'))
+            .filter(|t| !t.starts_with('/'))
+        {
+            block = Some((tag.to_string(), String::new()));
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        let secret = line.split_once('=').filter(|(key, _)| {
+            let key = key.trim();
+            key.eq_ignore_ascii_case("PrivateKey") || key.eq_ignore_ascii_case("PresharedKey")
+        });
+        match secret {
+            Some((key, value)) => {
+                lines.push(format!("{} = ({})", key.trim(), fingerprint(value.trim())));
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_index_resolves_relative_entries() {
+        let body =
+            "# servers\nus.conf\n/other/de.ovpn\nhttps://cdn.example.com/fr.conf?t=1\nus.conf\n";
+        assert_eq!(
+            parse_index("https://vpn.example.com/sub/index.txt?token=x", body),
+            Some(vec![
+                "https://vpn.example.com/sub/us.conf".to_string(),
+                "https://vpn.example.com/other/de.ovpn".to_string(),
+                "https://cdn.example.com/fr.conf?t=1".to_string(),
+            ])
+        );
+
+        let config = "# generated\n[Interface]\nPrivateKey = abc\n";
+        assert_eq!(parse_index("https://vpn.example.com/us.conf", config), None);
+    }
+
+    #[test]
+    fn test_diff_fingerprints_key_material() {
+        let old = "[Interface]\nPrivateKey = secret-one\n[Peer]\nEndpoint = 1.2.3.4:51820\n";
+        let new = "[Interface]\nPrivateKey = secret-one\n[Peer]\nEndpoint = 5.6.7.8:51820\n";
+        assert_eq!(
+            diff_lines(old, new),
+            vec!["- Endpoint = 1.2.3.4:51820", "+ Endpoint = 5.6.7.8:51820"]
+        );
+
+        let old = "client\n<key>\nAAAA\n</key>\n";
+        let new = "client\n<key>\nBBBB\n</key>\n";
+        let diff = diff_lines(old, new);
+        assert_eq!(diff.len(), 2);
+        assert!(diff.iter().all(|l| l.contains("<key> (")));
+        assert!(!diff
+            .iter()
+            .any(|l| l.contains("AAAA") || l.contains("BBBB")));
+
+        let diff = diff_lines("PrivateKey = one\n", "PrivateKey = two\n");
+        assert!(!diff.iter().any(|l| l.contains("one") || l.contains("two")));
+    }
+
+    #[test]
+    fn test_is_due() {
+        let mut subs = Vec::new();
+        let index = add(
+            &mut subs,
+            "https://vpn.example.com/index",
+            None,
+            Some(6),
+            false,
+        )
+        .unwrap();
+        let sub = &mut subs[index];
+        assert_eq!(sub.name, "vpn.example.com");
+        assert!(sub.is_due(1_000));
+
+        sub.refreshed_at = Some(1_000);
+        assert!(!sub.is_due(1_000 + 5 * 3600));
+        assert!(sub.is_due(1_000 + 6 * 3600));
+
+        sub.interval_hours = None;
+        assert!(!sub.is_due(u64::MAX));
+    }
+
+    #[test]
+    fn test_add_refuses_plain_http_without_opt_in() {
+        let mut subs = Vec::new();
+        let url = "http://vpn.example.com/index";
+        assert_eq!(
+            add(&mut subs, url, None, None, false),
+            Err(constants::ERR_INSECURE_URL.to_string())
+        );
+        let index = add(&mut subs, url, None, None, true).unwrap();
+        assert!(subs[index].insecure);
+    }
+}
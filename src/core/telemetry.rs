@@ -2,7 +2,7 @@
 //!
 //! This module handles asynchronous collection of network telemetry data
 //! including public IP address, ISP information, latency measurements,
-//! DNS configuration, and IPv6 leak detection.
+//! DNS configuration, and the optional remote IPv6 leak confirmation.
 //!
 //! The telemetry worker runs in a background thread and communicates
 //! updates via an MPSC channel to the main application.
@@ -29,10 +29,16 @@ pub enum TelemetryUpdate {
     Isp(String),
     /// Updated DNS server address.
     Dns(String),
+    /// Result of the route-based DNS leak check.
+    DnsCheck(crate::core::leaks::DnsReport),
     /// Updated physical location (City, Country).
     Location(String),
-    /// IPv6 leak detection result (true = leak detected).
+    /// Remote IPv6 confirmation result (true = an IPv6-only site answered).
     Ipv6Leak(bool),
+    /// Result of the local IPv6 leak check.
+    Ipv6Check(crate::core::leaks::Ipv6Report),
+    /// Routing table snapshot for the Routes panel.
+    Routes(crate::core::routes::RouteTable),
     /// Log message with level for production logging (uses centralized logger)
     Log(LogLevel, String),
 }
@@ -179,6 +185,16 @@ fn fetch_ip_and_isp(tx: &Sender<TelemetryUpdate>) {
     });
 }
 
+/// Fetch the public IP once, trying each API in turn (for CLI commands).
+pub fn fetch_public_ip() -> Option<String> {
+    let (tx, _rx) = mpsc::channel();
+    try_ipinfo_api(&tx)
+        .map(|(ip, _, _)| ip)
+        .or_else(|| try_ipify_api(&tx))
+        .or_else(|| try_icanhazip_api(&tx))
+        .or_else(|| try_ifconfig_api(&tx))
+}
+
 /// Try ipinfo.io API (returns IP and optionally ISP + Location) with retry
 fn try_ipinfo_api(
     tx: &Sender<TelemetryUpdate>,
@@ -551,7 +567,8 @@ fn fetch_latency(tx: &Sender<TelemetryUpdate>) {
     });
 }
 
-/// Fetches DNS configuration and checks for IPv6 leaks.
+/// Fetches DNS configuration and, when `VORTIX_IPV6_REMOTE_CHECK=1`,
+/// confirms IPv6 leaks by contacting IPv6-only sites.
 fn fetch_security_info(tx: &Sender<TelemetryUpdate>) {
     let tx_clone = tx.clone();
     thread::spawn(move || {
@@ -564,13 +581,18 @@ fn fetch_security_info(tx: &Sender<TelemetryUpdate>) {
             let _ = tx_clone.send(TelemetryUpdate::Dns(dns_server));
         }
 
+        // The local check in `leaks` needs no third parties; this one is opt-in
+        if std::env::var(constants::ENV_IPV6_REMOTE_CHECK).as_deref() != Ok("1") {
+            return;
+        }
+
         // Check for IPv6 connectivity with multiple endpoints (indicates potential leak when VPN active)
         let mut is_leaking = false;
         for endpoint in constants::IPV6_CHECK_APIS {
             let output6 = std::process::Command::new("curl")
                 .args(["-6", "-s", "--max-time", "2", endpoint])
                 .output();
-            if output6.map(|o| o.status.success()).unwrap_or(false) {
+            if output6.is_ok_and(|o| o.status.success()) {
                 is_leaking = true;
                 break;
             }
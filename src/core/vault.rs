@@ -0,0 +1,523 @@
+//! Optional encrypted-at-rest storage for profiles.
+//!
+//! When the vault is enabled (`vault.json` exists), every file in the
+//! profiles directory, including `OpenVPN` companion files, is stored
+//! encrypted with a key derived from the vault passphrase. The key is held in
+//! memory only after [`unlock`]. Configs are decrypted into a private 0600
+//! file on tmpfs (see [`materialize`]) only for as long as `wg-quick` or
+//! `openvpn` needs them: for `openvpn`, until the connection is down (see
+//! [`hold`]).
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use super::crypto::{self, SecretKey};
+use crate::utils::{base64_decode, base64_encode};
+
+/// Format version of the vault header and encrypted files.
+const VAULT_VERSION: u8 = 1;
+/// Plaintext encrypted into the header to check the passphrase.
+const CHECK_PLAINTEXT: &[u8] = b"vortix-vault";
+
+/// An encrypted file. The `vortix_vault` field doubles as the marker that
+/// tells encrypted files apart from plain configs.
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    vortix_vault: u8,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Contents of `vault.json`.
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    version: u8,
+    /// Base64 Argon2id salt for the vault key.
+    salt: String,
+    /// [`CHECK_PLAINTEXT`] encrypted with the vault key.
+    check: Envelope,
+}
+
+fn key_slot() -> &'static Mutex<Option<SecretKey>> {
+    static KEY: OnceLock<Mutex<Option<SecretKey>>> = OnceLock::new();
+    KEY.get_or_init(|| Mutex::new(None))
+}
+
+fn header_path() -> Result<PathBuf, String> {
+    crate::utils::get_app_config_dir()
+        .map(|dir| dir.join(crate::constants::VAULT_FILE_NAME))
+        .map_err(|e| format!("Failed to get config dir: {e}"))
+}
+
+fn read_header() -> Result<Header, String> {
+    let content =
+        fs::read_to_string(header_path()?).map_err(|e| format!("Failed to read vault: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Vault header is corrupted: {e}"))
+}
+
+fn seal_with(key: &SecretKey, data: &[u8]) -> Result<Envelope, String> {
+    let (nonce, ciphertext) = crypto::encrypt(key, data)?;
+    Ok(Envelope {
+        vortix_vault: VAULT_VERSION,
+        nonce: base64_encode(&nonce),
+        ciphertext: base64_encode(&ciphertext),
+    })
+}
+
+fn open_with(key: &SecretKey, envelope: &Envelope) -> Result<Vec<u8>, String> {
+    let corrupt = || "Encrypted profile is corrupted".to_string();
+    let nonce = base64_decode(&envelope.nonce).ok_or_else(corrupt)?;
+    let ciphertext = base64_decode(&envelope.ciphertext).ok_or_else(corrupt)?;
+    crypto::decrypt(key, &nonce, &ciphertext)
+}
+
+fn parse_envelope(data: &[u8]) -> Option<Envelope> {
+    // Cheap check first so plain configs are never run through the JSON parser
+    if !data.starts_with(b"{\"vortix_vault\"") {
+        return None;
+    }
+    serde_json::from_slice(data).ok()
+}
+
+/// Whether vault mode is enabled.
+pub fn is_enabled() -> bool {
+    header_path().is_ok_and(|p| p.exists())
+}
+
+/// Whether the vault key is loaded in memory.
+pub fn is_unlocked() -> bool {
+    key_slot().lock().is_ok_and(|k| k.is_some())
+}
+
+/// Whether a profile file is stored encrypted.
+pub fn is_sealed_file(path: &Path) -> bool {
+    fs::read(path).is_ok_and(|data| parse_envelope(&data).is_some())
+}
+
+/// Derive the vault key from the passphrase and keep it for this process.
+pub fn unlock(passphrase: &str) -> Result<(), String> {
+    let header = read_header()?;
+    let salt = base64_decode(&header.salt).ok_or("Vault header is corrupted")?;
+    let key = crypto::derive_key(passphrase, &salt)?;
+    if open_with(&key, &header.check)? != CHECK_PLAINTEXT {
+        return Err("Wrong vault passphrase".to_string());
+    }
+    if let Ok(mut slot) = key_slot().lock() {
+        *slot = Some(key);
+    }
+    Ok(())
+}
+
+/// Encrypt `data` for storage if the vault is enabled; plain otherwise.
+pub fn seal_bytes(data: &[u8]) -> Result<Vec<u8>, String> {
+    if !is_enabled() {
+        return Ok(data.to_vec());
+    }
+    let slot = key_slot().lock().map_err(|_| "Vault key unavailable")?;
+    let key = slot
+        .as_ref()
+        .ok_or_else(|| crate::constants::MSG_VAULT_LOCKED.to_string())?;
+    serde_json::to_vec(&seal_with(key, data)?).map_err(|e| format!("Failed to encode: {e}"))
+}
+
+/// Decrypt stored data if it is encrypted; plain data is returned as-is.
+pub fn open_bytes(data: Vec<u8>) -> Result<Vec<u8>, String> {
+    let Some(envelope) = parse_envelope(&data) else {
+        return Ok(data);
+    };
+    let slot = key_slot().lock().map_err(|_| "Vault key unavailable")?;
+    let key = slot
+        .as_ref()
+        .ok_or_else(|| crate::constants::MSG_VAULT_LOCKED.to_string())?;
+    open_with(key, &envelope)
+}
+
+/// Read a profile (or companion) file, decrypting it if needed.
+pub fn read_config(path: &Path) -> Result<String, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    String::from_utf8(open_bytes(data)?)
+        .map_err(|_| format!("{} is not valid UTF-8", path.display()))
+}
+
+/// Write `data` to a 0600 temporary file next to `path` and flush it to
+/// disk. Returns the temporary path, to be renamed over `path`.
+fn stage_private(path: &Path, data: &[u8]) -> std::io::Result<PathBuf> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = path.with_file_name(format!(".{name}.vortix-tmp"));
+    let _ = fs::remove_file(&tmp);
+    let written = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&tmp)
+        .and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        });
+    match written {
+        Ok(()) => Ok(tmp),
+        Err(e) => {
+            let _ = fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+/// Write a file only the owner can read.
+///
+/// The data goes to a temporary file first and replaces `path` by rename,
+/// so `path` always holds either the old or the new contents.
+pub fn write_private(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let tmp = stage_private(path, data)?;
+    let renamed = fs::rename(&tmp, path);
+    if renamed.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    renamed
+}
+
+/// Replace every `(path, data)` file, or none if any write fails.
+///
+/// All new versions are staged before the first rename, so an error while
+/// encrypting or writing leaves every file as it was.
+fn replace_all(files: Vec<(PathBuf, Vec<u8>)>) -> Result<(), String> {
+    let mut staged = Vec::new();
+    for (path, mut data) in files {
+        let tmp = stage_private(&path, &data);
+        data.zeroize();
+        match tmp {
+            Ok(tmp) => staged.push((tmp, path)),
+            Err(e) => {
+                for (tmp, _) in &staged {
+                    let _ = fs::remove_file(tmp);
+                }
+                return Err(format!("Failed to write {}: {e}", path.display()));
+            }
+        }
+    }
+    for (tmp, path) in &staged {
+        fs::rename(tmp, path).map_err(|e| format!("Failed to replace {}: {e}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Store a profile (or companion) file, encrypted when the vault is enabled.
+pub fn write_file(path: &Path, data: &[u8]) -> Result<(), String> {
+    let stored = seal_bytes(data)?;
+    write_private(path, &stored).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Every file under the profiles directory (configs and companion files).
+fn profile_files() -> Result<Vec<PathBuf>, String> {
+    let root =
+        crate::utils::get_profiles_dir().map_err(|e| format!("Failed to get profiles dir: {e}"))?;
+    let mut files = Vec::new();
+    let mut dirs = vec![root];
+    while let Some(dir) = dirs.pop() {
+        let entries =
+            fs::read_dir(&dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Turn vault mode on and encrypt every stored profile. Returns the number
+/// of files encrypted.
+///
+/// Every file is encrypted and staged before `vault.json` is written and
+/// the encrypted versions replace the plain ones, so a failure leaves the
+/// vault off and the profiles untouched. Running it again with the same
+/// passphrase encrypts any files that are still plain (e.g. after an
+/// interrupted run).
+pub fn enable(passphrase: &str) -> Result<usize, String> {
+    if passphrase.is_empty() {
+        return Err("Vault passphrase must not be empty".to_string());
+    }
+
+    // A new vault's key is only installed once its header is written
+    let new_vault = if is_enabled() {
+        unlock(passphrase)?;
+        None
+    } else {
+        let salt = crypto::random_salt()?;
+        let key = crypto::derive_key(passphrase, &salt)?;
+        let header = Header {
+            version: VAULT_VERSION,
+            salt: base64_encode(&salt),
+            check: seal_with(&key, CHECK_PLAINTEXT)?,
+        };
+        Some((key, header))
+    };
+
+    let mut sealed = Vec::new();
+    for path in profile_files()? {
+        let mut data =
+            fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        if parse_envelope(&data).is_none() {
+            let stored = match &new_vault {
+                Some((key, _)) => seal_with(key, &data).and_then(|envelope| {
+                    serde_json::to_vec(&envelope).map_err(|e| format!("Failed to encode: {e}"))
+                }),
+                None => seal_bytes(&data),
+            };
+            data.zeroize();
+            sealed.push((path, stored?));
+        } else {
+            data.zeroize();
+        }
+    }
+    let count = sealed.len();
+
+    // Flip the marker only once every file is ready to be swapped in
+    if let Some((key, header)) = new_vault {
+        let json =
+            serde_json::to_vec_pretty(&header).map_err(|e| format!("Failed to encode: {e}"))?;
+        write_private(&header_path()?, &json).map_err(|e| format!("Failed to write vault: {e}"))?;
+        if let Ok(mut slot) = key_slot().lock() {
+            *slot = Some(key);
+        }
+    }
+    replace_all(sealed)?;
+    Ok(count)
+}
+
+/// Decrypt every stored profile and turn vault mode off. Returns the number
+/// of files decrypted.
+///
+/// Nothing is replaced unless every file decrypts, and `vault.json` is only
+/// removed after all of them have been swapped in.
+pub fn disable(passphrase: &str) -> Result<usize, String> {
+    unlock(passphrase)?;
+
+    let mut opened = Vec::new();
+    for path in profile_files()? {
+        let data =
+            fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        if parse_envelope(&data).is_some() {
+            match open_bytes(data) {
+                Ok(plain) => opened.push((path, plain)),
+                Err(e) => {
+                    for (_, plain) in &mut opened {
+                        plain.zeroize();
+                    }
+                    return Err(e);
+                }
+            }
+        }
+    }
+    let count = opened.len();
+    replace_all(opened)?;
+
+    // Only remove the marker once every file is plain again
+    fs::remove_file(header_path()?).map_err(|e| format!("Failed to remove vault: {e}"))?;
+    if let Ok(mut slot) = key_slot().lock() {
+        *slot = None;
+    }
+    Ok(count)
+}
+
+// === Decrypted copies for wg-quick / openvpn ===
+
+/// Memory-backed directory for decrypted copies: `/dev/shm` or
+/// `$XDG_RUNTIME_DIR` when available. macOS has neither; there it is
+/// `$XDG_RUNTIME_DIR` pointed at a RAM disk, or nothing.
+fn memory_dir() -> Option<PathBuf> {
+    let shm = Path::new("/dev/shm");
+    if shm.is_dir() {
+        return Some(shm.to_path_buf());
+    }
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|p| p.is_dir())
+}
+
+/// A private (0700) directory, removed on drop.
+pub struct ScratchDir {
+    pub path: PathBuf,
+}
+
+impl ScratchDir {
+    /// Create a fresh scratch directory named after `label` for staging,
+    /// in memory-backed storage when there is any and the system temp dir
+    /// otherwise.
+    pub fn new(label: &str) -> Result<Self, String> {
+        Self::create_in(&memory_dir().unwrap_or_else(std::env::temp_dir), label)
+    }
+
+    /// Create a scratch directory for secrets (decrypted profiles,
+    /// credentials), refusing to fall back to persistent storage.
+    pub fn in_memory(label: &str) -> Result<Self, String> {
+        let dir = memory_dir().ok_or(
+            "No memory-backed directory for decrypted files: \
+             /dev/shm is missing and $XDG_RUNTIME_DIR is not set",
+        )?;
+        Self::create_in(&dir, label)
+    }
+
+    fn create_in(dir: &Path, label: &str) -> Result<Self, String> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        let path = dir.join(format!("vortix-{label}-{}-{nanos}", std::process::id()));
+        fs::DirBuilder::new()
+            .mode(0o700)
+            .create(&path)
+            .map_err(|e| format!("Failed to create private directory: {e}"))?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// A private copy of a profile (decrypted if it is sealed), removed on drop.
+pub struct Decrypted {
+    dir: ScratchDir,
+    /// Path of the copied config (same file name as the stored one, so
+    /// `wg-quick` derives the same interface name).
+    pub config: PathBuf,
+}
+
+/// A private copy of the plain profile at `config_path` holding `content`,
+/// for a daemon that must read a changed version of the config.
+pub fn copy_with(config_path: &Path, content: &str) -> Result<Decrypted, String> {
+    let dir = ScratchDir::new("copy")?;
+    let config = dir.path.join(config_path.file_name().unwrap_or_default());
+    write_private(&config, content.as_bytes())
+        .map_err(|e| format!("Failed to write profile copy: {e}"))?;
+    Ok(Decrypted { dir, config })
+}
+
+/// Decrypted copies kept for a running daemon, by stored config path.
+fn held_copies() -> &'static Mutex<HashMap<PathBuf, Decrypted>> {
+    static HELD: OnceLock<Mutex<HashMap<PathBuf, Decrypted>>> = OnceLock::new();
+    HELD.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Keep a decrypted copy alive for the connection of `config_path`, for a
+/// daemon such as `openvpn` that re-reads its files on restart signals.
+pub fn hold(config_path: &Path, copy: Decrypted) {
+    if let Ok(mut held) = held_copies().lock() {
+        held.insert(config_path.to_path_buf(), copy);
+    }
+}
+
+/// Remove the copy kept for `config_path` once its connection is down.
+pub fn release(config_path: &Path) {
+    if let Ok(mut held) = held_copies().lock() {
+        held.remove(config_path);
+    }
+}
+
+/// Remove every kept copy (no connection is up any more).
+pub fn release_all() {
+    if let Ok(mut held) = held_copies().lock() {
+        held.clear();
+    }
+}
+
+/// Decrypt an encrypted profile (and its companion files) into a private
+/// directory for the duration of a `wg-quick` call or an `openvpn` session.
+///
+/// Returns `None` for plain profiles, which can be used in place.
+pub fn materialize(config_path: &Path) -> Result<Option<Decrypted>, String> {
+    if !is_sealed_file(config_path) {
+        return Ok(None);
+    }
+    let mut content = read_config(config_path)?;
+
+    let dir = ScratchDir::in_memory("vault")?;
+    let file_name = config_path.file_name().unwrap_or_default();
+    let decrypted = Decrypted {
+        config: dir.path.join(file_name),
+        dir,
+    };
+
+    // Companion files are referenced by absolute path; point the copy at
+    // decrypted versions instead
+    let files_dir = crate::vpn::companion_dir(config_path);
+    if files_dir.is_dir() {
+        let copies_dir = decrypted.dir.path.join("files");
+        fs::DirBuilder::new()
+            .mode(0o700)
+            .create(&copies_dir)
+            .map_err(|e| format!("Failed to create decrypted profile dir: {e}"))?;
+
+        let (config, _) = crate::vpn::openvpn::parse(&content);
+        let mut replacements = Vec::new();
+        for file_ref in &config.file_refs {
+            let source = Path::new(&file_ref.path);
+            let Ok(relative) = source.strip_prefix(&files_dir) else {
+                continue;
+            };
+            let copy = copies_dir.join(relative);
+            let mut data = open_bytes(
+                fs::read(source)
+                    .map_err(|e| format!("Failed to read {}: {e}", source.display()))?,
+            )?;
+            let written = write_private(&copy, &data);
+            data.zeroize();
+            written.map_err(|e| format!("Failed to write decrypted file: {e}"))?;
+            replacements.push((
+                file_ref.line,
+                file_ref.path.clone(),
+                copy.to_string_lossy().into_owned(),
+            ));
+        }
+        let rewritten = crate::vpn::openvpn::rewrite_file_refs(&content, &replacements);
+        content.zeroize();
+        content = rewritten;
+    }
+
+    let written = write_private(&decrypted.config, content.as_bytes());
+    content.zeroize();
+    written.map_err(|e| format!("Failed to write decrypted profile: {e}"))?;
+    Ok(Some(decrypted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_roundtrip() {
+        let salt = crypto::random_salt().unwrap();
+        let key = crypto::derive_key("vault pass", &salt).unwrap();
+        let envelope = seal_with(&key, b"[Interface]\nPrivateKey = x\n").unwrap();
+        let stored = serde_json::to_vec(&envelope).unwrap();
+
+        let parsed = parse_envelope(&stored).expect("stored data is recognised as sealed");
+        assert_eq!(
+            open_with(&key, &parsed).unwrap(),
+            b"[Interface]\nPrivateKey = x\n"
+        );
+
+        let other = crypto::derive_key("other", &salt).unwrap();
+        assert!(open_with(&other, &parsed).is_err());
+    }
+
+    #[test]
+    fn test_plain_configs_are_not_sealed() {
+        assert!(parse_envelope(b"[Interface]\nPrivateKey = x\n").is_none());
+        assert!(parse_envelope(b"{\"other\": 1}").is_none());
+        // Plain data passes through without needing a key
+        assert_eq!(open_bytes(b"remote a".to_vec()).unwrap(), b"remote a");
+    }
+}
@@ -52,23 +52,16 @@ impl EventHandler {
 
                 if event::poll(timeout).unwrap_or(false) {
                     if let Ok(evt) = event::read() {
-                        match evt {
-                            CrosstermEvent::Key(key) => {
-                                if sender.send(Event::Key(key)).is_err() {
-                                    return;
-                                }
+                        let event = match evt {
+                            CrosstermEvent::Key(key) => Some(Event::Key(key)),
+                            CrosstermEvent::Resize(w, h) => Some(Event::Resize(w, h)),
+                            CrosstermEvent::Mouse(mouse) => Some(Event::Mouse(mouse)),
+                            _ => None,
+                        };
+                        if let Some(event) = event {
+                            if sender.send(event).is_err() {
+                                return;
                             }
-                            CrosstermEvent::Resize(w, h) => {
-                                if sender.send(Event::Resize(w, h)).is_err() {
-                                    return;
-                                }
-                            }
-                            CrosstermEvent::Mouse(mouse) => {
-                                if sender.send(Event::Mouse(mouse)).is_err() {
-                                    return;
-                                }
-                            }
-                            _ => {}
                         }
                     }
                 }
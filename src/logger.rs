@@ -60,7 +60,7 @@ pub struct LogEntry {
 impl LogEntry {
     /// Format the log entry for display
     pub fn format(&self) -> String {
-        let elapsed = self.timestamp.elapsed().map(|d| d.as_secs()).unwrap_or(0);
+        let elapsed = self.timestamp.elapsed().map_or(0, |d| d.as_secs());
 
         let time_str = if elapsed < 60 {
             format!("{elapsed}s")
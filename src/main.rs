@@ -46,6 +46,7 @@ fn main() -> Result<()> {
 
     // Run the TUI application
     let terminal = init_terminal()?;
+    core::guard::install(restore_terminal);
     let result = run_tui(terminal);
     restore_terminal();
 
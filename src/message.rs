@@ -52,6 +52,16 @@ pub enum Message {
     // === Profile Management ===
     /// Move selection in profile list
     ProfileMove(SelectionMove),
+    /// Open the sidebar search bar
+    OpenSearch,
+    /// Filter the sidebar by a fuzzy query (empty clears the filter)
+    FilterProfiles(String),
+    /// Cycle sidebar grouping (None → Country → Protocol → Tag)
+    CycleGrouping,
+    /// Cycle sidebar sort order (Name → Last Used → Latency → Usage)
+    CycleSort,
+    /// Collapse or expand a sidebar group
+    ToggleGroup(String),
 
     // === Connection ===
     /// Toggle connection for profile at index (None = selected)
@@ -62,6 +72,20 @@ pub enum Message {
     Reconnect,
     /// Connect to quick slot (0-8)
     QuickConnect(usize),
+    /// Submit the credential prompt and connect
+    SubmitCredentials,
+    /// Unlock the profile vault with a passphrase
+    UnlockVault(String),
+    /// Delete saved credentials for the selected profile
+    ForgetCredentials,
+    /// Split tunnel rules resolved for the connection being started
+    SplitTunnelApplied(crate::core::split_tunnel::Policy),
+    /// Server hostnames resolved for the kill switch rules, with a sender
+    /// to acknowledge once the rules allow them
+    EndpointsResolved(
+        Vec<crate::core::killswitch::ResolvedEndpoint>,
+        Option<std::sync::mpsc::Sender<()>>,
+    ),
 
     // === UI Overlays ===
     /// Close current overlay (Action menu, Help, Config, etc.)
@@ -86,8 +110,43 @@ pub enum Message {
     Scroll(ScrollMove),
 
     // === Import ===
+    /// Open the new `WireGuard` profile wizard
+    OpenNewProfile,
+    /// Generate keys and save the profile from the wizard
+    CreateProfile,
+    /// Open the QR warning for the selected profile
+    OpenQr,
+    /// Render the QR code once the warning is confirmed
+    ShowQr,
+
     /// Open import dialog
     OpenImport,
+    /// Refresh all subscriptions in the background
+    RefreshSubscriptions,
+    /// Background subscription refresh finished
+    SubscriptionsRefreshed {
+        manual: bool,
+        changed: bool,
+        failed: usize,
+    },
+    /// Open the export dialog for the filtered profiles
+    OpenExport,
+    /// Export the filtered profiles into a bundle, optionally encrypted
+    ExportProfiles {
+        path: String,
+        passphrase: Option<String>,
+    },
+    /// Save the profile reviewed in the import confirmation
+    SaveImport,
+    /// Import the files checked in the import preview
+    ConfirmImportPreview,
+    /// Decide a duplicate found while importing (`None` stops the import)
+    ResolveDuplicate(Option<crate::core::importer::Conflict>),
+    /// Import an exported bundle, with its passphrase if encrypted
+    ImportBundle {
+        path: std::path::PathBuf,
+        passphrase: Option<String>,
+    },
 
     // === System ===
     /// Log a message
@@ -114,6 +173,22 @@ pub enum Message {
     // === Kill Switch ===
     /// Toggle kill switch mode (Off → Auto → `AlwaysOn` → Off)
     ToggleKillSwitch,
+    /// Show the firewall rules the kill switch would install
+    OpenKillSwitchPreview,
+    /// Keep blocking with the rules a dead session left behind
+    RestoreStaleKillSwitch,
+    /// Release the rules a dead session left behind
+    ReleaseStaleKillSwitch,
+    /// Offer a captive portal window, or close the open one
+    TogglePortalWindow,
+    /// Confirmed: let the portal's login page through
+    OpenPortalWindow,
+    /// Addresses of the captive portal probe URL
+    PortalProbeResolved(Vec<crate::core::killswitch::ResolvedEndpoint>),
+    /// Portal window exception ready to load
+    PortalWindowReady(crate::core::killswitch::PortalWindow),
+    /// Result of a captive portal probe
+    PortalProbed(crate::core::portal::Probe),
 }
 
 /// An item in the action menu, mapping a key to a message.
@@ -139,6 +214,21 @@ pub fn get_single_actions(focused_panel: &FocusedPanel) -> Vec<ActionMenuItem> {
                 label: "Import Profiles",
                 message: Message::OpenImport,
             });
+            actions.push(ActionMenuItem {
+                key: "/",
+                label: "Search Profiles",
+                message: Message::OpenSearch,
+            });
+            actions.push(ActionMenuItem {
+                key: "t",
+                label: "Cycle Grouping",
+                message: Message::CycleGrouping,
+            });
+            actions.push(ActionMenuItem {
+                key: "s",
+                label: "Cycle Sort Order",
+                message: Message::CycleSort,
+            });
             actions.push(ActionMenuItem {
                 key: "c",
                 label: "Connect / Disconnect",
@@ -154,6 +244,11 @@ pub fn get_single_actions(focused_panel: &FocusedPanel) -> Vec<ActionMenuItem> {
                 label: "View Configuration",
                 message: Message::OpenConfig,
             });
+            actions.push(ActionMenuItem {
+                key: "f",
+                label: "Forget Saved Credentials",
+                message: Message::ForgetCredentials,
+            });
             actions.push(ActionMenuItem {
                 key: "DEL",
                 label: "Delete Profile",
@@ -174,8 +269,25 @@ pub fn get_single_actions(focused_panel: &FocusedPanel) -> Vec<ActionMenuItem> {
                 message: Message::CopyIp,
             });
         }
-        FocusedPanel::Security | FocusedPanel::Chart => {
-            // No specific panel actions yet for Security Guard or Chart
+        FocusedPanel::Security => {
+            actions.push(ActionMenuItem {
+                key: "p",
+                label: "Preview Kill Switch Rules",
+                message: Message::OpenKillSwitchPreview,
+            });
+            actions.push(ActionMenuItem {
+                key: "c",
+                label: "Captive Portal Window",
+                message: Message::TogglePortalWindow,
+            });
+            actions.push(ActionMenuItem {
+                key: "K",
+                label: "Cycle Kill Switch Mode",
+                message: Message::ToggleKillSwitch,
+            });
+        }
+        FocusedPanel::Chart | FocusedPanel::Routes => {
+            // No specific panel actions yet for Chart or Routes
         }
     }
 
@@ -197,6 +309,21 @@ pub fn get_bulk_actions() -> Vec<ActionMenuItem> {
             label: "Import Profiles",
             message: Message::OpenImport,
         },
+        ActionMenuItem {
+            key: "n",
+            label: "New WireGuard Profile",
+            message: Message::OpenNewProfile,
+        },
+        ActionMenuItem {
+            key: "u",
+            label: "Refresh Subscriptions",
+            message: Message::RefreshSubscriptions,
+        },
+        ActionMenuItem {
+            key: "e",
+            label: "Export Profiles",
+            message: Message::OpenExport,
+        },
         ActionMenuItem {
             key: "r",
             label: "Reconnect All",
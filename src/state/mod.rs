@@ -15,4 +15,8 @@ mod ui;
 pub use connection::{ConnectionState, DetailedConnectionInfo};
 pub use killswitch::{KillSwitchMode, KillSwitchState};
 pub use profile::{Protocol, VpnProfile};
-pub use ui::{FocusedPanel, InputMode, Toast, ToastType, DISMISS_DURATION};
+pub use ui::{
+    CredentialField, CredentialPrompt, CredentialStore, FocusedPanel, GroupBy, ImportConfirm,
+    InputMode, NewProfileWizard, PreviewItem, PromptField, SortBy, Toast, ToastType, WizardField,
+    DISMISS_DURATION,
+};
@@ -3,6 +3,8 @@
 use std::path::PathBuf;
 use std::time::SystemTime;
 
+use crate::core::split_tunnel::SplitTunnel;
+
 /// Supported VPN protocol types.
 #[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
 pub enum Protocol {
@@ -33,8 +35,19 @@ pub struct VpnProfile {
     pub protocol: Protocol,
     /// Geographic location or server identifier.
     pub location: String,
+    /// Location set by the user (stored in `metadata.json`), shown instead
+    /// of the one derived from the name.
+    pub custom_location: Option<String>,
     /// Path to the configuration file on disk.
     pub config_path: PathBuf,
     /// Last time this profile was used.
     pub last_used: Option<SystemTime>,
+    /// User-defined labels (stored in `metadata.json`), used for filtering.
+    pub tags: Vec<String>,
+    /// Most recent latency measured while connected to this profile.
+    pub last_latency_ms: Option<u64>,
+    /// Number of successful connections made with this profile.
+    pub connect_count: u32,
+    /// Split tunnel rules (stored in `metadata.json`).
+    pub split_tunnel: SplitTunnel,
 }
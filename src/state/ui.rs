@@ -1,6 +1,7 @@
 //! UI state types.
 
 use super::Protocol;
+use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 
 /// Duration for toast notifications to remain visible.
@@ -14,14 +15,443 @@ pub enum FocusedPanel {
     Sidebar,
     /// Connection details panel (bottom left).
     ConnectionDetails,
-    /// Throughput chart (top right).
+    /// Throughput chart (top right -> left).
     Chart,
+    /// Routing table inspector (top right -> right).
+    Routes,
     /// Security guard panel (bottom right -> left).
     Security,
     /// Activity log panel (bottom right -> right).
     Logs,
 }
 
+/// How the profile sidebar groups its entries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GroupBy {
+    /// Flat list, no group headers.
+    #[default]
+    None,
+    /// Group by the country derived from the profile location.
+    Country,
+    /// Group by VPN protocol.
+    Protocol,
+    /// Group by user tag (a profile with several tags appears in each group).
+    Tag,
+}
+
+impl GroupBy {
+    /// Cycle to next grouping: None → Country → Protocol → Tag → None
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::Country,
+            Self::Country => Self::Protocol,
+            Self::Protocol => Self::Tag,
+            Self::Tag => Self::None,
+        }
+    }
+
+    /// Short label for titles and toasts.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Country => "Country",
+            Self::Protocol => "Protocol",
+            Self::Tag => "Tag",
+        }
+    }
+}
+
+/// Sort order for profiles in the sidebar (applied within each group).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SortBy {
+    /// Alphabetical by profile name.
+    #[default]
+    Name,
+    /// Most recently connected first.
+    LastUsed,
+    /// Lowest latency first: the last latency seen while connected to each
+    /// profile.
+    Latency,
+    /// Most frequently connected first.
+    Usage,
+}
+
+impl SortBy {
+    /// Cycle to next sort order: Name → `LastUsed` → Latency → Usage → Name
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Name => Self::LastUsed,
+            Self::LastUsed => Self::Latency,
+            Self::Latency => Self::Usage,
+            Self::Usage => Self::Name,
+        }
+    }
+
+    /// Short label for titles and toasts.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::LastUsed => "Last Used",
+            Self::Latency => "Latency",
+            Self::Usage => "Usage",
+        }
+    }
+}
+
+/// What a field in the credential prompt asks for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CredentialField {
+    Username,
+    Password,
+    /// Passphrase of an encrypted private key.
+    KeyPassphrase,
+    /// Response to the config's `static-challenge` (e.g. a TOTP code).
+    Challenge,
+    /// Passphrase for credentials that were saved encrypted.
+    Unlock,
+    /// Passphrase used to encrypt credentials being saved.
+    StorePassphrase,
+}
+
+impl CredentialField {
+    /// Label shown in front of the input.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Username => "Username",
+            Self::Password => "Password",
+            Self::KeyPassphrase => "Key passphrase",
+            Self::Challenge => "Challenge",
+            Self::Unlock => "Unlock passphrase",
+            Self::StorePassphrase => "Save passphrase",
+        }
+    }
+}
+
+/// A single input in the credential prompt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PromptField {
+    pub kind: CredentialField,
+    pub value: String,
+    /// Render the value as bullets.
+    pub masked: bool,
+}
+
+impl PromptField {
+    /// An empty field, masked unless it is the username.
+    #[must_use]
+    pub fn new(kind: CredentialField) -> Self {
+        Self {
+            kind,
+            value: String::new(),
+            masked: kind != CredentialField::Username,
+        }
+    }
+}
+
+/// Whether (and how) credentials entered in the prompt are remembered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CredentialStore {
+    /// Use them for this connection only.
+    #[default]
+    None,
+    /// Save to a `chmod 600` file.
+    File,
+    /// Save encrypted with a passphrase.
+    Encrypted,
+}
+
+impl CredentialStore {
+    /// Cycle: None → File → Encrypted → None
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::None => Self::File,
+            Self::File => Self::Encrypted,
+            Self::Encrypted => Self::None,
+        }
+    }
+
+    /// Short label for the prompt.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::None => "Don't save",
+            Self::File => "Save (0600 file)",
+            Self::Encrypted => "Save (encrypted)",
+        }
+    }
+}
+
+/// State of the masked credential prompt shown before an `OpenVPN` connect.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CredentialPrompt {
+    /// Index of the profile being connected.
+    pub profile_index: usize,
+    /// Name of the profile being connected.
+    pub profile: String,
+    /// `static-challenge` text, shown above the inputs.
+    pub challenge: Option<String>,
+    pub fields: Vec<PromptField>,
+    /// Focused row: a field index, or `fields.len()` for the save selector.
+    pub focused: usize,
+    /// Save selector; `None` when saving is not offered (unlocking saved ones).
+    pub store: Option<CredentialStore>,
+    /// Validation or unlock error shown at the bottom.
+    pub error: Option<String>,
+}
+
+impl CredentialPrompt {
+    /// Value of the given field, if the prompt asks for it.
+    #[must_use]
+    pub fn value(&self, kind: CredentialField) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|f| f.kind == kind)
+            .map(|f| f.value.as_str())
+    }
+
+    fn rows(&self) -> usize {
+        self.fields.len() + usize::from(self.store.is_some())
+    }
+
+    /// Whether the save selector is focused.
+    #[must_use]
+    pub fn on_store_row(&self) -> bool {
+        self.store.is_some() && self.focused == self.fields.len()
+    }
+
+    /// Move focus down, wrapping around.
+    pub fn focus_next(&mut self) {
+        self.focused = (self.focused + 1) % self.rows();
+    }
+
+    /// Move focus up, wrapping around.
+    pub fn focus_prev(&mut self) {
+        self.focused = (self.focused + self.rows() - 1) % self.rows();
+    }
+
+    /// The focused text field, if focus is not on the save selector.
+    pub fn focused_field(&mut self) -> Option<&mut PromptField> {
+        self.fields.get_mut(self.focused)
+    }
+
+    /// Cycle the save selector, adding the save passphrase field only while
+    /// encrypted saving is selected.
+    pub fn cycle_store(&mut self) {
+        let Some(store) = self.store else {
+            return;
+        };
+        let next = store.next();
+        self.store = Some(next);
+        if next == CredentialStore::Encrypted {
+            self.fields
+                .push(PromptField::new(CredentialField::StorePassphrase));
+        } else {
+            self.fields
+                .retain(|f| f.kind != CredentialField::StorePassphrase);
+        }
+        // Keep focus on the selector, which sits after the fields
+        self.focused = self.fields.len();
+    }
+}
+
+/// What a field in the new-profile wizard asks for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WizardField {
+    Name,
+    Address,
+    Dns,
+    PeerPublicKey,
+    Endpoint,
+    AllowedIps,
+    Keepalive,
+}
+
+impl WizardField {
+    /// Fields in display order.
+    pub const ALL: [Self; 7] = [
+        Self::Name,
+        Self::Address,
+        Self::Dns,
+        Self::PeerPublicKey,
+        Self::Endpoint,
+        Self::AllowedIps,
+        Self::Keepalive,
+    ];
+
+    /// Label shown in front of the input.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Name => "Profile name",
+            Self::Address => "Address",
+            Self::Dns => "DNS",
+            Self::PeerPublicKey => "Server key",
+            Self::Endpoint => "Endpoint",
+            Self::AllowedIps => "Allowed IPs",
+            Self::Keepalive => "Keepalive (s)",
+        }
+    }
+
+    /// Example shown while the field is empty.
+    #[must_use]
+    pub const fn placeholder(self) -> &'static str {
+        match self {
+            Self::Name => "home",
+            Self::Address => "10.0.0.2/32",
+            Self::Dns => "optional, e.g. 10.0.0.1",
+            Self::PeerPublicKey => "server's base64 public key",
+            Self::Endpoint => "vpn.example.com:51820",
+            Self::AllowedIps => "0.0.0.0/0, ::/0",
+            Self::Keepalive => "optional, 0 = off",
+        }
+    }
+}
+
+/// State of the new `WireGuard` profile wizard.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewProfileWizard {
+    /// One value per [`WizardField::ALL`] entry.
+    pub values: [String; WizardField::ALL.len()],
+    /// Index of the focused field.
+    pub focused: usize,
+    /// Validation or write error shown at the bottom.
+    pub error: Option<String>,
+}
+
+impl Default for NewProfileWizard {
+    fn default() -> Self {
+        let mut wizard = Self {
+            values: Default::default(),
+            focused: 0,
+            error: None,
+        };
+        *wizard.value_mut(WizardField::AllowedIps) = "0.0.0.0/0, ::/0".to_string();
+        *wizard.value_mut(WizardField::Keepalive) = "25".to_string();
+        wizard
+    }
+}
+
+impl NewProfileWizard {
+    fn index(field: WizardField) -> usize {
+        WizardField::ALL
+            .iter()
+            .position(|f| *f == field)
+            .unwrap_or_default()
+    }
+
+    /// Value of the given field.
+    #[must_use]
+    pub fn value(&self, field: WizardField) -> &str {
+        &self.values[Self::index(field)]
+    }
+
+    fn value_mut(&mut self, field: WizardField) -> &mut String {
+        &mut self.values[Self::index(field)]
+    }
+
+    /// The focused field's value.
+    pub fn focused_value(&mut self) -> &mut String {
+        &mut self.values[self.focused]
+    }
+
+    /// Move focus down, wrapping around.
+    pub fn focus_next(&mut self) {
+        self.focused = (self.focused + 1) % WizardField::ALL.len();
+    }
+
+    /// Move focus up, wrapping around.
+    pub fn focus_prev(&mut self) {
+        self.focused = (self.focused + WizardField::ALL.len() - 1) % WizardField::ALL.len();
+    }
+}
+
+/// State of the confirmation shown before a single file is imported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportConfirm {
+    pub path: std::path::PathBuf,
+    /// What the file parsed to.
+    pub preview: crate::vpn::Preview,
+    /// Profile name, editable.
+    pub name: String,
+    /// Location, editable; kept as an override when changed.
+    pub location: String,
+    /// Whether the location field (rather than the name) has focus.
+    pub location_focused: bool,
+    /// Stored profile this file duplicates, with the reason.
+    pub duplicate_of: Option<String>,
+    /// Why the last save failed.
+    pub error: Option<String>,
+}
+
+impl ImportConfirm {
+    /// Start reviewing `path`, with the parsed name and location as defaults.
+    pub fn new(
+        path: &std::path::Path,
+        preview: crate::vpn::Preview,
+        duplicate_of: Option<String>,
+    ) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            name: preview.name.clone(),
+            location: preview.location.clone(),
+            preview,
+            location_focused: false,
+            duplicate_of,
+            error: None,
+        }
+    }
+
+    /// Switch focus between the name and the location field.
+    pub fn toggle_focus(&mut self) {
+        self.location_focused = !self.location_focused;
+    }
+
+    /// Store the file in `profiles_dir` under the edited name. A location
+    /// that differs from the derived one is set as the profile's override.
+    pub fn save(
+        &self,
+        profiles_dir: &std::path::Path,
+    ) -> Result<(super::VpnProfile, Vec<String>), String> {
+        let (mut profile, warnings) =
+            crate::vpn::import_profile_named(&self.path, &self.name, profiles_dir)?;
+        let location = self.location.trim();
+        if !location.is_empty() && location != self.preview.location {
+            profile.location = location.to_string();
+            profile.custom_location = Some(location.to_string());
+        }
+        Ok((profile, warnings))
+    }
+
+    /// The focused field's value.
+    pub fn focused_value(&mut self) -> &mut String {
+        if self.location_focused {
+            &mut self.location
+        } else {
+            &mut self.name
+        }
+    }
+}
+
+/// A file in the import preview list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreviewItem {
+    pub path: std::path::PathBuf,
+    /// Path relative to the imported folder or archive.
+    pub label: String,
+    /// What importing the file would produce, or why it cannot be imported.
+    pub preview: Result<crate::vpn::Preview, String>,
+    /// Name of the stored profile this file duplicates.
+    pub duplicate_of: Option<String>,
+    /// Whether the file is imported on confirm.
+    pub checked: bool,
+}
+
 /// Current input mode determining keyboard behavior.
 #[derive(Clone, PartialEq, Default)]
 pub enum InputMode {
@@ -35,6 +465,11 @@ pub enum InputMode {
         /// Current cursor position in the path string.
         cursor: usize,
     },
+    /// Sidebar search bar is active (query is `App::profile_filter`).
+    Search {
+        /// Current cursor position in the query, in chars.
+        cursor: usize,
+    },
     /// Dependency error dialog showing missing tools.
     DependencyError {
         /// Protocol that requires the missing dependencies.
@@ -47,6 +482,111 @@ pub enum InputMode {
         /// Description of the action that was denied.
         action: String,
     },
+    /// Export dialog for the profiles matching the sidebar filter.
+    Export {
+        /// Destination bundle path.
+        path: String,
+        /// Optional passphrase (rendered masked); empty means unencrypted.
+        passphrase: String,
+        /// Whether the passphrase field has focus.
+        on_passphrase: bool,
+    },
+    /// Passphrase prompt for importing an encrypted bundle.
+    BundlePassphrase {
+        /// Bundle being imported.
+        path: std::path::PathBuf,
+        /// Passphrase typed so far (rendered masked).
+        passphrase: String,
+        /// Error from the last attempt.
+        error: Option<String>,
+    },
+    /// Warning shown before a profile's QR code (it exposes the private key).
+    QrWarning {
+        /// Index of the profile to encode.
+        profile_index: usize,
+        /// Whether `PostUp`/`PostDown` lines are left out.
+        exclude_hooks: bool,
+    },
+    /// A profile rendered as a QR code.
+    QrCode {
+        /// Profile name for the title.
+        profile: String,
+        /// Half-block rows from `core::qr::render`.
+        rows: Vec<String>,
+    },
+    /// New `WireGuard` profile wizard.
+    NewProfile {
+        /// Fields and focus.
+        wizard: NewProfileWizard,
+    },
+    /// Result of the wizard: the public key to register with the server.
+    ProfileCreated {
+        /// Name of the new profile.
+        name: String,
+        /// Base64 public key of the generated key pair.
+        public_key: String,
+    },
+    /// Parsed profile shown for review before it is saved.
+    ConfirmImport { confirm: ImportConfirm },
+    /// Files found in a folder or archive, picked before importing.
+    ImportPreview {
+        items: Vec<PreviewItem>,
+        /// Highlighted row.
+        cursor: usize,
+    },
+    /// An import is paused on a file that duplicates a stored profile.
+    Duplicate {
+        /// File being imported.
+        source: String,
+        /// Name of the stored profile it duplicates.
+        existing: String,
+        /// How the two match, e.g. "identical content".
+        reason: String,
+        /// Whether the choice also applies to the rest of the batch.
+        apply_to_all: bool,
+        /// Files left in the batch after this one.
+        remaining: usize,
+    },
+    /// Vault passphrase prompt (shown at startup when profiles are encrypted).
+    Unlock {
+        /// Passphrase typed so far (rendered masked).
+        passphrase: String,
+        /// Error from the last attempt.
+        error: Option<String>,
+    },
+    /// Masked credential prompt for an `OpenVPN` connect.
+    Credentials {
+        /// Fields, focus and save choice.
+        prompt: CredentialPrompt,
+    },
+    /// Rules the kill switch would install, with an optional `pfctl -n`
+    /// check.
+    KillSwitchPreview {
+        /// VPN interface the rules are written for.
+        interface: String,
+        /// Generated rules and the last dry run.
+        preview: crate::core::killswitch::RulePreview,
+        /// First visible line.
+        scroll: u16,
+    },
+    /// Found at startup: a kill switch left blocking by a vortix session
+    /// that is no longer running.
+    StaleKillSwitch {
+        /// PID of the session that wrote the state, if recorded.
+        pid: Option<u32>,
+        /// Kill switch mode of that session.
+        mode: crate::state::KillSwitchMode,
+    },
+    /// Confirmation for a time-limited captive portal exception in the
+    /// kill switch.
+    PortalWindow {
+        /// Gateway whose network the login page is expected on.
+        gateway: crate::core::portal::Gateway,
+        /// Length of the window.
+        secs: u64,
+        /// Why the window is offered.
+        reason: &'static str,
+    },
     /// Delete confirmation dialog.
     ConfirmDelete {
         /// Index of the profile to delete.
@@ -58,6 +598,29 @@ pub enum InputMode {
     },
 }
 
+impl InputMode {
+    /// First overlay at startup: the stale kill switch prompt when
+    /// `persisted` was left blocking by a dead session, else the unlock
+    /// prompt when profiles are locked. Answering the stale prompt comes
+    /// back here without the state.
+    pub fn at_startup(
+        persisted: Option<&crate::core::killswitch::PersistedState>,
+        vault_locked: bool,
+    ) -> Self {
+        match persisted.filter(|p| p.is_stale()) {
+            Some(stale) => Self::StaleKillSwitch {
+                pid: stale.pid,
+                mode: stale.mode,
+            },
+            None if vault_locked => Self::Unlock {
+                passphrase: String::new(),
+                error: None,
+            },
+            None => Self::Normal,
+        }
+    }
+}
+
 /// Types of toast notifications for color coding.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum ToastType {
@@ -90,3 +653,109 @@ impl Toast {
         Instant::now() > self.expires
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::vault::ScratchDir;
+
+    const KEY: &str = "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=";
+
+    /// A reviewed config in `source` and an empty profiles directory.
+    fn review(source: &ScratchDir) -> ImportConfirm {
+        let path = source.path.join("us-nyc.conf");
+        std::fs::write(
+            &path,
+            format!(
+                "[Interface]\nPrivateKey = {KEY}\nAddress = 10.0.0.2/32\n\n[Peer]\n\
+                 PublicKey = {KEY}\nEndpoint = vpn.example.com:51820\nAllowedIPs = 10.0.0.0/8\n"
+            ),
+        )
+        .unwrap();
+        let preview = crate::vpn::preview_profile(&path).unwrap();
+        ImportConfirm::new(&path, preview, None)
+    }
+
+    #[test]
+    fn test_startup_prompts_for_stale_killswitch_before_unlock() {
+        use crate::core::killswitch::{takeover_mode, PersistedState};
+        use crate::state::{KillSwitchMode, KillSwitchState};
+
+        let persisted = |mode, pid| PersistedState {
+            mode,
+            state: KillSwitchState::Blocking,
+            vpn_interface: None,
+            endpoints: Vec::new(),
+            split: None,
+            pid,
+            pid_started: None,
+        };
+
+        // Left by a session that is gone: ask first, even with a locked vault
+        let stale = persisted(KillSwitchMode::AlwaysOn, Some(u32::MAX));
+        let InputMode::StaleKillSwitch { pid, mode } = InputMode::at_startup(Some(&stale), true)
+        else {
+            panic!("expected the stale kill switch prompt");
+        };
+        assert_eq!(pid, Some(u32::MAX));
+        assert_eq!(mode, KillSwitchMode::AlwaysOn);
+
+        // Restore keeps the mode, release turns it off
+        assert_eq!(takeover_mode(mode, true), KillSwitchMode::AlwaysOn);
+        assert_eq!(
+            takeover_mode(KillSwitchMode::Off, true),
+            KillSwitchMode::Auto
+        );
+        assert_eq!(takeover_mode(mode, false), KillSwitchMode::Off);
+
+        // Then on to the unlock prompt, or straight to the dashboard
+        assert!(matches!(
+            InputMode::at_startup(None, true),
+            InputMode::Unlock { .. }
+        ));
+        assert!(InputMode::at_startup(None, false) == InputMode::Normal);
+
+        // State still owned by this process is not stale
+        let ours = persisted(KillSwitchMode::Auto, Some(std::process::id()));
+        assert!(InputMode::at_startup(Some(&ours), false) == InputMode::Normal);
+    }
+
+    #[test]
+    fn test_import_confirm_saves_edited_name_and_location() {
+        let source = ScratchDir::new("test-confirm-src").unwrap();
+        let profiles = ScratchDir::new("test-confirm-dst").unwrap();
+        let mut confirm = review(&source);
+        assert_eq!(confirm.name, "us-nyc");
+
+        confirm.focused_value().clear();
+        confirm.focused_value().push_str("office");
+        confirm.toggle_focus();
+        confirm.focused_value().clear();
+        confirm.focused_value().push_str("Berlin");
+
+        let (profile, _) = confirm.save(&profiles.path).unwrap();
+        assert_eq!(profile.name, "office");
+        assert_eq!(profile.location, "Berlin");
+        assert_eq!(profile.custom_location.as_deref(), Some("Berlin"));
+        assert_eq!(profile.config_path, profiles.path.join("office.conf"));
+        assert!(profile.config_path.is_file());
+
+        // The same name again is refused instead of numbered
+        assert!(confirm.save(&profiles.path).is_err());
+    }
+
+    #[test]
+    fn test_import_confirm_keeps_derived_location_and_cancel_writes_nothing() {
+        let source = ScratchDir::new("test-confirm-keep-src").unwrap();
+        let profiles = ScratchDir::new("test-confirm-keep-dst").unwrap();
+
+        // Reviewing and then cancelling (dropping the dialog) stores nothing
+        drop(review(&source));
+        assert_eq!(std::fs::read_dir(&profiles.path).unwrap().count(), 0);
+
+        let confirm = review(&source);
+        let (profile, _) = confirm.save(&profiles.path).unwrap();
+        assert_eq!(profile.location, confirm.preview.location);
+        assert_eq!(profile.custom_location, None);
+    }
+}
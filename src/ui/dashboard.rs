@@ -1,4 +1,8 @@
-use crate::app::{App, ConnectionState, InputMode, Protocol};
+use crate::app::{App, ConnectionState, GroupBy, InputMode, Protocol, SortBy};
+use crate::core::audit::Status;
+use crate::core::grouping::{self, SidebarRow};
+use crate::core::leaks::{DnsReport, Ipv6Report, Verdict};
+use crate::core::routes::RouteKind;
 use ratatui::{
     layout::{Alignment, Constraint, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -6,7 +10,7 @@ use ratatui::{
     widgets::{
         canvas::{Canvas, Line as CanvasLine},
         Block, Borders, Cell, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, Table,
+        ScrollbarState, Table, Wrap,
     },
     Frame,
 };
@@ -52,7 +56,12 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         Layout::vertical([Constraint::Percentage(55), Constraint::Percentage(45)])
             .split(main_layout[1]);
 
-    render_throughput_chart(frame, app, workspace_chunks[0]);
+    // Top Row: Left (Chart) | Right (Routing Table)
+    let top_chunks = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(workspace_chunks[0]);
+
+    render_throughput_chart(frame, app, top_chunks[0]);
+    render_routes(frame, app, top_chunks[1]);
 
     // Bottom Dash: Left (Security Guard) | Right (Event Log)
     let dash_chunks = Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
@@ -69,7 +78,9 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         sidebar_layout[1],
     );
     app.panel_areas
-        .insert(crate::app::FocusedPanel::Chart, workspace_chunks[0]);
+        .insert(crate::app::FocusedPanel::Chart, top_chunks[0]);
+    app.panel_areas
+        .insert(crate::app::FocusedPanel::Routes, top_chunks[1]);
     app.panel_areas
         .insert(crate::app::FocusedPanel::Security, dash_chunks[0]);
     app.panel_areas
@@ -89,12 +100,14 @@ pub fn render(frame: &mut Frame, app: &mut App) {
                 render_connection_details(frame, app, area);
             }
             crate::app::FocusedPanel::Chart => render_throughput_chart(frame, app, area),
+            crate::app::FocusedPanel::Routes => render_routes(frame, app, area),
             crate::app::FocusedPanel::Security => render_security_guard(frame, app, area),
             crate::app::FocusedPanel::Logs => render_activity_log(frame, app, area),
         }
     }
 }
 
+#[allow(clippy::too_many_lines)]
 fn render_overlays(frame: &mut Frame, app: &mut App) {
     // Overlays still take priority
     match &app.input_mode {
@@ -108,7 +121,90 @@ fn render_overlays(frame: &mut Frame, app: &mut App) {
             confirm_selected,
             ..
         } => render_delete_confirm(frame, name, *confirm_selected),
-        InputMode::Normal => {}
+        InputMode::Credentials { prompt } => super::overlays::credentials::render(frame, prompt),
+        InputMode::Unlock { passphrase, error } => render_passphrase_overlay(
+            frame,
+            [
+                constants::TITLE_VAULT_UNLOCK,
+                constants::TITLE_VAULT_UNLOCK_FOOTER,
+                constants::PROMPT_VAULT_PASSPHRASE,
+            ],
+            passphrase,
+            error.as_deref(),
+        ),
+        InputMode::BundlePassphrase {
+            passphrase, error, ..
+        } => render_passphrase_overlay(
+            frame,
+            [
+                constants::TITLE_BUNDLE_UNLOCK,
+                constants::TITLE_BUNDLE_UNLOCK_FOOTER,
+                constants::PROMPT_BUNDLE_PASSPHRASE,
+            ],
+            passphrase,
+            error.as_deref(),
+        ),
+        InputMode::QrWarning {
+            profile_index,
+            exclude_hooks,
+        } => super::overlays::qr::render_warning(
+            frame,
+            app.profiles
+                .get(*profile_index)
+                .map_or("", |p| p.name.as_str()),
+            *exclude_hooks,
+        ),
+        InputMode::QrCode { profile, rows } => {
+            super::overlays::qr::render_code(frame, profile, rows);
+        }
+        InputMode::Export {
+            path,
+            passphrase,
+            on_passphrase,
+        } => super::overlays::export::render(
+            frame,
+            path,
+            passphrase,
+            *on_passphrase,
+            crate::core::search::filter_profiles(&app.profiles, &app.profile_filter).len(),
+        ),
+        InputMode::NewProfile { wizard } => super::overlays::new_profile::render(frame, wizard),
+        InputMode::ProfileCreated { name, public_key } => {
+            super::overlays::new_profile::render_created(frame, name, public_key);
+        }
+        InputMode::ConfirmImport { confirm } => {
+            super::overlays::import_confirm::render(frame, confirm);
+        }
+        InputMode::KillSwitchPreview { .. } => {
+            super::overlays::killswitch_preview::render(frame, app);
+        }
+        InputMode::ImportPreview { items, cursor } => {
+            super::overlays::import_preview::render(frame, items, *cursor);
+        }
+        InputMode::Duplicate {
+            source,
+            existing,
+            reason,
+            apply_to_all,
+            remaining,
+        } => super::overlays::duplicate::render(
+            frame,
+            source,
+            existing,
+            reason,
+            *apply_to_all,
+            *remaining,
+        ),
+        InputMode::StaleKillSwitch { pid, mode } => {
+            super::overlays::stale_killswitch::render(frame, *pid, *mode);
+        }
+        InputMode::PortalWindow {
+            gateway,
+            secs,
+            reason,
+        } => super::overlays::portal_window::render(frame, gateway, *secs, reason),
+        // Search bar is drawn inside the sidebar
+        InputMode::Search { .. } | InputMode::Normal => {}
     }
 
     if app.show_config {
@@ -406,6 +502,116 @@ fn get_killswitch_indicator(app: &App) -> Span<'static> {
     }
 }
 
+/// Sidebar header row for a profile group.
+fn render_group_row(
+    key: &str,
+    members: usize,
+    connected: usize,
+    collapsed: bool,
+    is_selected: bool,
+) -> Row<'static> {
+    let arrow = if collapsed { "▸" } else { "▾" };
+    let label_style = if is_selected {
+        Style::default()
+            .fg(theme::ROW_SELECTED_FG)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+            .fg(theme::ACCENT_PRIMARY)
+            .add_modifier(Modifier::BOLD)
+    };
+    let row_style = if is_selected {
+        Style::default().bg(theme::ROW_SELECTED_BG)
+    } else {
+        Style::default()
+    };
+
+    let connected_cell = if connected > 0 {
+        Cell::from(Span::styled(
+            format!("● {connected}"),
+            Style::default().fg(theme::SUCCESS),
+        ))
+    } else {
+        Cell::from("")
+    };
+
+    Row::new(vec![
+        Cell::from(Span::styled(
+            arrow,
+            Style::default().fg(theme::TEXT_SECONDARY),
+        )),
+        Cell::from(Span::styled(format!("{key} ({members})"), label_style)),
+        Cell::from(""),
+        connected_cell,
+    ])
+    .style(row_style)
+}
+
+/// Split `name` into spans, emphasising the chars matched by the search filter.
+fn highlight_matches(name: &str, positions: &[usize], base: Style) -> Line<'static> {
+    if positions.is_empty() {
+        return Line::from(Span::styled(name.to_string(), base));
+    }
+
+    let match_style = base
+        .fg(theme::WARNING)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_is_match = false;
+    for (i, c) in name.chars().enumerate() {
+        let is_match = positions.binary_search(&i).is_ok();
+        if is_match != run_is_match && !run.is_empty() {
+            let style = if run_is_match { match_style } else { base };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_is_match = is_match;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        let style = if run_is_match { match_style } else { base };
+        spans.push(Span::styled(run, style));
+    }
+    Line::from(spans)
+}
+
+/// One-line search bar at the bottom of the sidebar. The cursor is only
+/// drawn while the query is being edited.
+fn render_search_bar(frame: &mut Frame, query: &str, cursor: Option<usize>, area: Rect) {
+    let prompt = Span::styled(
+        "/",
+        Style::default()
+            .fg(theme::ACCENT_PRIMARY)
+            .add_modifier(Modifier::BOLD),
+    );
+    let text_style = Style::default().fg(theme::TEXT_PRIMARY);
+
+    let line = if let Some(cursor) = cursor {
+        let before: String = query.chars().take(cursor).collect();
+        let at: String = query.chars().skip(cursor).take(1).collect();
+        let after: String = query.chars().skip(cursor + 1).collect();
+        let at = if at.is_empty() { " ".to_string() } else { at };
+        Line::from(vec![
+            prompt,
+            Span::styled(before, text_style),
+            Span::styled(at, text_style.add_modifier(Modifier::REVERSED)),
+            Span::styled(after, text_style),
+        ])
+    } else {
+        Line::from(vec![
+            prompt,
+            Span::styled(query.to_string(), text_style),
+            Span::styled(
+                constants::HINT_SEARCH_CLEAR,
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])
+    };
+
+    frame.render_widget(Paragraph::new(line), area);
+}
+
 #[allow(clippy::too_many_lines)]
 fn render_profiles_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
     let is_focused = app.should_draw_focus(&crate::app::FocusedPanel::Sidebar);
@@ -415,17 +621,66 @@ fn render_profiles_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
         Style::default().fg(theme::BORDER_DEFAULT)
     };
 
-    let block = Block::default()
+    let search_cursor = match app.input_mode {
+        InputMode::Search { cursor } => Some(cursor),
+        _ => None,
+    };
+    let show_search = search_cursor.is_some() || !app.profile_filter.is_empty();
+
+    let title = if app.profile_filter.is_empty() {
+        constants::TITLE_PROFILES.to_string()
+    } else {
+        format!(
+            " Profiles ({}/{}) ",
+            app.visible_profiles.len(),
+            app.profiles.len()
+        )
+    };
+
+    // Current grouping / sort, shown only when they differ from the defaults
+    let mut view_parts = Vec::new();
+    if app.group_by != GroupBy::None {
+        view_parts.push(format!("by {}", app.group_by.label()));
+    }
+    if app.sort_by != SortBy::Name {
+        view_parts.push(format!("↕ {}", app.sort_by.label()));
+    }
+
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .border_style(border_style)
-        .title(" Profiles ");
+        .title(title);
+    if !view_parts.is_empty() {
+        block = block.title_bottom(
+            Line::from(Span::styled(
+                format!(" {} ", view_parts.join(" · ")),
+                Style::default().fg(theme::TEXT_SECONDARY),
+            ))
+            .right_aligned(),
+        );
+    }
 
-    let inner = block.inner(area);
+    let mut inner = block.inner(area);
     frame.render_widget(block, area);
 
-    if app.profiles.is_empty() {
+    if show_search && inner.height > 1 {
+        let search_area = Rect {
+            y: inner.y + inner.height - 1,
+            height: 1,
+            ..inner
+        };
+        inner.height -= 1;
+        render_search_bar(frame, &app.profile_filter, search_cursor, search_area);
+    }
+
+    if app.sidebar_rows.is_empty() {
+        let empty_msg = if app.profiles.is_empty() {
+            "No profiles found"
+        } else {
+            constants::MSG_NO_SEARCH_MATCHES
+        };
         frame.render_widget(
-            Paragraph::new("No profiles found").alignment(Alignment::Center),
+            Paragraph::new(empty_msg).alignment(Alignment::Center),
             inner,
         );
         return;
@@ -438,12 +693,34 @@ fn render_profiles_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
         ConnectionState::Disconnected => None,
     };
 
+    // Groups containing the active profile (for the connected count on headers)
+    let active_groups: Vec<String> = active_profile
+        .as_ref()
+        .and_then(|name| app.profiles.iter().position(|p| &p.name == name))
+        .filter(|idx| {
+            app.profile_filter.is_empty() || app.visible_profiles.iter().any(|m| m.index == *idx)
+        })
+        .map(|idx| grouping::group_keys(&app.profiles[idx], app.group_by))
+        .unwrap_or_default();
+
     let items: Vec<Row> = app
-        .profiles
+        .sidebar_rows
         .iter()
         .enumerate()
-        .map(|(idx, p)| {
-            let is_selected = app.profile_list_state.selected() == Some(idx);
+        .map(|(row, entry)| {
+            let is_selected = app.profile_list_state.selected() == Some(row);
+            let m = match entry {
+                SidebarRow::Profile(m) => m,
+                SidebarRow::Group {
+                    key,
+                    members,
+                    collapsed,
+                } => {
+                    let connected = active_groups.iter().filter(|k| *k == key).count();
+                    return render_group_row(key, *members, connected, *collapsed, is_selected);
+                }
+            };
+            let p = &app.profiles[m.index];
             let is_active = active_profile.as_ref() == Some(&p.name);
             let is_never_used = p.last_used.is_none();
 
@@ -479,16 +756,25 @@ fn render_profiles_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
                 theme::TEXT_SECONDARY
             };
 
-            // Last used time
-            let time_str = if let Some(last_used) = p.last_used {
-                let relative = utils::format_relative_time(last_used);
-                if !relative.ends_with("ago") && !relative.is_empty() {
-                    format!("{relative} ago")
-                } else {
-                    relative
+            // Last column follows the sort order (last used time by default)
+            let time_str = match app.sort_by {
+                SortBy::Latency => p.last_latency_ms.map_or_else(
+                    || constants::MSG_NO_DATA.to_string(),
+                    |ms| format!("{ms}ms"),
+                ),
+                SortBy::Usage => format!("{}×", p.connect_count),
+                SortBy::Name | SortBy::LastUsed => {
+                    if let Some(last_used) = p.last_used {
+                        let relative = utils::format_relative_time(last_used);
+                        if !relative.ends_with("ago") && !relative.is_empty() {
+                            format!("{relative} ago")
+                        } else {
+                            relative
+                        }
+                    } else {
+                        "never".to_string()
+                    }
                 }
-            } else {
-                "never".to_string()
             };
 
             let row_style = if is_selected {
@@ -500,7 +786,7 @@ fn render_profiles_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
             // Create cells for each column
             let status_cell =
                 Cell::from(Span::styled(status_char, Style::default().fg(status_color)));
-            let name_cell = Cell::from(Span::styled(p.name.clone(), name_style));
+            let name_cell = Cell::from(highlight_matches(&p.name, &m.name_positions, name_style));
             let proto_cell = Cell::from(Span::styled(proto_icon, Style::default().fg(proto_color)));
             let time_cell =
                 Cell::from(Span::styled(time_str, Style::default().fg(Color::DarkGray)));
@@ -529,7 +815,7 @@ fn render_profiles_sidebar(frame: &mut Frame, app: &mut App, area: Rect) {
         .thumb_style(Style::default().fg(theme::ACCENT_PRIMARY));
 
     let mut scrollbar_state =
-        ScrollbarState::new(app.profiles.len().saturating_sub(inner.height as usize))
+        ScrollbarState::new(app.sidebar_rows.len().saturating_sub(inner.height as usize))
             .position(app.profile_list_state.selected().unwrap_or(0));
 
     frame.render_stateful_widget(
@@ -660,6 +946,117 @@ fn render_throughput_chart(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(canvas, chunks[1]);
 }
 
+/// Routing table with routes through the tunnel highlighted, policy rules
+/// above it and bypassing routes called out.
+fn render_routes(frame: &mut Frame, app: &App, area: Rect) {
+    let is_focused = app.should_draw_focus(&crate::app::FocusedPanel::Routes);
+    let border_style = if is_focused {
+        Style::default().fg(theme::BORDER_FOCUSED)
+    } else {
+        Style::default().fg(theme::BORDER_DEFAULT)
+    };
+
+    let table = &app.route_table;
+    let tunnel = match &app.connection_state {
+        ConnectionState::Connected { details, .. } if !details.interface.is_empty() => {
+            Some(details.interface.as_str())
+        }
+        _ => None,
+    };
+    let bypassing = table
+        .routes
+        .iter()
+        .filter(|r| r.kind == RouteKind::Bypass)
+        .count();
+    let summary = match tunnel {
+        None => (
+            constants::MSG_ROUTES_NO_TUNNEL.to_string(),
+            theme::TEXT_SECONDARY,
+        ),
+        Some(_) if bypassing > 0 => (
+            format!("{bypassing} route(s) bypass the tunnel"),
+            theme::ERROR,
+        ),
+        Some(iface) if table.full_tunnel => (format!("Full tunnel via {iface}"), theme::SUCCESS),
+        Some(iface) => (format!("Split tunnel via {iface}"), theme::WARNING),
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(border_style)
+        .title(" Routes ")
+        .title_bottom(Line::from(Span::styled(
+            format!(" {} ", summary.0),
+            Style::default().fg(summary.1),
+        )));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // Policy rules (WireGuard's fwmark rules) above the table
+    let rule_lines: Vec<Line> = table
+        .rules
+        .iter()
+        .map(|rule| {
+            Line::from(Span::styled(
+                utils::truncate(&format!("rule {rule}"), inner.width as usize),
+                Style::default().fg(theme::NORD_PURPLE),
+            ))
+        })
+        .collect();
+    let rule_height = u16::try_from(rule_lines.len())
+        .unwrap_or(u16::MAX)
+        .min(inner.height / 2);
+    let chunks =
+        Layout::vertical([Constraint::Length(rule_height), Constraint::Min(0)]).split(inner);
+    frame.render_widget(Paragraph::new(rule_lines), chunks[0]);
+
+    let routes = table.routes.iter().map(|route| {
+        let (note, style) = match route.kind {
+            RouteKind::Tunnel => (
+                "tunnel",
+                Style::default()
+                    .fg(theme::SUCCESS)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            RouteKind::Default if table.full_tunnel => (
+                "default (overridden)",
+                Style::default().fg(theme::TEXT_SECONDARY),
+            ),
+            RouteKind::Default => ("default", Style::default().fg(theme::WARNING)),
+            RouteKind::Endpoint => ("vpn server", Style::default().fg(theme::ACCENT_PRIMARY)),
+            RouteKind::Local => ("local", Style::default().fg(Color::DarkGray)),
+            RouteKind::Bypass => ("BYPASS", Style::default().fg(theme::ERROR)),
+            RouteKind::Other => ("", Style::default().fg(theme::TEXT_PRIMARY)),
+        };
+        let note = match &route.table {
+            Some(t) if note.is_empty() => format!("table {t}"),
+            Some(t) => format!("{note}, table {t}"),
+            None => note.to_string(),
+        };
+        Row::new(vec![
+            Cell::from(route.destination.clone()),
+            Cell::from(route.gateway.clone().unwrap_or_else(|| "-".to_string())),
+            Cell::from(route.interface.clone()),
+            Cell::from(note),
+        ])
+        .style(style)
+    });
+    let rows: Vec<Row> = routes.skip(app.routes_scroll as usize).collect();
+
+    let widths = [
+        Constraint::Percentage(32),
+        Constraint::Percentage(28),
+        Constraint::Percentage(12),
+        Constraint::Percentage(28),
+    ];
+    let header = Row::new(vec!["Destination", "Gateway", "Dev", "Note"]).style(
+        Style::default()
+            .fg(theme::TEXT_SECONDARY)
+            .add_modifier(Modifier::BOLD),
+    );
+    frame.render_widget(Table::new(rows, widths).header(header), chunks[1]);
+}
+
 #[allow(clippy::too_many_lines)]
 fn render_security_guard(frame: &mut Frame, app: &App, area: Rect) {
     let is_focused = app.should_draw_focus(&crate::app::FocusedPanel::Security);
@@ -679,11 +1076,12 @@ fn render_security_guard(frame: &mut Frame, app: &App, area: Rect) {
 
     // Security checks
     let is_connected = !matches!(app.connection_state, ConnectionState::Disconnected);
-    let ipv6_leaking = app.ipv6_leak;
+    let ipv6_report = app.ipv6_report.as_ref();
+    let ipv6_leaking = app.ipv6_leak || ipv6_report.is_some_and(Ipv6Report::is_leaking);
 
     if !is_connected {
         // Disconnected state - show warning
-        let audit = vec![
+        let mut audit = vec![
             Line::from(vec![Span::styled(
                 " ⚠ EXPOSED ",
                 Style::default()
@@ -701,12 +1099,40 @@ fn render_security_guard(frame: &mut Frame, app: &App, area: Rect) {
                 Style::default().fg(theme::TEXT_SECONDARY),
             )),
         ];
+        if app.killswitch_state.is_blocking() {
+            audit.push(Line::from(""));
+            audit.push(Line::from(Span::styled(
+                "Kill switch is blocking.",
+                Style::default().fg(theme::TEXT_SECONDARY),
+            )));
+            audit.push(match &app.portal_window {
+                Some((window, until)) => Line::from(Span::styled(
+                    utils::truncate(
+                        &format!(
+                            "Portal window: {}s left ({})",
+                            until
+                                .saturating_duration_since(std::time::Instant::now())
+                                .as_secs(),
+                            window.network
+                        ),
+                        inner.width as usize,
+                    ),
+                    Style::default().fg(theme::WARNING),
+                )),
+                None => Line::from(Span::styled(
+                    constants::MSG_PORTAL_HINT,
+                    Style::default().fg(Color::DarkGray),
+                )),
+            });
+        }
         frame.render_widget(Paragraph::new(audit), inner);
         return;
     }
 
     // Connected - show security checklist with visual indicators
-    let dns_leaking = utils::is_private_ip(&app.dns_server);
+    let dns_report = app.dns_report.as_ref();
+    let dns_leaking = dns_report.is_some_and(DnsReport::is_leaking);
+    let dns_secure = dns_report.is_some_and(DnsReport::is_secure);
 
     // Check if IP is actually masked (different from real IP captured when disconnected)
     let ip_status = match &app.real_ip {
@@ -756,11 +1182,20 @@ fn render_security_guard(frame: &mut Frame, app: &App, area: Rect) {
         Line::from(vec![Span::styled(
             "   PROTECTED",
             Style::default()
-                .fg(if ip_masked && !dns_leaking && !ipv6_leaking {
-                    theme::SUCCESS
-                } else {
-                    theme::WARNING
-                })
+                .fg(
+                    if ip_masked
+                        && !dns_leaking
+                        && !ipv6_leaking
+                        && !app
+                            .audit
+                            .iter()
+                            .any(|c| c.status == Status::Fail && c.id != "kill_switch")
+                    {
+                        theme::SUCCESS
+                    } else {
+                        theme::WARNING
+                    },
+                )
                 .add_modifier(Modifier::BOLD),
         )]),
         Line::from(""),
@@ -817,16 +1252,20 @@ fn render_security_guard(frame: &mut Frame, app: &App, area: Rect) {
     audit.push(Line::from(vec![
         if dns_leaking {
             check_fail.clone()
-        } else {
+        } else if dns_secure {
             check_pass.clone()
+        } else {
+            check_warn.clone()
         },
         Span::styled("DNS Secure : ", Style::default().fg(theme::TEXT_SECONDARY)),
         Span::styled(
             utils::truncate(&app.dns_server, max_val),
             Style::default().fg(if dns_leaking {
                 theme::ERROR
-            } else {
+            } else if dns_secure {
                 theme::SUCCESS
+            } else {
+                theme::WARNING
             }),
         ),
     ]));
@@ -836,26 +1275,39 @@ fn render_security_guard(frame: &mut Frame, app: &App, area: Rect) {
             Span::styled(dns_provider, Style::default().fg(Color::DarkGray)),
         ]));
     }
+    audit.extend(dns_findings(dns_report, inner.width as usize));
 
     audit.push(Line::from(""));
 
     // IPv6 Check
+    let (ipv6_icon, ipv6_text, ipv6_color) = match ipv6_report.map(|r| r.verdict) {
+        _ if ipv6_leaking => (check_fail.clone(), "Leaking", theme::ERROR),
+        Some(Verdict::Tunnel) => (check_pass.clone(), "Blocked", theme::SUCCESS),
+        Some(_) => (check_warn.clone(), "Unknown", theme::WARNING),
+        None => (check_warn.clone(), "Checking...", theme::WARNING),
+    };
     audit.push(Line::from(vec![
-        if ipv6_leaking {
-            check_fail.clone()
-        } else {
-            check_pass.clone()
-        },
+        ipv6_icon,
         Span::styled("IPv6       : ", Style::default().fg(theme::TEXT_SECONDARY)),
-        Span::styled(
-            if ipv6_leaking { "Leaking" } else { "Blocked" },
+        Span::styled(ipv6_text, Style::default().fg(ipv6_color)),
+    ]));
+    let ipv6_reason = match ipv6_report {
+        Some(report) if app.ipv6_leak && !report.is_leaking() => {
+            Some(constants::MSG_IPV6_REMOTE_LEAK)
+        }
+        Some(report) => Some(report.reason.as_str()),
+        None => None,
+    };
+    if let Some(reason) = ipv6_reason {
+        audit.push(Line::from(Span::styled(
+            utils::truncate(&format!("  {reason}"), inner.width as usize),
             Style::default().fg(if ipv6_leaking {
                 theme::ERROR
             } else {
-                theme::SUCCESS
+                Color::DarkGray
             }),
-        ),
-    ]));
+        )));
+    }
 
     audit.push(Line::from(""));
 
@@ -879,6 +1331,42 @@ fn render_security_guard(frame: &mut Frame, app: &App, area: Rect) {
         Span::styled("Kill Switch: ", Style::default().fg(theme::TEXT_SECONDARY)),
         Span::styled(ks_text, Style::default().fg(ks_color)),
     ]));
+    if app.killswitch_mode != crate::state::KillSwitchMode::Off {
+        audit.push(Line::from(Span::styled(
+            utils::truncate(
+                &format!("  {}", app.killswitch_policy.summary()),
+                inner.width as usize,
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    audit.push(Line::from(""));
+
+    // Tunnel coverage checks shared with `vortix audit`
+    for (id, label) in [
+        ("default_route", "Route      : "),
+        ("allowed_ips", "AllowedIPs : "),
+        ("handshake", "Handshake  : "),
+    ] {
+        let Some(check) = app.audit.iter().find(|c| c.id == id) else {
+            continue;
+        };
+        let (icon, color) = match check.status {
+            Status::Pass => (check_pass.clone(), theme::SUCCESS),
+            Status::Fail => (check_fail.clone(), theme::ERROR),
+            Status::Warn => (check_warn.clone(), theme::WARNING),
+            Status::Skip => (check_warn.clone(), Color::DarkGray),
+        };
+        audit.push(Line::from(vec![
+            icon,
+            Span::styled(label, Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(
+                utils::truncate(&check.detail, max_val),
+                Style::default().fg(color),
+            ),
+        ]));
+    }
 
     audit.push(Line::from(""));
 
@@ -899,6 +1387,55 @@ fn render_security_guard(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(Paragraph::new(audit), inner);
 }
 
+/// One line per resolver explaining how it is reached, then the profile's
+/// servers that are not in use.
+fn dns_findings(report: Option<&DnsReport>, width: usize) -> Vec<Line<'static>> {
+    let Some(report) = report else {
+        return vec![Line::from(Span::styled(
+            constants::MSG_DNS_CHECKING,
+            Style::default().fg(Color::DarkGray),
+        ))];
+    };
+
+    let findings: Vec<(String, Color)> = report
+        .resolvers
+        .iter()
+        .map(|r| {
+            let color = match r.verdict {
+                Verdict::Tunnel => Color::DarkGray,
+                Verdict::Leak => theme::ERROR,
+                Verdict::Local | Verdict::Unknown => theme::WARNING,
+            };
+            (format!("  {}: {}", r.server, r.reason), color)
+        })
+        .chain(report.unused.iter().map(|server| {
+            (
+                format!("  {server}: {}", constants::MSG_DNS_UNUSED),
+                theme::WARNING,
+            )
+        }))
+        .collect();
+
+    let mut lines: Vec<Line<'static>> = findings
+        .iter()
+        .take(constants::DNS_FINDINGS_SHOWN)
+        .map(|(text, color)| {
+            Line::from(Span::styled(
+                utils::truncate(text, width),
+                Style::default().fg(*color),
+            ))
+        })
+        .collect();
+    let hidden = findings.len().saturating_sub(constants::DNS_FINDINGS_SHOWN);
+    if hidden > 0 {
+        lines.push(Line::from(Span::styled(
+            format!("  +{hidden} more"),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines
+}
+
 fn render_dependency_alert(frame: &mut Frame, protocol: Protocol, missing: &[String]) {
     let area = frame.area();
     let popup_layout = Layout::vertical([
@@ -1059,6 +1596,58 @@ fn render_permission_denied(frame: &mut Frame, action: &str) {
     frame.render_widget(Paragraph::new(text).alignment(Alignment::Center), chunks[1]);
 }
 
+/// Masked passphrase prompt; `text` is `[title, footer, prompt]`.
+fn render_passphrase_overlay(
+    frame: &mut Frame,
+    text: [&str; 3],
+    passphrase: &str,
+    error: Option<&str>,
+) {
+    let [title, footer, prompt] = text;
+    let popup_area = centered_rect(50, 30, frame.area());
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::ACCENT_PRIMARY))
+        .title(title)
+        .title_bottom(Line::from(footer).centered());
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut text = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            prompt,
+            Style::default().fg(theme::TEXT_PRIMARY),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(" > ", Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(
+                "•".repeat(passphrase.chars().count()),
+                Style::default().fg(theme::TEXT_PRIMARY),
+            ),
+            Span::styled(
+                "█",
+                Style::default()
+                    .fg(theme::ACCENT_SECONDARY)
+                    .add_modifier(Modifier::SLOW_BLINK),
+            ),
+        ]),
+    ];
+    if let Some(error) = error {
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            error,
+            Style::default().fg(theme::ERROR),
+        )));
+    }
+
+    frame.render_widget(Paragraph::new(text).wrap(Wrap { trim: false }), inner);
+}
+
 fn render_delete_confirm(frame: &mut Frame, name: &str, confirm_selected: bool) {
     let area = frame.area();
     let popup_layout = Layout::vertical([
@@ -1177,7 +1766,7 @@ fn render_activity_log(frame: &mut Frame, app: &App, area: Rect) {
         .iter()
         .map(|entry| {
             // Format timestamp from LogEntry
-            let elapsed = entry.timestamp.elapsed().map(|d| d.as_secs()).unwrap_or(0);
+            let elapsed = entry.timestamp.elapsed().map_or(0, |d| d.as_secs());
 
             let time_str = if elapsed < 60 {
                 format!("{elapsed}s")
@@ -1253,6 +1842,18 @@ fn render_activity_log(frame: &mut Frame, app: &App, area: Rect) {
 
 // === Helper Utilities ===
 
+/// `Split   : only 10.0.0.0/8 +1, bypass corp.example`
+fn split_line(summary: &str, width: u16) -> Line<'static> {
+    let room = usize::from(width).saturating_sub(10);
+    Line::from(vec![
+        Span::styled("Split   : ", Style::default().fg(theme::TEXT_SECONDARY)),
+        Span::styled(
+            utils::truncate(summary, room),
+            Style::default().fg(theme::NORD_YELLOW),
+        ),
+    ])
+}
+
 #[allow(clippy::too_many_lines, clippy::similar_names)]
 fn render_connection_details(frame: &mut Frame, app: &App, area: Rect) {
     let is_focused = app.should_draw_focus(&crate::app::FocusedPanel::ConnectionDetails);
@@ -1270,7 +1871,10 @@ fn render_connection_details(frame: &mut Frame, app: &App, area: Rect) {
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    if let ConnectionState::Connected { details, .. } = &app.connection_state {
+    if let ConnectionState::Connected {
+        details, profile, ..
+    } = &app.connection_state
+    {
         let is_openvpn = details.public_key == "OpenVPN" || details.public_key.is_empty();
 
         // MTU value
@@ -1323,6 +1927,17 @@ fn render_connection_details(frame: &mut Frame, app: &App, area: Rect) {
             ]),
         ];
 
+        // Split tunnel rules, only when the profile has any
+        let split = app
+            .profiles
+            .iter()
+            .find(|p| &p.name == profile)
+            .map(|p| &p.split_tunnel)
+            .filter(|split| !split.is_empty());
+        if let Some(split) = split {
+            text.push(split_line(&split.summary(), inner.width));
+        }
+
         // Row 4: Crypto/Protocol Info
         let (proto_label, proto_value, proto_color) = if is_openvpn {
             let cipher = if details.latest_handshake.starts_with("Cipher:") {
@@ -1473,22 +2088,23 @@ fn render_connection_details(frame: &mut Frame, app: &App, area: Rect) {
         ];
 
         // Show selected profile info with pre-connect ping
-        if let Some(idx) = app.profile_list_state.selected() {
-            if let Some(profile) = app.profiles.get(idx) {
-                text.push(Line::from(vec![
-                    Span::styled("Selected: ", Style::default().fg(theme::TEXT_SECONDARY)),
-                    Span::styled(&profile.name, Style::default().fg(theme::ACCENT_PRIMARY)),
-                ]));
-                text.push(Line::from(vec![
-                    Span::styled("Protocol: ", Style::default().fg(theme::TEXT_SECONDARY)),
-                    Span::styled(
-                        profile.protocol.to_string(),
-                        Style::default().fg(theme::TEXT_PRIMARY),
-                    ),
-                ]));
-                // Note: Location is only shown when connected (in header and connection details)
-                // Profile metadata doesn't include reliable location information
+        if let Some(profile) = app.selected_profile() {
+            text.push(Line::from(vec![
+                Span::styled("Selected: ", Style::default().fg(theme::TEXT_SECONDARY)),
+                Span::styled(&profile.name, Style::default().fg(theme::ACCENT_PRIMARY)),
+            ]));
+            text.push(Line::from(vec![
+                Span::styled("Protocol: ", Style::default().fg(theme::TEXT_SECONDARY)),
+                Span::styled(
+                    profile.protocol.to_string(),
+                    Style::default().fg(theme::TEXT_PRIMARY),
+                ),
+            ]));
+            if !profile.split_tunnel.is_empty() {
+                text.push(split_line(&profile.split_tunnel.summary(), inner.width));
             }
+            // Note: Location is only shown when connected (in header and connection details)
+            // Profile metadata doesn't include reliable location information
         }
 
         frame.render_widget(Paragraph::new(text), inner);
@@ -9,7 +9,6 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
-use std::fs;
 use std::path::PathBuf;
 
 /// Render config file viewer overlay
@@ -21,20 +20,12 @@ pub fn render(frame: &mut Frame, app: &App) {
 
     // Get the current profile's config path
     let (config_content, profile_name, config_path): (String, String, PathBuf) =
-        if let Some(idx) = app.profile_list_state.selected() {
-            if let Some(profile) = app.profiles.get(idx) {
-                let content = match fs::read_to_string(&profile.config_path) {
-                    Ok(c) => c,
-                    Err(e) => format!("Error reading config: {e}"),
-                };
-                (content, profile.name.clone(), profile.config_path.clone())
-            } else {
-                (
-                    "No profile selected".to_string(),
-                    String::new(),
-                    PathBuf::new(),
-                )
-            }
+        if let Some(profile) = app.selected_profile() {
+            let content = match crate::core::vault::read_config(&profile.config_path) {
+                Ok(c) => c,
+                Err(e) => format!("Error reading config: {e}"),
+            };
+            (content, profile.name.clone(), profile.config_path.clone())
         } else {
             (
                 "No profile selected".to_string(),
@@ -53,7 +44,7 @@ pub fn render(frame: &mut Frame, app: &App) {
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme::BORDER_FOCUSED))
         .title(title)
-        .title_bottom(Line::from(" [Esc] Close  [↑/↓] Scroll ").centered());
+        .title_bottom(Line::from(" [Esc] Close  [↑/↓] Scroll  [Q] QR Code ").centered());
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
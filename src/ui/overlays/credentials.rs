@@ -0,0 +1,118 @@
+//! Masked credential prompt overlay for `OpenVPN` connects
+
+use crate::app::CredentialPrompt;
+use crate::constants;
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Flex, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Width reserved for field labels.
+const LABEL_WIDTH: usize = 18;
+
+/// Render the credential prompt
+pub fn render(frame: &mut Frame, prompt: &CredentialPrompt) {
+    let height = u16::try_from(prompt.fields.len() * 2 + 9).unwrap_or(u16::MAX);
+    let vertical = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center);
+    let [area] = vertical.areas(frame.area());
+    let [area] = horizontal.areas(area);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::ACCENT_PRIMARY))
+        .title(format!(
+            "{}{} ",
+            constants::TITLE_CREDENTIALS,
+            prompt.profile
+        ))
+        .title_bottom(Line::from(constants::TITLE_CREDENTIALS_FOOTER).centered());
+
+    let mut lines = vec![Line::from("")];
+    if let Some(challenge) = &prompt.challenge {
+        lines.push(Line::from(Span::styled(
+            format!(" {challenge}"),
+            Style::default()
+                .fg(theme::WARNING)
+                .add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(""));
+    }
+
+    for (i, field) in prompt.fields.iter().enumerate() {
+        let focused = i == prompt.focused;
+        let value = if field.masked {
+            "•".repeat(field.value.chars().count())
+        } else {
+            field.value.clone()
+        };
+
+        let mut spans = vec![
+            Span::styled(
+                format!(" {:<LABEL_WIDTH$}", field.kind.label()),
+                label_style(focused),
+            ),
+            Span::styled(value, Style::default().fg(theme::TEXT_PRIMARY)),
+        ];
+        if focused {
+            spans.push(Span::styled(
+                "█",
+                Style::default()
+                    .fg(theme::ACCENT_SECONDARY)
+                    .add_modifier(Modifier::SLOW_BLINK),
+            ));
+        }
+        lines.push(Line::from(spans));
+        lines.push(Line::from(""));
+    }
+
+    if let Some(store) = prompt.store {
+        let focused = prompt.on_store_row();
+        let choice_style = if focused {
+            Style::default()
+                .bg(theme::ROW_SELECTED_BG)
+                .fg(theme::ROW_SELECTED_FG)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme::TEXT_SECONDARY)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(" {:<LABEL_WIDTH$}", constants::LABEL_SAVE_CREDENTIALS),
+                label_style(focused),
+            ),
+            Span::styled(format!(" ◂ {} ▸ ", store.label()), choice_style),
+        ]));
+    }
+
+    if let Some(error) = &prompt.error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(" {error}"),
+            Style::default().fg(theme::ERROR),
+        )));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn label_style(focused: bool) -> Style {
+    if focused {
+        Style::default()
+            .fg(theme::ACCENT_PRIMARY)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme::TEXT_SECONDARY)
+    }
+}
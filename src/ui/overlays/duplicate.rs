@@ -0,0 +1,78 @@
+//! Duplicate profile prompt shown while importing
+
+use crate::constants;
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Render the choice for a file that duplicates a stored profile
+pub fn render(
+    frame: &mut Frame,
+    source: &str,
+    existing: &str,
+    reason: &str,
+    apply_to_all: bool,
+    remaining: usize,
+) {
+    let area = centered(frame.area(), 64, 12);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::WARNING))
+        .title(constants::TITLE_DUPLICATE)
+        .title_bottom(Line::from(constants::TITLE_DUPLICATE_FOOTER).centered());
+
+    let checkbox = if apply_to_all { "[x]" } else { "[ ]" };
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {source}"),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!(" duplicates '{existing}' ({reason})"),
+            Style::default().fg(theme::WARNING),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {remaining} more file(s) queued"),
+            Style::default().fg(theme::TEXT_SECONDARY),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!(" {checkbox} "),
+                Style::default().fg(theme::ACCENT_PRIMARY),
+            ),
+            Span::styled(
+                constants::LABEL_DUPLICATE_APPLY_ALL,
+                Style::default().fg(theme::TEXT_SECONDARY),
+            ),
+        ]),
+    ];
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Length(width.min(area.width))])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
@@ -0,0 +1,86 @@
+//! Export dialog for writing profiles into a bundle
+
+use crate::constants;
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Flex, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Width reserved for field labels.
+const LABEL_WIDTH: usize = 14;
+
+/// Render the export dialog for `count` profiles
+pub fn render(frame: &mut Frame, path: &str, passphrase: &str, on_passphrase: bool, count: usize) {
+    let vertical = Layout::vertical([Constraint::Length(12)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center);
+    let [area] = vertical.areas(frame.area());
+    let [area] = horizontal.areas(area);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::ACCENT_PRIMARY))
+        .title(constants::TITLE_EXPORT_PROFILES)
+        .title_bottom(Line::from(constants::TITLE_EXPORT_FOOTER).centered());
+
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" Exporting {count} profile(s) matching the sidebar filter"),
+            Style::default().fg(theme::TEXT_PRIMARY),
+        )),
+        Line::from(""),
+        field_line(
+            constants::LABEL_EXPORT_PATH,
+            path.to_string(),
+            !on_passphrase,
+        ),
+        Line::from(""),
+        field_line(
+            constants::LABEL_EXPORT_PASSPHRASE,
+            "•".repeat(passphrase.chars().count()),
+            on_passphrase,
+        ),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {}", constants::HINT_EXPORT_PASSPHRASE),
+            Style::default().fg(theme::ACCENT_SECONDARY),
+        )),
+    ];
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn field_line(label: &str, value: String, focused: bool) -> Line<'_> {
+    let label_style = if focused {
+        Style::default()
+            .fg(theme::ACCENT_PRIMARY)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme::TEXT_SECONDARY)
+    };
+
+    let mut spans = vec![
+        Span::styled(format!(" {label:<LABEL_WIDTH$}"), label_style),
+        Span::styled(value, Style::default().fg(theme::TEXT_PRIMARY)),
+    ];
+    if focused {
+        spans.push(Span::styled(
+            "█",
+            Style::default()
+                .fg(theme::ACCENT_SECONDARY)
+                .add_modifier(Modifier::SLOW_BLINK),
+        ));
+    }
+    Line::from(spans)
+}
@@ -0,0 +1,127 @@
+//! Review of a parsed profile before it is saved
+
+use crate::constants;
+use crate::state::{ImportConfirm, Protocol};
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Width reserved for field labels.
+const LABEL_WIDTH: usize = 12;
+
+/// Render the confirmation with the editable name and location
+pub fn render(frame: &mut Frame, confirm: &ImportConfirm) {
+    let preview = &confirm.preview;
+    let notes = preview.warnings.len()
+        + usize::from(confirm.duplicate_of.is_some())
+        + usize::from(confirm.error.is_some());
+    let height = u16::try_from(14 + notes).unwrap_or(u16::MAX);
+    let area = centered(frame.area(), height);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::ACCENT_PRIMARY))
+        .title(constants::TITLE_IMPORT_CONFIRM)
+        .title_bottom(Line::from(constants::TITLE_IMPORT_CONFIRM_FOOTER).centered());
+
+    let or_none = |values: &[String], none: &str| {
+        if values.is_empty() {
+            none.to_string()
+        } else {
+            values.join(", ")
+        }
+    };
+    let routes = match preview.protocol {
+        Protocol::WireGuard => or_none(&preview.allowed_ips, "none"),
+        Protocol::OpenVPN => constants::LABEL_ROUTES_FROM_SERVER.to_string(),
+    };
+
+    let mut lines = vec![
+        Line::from(""),
+        edit_line("Name", &confirm.name, !confirm.location_focused),
+        edit_line("Location", &confirm.location, confirm.location_focused),
+        Line::from(""),
+        info_line("Protocol", &preview.protocol.to_string()),
+        info_line("Endpoint", preview.endpoint.as_deref().unwrap_or("none")),
+        info_line("DNS", &or_none(&preview.dns, "none")),
+        info_line("AllowedIPs", &routes),
+        Line::from(""),
+    ];
+
+    let note = |text: String, color| Line::from(Span::styled(text, Style::default().fg(color)));
+    if let Some(existing) = &confirm.duplicate_of {
+        lines.push(note(
+            format!(" ⚠ Duplicates {existing}; saving keeps both"),
+            theme::WARNING,
+        ));
+    }
+    for warning in &preview.warnings {
+        lines.push(note(format!(" ⚠ {warning}"), theme::WARNING));
+    }
+    if notes == 0 {
+        lines.push(note(
+            format!(" {}", constants::MSG_IMPORT_PREVIEW_OK),
+            theme::SUCCESS,
+        ));
+    }
+    if let Some(error) = &confirm.error {
+        lines.push(note(format!(" ✗ {error}"), theme::ERROR));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn edit_line(label: &str, value: &str, focused: bool) -> Line<'static> {
+    let label_style = if focused {
+        Style::default()
+            .fg(theme::ACCENT_PRIMARY)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme::TEXT_SECONDARY)
+    };
+    let mut spans = vec![
+        Span::styled(format!(" {label:<LABEL_WIDTH$}"), label_style),
+        Span::styled(value.to_string(), Style::default().fg(theme::TEXT_PRIMARY)),
+    ];
+    if focused {
+        spans.push(Span::styled(
+            "█",
+            Style::default()
+                .fg(theme::ACCENT_SECONDARY)
+                .add_modifier(Modifier::SLOW_BLINK),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn info_line(label: &str, value: &str) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!(" {label:<LABEL_WIDTH$}"),
+            Style::default().fg(theme::TEXT_SECONDARY),
+        ),
+        Span::styled(value.to_string(), Style::default().fg(theme::TEXT_PRIMARY)),
+    ])
+}
+
+/// A 60%-wide rect of `height` rows centered in `area`
+fn centered(area: Rect, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
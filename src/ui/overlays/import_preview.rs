@@ -0,0 +1,140 @@
+//! Import preview: the files found in a folder or archive, with checkboxes
+
+use crate::constants;
+use crate::state::PreviewItem;
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+/// Render the preview list with details for the highlighted file
+pub fn render(frame: &mut Frame, items: &[PreviewItem], cursor: usize) {
+    let screen = frame.area();
+    let area = centered(screen, screen.width * 4 / 5, screen.height * 7 / 10);
+    frame.render_widget(Clear, area);
+
+    let checked = items.iter().filter(|i| i.checked).count();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_FOCUSED))
+        .title(format!(
+            "{}({checked} of {} selected) ",
+            constants::TITLE_IMPORT_PREVIEW,
+            items.len()
+        ))
+        .title_bottom(Line::from(constants::TITLE_IMPORT_PREVIEW_FOOTER).centered());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [list_area, details_area] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(4)]).areas(inner);
+
+    let rows: Vec<ListItem> = items.iter().map(row).collect();
+    let list = List::new(rows)
+        .highlight_style(
+            Style::default()
+                .bg(theme::ROW_SELECTED_BG)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default().with_selected(Some(cursor));
+    frame.render_stateful_widget(list, list_area, &mut state);
+
+    if let Some(item) = items.get(cursor) {
+        frame.render_widget(
+            Paragraph::new(details(item))
+                .block(
+                    Block::default()
+                        .borders(Borders::TOP)
+                        .border_style(Style::default().fg(theme::BORDER_DEFAULT)),
+                )
+                .wrap(Wrap { trim: true }),
+            details_area,
+        );
+    }
+}
+
+fn row(item: &PreviewItem) -> ListItem<'static> {
+    let checkbox = if item.checked { "[x] " } else { "[ ] " };
+    let mut spans = vec![
+        Span::styled(checkbox, Style::default().fg(theme::ACCENT_PRIMARY)),
+        Span::styled(item.label.clone(), Style::default().fg(theme::TEXT_PRIMARY)),
+    ];
+    match &item.preview {
+        Ok(preview) => {
+            spans.push(Span::styled(
+                format!(
+                    "  → {} · {} · {}",
+                    preview.name, preview.protocol, preview.location
+                ),
+                Style::default().fg(theme::TEXT_SECONDARY),
+            ));
+            if item.duplicate_of.is_some() {
+                spans.push(Span::styled(
+                    "  duplicate",
+                    Style::default().fg(theme::WARNING),
+                ));
+            }
+            if !preview.warnings.is_empty() {
+                spans.push(Span::styled(
+                    format!("  ⚠ {}", preview.warnings.len()),
+                    Style::default().fg(theme::WARNING),
+                ));
+            }
+        }
+        Err(_) => spans.push(Span::styled(
+            "  ✗ invalid",
+            Style::default().fg(theme::ERROR),
+        )),
+    }
+    ListItem::new(Line::from(spans))
+}
+
+/// Error, duplicate and warnings of the highlighted file
+fn details(item: &PreviewItem) -> Vec<Line<'static>> {
+    match &item.preview {
+        Err(e) => vec![Line::from(Span::styled(
+            e.clone(),
+            Style::default().fg(theme::ERROR),
+        ))],
+        Ok(preview) => {
+            let mut lines: Vec<Line> = item
+                .duplicate_of
+                .iter()
+                .map(|name| {
+                    Line::from(Span::styled(
+                        format!("Duplicates '{name}'"),
+                        Style::default().fg(theme::WARNING),
+                    ))
+                })
+                .chain(preview.warnings.iter().map(|w| {
+                    Line::from(Span::styled(
+                        format!("⚠ {w}"),
+                        Style::default().fg(theme::WARNING),
+                    ))
+                }))
+                .collect();
+            if lines.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    constants::MSG_IMPORT_PREVIEW_OK,
+                    Style::default().fg(theme::SUCCESS),
+                )));
+            }
+            lines
+        }
+    }
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Length(width.min(area.width))])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
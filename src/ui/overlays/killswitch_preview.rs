@@ -0,0 +1,145 @@
+//! Kill switch rule preview overlay
+
+use crate::app::App;
+use crate::constants;
+use crate::state::InputMode;
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
+};
+
+/// pf keywords shown like config keys.
+const KEYWORDS: &[&str] = &[
+    "quick", "in", "out", "on", "proto", "from", "to", "port", "all", "any", "inet", "inet6",
+];
+
+/// Render the rules the kill switch would install
+pub fn render(frame: &mut Frame, app: &App) {
+    let InputMode::KillSwitchPreview {
+        interface,
+        preview,
+        scroll,
+    } = &app.input_mode
+    else {
+        return;
+    };
+    let scroll = *scroll;
+
+    let area = centered_rect(85, 85, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_FOCUSED))
+        .title(format!(" Kill Switch Rules ({interface}) "))
+        .title_bottom(Line::from(constants::TITLE_KS_PREVIEW_FOOTER).centered());
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [header, status, content] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Min(1),
+    ])
+    .areas(inner);
+
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("Mode: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{:?}", app.killswitch_mode),
+                Style::default().fg(theme::TEXT_SECONDARY),
+            ),
+            Span::styled("  Policy: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                app.killswitch_policy.summary(),
+                Style::default().fg(theme::TEXT_SECONDARY),
+            ),
+        ])),
+        header,
+    );
+
+    let status_line = match &preview.validation {
+        None => Span::styled(
+            constants::MSG_KS_PREVIEW_HINT,
+            Style::default().fg(Color::DarkGray),
+        ),
+        Some(Ok(())) => Span::styled(
+            constants::MSG_KS_RULES_VALID,
+            Style::default().fg(theme::SUCCESS),
+        ),
+        Some(Err(e)) => Span::styled(format!("✗ {e}"), Style::default().fg(theme::ERROR)),
+    };
+    frame.render_widget(Paragraph::new(Line::from(status_line)), status);
+
+    let lines: Vec<Line> = preview.rules.lines().map(highlight_rule_line).collect();
+    let total_lines = lines.len();
+    frame.render_widget(
+        Paragraph::new(lines)
+            .style(Style::default().fg(theme::TEXT_PRIMARY))
+            .scroll((scroll, 0)),
+        content,
+    );
+
+    let scrollbar = Scrollbar::default()
+        .orientation(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(Some("↑"))
+        .end_symbol(Some("↓"))
+        .style(Style::default().fg(theme::NORD_POLAR_NIGHT_4))
+        .thumb_style(Style::default().fg(theme::ACCENT_PRIMARY));
+    let mut scrollbar_state =
+        ScrollbarState::new(total_lines.saturating_sub(content.height as usize))
+            .position(scroll as usize);
+    let scroll_area = Rect {
+        x: area.right().saturating_sub(1),
+        y: content.y,
+        width: 1,
+        height: content.height,
+    };
+    frame.render_stateful_widget(scrollbar, scroll_area, &mut scrollbar_state);
+}
+
+/// Comments dimmed, `block`/`pass` in red/green, keywords like config keys
+fn highlight_rule_line(line: &str) -> Line<'static> {
+    if line.trim_start().starts_with('#') {
+        return Line::from(Span::styled(
+            line.to_string(),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    let mut spans = Vec::new();
+    for (i, word) in line.split(' ').enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(" "));
+        }
+        let style = match word {
+            "block" => Style::default()
+                .fg(theme::ERROR)
+                .add_modifier(Modifier::BOLD),
+            "pass" => Style::default()
+                .fg(theme::SUCCESS)
+                .add_modifier(Modifier::BOLD),
+            w if KEYWORDS.contains(&w) => Style::default().fg(theme::NORD_FROST_2),
+            "!" | "{" | "}" => Style::default().fg(Color::DarkGray),
+            _ => Style::default().fg(theme::TEXT_PRIMARY),
+        };
+        spans.push(Span::styled(word.to_string(), style));
+    }
+    Line::from(spans)
+}
+
+/// Create a centered rectangle
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
@@ -2,4 +2,14 @@
 
 pub mod action_menu;
 pub mod config_viewer;
+pub mod credentials;
+pub mod duplicate;
+pub mod export;
+pub mod import_confirm;
+pub mod import_preview;
+pub mod killswitch_preview;
+pub mod new_profile;
+pub mod portal_window;
+pub mod qr;
+pub mod stale_killswitch;
 pub mod toast;
@@ -0,0 +1,138 @@
+//! New `WireGuard` profile wizard and its result screen
+
+use crate::constants;
+use crate::state::{NewProfileWizard, WizardField};
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Width reserved for field labels.
+const LABEL_WIDTH: usize = 15;
+
+/// Render the wizard form
+pub fn render(frame: &mut Frame, wizard: &NewProfileWizard) {
+    let area = centered(frame.area(), 22);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::ACCENT_PRIMARY))
+        .title(constants::TITLE_NEW_PROFILE)
+        .title_bottom(Line::from(constants::TITLE_NEW_PROFILE_FOOTER).centered());
+
+    let mut lines = vec![Line::from("")];
+    for (i, field) in WizardField::ALL.into_iter().enumerate() {
+        lines.push(field_line(field, wizard.value(field), i == wizard.focused));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        format!(" {}", constants::HINT_NEW_PROFILE),
+        Style::default().fg(theme::ACCENT_SECONDARY),
+    )));
+    if let Some(error) = &wizard.error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(" ✗ {error}"),
+            Style::default().fg(theme::ERROR),
+        )));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+/// Render the public key of a freshly created profile
+pub fn render_created(frame: &mut Frame, name: &str, public_key: &str) {
+    let area = centered(frame.area(), 10);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::SUCCESS))
+        .title(constants::TITLE_PROFILE_CREATED)
+        .title_bottom(Line::from(constants::TITLE_PROFILE_CREATED_FOOTER).centered());
+
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {name}"),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {}", constants::MSG_PROFILE_CREATED_HINT),
+            Style::default().fg(theme::TEXT_SECONDARY),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {public_key}"),
+            Style::default()
+                .fg(theme::ACCENT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )),
+    ];
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn field_line(field: WizardField, value: &str, focused: bool) -> Line<'static> {
+    let label_style = if focused {
+        Style::default()
+            .fg(theme::ACCENT_PRIMARY)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme::TEXT_SECONDARY)
+    };
+
+    let mut spans = vec![Span::styled(
+        format!(" {:<LABEL_WIDTH$}", field.label()),
+        label_style,
+    )];
+    if value.is_empty() && !focused {
+        spans.push(Span::styled(
+            field.placeholder(),
+            Style::default().fg(theme::TEXT_SECONDARY),
+        ));
+    } else {
+        spans.push(Span::styled(
+            value.to_string(),
+            Style::default().fg(theme::TEXT_PRIMARY),
+        ));
+    }
+    if focused {
+        spans.push(Span::styled(
+            "█",
+            Style::default()
+                .fg(theme::ACCENT_SECONDARY)
+                .add_modifier(Modifier::SLOW_BLINK),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// A 60%-wide rect of `height` rows centered in `area`
+fn centered(area: Rect, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
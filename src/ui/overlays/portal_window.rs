@@ -0,0 +1,72 @@
+//! Confirmation for a captive portal window in the kill switch
+
+use crate::constants;
+use crate::core::portal::Gateway;
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Render what the portal window would allow and for how long
+pub fn render(frame: &mut Frame, gateway: &Gateway, secs: u64, reason: &str) {
+    let area = centered(frame.area(), 68, 14);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::WARNING))
+        .title(constants::TITLE_PORTAL)
+        .title_bottom(Line::from(constants::TITLE_PORTAL_FOOTER).centered());
+
+    let detail = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(
+                format!("   {label:<10}"),
+                Style::default().fg(theme::TEXT_SECONDARY),
+            ),
+            Span::styled(value, Style::default().fg(theme::WARNING)),
+        ])
+    };
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {reason}"),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        detail("Allow", format!("HTTP/HTTPS to {}", gateway.network)),
+        detail(
+            "Gateway",
+            format!("{} on {} (DNS)", gateway.address, gateway.interface),
+        ),
+        detail("For", format!("{secs} seconds")),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {}", constants::MSG_PORTAL_REARM),
+            Style::default().fg(theme::TEXT_SECONDARY),
+        )),
+    ];
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Length(width.min(area.width))])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
@@ -0,0 +1,106 @@
+//! QR code overlays: the private key warning and the code itself
+
+use crate::constants;
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Render the warning shown before a QR code
+pub fn render_warning(frame: &mut Frame, profile: &str, exclude_hooks: bool) {
+    let area = centered(frame.area(), 60, 13);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::WARNING))
+        .title(constants::TITLE_QR_WARNING)
+        .title_bottom(Line::from(constants::TITLE_QR_WARNING_FOOTER).centered());
+
+    let checkbox = if exclude_hooks { "[x]" } else { "[ ]" };
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {profile}"),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {}", constants::QR_WARNING_TEXT),
+            Style::default().fg(theme::WARNING),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!(" {checkbox} "),
+                Style::default().fg(theme::ACCENT_PRIMARY),
+            ),
+            Span::styled(
+                constants::LABEL_QR_EXCLUDE_HOOKS,
+                Style::default().fg(theme::TEXT_SECONDARY),
+            ),
+        ]),
+    ];
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+/// Render a QR code dark-on-light, or a hint when the terminal is too small
+pub fn render_code(frame: &mut Frame, profile: &str, rows: &[String]) {
+    let code_width = rows.first().map_or(0, |r| r.chars().count());
+    let width = u16::try_from(code_width + 2).unwrap_or(u16::MAX);
+    let height = u16::try_from(rows.len() + 2).unwrap_or(u16::MAX);
+    let screen = frame.area();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::ACCENT_PRIMARY))
+        .title(format!(" {profile} "))
+        .title_bottom(Line::from(constants::TITLE_QR_FOOTER).centered());
+
+    if width > screen.width || height > screen.height {
+        let area = centered(screen, 60, 5);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(format!(
+                " Enlarge the terminal to at least {width}x{height} to show the QR code"
+            ))
+            .style(Style::default().fg(theme::WARNING))
+            .wrap(Wrap { trim: false })
+            .block(block),
+            area,
+        );
+        return;
+    }
+
+    let area = centered(screen, width, height);
+    frame.render_widget(Clear, area);
+    let code_style = Style::default().fg(Color::Black).bg(Color::White);
+    let lines: Vec<Line> = rows
+        .iter()
+        .map(|row| Line::from(Span::styled(row.as_str(), code_style)))
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// A `width` x `height` rect centered in `area` (clamped to it)
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Length(width.min(area.width))])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
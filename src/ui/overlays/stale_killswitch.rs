@@ -0,0 +1,62 @@
+//! Prompt for a kill switch left blocking by a vortix that is gone
+
+use crate::constants;
+use crate::state::KillSwitchMode;
+use crate::theme;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Render the restore/release choice for a stale kill switch
+pub fn render(frame: &mut Frame, pid: Option<u32>, mode: KillSwitchMode) {
+    let area = centered(frame.area(), 64, 11);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::WARNING))
+        .title(constants::TITLE_KS_STALE)
+        .title_bottom(Line::from(constants::TITLE_KS_STALE_FOOTER).centered());
+
+    let owner = pid.map_or_else(|| "unknown PID".to_string(), |pid| format!("PID {pid}"));
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {}", constants::MSG_KS_STALE),
+            Style::default()
+                .fg(theme::TEXT_PRIMARY)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" Mode: {mode:?}  Session: {owner} (not running)"),
+            Style::default().fg(theme::WARNING),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(" {}", constants::MSG_KS_STALE_CHOICES),
+            Style::default().fg(theme::TEXT_SECONDARY),
+        )),
+    ];
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Length(width.min(area.width))])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
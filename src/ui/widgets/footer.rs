@@ -23,11 +23,18 @@ pub fn render_dashboard(frame: &mut Frame, app: &App, area: Rect) {
         return;
     }
 
+    if let crate::app::InputMode::Search { .. } = app.input_mode {
+        let hints = vec![("↑↓", "Select"), ("Enter", "Keep Filter"), ("Esc", "Clear")];
+        render_hints(frame, area, &hints, Some("Search"));
+        return;
+    }
+
     // Determine focused panel name for display
     let panel_name = match &app.focused_panel {
         crate::app::FocusedPanel::Sidebar => "Profiles",
         crate::app::FocusedPanel::ConnectionDetails => "Details",
         crate::app::FocusedPanel::Chart => "Chart",
+        crate::app::FocusedPanel::Routes => "Routes",
         crate::app::FocusedPanel::Security => "Security",
         crate::app::FocusedPanel::Logs => "Logs",
     };
@@ -40,7 +47,12 @@ pub fn render_dashboard(frame: &mut Frame, app: &App, area: Rect) {
         hints.push(("1-9", "Quick Connect"));
     }
 
+    if !app.profile_filter.is_empty() {
+        hints.push(("Esc", "Clear Filter"));
+    }
+
     hints.extend_from_slice(&[
+        ("/", "Search"),
         ("i", "Import"),
         ("d", "Disconnect"),
         ("Tab", "Switch Panel"),
@@ -28,6 +28,45 @@ pub fn is_root() -> bool {
     false
 }
 
+/// Check whether a process with the given PID is still running.
+#[must_use]
+#[cfg(unix)]
+#[allow(unsafe_code)]
+pub fn is_process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: kill() with signal 0 only checks that the process exists and
+    // may be signalled; nothing is sent.
+    let alive = unsafe { libc::kill(pid, 0) } == 0;
+    // EPERM: it exists but belongs to another user (e.g. vortix under sudo)
+    alive || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Check whether a process with the given PID is still running.
+///
+/// Always `true` on non-Unix platforms, where this cannot be checked.
+#[must_use]
+#[cfg(not(unix))]
+pub fn is_process_alive(_pid: u32) -> bool {
+    true
+}
+
+/// When the process with the given PID started, as `ps` prints it.
+///
+/// A PID can be reused after its process exits (or after a reboot); the
+/// start time tells a new process from the one that was recorded. `None`
+/// when there is no such process or `ps` is unavailable.
+#[must_use]
+pub fn process_start_time(pid: u32) -> Option<String> {
+    let output = Command::new("ps")
+        .args(["-o", "lstart=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let started = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !started.is_empty()).then_some(started)
+}
+
 /// Formats bytes per second into a human-readable string.
 ///
 /// # Arguments
@@ -85,6 +124,77 @@ pub fn is_private_ip(ip: &str) -> bool {
     }
 }
 
+/// Decode standard (padded) base64, as used for `WireGuard` keys.
+///
+/// Returns `None` for invalid characters, bad padding or non-canonical
+/// trailing bits.
+pub fn base64_decode(input: &str) -> Option<Vec<u8>> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some(u32::from(c - b'A')),
+            b'a'..=b'z' => Some(u32::from(c - b'a') + 26),
+            b'0'..=b'9' => Some(u32::from(c - b'0') + 52),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let bytes = input.as_bytes();
+    if bytes.is_empty() || bytes.len() % 4 != 0 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() / 4 * 3);
+    for (i, chunk) in bytes.chunks(4).enumerate() {
+        let is_last = i == bytes.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 2 || (padding > 0 && !is_last) {
+            return None;
+        }
+
+        let mut acc = 0u32;
+        for &c in &chunk[..4 - padding] {
+            acc = (acc << 6) | value(c)?;
+        }
+        for _ in 0..padding {
+            acc <<= 6;
+        }
+
+        let [_, b0, b1, b2] = acc.to_be_bytes();
+        let decoded = [b0, b1, b2];
+        let keep = 3 - padding;
+        // Reject encodings whose unused trailing bits are set
+        if decoded[keep..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        out.extend_from_slice(&decoded[..keep]);
+    }
+    Some(out)
+}
+
+/// Encode bytes as standard (padded) base64.
+pub fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let mut buf = [0u8; 3];
+        buf[..chunk.len()].copy_from_slice(chunk);
+        let acc = u32::from_be_bytes([0, buf[0], buf[1], buf[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(
+                    ALPHABET[((acc >> (18 - 6 * i)) & 0x3f) as usize],
+                ));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
 /// Returns the application configuration directory path.
 ///
 /// Creates the directory at `~/.config/vortix` if it doesn't exist.
@@ -153,6 +263,17 @@ pub fn format_local_time() -> String {
     )
 }
 
+/// Current UTC time as RFC 3339 (`2026-01-31T18:04:05Z`), for reports.
+pub fn format_utc_timestamp() -> String {
+    Command::new("date")
+        .args(["-u", "+%Y-%m-%dT%H:%M:%SZ"])
+        .output()
+        .map_or_else(
+            |_| "unknown".to_string(),
+            |o| String::from_utf8_lossy(&o.stdout).trim().to_string(),
+        )
+}
+
 /// Formats a `SystemTime` into a compact relative time string (e.g., 1s, 2m, 3h, 4d).
 pub fn format_relative_time(time: std::time::SystemTime) -> String {
     let now = std::time::SystemTime::now();
@@ -187,7 +308,7 @@ pub fn home_dir() -> Option<std::path::PathBuf> {
 }
 
 /// Profile metadata for persistence
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct ProfileMetadata {
     #[serde(
         with = "systemtime_serde",
@@ -195,6 +316,36 @@ pub struct ProfileMetadata {
         default
     )]
     pub last_used: Option<std::time::SystemTime>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "is_zero", default)]
+    pub connect_count: u32,
+    /// Location chosen by the user instead of the derived one.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub location: Option<String>,
+    #[serde(
+        skip_serializing_if = "crate::core::split_tunnel::SplitTunnel::is_empty",
+        default
+    )]
+    pub split_tunnel: crate::core::split_tunnel::SplitTunnel,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+/// Sidebar layout preferences, persisted across restarts
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+pub struct SidebarPrefs {
+    #[serde(default)]
+    pub group_by: crate::state::GroupBy,
+    #[serde(default)]
+    pub sort_by: crate::state::SortBy,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub collapsed_groups: Vec<String>,
 }
 
 mod systemtime_serde {
@@ -270,6 +421,45 @@ pub fn save_profile_metadata(
     Ok(())
 }
 
+/// Load sidebar preferences from disk, falling back to defaults
+pub fn load_sidebar_prefs() -> SidebarPrefs {
+    let Ok(dir) = get_app_config_dir() else {
+        return SidebarPrefs::default();
+    };
+    let path = dir.join(crate::constants::SIDEBAR_PREFS_FILE_NAME);
+
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return SidebarPrefs::default();
+    };
+
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        crate::logger::log(
+            crate::logger::LogLevel::Warning,
+            "CONFIG",
+            format!(
+                "Failed to parse {}: {}. Using defaults.",
+                crate::constants::SIDEBAR_PREFS_FILE_NAME,
+                e
+            ),
+        );
+        SidebarPrefs::default()
+    })
+}
+
+/// Save sidebar preferences to disk
+pub fn save_sidebar_prefs(prefs: &SidebarPrefs) -> Result<(), String> {
+    let path = get_app_config_dir()
+        .map_err(|e| format!("Failed to get config dir: {e}"))?
+        .join(crate::constants::SIDEBAR_PREFS_FILE_NAME);
+
+    let json = serde_json::to_string_pretty(prefs)
+        .map_err(|e| format!("Failed to serialize sidebar prefs: {e}"))?;
+
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write sidebar prefs: {e}"))?;
+
+    Ok(())
+}
+
 /// Returns a unique path by appending (n) if the file already exists.
 ///
 /// # Arguments
@@ -333,6 +523,27 @@ mod tests {
         assert_eq!(format_bytes_speed(100_000_000), "100.0 MB/s");
     }
 
+    #[test]
+    fn test_base64_decode() {
+        assert_eq!(base64_decode("aGVsbG8="), Some(b"hello".to_vec()));
+        assert_eq!(base64_decode("aGk="), Some(b"hi".to_vec()));
+        assert_eq!(base64_decode("YWJj"), Some(b"abc".to_vec()));
+        assert_eq!(base64_decode("aGVsbG8"), None); // missing padding
+        assert_eq!(base64_decode("aGk*"), None); // bad character
+        assert_eq!(base64_decode("aGl="), None); // non-canonical trailing bits
+        assert_eq!(base64_decode("=aGk"), None);
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b"hello"), "aGVsbG8=");
+        assert_eq!(base64_encode(b"hi"), "aGk=");
+        assert_eq!(base64_encode(b"abc"), "YWJj");
+        assert_eq!(base64_encode(b""), "");
+        let key = [0xffu8; 32];
+        assert_eq!(base64_decode(&base64_encode(&key)), Some(key.to_vec()));
+    }
+
     #[test]
     fn test_truncate_short_string() {
         assert_eq!(truncate("hello", 10), "hello");
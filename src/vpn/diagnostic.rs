@@ -0,0 +1,66 @@
+//! Line-numbered findings produced by the profile parsers.
+
+use std::fmt;
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The config cannot be used as-is.
+    Error,
+    /// The config works but is probably not what the user wants.
+    Warning,
+}
+
+/// A problem found while parsing a config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 1-based line number, `None` for whole-file findings.
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl Diagnostic {
+    /// An error at the given line.
+    pub fn error(line: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+
+    /// A warning at the given line.
+    pub fn warning(line: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+}
+
+/// Turn parser diagnostics into an import decision.
+///
+/// Returns the warnings if there are no errors, otherwise every error joined
+/// into one message prefixed with `kind` (e.g. `"Invalid WireGuard config"`).
+pub fn into_result(diagnostics: &[Diagnostic], kind: &str) -> Result<Vec<String>, String> {
+    let (errors, warnings): (Vec<_>, Vec<_>) = diagnostics
+        .iter()
+        .partition(|d| d.severity == Severity::Error);
+    if errors.is_empty() {
+        Ok(warnings.iter().map(ToString::to_string).collect())
+    } else {
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        Err(format!("{kind}: {}", errors.join("; ")))
+    }
+}
@@ -1,57 +1,52 @@
 //! VPN profile import functionality
 
+pub mod diagnostic;
+pub mod openvpn;
+pub mod wireguard;
+
+use crate::constants;
+use crate::core::killswitch::ResolvedEndpoint;
+use crate::core::vault;
 use crate::logger::{self, LogLevel};
 use crate::state::{Protocol, VpnProfile};
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
+use zeroize::Zeroize;
 
 /// Import a VPN profile from a file
-pub fn import_profile(path: &Path) -> Result<VpnProfile, String> {
+///
+/// Returns the imported profile together with non-fatal validation warnings
+/// (e.g. a full tunnel that does not route IPv6).
+pub fn import_profile(path: &Path) -> Result<(VpnProfile, Vec<String>), String> {
+    import_as(path, None)
+}
+
+/// Import `path`, stored at `dest` when given instead of a free file name
+/// derived from the config.
+fn import_as(path: &Path, dest: Option<&Path>) -> Result<(VpnProfile, Vec<String>), String> {
     logger::log(
         LogLevel::Debug,
         "IMPORT",
         format!("Importing profile from: {}", path.display()),
     );
 
-    // Check file exists
-    if !path.exists() {
-        logger::log(
-            LogLevel::Error,
-            "IMPORT",
-            format!("File not found: {}", path.display()),
-        );
-        return Err(format!("File not found: {}", path.display()));
-    }
-
-    // Determine protocol from extension
+    let (protocol, content) = read_source(path)?;
     let extension = path
         .extension()
         .and_then(|e| e.to_str())
         .unwrap_or("")
         .to_lowercase();
 
-    let protocol = match extension.as_str() {
-        "conf" => Protocol::WireGuard,
-        "ovpn" => Protocol::OpenVPN,
-        _ => {
-            logger::log(
-                LogLevel::Error,
-                "IMPORT",
-                format!("Unsupported file type: .{extension}"),
-            );
-            return Err(format!("Unsupported file type: .{extension}"));
-        }
-    };
+    if vault::is_enabled() && !vault::is_unlocked() {
+        logger::log(LogLevel::Error, "IMPORT", constants::MSG_VAULT_LOCKED);
+        return Err(constants::MSG_VAULT_LOCKED.to_string());
+    }
 
-    // Read and parse the file
-    let content = fs::read_to_string(path).map_err(|e| {
-        logger::log(
-            LogLevel::Error,
-            "IMPORT",
-            format!("Failed to read file: {e}"),
-        );
-        format!("Failed to read file: {e}")
+    // Validate before anything is copied into the profiles directory
+    let mut warnings = validate_config(protocol, &content).map_err(|e| {
+        logger::log(LogLevel::Error, "IMPORT", &e);
+        e
     })?;
 
     // Extract profile info
@@ -65,7 +60,10 @@ pub fn import_profile(path: &Path) -> Result<VpnProfile, String> {
     let dest_filename = format!("{name}.{extension}");
 
     // Ensure unique destination path to avoid overwriting existing profiles
-    let dest_path = crate::utils::get_unique_path(&profiles_dir, &dest_filename);
+    let dest_path = dest.map_or_else(
+        || crate::utils::get_unique_path(&profiles_dir, &dest_filename),
+        Path::to_path_buf,
+    );
 
     // Update name if filename changed (e.g. from "client" to "client(1)")
     let name = dest_path
@@ -74,14 +72,7 @@ pub fn import_profile(path: &Path) -> Result<VpnProfile, String> {
         .unwrap_or(&name)
         .to_string();
 
-    fs::copy(path, &dest_path).map_err(|e| {
-        logger::log(
-            LogLevel::Error,
-            "IMPORT",
-            format!("Failed to copy profile: {e}"),
-        );
-        format!("Failed to copy profile: {e}")
-    })?;
+    warnings.extend(write_profile(protocol, &content, path, &dest_path)?);
 
     // Secure the file (chmod 600)
     let mut perms = fs::metadata(&dest_path)
@@ -101,16 +92,656 @@ pub fn import_profile(path: &Path) -> Result<VpnProfile, String> {
             dest_path.display()
         ),
     );
+    for warning in &warnings {
+        logger::log(LogLevel::Warning, "IMPORT", format!("{name}: {warning}"));
+    }
 
-    Ok(VpnProfile {
+    let profile = VpnProfile {
         name,
         protocol,
         location,
+        custom_location: None,
         config_path: dest_path,
         last_used: None,
+        tags: Vec::new(),
+        last_latency_ms: None,
+        connect_count: 0,
+        split_tunnel: crate::core::split_tunnel::SplitTunnel::default(),
+    };
+    Ok((profile, warnings))
+}
+
+/// What importing a file would produce.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preview {
+    pub protocol: Protocol,
+    /// Name the profile would be stored under.
+    pub name: String,
+    pub location: String,
+    /// First server, as `host:port`.
+    pub endpoint: Option<String>,
+    pub dns: Vec<String>,
+    /// Networks routed through the tunnel (`WireGuard` only; `OpenVPN`
+    /// routes are usually pushed by the server).
+    pub allowed_ips: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Check a config the way [`import_profile`] does, without storing it.
+pub fn preview_profile(path: &Path) -> Result<Preview, String> {
+    let (protocol, content) = read_source(path)?;
+    let mut warnings = validate_config(protocol, &content)?;
+    let (name, location) = match protocol {
+        Protocol::WireGuard => parse_wireguard_config(&content, path)?,
+        Protocol::OpenVPN => {
+            warnings.extend(check_openvpn_files(&content, path)?);
+            parse_openvpn_config(&content, path)?
+        }
+    };
+
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let dest = crate::utils::get_unique_path(&get_profiles_dir()?, &format!("{name}.{extension}"));
+    let name = dest
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&name)
+        .to_string();
+
+    let (endpoint, dns, allowed_ips) = match protocol {
+        Protocol::WireGuard => {
+            let (config, _) = wireguard::parse(&content);
+            (
+                config.endpoint().map(ToString::to_string),
+                config.interface.dns.clone(),
+                config
+                    .peers
+                    .iter()
+                    .flat_map(|p| p.allowed_ips.iter().map(ToString::to_string))
+                    .collect(),
+            )
+        }
+        Protocol::OpenVPN => {
+            let (config, _) = openvpn::parse(&content);
+            let dns = openvpn::dhcp_dns(&content);
+            (
+                config
+                    .remotes
+                    .first()
+                    .map(|r| format!("{}:{}", r.host, r.port)),
+                dns,
+                Vec::new(),
+            )
+        }
+    };
+
+    Ok(Preview {
+        protocol,
+        name,
+        location,
+        endpoint,
+        dns,
+        allowed_ips,
+        warnings,
     })
 }
 
+/// Import a VPN profile into `profiles_dir` under a name chosen by the user.
+///
+/// Unlike [`import_profile`] this fails instead of picking a free name when
+/// the profile exists.
+pub fn import_profile_named(
+    path: &Path,
+    name: &str,
+    profiles_dir: &Path,
+) -> Result<(VpnProfile, Vec<String>), String> {
+    let name = name.trim();
+    check_profile_name(name)?;
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let dest = profiles_dir.join(format!("{name}.{extension}"));
+    if dest.exists() {
+        return Err(format!("A profile named '{name}' already exists"));
+    }
+    import_as(path, Some(&dest))
+}
+
+/// Reject names that cannot be used as a file name in the profiles
+/// directory.
+fn check_profile_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("'{name}' is not a valid profile name"));
+    }
+    Ok(())
+}
+
+/// Read a config, taking its protocol from the file extension.
+fn read_source(path: &Path) -> Result<(Protocol, String), String> {
+    // Check file exists
+    if !path.exists() {
+        logger::log(
+            LogLevel::Error,
+            "IMPORT",
+            format!("File not found: {}", path.display()),
+        );
+        return Err(format!("File not found: {}", path.display()));
+    }
+
+    // Determine protocol from extension
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let protocol = match extension.as_str() {
+        "conf" => Protocol::WireGuard,
+        "ovpn" => Protocol::OpenVPN,
+        _ => {
+            logger::log(
+                LogLevel::Error,
+                "IMPORT",
+                format!("Unsupported file type: .{extension}"),
+            );
+            return Err(format!("Unsupported file type: .{extension}"));
+        }
+    };
+
+    // Read and parse the file
+    let content = fs::read_to_string(path).map_err(|e| {
+        logger::log(
+            LogLevel::Error,
+            "IMPORT",
+            format!("Failed to read file: {e}"),
+        );
+        format!("Failed to read file: {e}")
+    })?;
+    Ok((protocol, content))
+}
+
+/// Create a `WireGuard` profile with a freshly generated key pair.
+///
+/// The rendered config is staged in a private scratch directory and stored
+/// through [`import_profile`], so it gets the same validation, vault
+/// encryption and permissions as an imported file. Returns the profile, its
+/// public key (to register with the server) and any warnings.
+pub fn create_wireguard_profile(
+    name: &str,
+    spec: &wireguard::NewProfile,
+) -> Result<(VpnProfile, String, Vec<String>), String> {
+    let name = name.trim();
+    check_profile_name(name)?;
+    if get_profiles_dir()?.join(format!("{name}.conf")).exists() {
+        return Err(format!("A profile named '{name}' already exists"));
+    }
+    spec.validate()?;
+
+    let keys = wireguard::generate_keypair()?;
+    let mut content = spec.render(&keys.private_key);
+    let staging = vault::ScratchDir::new("new")?;
+    let path = staging.path.join(format!("{name}.conf"));
+    let written = vault::write_private(&path, content.as_bytes());
+    content.zeroize();
+    written.map_err(|e| format!("Failed to write profile: {e}"))?;
+
+    let (profile, warnings) = import_profile(&path)?;
+    Ok((profile, keys.public_key.clone(), warnings))
+}
+
+/// Validate a config before import.
+///
+/// Returns warnings on success, or all errors (with line numbers) joined into
+/// a single message.
+fn validate_config(protocol: Protocol, content: &str) -> Result<Vec<String>, String> {
+    match protocol {
+        Protocol::WireGuard => {
+            let (_, diagnostics) = wireguard::parse(content);
+            diagnostic::into_result(&diagnostics, "Invalid WireGuard config")
+        }
+        Protocol::OpenVPN => {
+            let (_, diagnostics) = openvpn::parse(content);
+            diagnostic::into_result(&diagnostics, "Invalid OpenVPN config")
+        }
+    }
+}
+
+/// Place a validated config at `dest_path`.
+///
+/// `WireGuard` configs are stored as-is. `OpenVPN` configs get their relative
+/// file references bundled (see `bundle_openvpn_files`) since those would
+/// break once the config is moved. Everything is encrypted when the vault is
+/// enabled. Returns any extra warnings.
+fn write_profile(
+    protocol: Protocol,
+    content: &str,
+    source: &Path,
+    dest_path: &Path,
+) -> Result<Vec<String>, String> {
+    if protocol == Protocol::WireGuard {
+        vault::write_file(dest_path, content.as_bytes()).map_err(|e| {
+            logger::log(
+                LogLevel::Error,
+                "IMPORT",
+                format!("Failed to copy profile: {e}"),
+            );
+            format!("Failed to copy profile: {e}")
+        })?;
+        return Ok(Vec::new());
+    }
+
+    let (rewritten, warnings) = bundle_openvpn_files(content, source, dest_path).map_err(|e| {
+        logger::log(LogLevel::Error, "IMPORT", &e);
+        e
+    })?;
+    vault::write_file(dest_path, rewritten.as_bytes()).map_err(|e| {
+        let _ = fs::remove_dir_all(companion_dir(dest_path));
+        logger::log(
+            LogLevel::Error,
+            "IMPORT",
+            format!("Failed to write profile: {e}"),
+        );
+        format!("Failed to write profile: {e}")
+    })?;
+    Ok(warnings)
+}
+
+/// Replace a stored profile with the config at `source`, keeping its file
+/// name so metadata keyed by the path carries over.
+///
+/// The new config is imported under a hidden staging name next to the old
+/// one, and only swapped in once that succeeded, so a config that fails
+/// validation or references a missing file leaves the stored profile as it
+/// was.
+pub fn replace_profile(
+    config_path: &Path,
+    source: &Path,
+) -> Result<(VpnProfile, Vec<String>), String> {
+    let extension = |path: &Path| {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+    };
+    let protocol = match extension(source).as_deref() {
+        Some("conf") => Protocol::WireGuard,
+        Some("ovpn") => Protocol::OpenVPN,
+        _ => return Err(format!("Unsupported file: {}", source.display())),
+    };
+    if extension(config_path) != extension(source) {
+        return Err(format!(
+            "Cannot replace {} with a different kind of profile",
+            config_path.display()
+        ));
+    }
+    let content = fs::read_to_string(source).map_err(|e| format!("Failed to read file: {e}"))?;
+    validate_config(protocol, &content)?;
+
+    let file_name = config_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid profile path {}", config_path.display()))?;
+    let staged = config_path.with_file_name(format!(".replacing.{file_name}"));
+    remove_profile(&staged);
+    let (mut profile, warnings) = import_as(source, Some(&staged)).map_err(|e| {
+        remove_profile(&staged);
+        e
+    })?;
+    swap_in(&staged, config_path).map_err(|e| {
+        remove_profile(&staged);
+        e
+    })?;
+
+    profile.name = config_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&profile.name)
+        .to_string();
+    profile.config_path = config_path.to_path_buf();
+    Ok((profile, warnings))
+}
+
+/// Move a profile stored at `staged` (and its companion files) to
+/// `config_path`, replacing what is there.
+///
+/// References to the staged companion directory are rewritten to the final
+/// one, and the config itself is replaced last with an atomic write.
+fn swap_in(staged: &Path, config_path: &Path) -> Result<(), String> {
+    let staged_files = companion_dir(staged);
+    let files_dir = companion_dir(config_path);
+    let mut content = vault::read_config(staged)?;
+
+    if staged_files.is_dir() {
+        let (config, _) = openvpn::parse(&content);
+        let replacements: Vec<_> = config
+            .file_refs
+            .iter()
+            .filter_map(|file_ref| {
+                let relative = Path::new(&file_ref.path).strip_prefix(&staged_files).ok()?;
+                Some((
+                    file_ref.line,
+                    file_ref.path.clone(),
+                    files_dir.join(relative).to_string_lossy().into_owned(),
+                ))
+            })
+            .collect();
+        let rewritten = openvpn::rewrite_file_refs(&content, &replacements);
+        content.zeroize();
+        content = rewritten;
+    }
+
+    let _ = fs::remove_dir_all(&files_dir);
+    let moved = if staged_files.is_dir() {
+        fs::rename(&staged_files, &files_dir)
+            .map_err(|e| format!("Failed to move profile files: {e}"))
+    } else {
+        Ok(())
+    };
+    let written = moved.and_then(|()| vault::write_file(config_path, content.as_bytes()));
+    content.zeroize();
+    written?;
+    let _ = fs::remove_file(staged);
+    Ok(())
+}
+
+/// Delete a stored profile and its companion files.
+pub fn remove_profile(config_path: &Path) {
+    if config_path.exists() {
+        let _ = fs::remove_file(config_path);
+    }
+    let files_dir = companion_dir(config_path);
+    if files_dir.is_dir() {
+        let _ = fs::remove_dir_all(&files_dir);
+    }
+}
+
+/// Directory holding the files an `OpenVPN` profile references
+/// (e.g. `profiles/work.files/` for `profiles/work.ovpn`).
+pub fn companion_dir(config_path: &Path) -> PathBuf {
+    config_path.with_extension("files")
+}
+
+/// Log file `OpenVPN` writes for a profile, so options pushed by the server
+/// can be read back (e.g. `logs/openvpn-work.log`).
+pub fn openvpn_log_path(config_path: &Path) -> Option<PathBuf> {
+    let stem = config_path.file_stem()?.to_str()?;
+    let dir = crate::utils::get_app_config_dir()
+        .ok()?
+        .join(constants::LOGS_DIR_NAME);
+    std::fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(format!("openvpn-{stem}.log")))
+}
+
+/// DNS servers a connected profile is expected to use: `DNS =` for
+/// `WireGuard`; `dhcp-option DNS` from the config and the server's push
+/// reply for `OpenVPN`. Search domains are left out.
+pub fn expected_dns(profile: &VpnProfile) -> Vec<String> {
+    let content = vault::read_config(&profile.config_path).unwrap_or_default();
+    let mut servers = match profile.protocol {
+        Protocol::WireGuard => wireguard::parse(&content).0.interface.dns,
+        Protocol::OpenVPN => {
+            let mut servers = openvpn::dhcp_dns(&content);
+            if let Some(log) = openvpn_log_path(&profile.config_path)
+                .and_then(|path| std::fs::read_to_string(path).ok())
+            {
+                servers.extend(openvpn::pushed_dns(&log));
+            }
+            servers
+        }
+    };
+    servers.retain(|s| s.parse::<std::net::IpAddr>().is_ok());
+    servers.dedup();
+    servers
+}
+
+/// Every server a profile may connect to: each peer's `Endpoint` host for
+/// `WireGuard`; each `remote` for `OpenVPN`, including those inside
+/// `<connection>` blocks it fails over to.
+pub fn server_hosts(profile: &VpnProfile) -> Vec<String> {
+    let content = vault::read_config(&profile.config_path).unwrap_or_default();
+    hosts_in(profile.protocol, &content)
+}
+
+/// `content` with each server hostname replaced by its addresses from
+/// `endpoints`, so `wg-quick` or `openvpn` can connect without DNS while the
+/// kill switch blocks it. `None` if no host needed replacing.
+///
+/// A `WireGuard` `Endpoint` takes one address, IPv4 first. A top-level
+/// `OpenVPN` `remote` becomes one entry per address to keep failing over
+/// between them; one inside a `<connection>` block, which allows a single
+/// `remote`, takes the first.
+pub fn pin_servers(
+    protocol: Protocol,
+    content: &str,
+    endpoints: &[ResolvedEndpoint],
+) -> Option<String> {
+    let addresses = |host: &str| -> Vec<&str> {
+        if host.parse::<std::net::IpAddr>().is_ok() {
+            return Vec::new();
+        }
+        let mut addresses: Vec<&str> = endpoints
+            .iter()
+            .find(|e| e.host == host)
+            .map(|e| e.addresses.iter().map(String::as_str).collect())
+            .unwrap_or_default();
+        addresses.sort_by_key(|a| a.contains(':'));
+        addresses
+    };
+
+    let mut pinned = false;
+    let mut out = String::with_capacity(content.len());
+    match protocol {
+        Protocol::WireGuard => {
+            for line in content.split_inclusive('\n') {
+                let trimmed = line.split('#').next().unwrap_or("").trim();
+                let endpoint = trimmed
+                    .split_once('=')
+                    .filter(|(key, _)| key.trim().eq_ignore_ascii_case("Endpoint"))
+                    .and_then(|(_, value)| value.trim().parse::<wireguard::Endpoint>().ok());
+                let Some(mut endpoint) = endpoint else {
+                    out.push_str(line);
+                    continue;
+                };
+                let Some(address) = addresses(&endpoint.host).first().copied() else {
+                    out.push_str(line);
+                    continue;
+                };
+                endpoint.host = address.to_string();
+                let ending = &line[line.trim_end().len()..];
+                out.push_str("Endpoint = ");
+                out.push_str(&endpoint.to_string());
+                out.push_str(ending);
+                pinned = true;
+            }
+        }
+        Protocol::OpenVPN => {
+            let (config, _) = openvpn::parse(content);
+            let mut in_connection = false;
+            for (idx, line) in content.split_inclusive('\n').enumerate() {
+                let trimmed = line.trim();
+                if trimmed == "<connection>" {
+                    in_connection = true;
+                } else if trimmed == "</connection>" {
+                    in_connection = false;
+                }
+                let Some(remote) = config.remotes.iter().find(|r| r.line == idx + 1) else {
+                    out.push_str(line);
+                    continue;
+                };
+                let mut addresses = addresses(&remote.host);
+                if addresses.is_empty() {
+                    out.push_str(line);
+                    continue;
+                }
+                if in_connection {
+                    addresses.truncate(1);
+                }
+                let indent = line.len() - line.trim_start().len();
+                let directive_end = line[indent..]
+                    .find(char::is_whitespace)
+                    .map_or(line.len(), |i| indent + i);
+                let (head, tail) = line.split_at(directive_end);
+                let ending = &line[line.trim_end().len()..];
+                let entries: Vec<String> = addresses
+                    .iter()
+                    .map(|address| {
+                        format!(
+                            "{head}{}",
+                            tail.trim_end().replacen(&remote.host, address, 1)
+                        )
+                    })
+                    .collect();
+                out.push_str(&entries.join(if ending.is_empty() { "\n" } else { ending }));
+                out.push_str(ending);
+                pinned = true;
+            }
+        }
+    }
+    pinned.then_some(out)
+}
+
+/// Whether a `WireGuard` profile sets `PersistentKeepalive` on a peer, so
+/// its tunnel keeps handshaking while idle.
+pub fn has_keepalive(profile: &VpnProfile) -> bool {
+    if profile.protocol != Protocol::WireGuard {
+        return false;
+    }
+    let content = vault::read_config(&profile.config_path).unwrap_or_default();
+    wireguard::parse(&content)
+        .0
+        .peers
+        .iter()
+        .any(|p| p.persistent_keepalive.is_some_and(|secs| secs > 0))
+}
+
+/// Networks a `WireGuard` profile routes through its tunnel (every peer's
+/// `AllowedIPs`), or `None` for `OpenVPN`, whose routes are pushed.
+pub fn allowed_ips(profile: &VpnProfile) -> Option<Vec<wireguard::Cidr>> {
+    if profile.protocol != Protocol::WireGuard {
+        return None;
+    }
+    let content = vault::read_config(&profile.config_path).unwrap_or_default();
+    let (config, _) = wireguard::parse(&content);
+    Some(
+        config
+            .peers
+            .into_iter()
+            .flat_map(|p| p.allowed_ips)
+            .collect(),
+    )
+}
+
+fn hosts_in(protocol: Protocol, content: &str) -> Vec<String> {
+    let hosts: Vec<String> = match protocol {
+        Protocol::WireGuard => wireguard::parse(content)
+            .0
+            .peers
+            .into_iter()
+            .filter_map(|p| p.endpoint.map(|e| e.host))
+            .collect(),
+        Protocol::OpenVPN => openvpn::parse(content)
+            .0
+            .remotes
+            .into_iter()
+            .map(|r| r.host)
+            .collect(),
+    };
+    let mut unique = Vec::new();
+    for host in hosts {
+        if !unique.contains(&host) {
+            unique.push(host);
+        }
+    }
+    unique
+}
+
+/// Copy relatively referenced files (`ca`, `cert`, `tls-auth`, ...) into the
+/// profile's companion directory and rewrite the config to use them.
+///
+/// Returns the rewritten config and warnings for absolute references that
+/// do not exist. A missing relative reference is an error.
+fn bundle_openvpn_files(
+    content: &str,
+    source: &Path,
+    dest_path: &Path,
+) -> Result<(String, Vec<String>), String> {
+    let warnings = check_openvpn_files(content, source)?;
+    let (config, _) = openvpn::parse(content);
+    let base = source.parent().unwrap_or_else(|| Path::new("."));
+    let files_dir = companion_dir(dest_path);
+
+    let mut copied: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let mut replacements = Vec::new();
+
+    for file_ref in &config.file_refs {
+        if !file_ref.is_relative() {
+            continue;
+        }
+
+        let src = base.join(&file_ref.path);
+        let dest = if let Some((_, dest)) = copied.iter().find(|(s, _)| *s == src) {
+            dest.clone()
+        } else {
+            let file_name = src
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("file")
+                .to_string();
+            let copy_err = |e: String| {
+                let _ = fs::remove_dir_all(&files_dir);
+                format!("Failed to copy {}: {e}", src.display())
+            };
+            fs::create_dir_all(&files_dir).map_err(|e| copy_err(e.to_string()))?;
+            let dest = crate::utils::get_unique_path(&files_dir, &file_name);
+            let data = fs::read(&src).map_err(|e| copy_err(e.to_string()))?;
+            vault::write_file(&dest, &data).map_err(copy_err)?;
+            copied.push((src, dest.clone()));
+            dest
+        };
+
+        replacements.push((
+            file_ref.line,
+            file_ref.path.clone(),
+            dest.to_string_lossy().into_owned(),
+        ));
+    }
+
+    Ok((openvpn::rewrite_file_refs(content, &replacements), warnings))
+}
+
+/// The checks [`bundle_openvpn_files`] makes, without copying anything:
+/// warnings for missing absolute references, an error for a missing
+/// relative one.
+fn check_openvpn_files(content: &str, source: &Path) -> Result<Vec<String>, String> {
+    let (config, _) = openvpn::parse(content);
+    let base = source.parent().unwrap_or_else(|| Path::new("."));
+
+    let mut warnings = Vec::new();
+    for file_ref in &config.file_refs {
+        if !file_ref.is_relative() {
+            if !Path::new(&file_ref.path).is_file() {
+                warnings.push(format!(
+                    "line {}: {} file '{}' does not exist",
+                    file_ref.line, file_ref.directive, file_ref.path
+                ));
+            }
+        } else if !base.join(&file_ref.path).is_file() {
+            return Err(format!(
+                "Invalid OpenVPN config: line {}: {} file '{}' not found next to {}",
+                file_ref.line,
+                file_ref.directive,
+                file_ref.path,
+                source.display()
+            ));
+        }
+    }
+    Ok(warnings)
+}
+
 /// Parse `WireGuard` config file
 fn parse_wireguard_config(content: &str, path: &Path) -> Result<(String, String), String> {
     // Extract name from filename
@@ -120,20 +751,9 @@ fn parse_wireguard_config(content: &str, path: &Path) -> Result<(String, String)
         .unwrap_or("unknown")
         .to_string();
 
-    // Look for Endpoint in [Peer] section
-    let mut server = String::new();
-    for line in content.lines() {
-        let line = line.trim();
-        if line.to_lowercase().starts_with("endpoint") {
-            if let Some(value) = line.split('=').nth(1) {
-                // Format: Endpoint = server:port
-                server = value.trim().split(':').next().unwrap_or("").to_string();
-                break;
-            }
-        }
-    }
-
-    if server.is_empty() {
+    // Only the endpoint is required here; full validation happens on import
+    let (config, _) = wireguard::parse(content);
+    if config.endpoint().is_none() {
         return Err("No Endpoint found in WireGuard config".to_string());
     }
 
@@ -152,21 +772,9 @@ fn parse_openvpn_config(content: &str, path: &Path) -> Result<(String, String),
         .unwrap_or("unknown")
         .to_string();
 
-    // Look for 'remote' directive
-    let mut server = String::new();
-    for line in content.lines() {
-        let line = line.trim();
-        if line.to_lowercase().starts_with("remote ") {
-            // Format: remote server port
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 {
-                server = parts[1].to_string();
-                break;
-            }
-        }
-    }
-
-    if server.is_empty() {
+    // Only a remote is required here; full validation happens on import
+    let (config, _) = openvpn::parse(content);
+    if config.remotes.is_empty() {
         return Err("No 'remote' directive found in OpenVPN config".to_string());
     }
 
@@ -175,6 +783,17 @@ fn parse_openvpn_config(content: &str, path: &Path) -> Result<(String, String),
     Ok((name, location))
 }
 
+/// Name and location for an encrypted profile while the vault is locked.
+fn locked_profile_info(path: &Path) -> (String, String) {
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    let location = derive_location_from_name(&name);
+    (name, location)
+}
+
 /// Derive location from profile name
 ///
 /// IMPORTANT: This is a best-effort heuristic based on common naming patterns.
@@ -313,54 +932,61 @@ pub fn load_profiles() -> Vec<VpnProfile> {
     if let Ok(entries) = fs::read_dir(&profiles_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.is_file() {
+            // Hidden files are staging copies (see `replace_profile`)
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if path.is_file() && !hidden {
                 let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
                 if ext == "conf" || ext == "ovpn" {
-                    if let Ok(content) = fs::read_to_string(&path) {
-                        let result = match ext {
-                            "conf" => parse_wireguard_config(&content, &path),
-                            "ovpn" => parse_openvpn_config(&content, &path),
-                            _ => continue,
-                        };
-
-                        match result {
-                            Ok((name, location)) => {
-                                // Enforce secure permissions (chmod 600) whenever loaded
-                                if let Ok(metadata) = fs::metadata(&path) {
-                                    let mut perms = metadata.permissions();
-                                    if perms.mode() & 0o777 != 0o600 {
-                                        perms.set_mode(0o600);
-                                        let _ = fs::set_permissions(&path, perms);
-                                        logger::log(
-                                            LogLevel::Debug,
-                                            "PROFILE",
-                                            format!("Fixed permissions for '{name}'"),
-                                        );
-                                    }
-                                }
+                    let result = match vault::read_config(&path) {
+                        Ok(content) if ext == "conf" => parse_wireguard_config(&content, &path),
+                        Ok(content) => parse_openvpn_config(&content, &path),
+                        // Locked vault: list by file name, validated once unlocked
+                        Err(_) if vault::is_sealed_file(&path) => Ok(locked_profile_info(&path)),
+                        Err(_) => continue,
+                    };
 
-                                let protocol = if ext == "conf" {
-                                    Protocol::WireGuard
-                                } else {
-                                    Protocol::OpenVPN
-                                };
-
-                                profiles.push(VpnProfile {
-                                    name,
-                                    protocol,
-                                    location,
-                                    config_path: path.clone(),
-                                    last_used: None,
-                                });
-                            }
-                            Err(e) => {
-                                logger::log(
-                                    LogLevel::Warning,
-                                    "PROFILE",
-                                    format!("Skipped {}: {}", path.display(), e),
-                                );
-                                errors += 1;
+                    match result {
+                        Ok((name, location)) => {
+                            // Enforce secure permissions (chmod 600) whenever loaded
+                            if let Ok(metadata) = fs::metadata(&path) {
+                                let mut perms = metadata.permissions();
+                                if perms.mode() & 0o777 != 0o600 {
+                                    perms.set_mode(0o600);
+                                    let _ = fs::set_permissions(&path, perms);
+                                    logger::log(
+                                        LogLevel::Debug,
+                                        "PROFILE",
+                                        format!("Fixed permissions for '{name}'"),
+                                    );
+                                }
                             }
+
+                            let protocol = if ext == "conf" {
+                                Protocol::WireGuard
+                            } else {
+                                Protocol::OpenVPN
+                            };
+
+                            profiles.push(VpnProfile {
+                                name,
+                                protocol,
+                                location,
+                                custom_location: None,
+                                config_path: path.clone(),
+                                last_used: None,
+                                tags: Vec::new(),
+                                last_latency_ms: None,
+                                connect_count: 0,
+                                split_tunnel: crate::core::split_tunnel::SplitTunnel::default(),
+                            });
+                        }
+                        Err(e) => {
+                            logger::log(
+                                LogLevel::Warning,
+                                "PROFILE",
+                                format!("Skipped {}: {}", path.display(), e),
+                            );
+                            errors += 1;
                         }
                     }
                 }
@@ -400,6 +1026,42 @@ pub fn load_profiles() -> Vec<VpnProfile> {
 mod tests {
     use super::*;
 
+    fn resolved(host: &str, addresses: &[&str]) -> ResolvedEndpoint {
+        ResolvedEndpoint {
+            host: host.to_string(),
+            addresses: addresses.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn test_pin_servers_wireguard() {
+        let content = "[Interface]\nPrivateKey = k\n\n[Peer]\nEndpoint = vpn.example.com:51820 # main\nAllowedIPs = 0.0.0.0/0\n";
+        let endpoints = [resolved("vpn.example.com", &["2001:db8::1", "203.0.113.5"])];
+        let pinned = pin_servers(Protocol::WireGuard, content, &endpoints).unwrap();
+        assert!(pinned.contains("\nEndpoint = 203.0.113.5:51820\n"));
+        assert!(pinned.contains("AllowedIPs = 0.0.0.0/0\n"));
+
+        let v6_only = [resolved("vpn.example.com", &["2001:db8::1"])];
+        let pinned = pin_servers(Protocol::WireGuard, content, &v6_only).unwrap();
+        assert!(pinned.contains("Endpoint = [2001:db8::1]:51820\n"));
+
+        // Literal or unresolved endpoints need no copy
+        assert!(pin_servers(Protocol::WireGuard, content, &[]).is_none());
+        let literal = "[Peer]\nEndpoint = 203.0.113.5:51820\n";
+        assert!(pin_servers(Protocol::WireGuard, literal, &endpoints).is_none());
+    }
+
+    #[test]
+    fn test_pin_servers_openvpn() {
+        let content = "client\nremote vpn.example.com 1194 udp\n<connection>\nremote vpn.example.com 443 tcp\n</connection>\nremote 198.51.100.7\n";
+        let endpoints = [resolved("vpn.example.com", &["203.0.113.5", "203.0.113.6"])];
+        let pinned = pin_servers(Protocol::OpenVPN, content, &endpoints).unwrap();
+        assert_eq!(
+            pinned,
+            "client\nremote 203.0.113.5 1194 udp\nremote 203.0.113.6 1194 udp\n<connection>\nremote 203.0.113.5 443 tcp\n</connection>\nremote 198.51.100.7\n"
+        );
+    }
+
     #[test]
     fn test_derive_location_us() {
         assert_eq!(derive_location_from_name("us-east-1"), "United States");
@@ -479,6 +1141,27 @@ AllowedIPs = 0.0.0.0/0
         assert!(result.unwrap_err().contains("No Endpoint found"));
     }
 
+    #[test]
+    fn test_validate_config_wireguard() {
+        let bad = "[Interface]\nPrivateKey = abc123\n\n[Peer]\nPublicKey = xyz789\nEndpoint = vpn.example.com:51820\n";
+        let err = validate_config(Protocol::WireGuard, bad).unwrap_err();
+        assert!(err.contains("line 2: PrivateKey"));
+        assert!(err.contains("line 5: PublicKey"));
+
+        let leaky = "[Interface]
+PrivateKey = yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=
+DNS = 10.0.0.1
+
+[Peer]
+PublicKey = xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=
+Endpoint = vpn.example.com:51820
+AllowedIPs = 0.0.0.0/0
+";
+        let warnings = validate_config(Protocol::WireGuard, leaky).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("::/0"));
+    }
+
     #[test]
     fn test_parse_openvpn_config_basic() {
         let config = r"
@@ -508,6 +1191,14 @@ proto udp
         assert!(result.unwrap_err().contains("No 'remote' directive"));
     }
 
+    #[test]
+    fn test_check_profile_name() {
+        assert!(check_profile_name("de-fra").is_ok());
+        assert!(check_profile_name("").is_err());
+        assert!(check_profile_name(".hidden").is_err());
+        assert!(check_profile_name("../escape").is_err());
+    }
+
     #[test]
     fn test_import_profile_nonexistent_file() {
         let path = std::path::Path::new("/nonexistent/path/file.conf");
@@ -530,4 +1221,81 @@ proto udp
         // Cleanup
         let _ = std::fs::remove_file(&path);
     }
+
+    #[test]
+    fn test_bundle_openvpn_files() {
+        let root = std::env::temp_dir().join(format!("vortix-bundle-{}", std::process::id()));
+        let src_dir = root.join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::write(src_dir.join("ca.crt"), "ca").unwrap();
+        let source = src_dir.join("work.ovpn");
+        let dest = root.join("work.ovpn");
+
+        let content = "remote a\nca ca.crt\ncert /nonexistent/client.crt\n";
+        let (rewritten, warnings) = bundle_openvpn_files(content, &source, &dest).unwrap();
+        let copied = root.join("work.files").join("ca.crt");
+        assert!(copied.is_file());
+        assert!(rewritten.contains(&format!("ca {}", copied.display())));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("line 3:"));
+
+        let missing = bundle_openvpn_files("remote a\nkey client.key\n", &source, &dest);
+        assert!(missing
+            .unwrap_err()
+            .contains("line 2: key file 'client.key' not found"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_swap_in_moves_staged_profile_and_files() {
+        let root = std::env::temp_dir().join(format!("vortix-swap-{}", std::process::id()));
+        let old_files = root.join("work.files");
+        std::fs::create_dir_all(&old_files).unwrap();
+        std::fs::write(old_files.join("old.crt"), "old").unwrap();
+        let config_path = root.join("work.ovpn");
+        std::fs::write(&config_path, "remote old\n").unwrap();
+
+        let staged = root.join(".replacing.work.ovpn");
+        let staged_files = companion_dir(&staged);
+        std::fs::create_dir_all(&staged_files).unwrap();
+        std::fs::write(staged_files.join("ca.crt"), "new").unwrap();
+        std::fs::write(
+            &staged,
+            format!("remote new\nca {}\n", staged_files.join("ca.crt").display()),
+        )
+        .unwrap();
+
+        swap_in(&staged, &config_path).unwrap();
+        assert!(!staged.exists() && !staged_files.exists());
+        assert!(!old_files.join("old.crt").exists());
+        assert_eq!(
+            std::fs::read_to_string(&config_path).unwrap(),
+            format!("remote new\nca {}\n", old_files.join("ca.crt").display())
+        );
+        assert_eq!(
+            std::fs::read_to_string(old_files.join("ca.crt")).unwrap(),
+            "new"
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_server_hosts_cover_failover_remotes() {
+        let ovpn = "client\nremote a.example.com 1194\nremote a.example.com 443 tcp\n\
+                    <connection>\nremote b.example.com 1194\n</connection>\n\
+                    <connection>\nremote 198.51.100.7 443 tcp\n</connection>\n";
+        assert_eq!(
+            hosts_in(Protocol::OpenVPN, ovpn),
+            ["a.example.com", "b.example.com", "198.51.100.7"]
+        );
+
+        let wg = "[Interface]\nPrivateKey = abc\n[Peer]\nEndpoint = vpn.example.com:51820\n\
+                  [Peer]\nEndpoint = [2001:db8::1]:51820\n";
+        assert_eq!(
+            hosts_in(Protocol::WireGuard, wg),
+            ["vpn.example.com", "2001:db8::1"]
+        );
+    }
 }
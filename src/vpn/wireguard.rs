@@ -0,0 +1,734 @@
+//! Typed `WireGuard` configuration model and validation.
+//!
+//! Parses the `wg-quick` format (`[Interface]` plus any number of `[Peer]`
+//! sections) into a [`WireGuardConfig`] and reports line-numbered
+//! [`Diagnostic`]s for invalid keys, addresses and ports, as well as warnings
+//! for setups that work but are likely to leak traffic.
+//!
+//! Also generates key pairs and configs for new profiles ([`NewProfile`]).
+
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroize;
+
+use super::diagnostic::Diagnostic;
+use crate::utils::base64_encode;
+
+/// Length in bytes of a decoded Curve25519 key.
+const KEY_LEN: usize = 32;
+
+/// An IP network in CIDR notation (`10.0.0.2/32`, `::/0`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    pub addr: IpAddr,
+    pub prefix: u8,
+}
+
+impl Cidr {
+    /// Whether this network covers the whole address family (`/0`).
+    pub fn is_default_route(&self) -> bool {
+        self.prefix == 0
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = String;
+
+    /// Parses `addr/prefix`; a bare address is treated as a host route.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .trim()
+            .parse()
+            .map_err(|_| format!("'{s}' is not a valid IP address"))?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(p) => p
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(|| format!("'{s}' has an invalid prefix length (0-{max})"))?,
+            None => max,
+        };
+        Ok(Self { addr, prefix })
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+impl serde::Serialize for Cidr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Cidr {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// A peer endpoint (`host:port` or `[v6]:port`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub host: String,
+    pub port: u16,
+}
+
+impl FromStr for Endpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (host, port) = if let Some(rest) = s.strip_prefix('[') {
+            let (host, port) = rest
+                .split_once("]:")
+                .ok_or_else(|| format!("'{s}' is not a valid [IPv6]:port endpoint"))?;
+            (host, port)
+        } else {
+            s.rsplit_once(':')
+                .ok_or_else(|| format!("'{s}' is missing a port (host:port)"))?
+        };
+        if host.is_empty() {
+            return Err(format!("'{s}' is missing a host"));
+        }
+        let port = parse_port(port)?;
+        Ok(Self {
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// The `[Interface]` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Interface {
+    pub private_key: Option<String>,
+    pub addresses: Vec<Cidr>,
+    /// DNS servers and search domains.
+    pub dns: Vec<String>,
+    pub mtu: Option<u16>,
+    pub listen_port: Option<u16>,
+    pub pre_up: Vec<String>,
+    pub post_up: Vec<String>,
+    pub pre_down: Vec<String>,
+    pub post_down: Vec<String>,
+}
+
+/// A `[Peer]` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Peer {
+    pub public_key: Option<String>,
+    pub preshared_key: Option<String>,
+    pub endpoint: Option<Endpoint>,
+    pub allowed_ips: Vec<Cidr>,
+    pub persistent_keepalive: Option<u16>,
+    /// Line of the `[Peer]` header, for diagnostics.
+    pub line: usize,
+}
+
+/// A parsed `wg-quick` configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WireGuardConfig {
+    pub interface: Interface,
+    pub peers: Vec<Peer>,
+}
+
+impl WireGuardConfig {
+    /// First peer endpoint, used as the profile's server.
+    pub fn endpoint(&self) -> Option<&Endpoint> {
+        self.peers.iter().find_map(|p| p.endpoint.as_ref())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+    None,
+    Interface,
+    Peer,
+    Unknown,
+}
+
+/// Check that `value` is a base64-encoded 32-byte key.
+pub fn is_valid_key(value: &str) -> bool {
+    crate::utils::base64_decode(value).is_some_and(|bytes| bytes.len() == KEY_LEN)
+}
+
+fn parse_port(value: &str) -> Result<u16, String> {
+    match value.trim().parse::<u16>() {
+        Ok(0) | Err(_) => Err(format!("'{value}' is not a valid port (1-65535)")),
+        Ok(port) => Ok(port),
+    }
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Parse a `wg-quick` config, collecting every problem instead of stopping
+/// at the first one. Diagnostics are sorted by line, whole-file findings last.
+#[allow(clippy::too_many_lines)]
+pub fn parse(content: &str) -> (WireGuardConfig, Vec<Diagnostic>) {
+    let mut config = WireGuardConfig::default();
+    let mut diags = Vec::new();
+    let mut section = Section::None;
+    let mut interface_seen = false;
+    // Set when an Endpoint line fails to parse (already reported)
+    let mut endpoint_invalid = false;
+
+    for (idx, raw) in content.lines().enumerate() {
+        let line_no = idx + 1;
+        // wg-quick strips everything after '#'
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            let name = &line[1..line.len() - 1];
+            section = if name.eq_ignore_ascii_case("Interface") {
+                if interface_seen {
+                    diags.push(Diagnostic::error(
+                        Some(line_no),
+                        "duplicate [Interface] section".to_string(),
+                    ));
+                }
+                interface_seen = true;
+                Section::Interface
+            } else if name.eq_ignore_ascii_case("Peer") {
+                config.peers.push(Peer {
+                    line: line_no,
+                    ..Peer::default()
+                });
+                Section::Peer
+            } else {
+                diags.push(Diagnostic::warning(
+                    Some(line_no),
+                    format!("unknown section [{name}] is ignored"),
+                ));
+                Section::Unknown
+            };
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            diags.push(Diagnostic::error(
+                Some(line_no),
+                format!("expected 'Key = Value', found '{line}'"),
+            ));
+            continue;
+        };
+        let key = key.trim();
+        let value = value.trim();
+        let lower = key.to_ascii_lowercase();
+
+        match section {
+            Section::None => {
+                diags.push(Diagnostic::error(
+                    Some(line_no),
+                    format!("'{key}' appears before any section"),
+                ));
+            }
+            Section::Unknown => {}
+            Section::Interface => {
+                let iface = &mut config.interface;
+                match lower.as_str() {
+                    "privatekey" => {
+                        if !is_valid_key(value) {
+                            diags.push(Diagnostic::error(
+                                Some(line_no),
+                                "PrivateKey is not a valid base64-encoded 32-byte key".to_string(),
+                            ));
+                        }
+                        iface.private_key = Some(value.to_string());
+                    }
+                    "address" => {
+                        for item in split_list(value) {
+                            match item.parse() {
+                                Ok(cidr) => iface.addresses.push(cidr),
+                                Err(e) => diags
+                                    .push(Diagnostic::error(Some(line_no), format!("Address {e}"))),
+                            }
+                        }
+                    }
+                    "dns" => iface.dns.extend(split_list(value).map(str::to_string)),
+                    "mtu" => match value.parse::<u16>() {
+                        Ok(mtu) if (576..=9000).contains(&mtu) => iface.mtu = Some(mtu),
+                        _ => diags.push(Diagnostic::error(
+                            Some(line_no),
+                            format!("MTU '{value}' is out of range (576-9000)"),
+                        )),
+                    },
+                    "listenport" => match parse_port(value) {
+                        Ok(port) => iface.listen_port = Some(port),
+                        Err(e) => {
+                            diags.push(Diagnostic::error(Some(line_no), format!("ListenPort {e}")));
+                        }
+                    },
+                    "preup" => iface.pre_up.push(value.to_string()),
+                    "postup" => iface.post_up.push(value.to_string()),
+                    "predown" => iface.pre_down.push(value.to_string()),
+                    "postdown" => iface.post_down.push(value.to_string()),
+                    "table" | "fwmark" | "saveconfig" => {}
+                    _ => diags.push(Diagnostic::warning(
+                        Some(line_no),
+                        format!("unknown [Interface] key '{key}' is ignored"),
+                    )),
+                }
+            }
+            Section::Peer => {
+                let Some(peer) = config.peers.last_mut() else {
+                    continue;
+                };
+                match lower.as_str() {
+                    "publickey" => {
+                        if !is_valid_key(value) {
+                            diags.push(Diagnostic::error(
+                                Some(line_no),
+                                "PublicKey is not a valid base64-encoded 32-byte key".to_string(),
+                            ));
+                        }
+                        peer.public_key = Some(value.to_string());
+                    }
+                    "presharedkey" => {
+                        if !is_valid_key(value) {
+                            diags.push(Diagnostic::error(
+                                Some(line_no),
+                                "PresharedKey is not a valid base64-encoded 32-byte key"
+                                    .to_string(),
+                            ));
+                        }
+                        peer.preshared_key = Some(value.to_string());
+                    }
+                    "endpoint" => match value.parse() {
+                        Ok(endpoint) => peer.endpoint = Some(endpoint),
+                        Err(e) => {
+                            endpoint_invalid = true;
+                            diags.push(Diagnostic::error(Some(line_no), format!("Endpoint {e}")));
+                        }
+                    },
+                    "allowedips" => {
+                        for item in split_list(value) {
+                            match item.parse() {
+                                Ok(cidr) => peer.allowed_ips.push(cidr),
+                                Err(e) => diags.push(Diagnostic::error(
+                                    Some(line_no),
+                                    format!("AllowedIPs {e}"),
+                                )),
+                            }
+                        }
+                    }
+                    "persistentkeepalive" => {
+                        if value.eq_ignore_ascii_case("off") {
+                            peer.persistent_keepalive = None;
+                        } else {
+                            match value.parse::<u16>() {
+                                Ok(secs) => peer.persistent_keepalive = Some(secs),
+                                Err(_) => diags.push(Diagnostic::error(
+                                    Some(line_no),
+                                    format!(
+                                        "PersistentKeepalive '{value}' is not 0-65535 or 'off'"
+                                    ),
+                                )),
+                            }
+                        }
+                    }
+                    _ => diags.push(Diagnostic::warning(
+                        Some(line_no),
+                        format!("unknown [Peer] key '{key}' is ignored"),
+                    )),
+                }
+            }
+        }
+    }
+
+    check_structure(&config, interface_seen, endpoint_invalid, &mut diags);
+    check_leaks(&config, &mut diags);
+
+    diags.sort_by_key(|d| d.line.unwrap_or(usize::MAX));
+    (config, diags)
+}
+
+/// Missing mandatory fields.
+///
+/// `endpoint_invalid` suppresses the missing-Endpoint error when an
+/// unparsable Endpoint line has already been reported.
+fn check_structure(
+    config: &WireGuardConfig,
+    interface_seen: bool,
+    endpoint_invalid: bool,
+    diags: &mut Vec<Diagnostic>,
+) {
+    let mut error = |line: Option<usize>, message: &str| {
+        diags.push(Diagnostic::error(line, message));
+    };
+
+    if !interface_seen {
+        error(None, "missing [Interface] section");
+    } else if config.interface.private_key.is_none() {
+        error(None, "[Interface] has no PrivateKey");
+    }
+    if config.peers.is_empty() {
+        error(None, "missing [Peer] section");
+    }
+    for peer in &config.peers {
+        if peer.public_key.is_none() {
+            error(Some(peer.line), "[Peer] has no PublicKey");
+        }
+    }
+    if !config.peers.is_empty() && config.endpoint().is_none() && !endpoint_invalid {
+        error(None, "No Endpoint found in WireGuard config");
+    }
+}
+
+/// Setups that connect fine but are likely to leak traffic.
+fn check_leaks(config: &WireGuardConfig, diags: &mut Vec<Diagnostic>) {
+    let mut warn = |line: Option<usize>, message: &str| {
+        diags.push(Diagnostic::warning(line, message));
+    };
+
+    let all_allowed: Vec<&Cidr> = config.peers.iter().flat_map(|p| &p.allowed_ips).collect();
+    let full_v4 = all_allowed
+        .iter()
+        .any(|c| c.addr.is_ipv4() && c.is_default_route());
+    let full_v6 = all_allowed
+        .iter()
+        .any(|c| c.addr.is_ipv6() && c.is_default_route());
+
+    if full_v4 && !full_v6 {
+        let line = config
+            .peers
+            .iter()
+            .find(|p| p.allowed_ips.iter().any(Cidr::is_default_route))
+            .map(|p| p.line);
+        warn(
+            line,
+            "AllowedIPs routes all IPv4 but not ::/0 - IPv6 traffic will bypass the tunnel",
+        );
+    }
+    if (full_v4 || full_v6) && config.interface.dns.is_empty() {
+        warn(
+            None,
+            "full tunnel without DNS - queries will go to your local resolver",
+        );
+    }
+    if !(config.interface.pre_up.is_empty()
+        && config.interface.post_up.is_empty()
+        && config.interface.pre_down.is_empty()
+        && config.interface.post_down.is_empty())
+    {
+        warn(
+            None,
+            "config runs PreUp/PostUp/PreDown/PostDown shell commands as root",
+        );
+    }
+}
+
+// === Keys and new profiles ===
+
+/// A Curve25519 key pair, base64-encoded like `wg genkey` / `wg pubkey`.
+/// The private key is wiped on drop.
+pub struct KeyPair {
+    pub private_key: String,
+    pub public_key: String,
+}
+
+impl Drop for KeyPair {
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+/// Generate a key pair from the OS random number generator.
+pub fn generate_keypair() -> Result<KeyPair, String> {
+    let mut secret = [0u8; KEY_LEN];
+    getrandom::getrandom(&mut secret).map_err(|e| format!("Failed to get random bytes: {e}"))?;
+    // Clamp like `wg genkey`, so the stored key is canonical
+    secret[0] &= 0b1111_1000;
+    secret[31] = (secret[31] & 0b0111_1111) | 0b0100_0000;
+    let pair = keypair_from_secret(secret);
+    secret.zeroize();
+    Ok(pair)
+}
+
+fn keypair_from_secret(secret: [u8; KEY_LEN]) -> KeyPair {
+    let public = PublicKey::from(&StaticSecret::from(secret));
+    KeyPair {
+        private_key: base64_encode(&secret),
+        public_key: base64_encode(public.as_bytes()),
+    }
+}
+
+/// Settings collected for a new profile, as typed by the user.
+///
+/// Lists (addresses, DNS, allowed IPs) are comma-separated; `dns` and
+/// `keepalive` may be empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewProfile {
+    pub address: String,
+    pub dns: String,
+    pub peer_public_key: String,
+    pub endpoint: String,
+    pub allowed_ips: String,
+    pub keepalive: String,
+}
+
+impl NewProfile {
+    /// Check every field, naming the first invalid one.
+    pub fn validate(&self) -> Result<(), String> {
+        let cidrs = |label: &str, value: &str| -> Result<(), String> {
+            if split_list(value).next().is_none() {
+                return Err(format!("{label} is required"));
+            }
+            split_list(value)
+                .try_for_each(|item| item.parse::<Cidr>().map(|_| ()))
+                .map_err(|e| format!("{label} {e}"))
+        };
+
+        cidrs("Address", &self.address)?;
+        if let Some(bad) = split_list(&self.dns).find(|d| d.contains(char::is_whitespace)) {
+            return Err(format!(
+                "DNS '{bad}' is not a valid server or search domain"
+            ));
+        }
+        if !is_valid_key(self.peer_public_key.trim()) {
+            return Err("Peer public key is not a valid base64-encoded 32-byte key".to_string());
+        }
+        self.endpoint
+            .trim()
+            .parse::<Endpoint>()
+            .map_err(|e| format!("Endpoint {e}"))?;
+        cidrs("AllowedIPs", &self.allowed_ips)?;
+        let keepalive = self.keepalive.trim();
+        if !keepalive.is_empty() && keepalive.parse::<u16>().is_err() {
+            return Err(format!("Keepalive '{keepalive}' is not 0-65535 seconds"));
+        }
+        Ok(())
+    }
+
+    /// Render a `wg-quick` config using `private_key`.
+    pub fn render(&self, private_key: &str) -> String {
+        let join = |value: &str| split_list(value).collect::<Vec<_>>().join(", ");
+
+        let mut lines = vec![
+            "[Interface]".to_string(),
+            format!("PrivateKey = {private_key}"),
+            format!("Address = {}", join(&self.address)),
+        ];
+        if split_list(&self.dns).next().is_some() {
+            lines.push(format!("DNS = {}", join(&self.dns)));
+        }
+        lines.extend([
+            String::new(),
+            "[Peer]".to_string(),
+            format!("PublicKey = {}", self.peer_public_key.trim()),
+            format!("Endpoint = {}", self.endpoint.trim()),
+            format!("AllowedIPs = {}", join(&self.allowed_ips)),
+        ]);
+        let keepalive = self.keepalive.trim();
+        if !keepalive.is_empty() && keepalive != "0" {
+            lines.push(format!("PersistentKeepalive = {keepalive}"));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vpn::diagnostic::Severity;
+
+    const KEY_A: &str = "yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=";
+    const KEY_B: &str = "xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=";
+
+    fn valid_config() -> String {
+        format!(
+            "[Interface]
+PrivateKey = {KEY_A}
+Address = 10.0.0.2/32, fd00::2/128
+DNS = 10.0.0.1
+MTU = 1420
+
+[Peer]
+PublicKey = {KEY_B}
+Endpoint = vpn.example.com:51820
+AllowedIPs = 0.0.0.0/0, ::/0
+PersistentKeepalive = 25
+"
+        )
+    }
+
+    fn errors(diags: &[Diagnostic]) -> Vec<&Diagnostic> {
+        diags
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_valid_config() {
+        let (config, diags) = parse(&valid_config());
+        assert!(diags.is_empty(), "{diags:?}");
+        assert_eq!(config.interface.addresses.len(), 2);
+        assert_eq!(config.interface.mtu, Some(1420));
+        assert_eq!(config.peers.len(), 1);
+        assert_eq!(config.peers[0].persistent_keepalive, Some(25));
+        assert_eq!(
+            config.endpoint().map(ToString::to_string),
+            Some("vpn.example.com:51820".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_multiple_peers_and_v6_endpoint() {
+        let content = format!(
+            "{}\n[Peer]\nPublicKey = {KEY_A}\nEndpoint = [2001:db8::1]:51820\nAllowedIPs = 10.1.0.0/16\n",
+            valid_config()
+        );
+        let (config, diags) = parse(&content);
+        assert!(errors(&diags).is_empty(), "{diags:?}");
+        assert_eq!(config.peers.len(), 2);
+        let endpoint = config.peers[1].endpoint.as_ref().unwrap();
+        assert_eq!(endpoint.host, "2001:db8::1");
+        assert_eq!(endpoint.to_string(), "[2001:db8::1]:51820");
+    }
+
+    #[test]
+    fn test_invalid_values_are_reported_with_lines() {
+        let content = "[Interface]
+PrivateKey = abc123
+Address = 10.0.0.300/32
+
+[Peer]
+PublicKey = xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=
+Endpoint = vpn.example.com:70000
+AllowedIPs = 0.0.0.0/33
+";
+        let (_, diags) = parse(content);
+        let lines: Vec<Option<usize>> = errors(&diags).iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![Some(2), Some(3), Some(7), Some(8)]);
+        assert!(diags[0].to_string().starts_with("line 2: PrivateKey"));
+    }
+
+    #[test]
+    fn test_missing_sections_and_endpoint() {
+        let (_, diags) = parse("[Interface]\nAddress = 10.0.0.2/32\n");
+        let messages: Vec<String> = errors(&diags).iter().map(|d| d.message.clone()).collect();
+        assert!(messages.contains(&"[Interface] has no PrivateKey".to_string()));
+        assert!(messages.contains(&"missing [Peer] section".to_string()));
+
+        let no_endpoint = valid_config().replace("Endpoint = vpn.example.com:51820\n", "");
+        let (_, diags) = parse(&no_endpoint);
+        assert!(diags
+            .iter()
+            .any(|d| d.message.contains("No Endpoint found")));
+    }
+
+    #[test]
+    fn test_warns_about_ipv6_leak() {
+        let content = valid_config().replace("0.0.0.0/0, ::/0", "0.0.0.0/0");
+        let (_, diags) = parse(&content);
+        assert!(errors(&diags).is_empty());
+        let warning = diags
+            .iter()
+            .find(|d| d.message.contains("::/0"))
+            .expect("IPv6 leak warning");
+        assert_eq!(warning.severity, Severity::Warning);
+        assert_eq!(warning.line, Some(7));
+    }
+
+    #[test]
+    fn test_warns_about_hooks_and_missing_dns() {
+        let content = valid_config().replace(
+            "DNS = 10.0.0.1\n",
+            "PostUp = iptables -A FORWARD -j ACCEPT\n",
+        );
+        let (config, diags) = parse(&content);
+        assert_eq!(config.interface.post_up.len(), 1);
+        assert!(diags.iter().any(|d| d.message.contains("without DNS")));
+        assert!(diags.iter().any(|d| d.message.contains("shell commands")));
+    }
+
+    #[test]
+    fn test_cidr_parsing() {
+        assert_eq!("10.0.0.1".parse::<Cidr>().unwrap().prefix, 32);
+        assert_eq!("::/0".parse::<Cidr>().unwrap().prefix, 0);
+        assert!("fd00::/129".parse::<Cidr>().is_err());
+        assert!("not-an-ip/24".parse::<Cidr>().is_err());
+    }
+
+    #[test]
+    fn test_keypair_matches_rfc7748_vector() {
+        let hex = |s: &str| -> [u8; KEY_LEN] {
+            let bytes: Vec<u8> = (0..s.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+                .collect();
+            bytes.try_into().unwrap()
+        };
+        // RFC 7748 section 6.1, Alice
+        let pair = keypair_from_secret(hex(
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        ));
+        assert_eq!(
+            pair.public_key,
+            base64_encode(&hex(
+                "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"
+            ))
+        );
+
+        let generated = generate_keypair().unwrap();
+        assert!(is_valid_key(&generated.private_key));
+        assert!(is_valid_key(&generated.public_key));
+        assert_ne!(generated.private_key, generated.public_key);
+    }
+
+    #[test]
+    fn test_new_profile_renders_valid_config() {
+        let mut spec = NewProfile {
+            address: "10.0.0.2/32, fd00::2/128".to_string(),
+            dns: "10.0.0.1".to_string(),
+            peer_public_key: KEY_B.to_string(),
+            endpoint: "vpn.example.com:51820".to_string(),
+            allowed_ips: "0.0.0.0/0,::/0".to_string(),
+            keepalive: "25".to_string(),
+        };
+        assert_eq!(spec.validate(), Ok(()));
+
+        let content = spec.render(KEY_A);
+        let (config, diags) = parse(&content);
+        assert!(diags.is_empty(), "{diags:?}");
+        assert_eq!(config.interface.addresses.len(), 2);
+        assert_eq!(config.peers[0].allowed_ips.len(), 2);
+        assert_eq!(config.peers[0].persistent_keepalive, Some(25));
+
+        spec.endpoint = "vpn.example.com".to_string();
+        assert!(spec.validate().unwrap_err().starts_with("Endpoint"));
+        spec.endpoint = "vpn.example.com:51820".to_string();
+        spec.address = String::new();
+        assert_eq!(spec.validate().unwrap_err(), "Address is required");
+    }
+}